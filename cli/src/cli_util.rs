@@ -130,6 +130,7 @@ use jj_lib::str_util::StringMatcher;
 use jj_lib::str_util::StringPattern;
 use jj_lib::transaction::Transaction;
 use jj_lib::transaction::TransactionCommitError;
+use jj_lib::word_tokenizer::WordTokenizerError;
 use jj_lib::working_copy;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::LockedWorkingCopy;
@@ -180,6 +181,8 @@ use crate::diff_util::DiffRenderer;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
+use crate::formatter::FormatterFactory;
+use crate::json_output::OutputFormat;
 use crate::merge_tools::DiffEditor;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
@@ -345,6 +348,11 @@ impl CommandHelper {
         &self.data.global_args
     }
 
+    /// Returns true if listing commands should print JSON records.
+    pub fn is_json_output(&self) -> bool {
+        self.global_args().output == OutputFormat::Json
+    }
+
     pub fn config_env(&self) -> &ConfigEnv {
         &self.data.config_env
     }
@@ -371,11 +379,22 @@ impl CommandHelper {
         &self,
         ui: &Ui,
         workspace_root: &Path,
+    ) -> Result<(UserSettings, ConfigEnv), CommandError> {
+        let repo_path = workspace_root.join(".jj").join("repo");
+        self.settings_for_workspace(ui, workspace_root, &repo_path)
+    }
+
+    /// Resolves configuration for workspace located at the specified path,
+    /// which may be different from the current workspace.
+    pub fn settings_for_workspace(
+        &self,
+        ui: &Ui,
+        workspace_root: &Path,
+        repo_path: &Path,
     ) -> Result<(UserSettings, ConfigEnv), CommandError> {
         let mut config_env = self.data.config_env.clone();
         let mut raw_config = self.data.raw_config.clone();
-        let repo_path = workspace_root.join(".jj").join("repo");
-        config_env.reset_repo_path(&repo_path);
+        config_env.reset_repo_path(repo_path);
         config_env.reload_repo_config(ui, &mut raw_config)?;
         config_env.reset_workspace_path(workspace_root);
         config_env.reload_workspace_config(ui, &mut raw_config)?;
@@ -744,6 +763,7 @@ impl CommandHelper {
                             )?;
                         }
                     }
+                    warn_unverified_operations(ui, &tx)?;
                     Ok(tx
                         .write("reconcile divergent operations")
                         .await?
@@ -2199,6 +2219,8 @@ to the current parents may contain changes from multiple commits.
         if num_rebased > 0 {
             writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
         }
+        let resolver_command_failures =
+            jj_lib::rewrite::run_conflict_resolver_commands(tx.repo_mut()).await?;
 
         for (name, wc_commit_id) in &tx.repo().view().wc_commit_ids().clone() {
             // This can fail if trunk() bookmark gets deleted or conflicted. If
@@ -2258,6 +2280,46 @@ to the current parents may contain changes from multiple commits.
             .map(|commit_id| tx.repo().store().get_commit(commit_id))
             .transpose()?;
 
+        #[cfg(feature = "git")]
+        crate::git_util::print_git_notes_copy_error(ui, tx.repo())?;
+
+        let auto_resolved_paths = tx
+            .repo()
+            .store()
+            .resolution_cache()
+            .map(|cache| cache.take_resolved_paths())
+            .unwrap_or_default();
+        if !auto_resolved_paths.is_empty()
+            && let Some(mut formatter) = ui.status_formatter()
+        {
+            writeln!(
+                formatter,
+                "Resolved conflicts in these files by using recorded resolutions:"
+            )?;
+            for path in &auto_resolved_paths {
+                writeln!(formatter, "  {}", self.format_file_path(path))?;
+            }
+        }
+
+        let resolver_failures = tx
+            .repo()
+            .conflict_resolver_failures()
+            .iter()
+            .map(|(path, err)| (path.as_ref(), err))
+            .chain(
+                resolver_command_failures
+                    .iter()
+                    .map(|(path, err)| (path.as_ref(), err)),
+            );
+        for (path, err) in resolver_failures {
+            writeln!(
+                ui.warning_default(),
+                "Failed to resolve conflicts in {} by using the configured resolver",
+                self.format_file_path(path)
+            )?;
+            print_error_sources(ui, Some(err))?;
+        }
+
         #[cfg(feature = "git")]
         if self.working_copy_shared_with_git && self.env.command.should_commit_transaction() {
             use std::error::Error as _;
@@ -2775,6 +2837,20 @@ jj git init",
     }
 }
 
+/// Warns about merged operations which aren't signed or whose signatures
+/// can't be verified. Operations are only verified if `operation.sign` is
+/// enabled.
+pub fn warn_unverified_operations(ui: &Ui, tx: &Transaction) -> io::Result<()> {
+    for (op, problem) in tx.unverified_operations() {
+        writeln!(
+            ui.warning_default(),
+            "Operation {} {problem}",
+            short_operation_hash(op.id())
+        )?;
+    }
+    Ok(())
+}
+
 pub fn start_repo_transaction(
     repo: &Arc<ReadonlyRepo>,
     workspace_name: &WorkspaceName,
@@ -3191,6 +3267,20 @@ pub fn print_unmatched_explicit_paths<'a>(
     Ok(())
 }
 
+/// Prints the errors of word tokenizers, for which words were split by the
+/// default tokenizer instead.
+pub fn print_word_tokenizer_errors(ui: &Ui, errors: &[WordTokenizerError]) -> io::Result<()> {
+    if let Some(error) = errors.first() {
+        writeln!(
+            ui.warning_default(),
+            "Failed to split words by external tokenizer. The default tokenizer was used \
+             instead."
+        )?;
+        print_error_sources(ui, Some(error))?;
+    }
+    Ok(())
+}
+
 pub async fn update_working_copy(
     repo: &Arc<ReadonlyRepo>,
     workspace: &mut Workspace,
@@ -3648,6 +3738,26 @@ pub struct GlobalArgs {
     #[arg(add = ArgValueCandidates::new(complete::operations))]
     pub at_operation: Option<String>,
 
+    /// Output format of listing commands
+    ///
+    /// With `--output json`, `jj log`, `jj evolog`, `jj op log`, `jj status`,
+    /// `jj bookmark list`, `jj tag list`, `jj workspace list`, `jj file list`,
+    /// `jj diff`, and `jj show` print one JSON object per line instead of
+    /// rendering the configured templates. Other commands reject `--output json`. Unlike the
+    /// templates, the schema is versioned and stays stable across releases.
+    /// See [`jj help -k templates`] for details.
+    ///
+    /// [`jj help -k templates`]:
+    ///     https://docs.jj-vcs.dev/latest/templates/#json-output
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        global = true,
+        default_value_t,
+        value_enum
+    )]
+    pub output: OutputFormat,
+
     /// Enable debug logging
     #[arg(long, global = true)]
     pub debug: bool,
@@ -4100,9 +4210,19 @@ fn command_name(mut matches: &ArgMatches) -> String {
 }
 
 pub fn format_template<C: Clone>(ui: &Ui, arg: &C, template: &TemplateRenderer<C>) -> String {
+    format_template_with(ui.formatter_factory(), arg, template)
+}
+
+/// Like [`format_template()`], but renders with formatters created by the
+/// given `formatter_factory`.
+pub fn format_template_with<C: Clone>(
+    formatter_factory: &FormatterFactory,
+    arg: &C,
+    template: &TemplateRenderer<C>,
+) -> String {
     let mut output = vec![];
     template
-        .format(arg, ui.new_formatter(&mut output).as_mut())
+        .format(arg, formatter_factory.new_formatter(&mut output).as_mut())
         .expect("write() to vec backed formatter should never fail");
     // Template output is usually UTF-8, but it can contain file content.
     output.into_string_lossy()
@@ -28,6 +28,7 @@ use jj_lib::config::ConfigFileSaveError;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
 use jj_lib::config::ConfigMigrateError;
+use jj_lib::conflict_resolvers::ConflictResolverError;
 use jj_lib::dsl_util::Diagnostics;
 use jj_lib::evolution::WalkPredecessorsError;
 use jj_lib::fileset::FilePatternParseError;
@@ -41,12 +42,14 @@ use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::patch::PatchApplyError;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
 use jj_lib::repo::RewriteRootCommit;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::UiPathParseError;
+use jj_lib::resolution_cache::ResolutionCacheError;
 use jj_lib::revset;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetParseError;
@@ -342,6 +345,23 @@ impl From<WorkspaceStoreError> for CommandError {
     }
 }
 
+impl From<PatchApplyError> for CommandError {
+    fn from(err: PatchApplyError) -> Self {
+        match err {
+            PatchApplyError::Backend(err) => err.into(),
+            PatchApplyError::ConflictedFile { .. }
+            | PatchApplyError::UnsupportedFile { .. }
+            | PatchApplyError::InvalidSymlink { .. } => user_error(err),
+        }
+    }
+}
+
+impl From<ResolutionCacheError> for CommandError {
+    fn from(err: ResolutionCacheError) -> Self {
+        internal_error_with_message("Unexpected error from resolution cache", err)
+    }
+}
+
 impl From<WorkspaceInitError> for CommandError {
     fn from(err: WorkspaceInitError) -> Self {
         match err {
@@ -425,7 +445,10 @@ impl From<ResetError> for CommandError {
 
 impl From<TransactionCommitError> for CommandError {
     fn from(err: TransactionCommitError) -> Self {
-        internal_error(err)
+        match err {
+            TransactionCommitError::Sign(_) => user_error(err),
+            _ => internal_error(err),
+        }
     }
 }
 
@@ -481,6 +504,15 @@ impl From<ConflictResolveError> for CommandError {
     }
 }
 
+impl From<ConflictResolverError> for CommandError {
+    fn from(err: ConflictResolverError) -> Self {
+        match err {
+            ConflictResolverError::Backend(err) => err.into(),
+            _ => user_error_with_message("Failed to resolve conflicts", err),
+        }
+    }
+}
+
 impl From<MergeToolPartialResolutionError> for CommandError {
     fn from(err: MergeToolPartialResolutionError) -> Self {
         user_error(err)
@@ -532,10 +564,12 @@ mod git {
     use jj_lib::git::GitExportError;
     use jj_lib::git::GitFetchError;
     use jj_lib::git::GitImportError;
+    use jj_lib::git::GitNotesError;
     use jj_lib::git::GitPushError;
     use jj_lib::git::GitRefExpansionError;
     use jj_lib::git::GitRemoteManagementError;
     use jj_lib::git::GitResetHeadError;
+    use jj_lib::git::GitTagError;
     use jj_lib::git::UnexpectedGitBackendError;
 
     use super::*;
@@ -576,7 +610,9 @@ jj currently does not support partial clones. To use jj with this repository, tr
                 GitFetchError::RemoteName(_) => {
                     user_error(err).hinted("Run `jj git remote rename` to give a different name.")
                 }
-                GitFetchError::RejectedUpdates(_) | GitFetchError::Subprocess(_) => user_error(err),
+                GitFetchError::RejectedUpdates(_)
+                | GitFetchError::Subprocess(_)
+                | GitFetchError::UnexpectedBackend(_) => user_error(err),
             }
         }
     }
@@ -613,6 +649,35 @@ jj currently does not support partial clones. To use jj with this repository, tr
         }
     }
 
+    impl From<GitNotesError> for CommandError {
+        fn from(err: GitNotesError) -> Self {
+            match err {
+                GitNotesError::OpStore(err) => err.into(),
+                GitNotesError::Push(err) => err.into(),
+                GitNotesError::RemoteName(_) => {
+                    user_error(err).hinted("Run `jj git remote rename` to give a different name.")
+                }
+                GitNotesError::Read(_) | GitNotesError::Write(_) => {
+                    internal_error_with_message("Failed to access Git notes", err)
+                }
+                GitNotesError::NoSuchRemote(_)
+                | GitNotesError::Subprocess(_)
+                | GitNotesError::UnexpectedBackend(_) => user_error(err),
+            }
+        }
+    }
+
+    impl From<GitTagError> for CommandError {
+        fn from(err: GitTagError) -> Self {
+            match err {
+                GitTagError::Read(_) | GitTagError::Write(_) => {
+                    internal_error_with_message("Failed to access Git tag", err)
+                }
+                GitTagError::Sign(_) | GitTagError::UnexpectedBackend(_) => user_error(err),
+            }
+        }
+    }
+
     impl From<GitRemoteManagementError> for CommandError {
         fn from(err: GitRemoteManagementError) -> Self {
             user_error(err)
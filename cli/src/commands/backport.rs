@@ -0,0 +1,246 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::slice;
+
+use clap_complete::ArgValueCompleter;
+use futures::TryStreamExt as _;
+use futures::future::try_join_all;
+use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::repo::Repo;
+use jj_lib::rewrite::DuplicateCommitsStats;
+use jj_lib::rewrite::duplicate_commits;
+use jj_lib::trailer::append_trailer_line;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::short_commit_hash;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::formatter::FormatterExt as _;
+use crate::ui::Ui;
+
+/// Copy changes onto one or more destinations, such as release branches
+///
+/// Unlike `jj duplicate --onto`, each revision given to `--onto` is a separate
+/// destination. The specified commits are duplicated onto every destination in
+/// a single operation, and a summary of which destinations ended up with new
+/// conflicts is printed afterwards. Conflicts which already existed in the
+/// backported commits or in their new parents aren't counted.
+///
+/// Each duplicated commit gets a `(cherry picked from commit ...)` line added
+/// to the trailers of its description. This can be customized with the
+/// `templates.backport_trailer` setting. Set it to an empty string to leave
+/// the descriptions untouched.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BackportArgs {
+    /// The revision(s) to backport (default: @) [aliases: -r]
+    #[arg(value_name = "REVSETS")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revisions_pos: Vec<RevisionArg>,
+
+    #[arg(short = 'r', hide = true, value_name = "REVSETS")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revisions_opt: Vec<RevisionArg>,
+
+    /// The revision(s) to backport onto (each revision is a separate
+    /// destination)
+    #[arg(
+        long,
+        visible_alias = "destination",
+        short,
+        visible_short_alias = 'd',
+        value_name = "REVSETS",
+        required = true
+    )]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    onto: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_backport(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BackportArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let to_backport: Vec<CommitId> =
+        if !args.revisions_pos.is_empty() || !args.revisions_opt.is_empty() {
+            workspace_command
+                .parse_union_revsets(ui, &[&*args.revisions_pos, &*args.revisions_opt].concat())?
+        } else {
+            workspace_command.parse_revset(ui, &RevisionArg::AT)?
+        }
+        .evaluate_to_commit_ids()?
+        .try_collect()
+        .await?; // in reverse topological order
+    if to_backport.is_empty() {
+        writeln!(ui.status(), "No revisions to backport.")?;
+        return Ok(());
+    }
+    if to_backport.last() == Some(workspace_command.repo().store().root_commit_id()) {
+        return Err(user_error("Cannot backport the root commit"));
+    }
+    let destinations = workspace_command
+        .resolve_some_revsets(ui, &args.onto)
+        .await?;
+
+    let mut tx = workspace_command.start_transaction();
+
+    for destination_id in &destinations {
+        for commit_id in &to_backport {
+            if tx.repo().index().is_ancestor(commit_id, destination_id)? {
+                writeln!(
+                    ui.warning_default(),
+                    "Destination {} already contains commit {}",
+                    short_commit_hash(destination_id),
+                    short_commit_hash(commit_id)
+                )?;
+                break;
+            }
+        }
+    }
+
+    let commits_to_backport: Vec<Commit> = try_join_all(
+        to_backport
+            .iter()
+            .map(|commit_id| tx.repo().store().get_commit_async(commit_id)),
+    )
+    .await?;
+    let new_descs = {
+        let template = tx.settings().get_string("templates.backport_trailer")?;
+        let parsed = tx.parse_commit_template(ui, &template)?;
+
+        commits_to_backport
+            .iter()
+            .map(|commit| {
+                let line = parsed.format_plain_text(commit);
+                let description =
+                    append_trailer_line(commit.description(), &String::from_utf8_lossy(&line));
+                (commit.id().clone(), description)
+            })
+            .collect()
+    };
+
+    // (destination, number of newly conflicted commits) in the order of the
+    // destination arguments
+    let mut results = Vec::with_capacity(destinations.len());
+    for destination_id in &destinations {
+        let DuplicateCommitsStats {
+            duplicated_commits,
+            num_rebased: _,
+        } = duplicate_commits(
+            tx.repo_mut(),
+            &to_backport,
+            &new_descs,
+            slice::from_ref(destination_id),
+            &[],
+        )
+        .await?;
+        let mut num_conflicted = 0;
+        for source in &commits_to_backport {
+            let duplicate = &duplicated_commits[source.id()];
+            if has_new_conflicts(tx.repo(), source, duplicate).await? {
+                num_conflicted += 1;
+            }
+        }
+        let destination = tx.repo().store().get_commit_async(destination_id).await?;
+        results.push((destination, num_conflicted));
+    }
+
+    let num_to_backport = to_backport.len();
+    let num_destinations = destinations.len();
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Backported {num_to_backport} commit{} onto {num_destinations} destination{}:",
+            if num_to_backport == 1 { "" } else { "s" },
+            if num_destinations == 1 { "" } else { "s" },
+        )?;
+        let statuses = results
+            .iter()
+            .map(|(_, num_conflicted)| match num_conflicted {
+                0 => "clean".to_owned(),
+                n => format!("conflicted ({n}/{num_to_backport})"),
+            })
+            .collect_vec();
+        let width = statuses
+            .iter()
+            .map(|status| status.len())
+            .max()
+            .unwrap_or(0);
+        for ((destination, num_conflicted), status) in results.iter().zip(&statuses) {
+            write!(formatter, "  ")?;
+            if *num_conflicted == 0 {
+                write!(formatter, "{status:<width$}")?;
+            } else {
+                write!(formatter.labeled("conflict"), "{status:<width$}")?;
+            }
+            write!(formatter, " ")?;
+            tx.write_commit_summary(formatter.as_mut(), destination)?;
+            writeln!(formatter)?;
+        }
+    }
+    let num_conflicted_destinations = results
+        .iter()
+        .filter(|(_, num_conflicted)| *num_conflicted > 0)
+        .count();
+    if num_conflicted_destinations > 0 {
+        writeln!(
+            ui.warning_default(),
+            "{num_conflicted_destinations} of {num_destinations} destination{} {} conflicts",
+            if num_destinations == 1 { "" } else { "s" },
+            if num_conflicted_destinations == 1 {
+                "has"
+            } else {
+                "have"
+            },
+        )?;
+    }
+    tx.finish(
+        ui,
+        format!("backport {num_to_backport} commit(s) onto {num_destinations} destination(s)"),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Returns true if `duplicate` has conflicts which exist neither in `source`
+/// nor in the parents of `duplicate`.
+async fn has_new_conflicts(
+    repo: &dyn Repo,
+    source: &Commit,
+    duplicate: &Commit,
+) -> BackendResult<bool> {
+    if !duplicate.has_conflict() {
+        return Ok(false);
+    }
+    let source_tree = source.tree();
+    let parent_tree = duplicate.parent_tree(repo).await?;
+    for (path, value) in duplicate.tree().conflicts() {
+        value?;
+        let source_value = source_tree.path_value(&path).await?;
+        let parent_value = parent_tree.path_value(&path).await?;
+        if source_value.is_resolved() && parent_value.is_resolved() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -31,6 +31,8 @@ use crate::commit_ref_list::RefFilterPredicates;
 use crate::commit_ref_list::SortKey;
 use crate::commit_templater::CommitRef;
 use crate::complete;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::revset_util::parse_union_name_patterns;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
@@ -149,15 +151,22 @@ pub async fn cmd_bookmark_list(
 
     let template: TemplateRenderer<Rc<CommitRef>> = {
         let language = workspace_command.commit_template_language();
-        let text = match &args.template {
-            Some(value) => value.to_owned(),
-            None => workspace_command
-                .settings()
-                .get("templates.bookmark_list")?,
-        };
-        workspace_command
-            .parse_template(ui, &language, &text)?
-            .labeled(["bookmark_list"])
+        if command.is_json_output() {
+            if args.template.is_some() {
+                return Err(text_only_arg_error("`--template`"));
+            }
+            parse_json_template(&language, "bookmark", "self")?
+        } else {
+            let text = match &args.template {
+                Some(value) => value.to_owned(),
+                None => workspace_command
+                    .settings()
+                    .get("templates.bookmark_list")?,
+            };
+            workspace_command
+                .parse_template(ui, &language, &text)?
+                .labeled(["bookmark_list"])
+        }
     };
 
     let ignored_tracked_remote = default_ignored_remote_name(repo.store());
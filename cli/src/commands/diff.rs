@@ -33,7 +33,13 @@ use crate::command_error::user_error;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
 use crate::diff_util::get_copy_records;
+use crate::diff_util::json_diff_formats_for;
 use crate::diff_util::show_templated;
+use crate::formatter::DocumentFormat;
+use crate::formatter::FormatterFactory;
+use crate::formatter::write_html_epilogue;
+use crate::formatter::write_html_prologue;
+use crate::json_output::text_only_arg_error;
 use crate::ui::Ui;
 
 /// Compare file contents between two revisions
@@ -109,6 +115,30 @@ pub(crate) struct DiffArgs {
     #[arg(add = ArgValueCandidates::new(complete::template_aliases))]
     template: Option<String>,
 
+    /// Output format
+    ///
+    /// `html` renders the diff as a standalone HTML document, where labels
+    /// are turned into CSS classes. Use `--output json` for machine-readable
+    /// output.
+    #[arg(
+        long = "format",
+        value_name = "FORMAT",
+        default_value = "text",
+        value_parser = DocumentFormat::value_parser(&[DocumentFormat::Text, DocumentFormat::Html]),
+        help_heading = "Diff Formatting Options",
+    )]
+    output_format: DocumentFormat,
+
+    /// Render the diff as a standalone HTML document
+    ///
+    /// Shorthand for `--format html`.
+    #[arg(
+        long,
+        conflicts_with = "output_format",
+        help_heading = "Diff Formatting Options"
+    )]
+    html: bool,
+
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -195,6 +225,15 @@ pub(crate) async fn cmd_diff(
     // -T disables both short/long rendering formats, but it might be okay to
     // enable long format if explicitly specified (assuming -T is for short or
     // summary output.)
+    let json_output = command.is_json_output();
+    let is_html = args.html || args.output_format == DocumentFormat::Html;
+    if json_output {
+        if args.template.is_some() {
+            return Err(text_only_arg_error("`--template`"));
+        } else if is_html {
+            return Err(text_only_arg_error("`--format html`"));
+        }
+    }
     let maybe_template;
     let diff_renderer;
     if let Some(text) = &args.template {
@@ -204,26 +243,43 @@ pub(crate) async fn cmd_diff(
             .labeled(["diff"]);
         maybe_template = Some(template);
         diff_renderer = workspace_command.diff_renderer(vec![]);
+    } else if json_output {
+        maybe_template = None;
+        let formats = json_diff_formats_for(workspace_command.settings(), &args.format)?;
+        diff_renderer = workspace_command.diff_renderer(formats);
     } else {
         maybe_template = None;
         diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     }
 
     ui.request_pager();
+    let formatter_factory = if is_html {
+        FormatterFactory::html(workspace_command.settings().config())?
+    } else {
+        ui.formatter_factory().clone()
+    };
+    let mut formatter = formatter_factory.new_formatter(ui.stdout());
+    if is_html {
+        write_html_prologue(formatter.raw_formatted()?.as_mut())?;
+    }
     if let Some(template) = &maybe_template {
         let tree_diff = from_tree.diff_stream_with_copies(&to_tree, &matcher, &copy_records);
-        show_templated(ui.stdout_formatter().as_mut(), tree_diff, template).await?;
+        show_templated(formatter.as_mut(), tree_diff, template).await?;
     }
     diff_renderer
         .show_diff(
             ui,
-            ui.stdout_formatter().as_mut(),
+            formatter.as_mut(),
             Diff::new(&from_tree, &to_tree),
             &matcher,
             &copy_records,
             ui.term_width(),
         )
         .await?;
+    if is_html {
+        write_html_epilogue(formatter.raw_formatted()?.as_mut())?;
+    }
+    drop(formatter);
     print_unmatched_explicit_paths(
         ui,
         &workspace_command,
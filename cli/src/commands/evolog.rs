@@ -36,6 +36,8 @@ use crate::complete;
 use crate::diff_util::DiffFormatArgs;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -118,17 +120,29 @@ pub(crate) async fn cmd_evolog(
     let graph_style = GraphStyle::from_settings(workspace_command.settings())?;
     let with_content_format = LogContentFormat::new(ui, workspace_command.settings())?;
 
+    let json_output = command.is_json_output();
+    if json_output {
+        if args.template.is_some() {
+            return Err(text_only_arg_error("`--template`"));
+        } else if diff_renderer.is_some() {
+            return Err(text_only_arg_error("Diff format options"));
+        }
+    }
     let template: TemplateRenderer<CommitEvolutionEntry>;
     let node_template: TemplateRenderer<Option<Commit>>;
     {
         let language = workspace_command.commit_template_language();
-        let template_string = match &args.template {
-            Some(value) => value.clone(),
-            None => workspace_command.settings().get("templates.evolog")?,
+        template = if json_output {
+            parse_json_template(&language, "evolution_entry", "self")?
+        } else {
+            let template_string = match &args.template {
+                Some(value) => value.clone(),
+                None => workspace_command.settings().get("templates.evolog")?,
+            };
+            workspace_command
+                .parse_template(ui, &language, &template_string)?
+                .labeled(["evolog"]) // TODO: add label for the context type?
         };
-        template = workspace_command
-            .parse_template(ui, &language, &template_string)?
-            .labeled(["evolog"]); // TODO: add label for the context type?
         node_template = workspace_command
             .parse_template(
                 ui,
@@ -148,7 +162,7 @@ pub(crate) async fn cmd_evolog(
     let repo = workspace_command.repo();
     let evolution_entries =
         block_on_stream(walk_predecessors(repo, &start_commit_ids).boxed_local());
-    if !args.no_graph {
+    if !args.no_graph && !json_output {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
 
@@ -219,7 +233,12 @@ pub(crate) async fn cmd_evolog(
 
         for entry in evolution_entries {
             let entry = entry?;
-            with_content_format.write(formatter, |formatter| template.format(&entry, formatter))?;
+            if json_output {
+                template.format(&entry, formatter)?;
+            } else {
+                with_content_format
+                    .write(formatter, |formatter| template.format(&entry, formatter))?;
+            }
             if let Some(renderer) = &diff_renderer {
                 let predecessors = entry.predecessors().await?;
                 let width = ui.term_width();
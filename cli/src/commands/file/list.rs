@@ -22,6 +22,8 @@ use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::commit_templater::TreeEntry;
 use crate::complete;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -69,13 +71,20 @@ pub(crate) async fn cmd_file_list(
     let matcher = fileset_expression.to_matcher();
     let template: TemplateRenderer<TreeEntry> = {
         let language = workspace_command.commit_template_language();
-        let text = match &args.template {
-            Some(value) => value.to_owned(),
-            None => workspace_command.settings().get("templates.file_list")?,
-        };
-        workspace_command
-            .parse_template(ui, &language, &text)?
-            .labeled(["file_list"])
+        if command.is_json_output() {
+            if args.template.is_some() {
+                return Err(text_only_arg_error("`--template`"));
+            }
+            parse_json_template(&language, "file", "self")?
+        } else {
+            let text = match &args.template {
+                Some(value) => value.to_owned(),
+                None => workspace_command.settings().get("templates.file_list")?,
+            };
+            workspace_command
+                .parse_template(ui, &language, &text)?
+                .labeled(["file_list"])
+        }
     };
 
     ui.request_pager();
@@ -0,0 +1,508 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::dag_walk;
+use jj_lib::git;
+use jj_lib::git::GitSubprocessOptions;
+use jj_lib::ref_name::RemoteName;
+use jj_lib::repo::Repo as _;
+use jj_lib::trailer::change_id_from_gerrit_change_id;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::commands::gerrit::gerrit_change_id;
+use crate::commands::gerrit::is_gerrit_change_id;
+use crate::commands::gerrit::upload::calculate_remote;
+use crate::git_util::GitSubprocessUi;
+use crate::http_util::HttpProgram;
+use crate::http_util::HttpRequest;
+use crate::ui::Ui;
+
+/// Download changes from Gerrit
+///
+/// Fetches the latest patchset of each change from the `refs/changes/*` refs
+/// of the Gerrit remote.
+///
+/// If the `Change-Id` of a downloaded patchset was derived from a local change
+/// (as done by `jj gerrit upload`), the local change is updated to the
+/// patchset instead of creating an unrelated commit. The downloaded commit is
+/// recorded as a predecessor of the change, and the descendants of the change
+/// are rebased. This way, amendments made in the Gerrit web UI flow back to
+/// the local change. Other patchsets are added as new commits.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DownloadArgs {
+    /// The changes to download
+    ///
+    /// A change can be specified by its number (e.g. `1234`) or by its
+    /// `Change-Id`. A specific patchset can be selected as
+    /// `<number>/<patchset>` (e.g. `1234/2`). Looking up a change by its
+    /// `Change-Id` requires the `gerrit.review-url` setting.
+    #[arg(required = true, value_name = "CHANGE")]
+    changes: Vec<String>,
+
+    /// The Gerrit remote to fetch from
+    ///
+    /// Can be configured with the `gerrit.default-remote` repository option as
+    /// well.
+    #[arg(long)]
+    remote: Option<String>,
+}
+
+/// Git ref namespace where the Gerrit changes are fetched to.
+const GERRIT_CHANGES_REF_NAMESPACE: &str = "refs/jj/gerrit/";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ChangeSpec {
+    Number { number: u32, patchset: Option<u32> },
+    ChangeId(String),
+}
+
+impl ChangeSpec {
+    fn parse(text: &str) -> Result<Self, CommandError> {
+        if is_gerrit_change_id(text) {
+            return Ok(Self::ChangeId(text.to_owned()));
+        }
+        let (number, patchset) = match text.split_once('/') {
+            Some((number, patchset)) => (number, Some(patchset)),
+            None => (text, None),
+        };
+        let parse_number = |s: &str| s.parse::<u32>().ok().filter(|&n| n > 0);
+        match (parse_number(number), patchset.map(parse_number)) {
+            (Some(number), None) => Ok(Self::Number {
+                number,
+                patchset: None,
+            }),
+            (Some(number), Some(Some(patchset))) => Ok(Self::Number {
+                number,
+                patchset: Some(patchset),
+            }),
+            _ => Err(user_error(format!(
+                "Invalid change '{text}'. Expected a change number or Change-Id"
+            ))),
+        }
+    }
+}
+
+/// Returns the remote ref pattern matching the patchsets of the change.
+fn change_ref_pattern(number: u32, patchset: Option<u32>) -> String {
+    let patchset = patchset.map_or_else(|| "*".to_owned(), |p| p.to_string());
+    format!("refs/changes/{:02}/{number}/{patchset}", number % 100)
+}
+
+/// Looks up the numbers of the changes with the `Change-Id` by using the REST
+/// API of the Gerrit server at `review_url`.
+fn query_change_numbers(
+    http: &HttpProgram,
+    review_url: &str,
+    change_id: &str,
+) -> Result<Vec<u32>, CommandError> {
+    let url = format!(
+        "{}/changes/?q=change:{change_id}",
+        review_url.trim_end_matches('/')
+    );
+    let response = http.send(&HttpRequest::new("GET", &url)).map_err(|err| {
+        user_error_with_message(format!("Failed to look up Change-Id {change_id}"), err)
+    })?;
+    parse_change_query_response(&response)
+        .ok_or_else(|| user_error(format!("Unexpected response from {url}")))
+}
+
+/// Parses the change numbers out of the response of the Gerrit change query
+/// endpoint.
+fn parse_change_query_response(data: &[u8]) -> Option<Vec<u32>> {
+    // Gerrit prefixes JSON responses with a magic line to prevent XSSI.
+    let data = data.strip_prefix(b")]}'").unwrap_or(data);
+    let changes: Vec<serde_json::Value> = serde_json::from_slice(data).ok()?;
+    changes
+        .iter()
+        .map(|change| change.get("_number")?.as_u64()?.try_into().ok())
+        .collect()
+}
+
+/// A patchset downloaded from Gerrit.
+#[derive(Clone, Debug)]
+struct Patchset {
+    number: u32,
+    patchset: u32,
+    commit: Commit,
+}
+
+impl Patchset {
+    /// Parses the `<number>/<patchset>` suffix of the fetched ref.
+    fn from_ref(ref_suffix: &str, commit: Commit) -> Option<Self> {
+        let (_shard, number, patchset) = ref_suffix.split('/').collect_tuple()?;
+        Some(Self {
+            number: number.parse().ok()?,
+            patchset: patchset.parse().ok()?,
+            commit,
+        })
+    }
+
+    fn matches(&self, number: u32, patchset: Option<u32>) -> bool {
+        self.number == number && patchset.is_none_or(|p| self.patchset == p)
+    }
+}
+
+/// Removes the `Change-Id` or `Link` trailer added by `jj gerrit upload`.
+fn strip_change_id_trailer(description: &str, gerrit_change_id: &str) -> String {
+    let is_change_id_trailer = |line: &str| {
+        line.strip_prefix("Change-Id: ") == Some(gerrit_change_id)
+            || line.starts_with("Link: ") && line.ends_with(&format!("/id/{gerrit_change_id}"))
+    };
+    let stripped = description
+        .lines()
+        .filter(|line| !is_change_id_trailer(line))
+        .join("\n");
+    let stripped = stripped.trim_end();
+    if stripped.is_empty() {
+        String::new()
+    } else {
+        format!("{stripped}\n")
+    }
+}
+
+pub async fn cmd_gerrit_download(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DownloadArgs,
+) -> Result<(), CommandError> {
+    let specs: Vec<ChangeSpec> = args
+        .changes
+        .iter()
+        .map(|text| ChangeSpec::parse(text))
+        .try_collect()?;
+
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let store = workspace_command.repo().store().clone();
+    let remote = calculate_remote(
+        &store,
+        command.settings(),
+        args.remote.as_deref(),
+        gix::remote::Direction::Fetch,
+    )?;
+    let remote_name = RemoteName::new(&remote);
+    let subprocess_options = GitSubprocessOptions::from_settings(command.settings())?;
+    let local_ref_prefix = format!("{GERRIT_CHANGES_REF_NAMESPACE}{remote}/changes/");
+    let review_url = command.settings().get_string("gerrit.review-url").ok();
+
+    let mut patchsets: Vec<Patchset> = Vec::new();
+    for (spec, text) in specs.iter().zip(&args.changes) {
+        let (number, patchset) = match spec {
+            ChangeSpec::Number { number, patchset } => (*number, *patchset),
+            ChangeSpec::ChangeId(change_id) => {
+                let Some(review_url) = &review_url else {
+                    return Err(user_error(format!(
+                        "Cannot look up Change-Id {change_id} without the Gerrit server URL"
+                    ))
+                    .hinted(
+                        "Set `gerrit.review-url` to the URL of the Gerrit server, or specify \
+                         the change by its number",
+                    ));
+                };
+                let http = HttpProgram::from_settings(command.settings())?;
+                let numbers = query_change_numbers(&http, review_url, change_id)?;
+                match numbers.as_slice() {
+                    [] => {
+                        return Err(user_error(format!(
+                            "Change {text} not found on remote '{remote}'"
+                        )));
+                    }
+                    [number] => (*number, None),
+                    _ => {
+                        return Err(user_error(format!(
+                            "Change-Id {text} matches multiple changes: {}",
+                            numbers.iter().join(", ")
+                        ))
+                        .hinted("Specify the change by its number instead"));
+                    }
+                }
+            }
+        };
+        let remote_pattern = change_ref_pattern(number, patchset);
+        let local_pattern = remote_pattern.replacen("refs/changes/", &local_ref_prefix, 1);
+        let fetched = git::fetch_raw_refs(
+            &store,
+            subprocess_options.clone(),
+            remote_name,
+            &remote_pattern,
+            &local_pattern,
+            &mut GitSubprocessUi::new(ui),
+        )?;
+        let candidates: Vec<Patchset> = if fetched {
+            git::import_raw_ref_commits(&store, &local_ref_prefix)
+                .await?
+                .into_iter()
+                .filter_map(|(name, commit)| {
+                    let suffix = name.as_str().strip_prefix(&local_ref_prefix)?;
+                    Patchset::from_ref(suffix, commit)
+                })
+                .filter(|candidate| candidate.matches(number, patchset))
+                .collect()
+        } else {
+            vec![]
+        };
+        let Some(latest) = candidates.into_iter().max_by_key(|p| p.patchset) else {
+            return Err(user_error(format!(
+                "Change {text} not found on remote '{remote}'"
+            )));
+        };
+        if !patchsets
+            .iter()
+            .any(|p| p.commit.id() == latest.commit.id())
+        {
+            patchsets.push(latest);
+        }
+    }
+
+    // Update the parent changes first so the children can be rebased onto
+    // them.
+    let patchset_ids: HashMap<CommitId, usize> = patchsets
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.commit.id().clone(), i))
+        .collect();
+    let ordered_indices = dag_walk::topo_order_forward(
+        0..patchsets.len(),
+        |&i| i,
+        |&i| {
+            patchsets[i]
+                .commit
+                .parent_ids()
+                .iter()
+                .filter_map(|id| patchset_ids.get(id).copied())
+                .collect_vec()
+        },
+        |_| panic!("cycle detected"),
+    )
+    .unwrap();
+
+    let mut tx = workspace_command.start_transaction();
+    // Maps downloaded commits and local changes to the updated commits.
+    let mut new_commit_ids: HashMap<CommitId, CommitId> = HashMap::new();
+    let mut new_change_commit_ids: HashMap<ChangeId, CommitId> = HashMap::new();
+    let mut summaries: Vec<(String, Commit)> = Vec::new();
+    for i in ordered_indices {
+        let Patchset {
+            number,
+            patchset,
+            commit: downloaded,
+        } = &patchsets[i];
+        let local_commit = match gerrit_change_id(downloaded.description())
+            .and_then(|id| change_id_from_gerrit_change_id(&id))
+        {
+            Some(change_id) => {
+                let targets = tx.repo().resolve_change_id(&change_id)?;
+                let visible = targets
+                    .iter()
+                    .flat_map(|targets| targets.visible_with_offsets())
+                    .map(|(_, id)| id.clone())
+                    .collect_vec();
+                match visible.as_slice() {
+                    [] => None,
+                    [id] => Some(store.get_commit_async(id).await?),
+                    _ => {
+                        writeln!(
+                            ui.warning_default(),
+                            "Skipping patchset {number}/{patchset} since the change it belongs to \
+                             is divergent"
+                        )?;
+                        continue;
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let mut new_parents = Vec::new();
+        for parent_id in downloaded.parent_ids() {
+            let new_parent_id = if let Some(id) = new_commit_ids.get(parent_id) {
+                id.clone()
+            } else {
+                let parent = store.get_commit_async(parent_id).await?;
+                match gerrit_change_id(parent.description())
+                    .and_then(|id| change_id_from_gerrit_change_id(&id))
+                {
+                    Some(change_id) => match new_change_commit_ids.get(&change_id) {
+                        Some(id) => id.clone(),
+                        None => tx
+                            .repo()
+                            .resolve_change_id(&change_id)?
+                            .and_then(|targets| {
+                                let (_, id) = targets.visible_with_offsets().exactly_one().ok()?;
+                                Some(id.clone())
+                            })
+                            .unwrap_or_else(|| parent_id.clone()),
+                    },
+                    None => parent_id.clone(),
+                }
+            };
+            new_parents.push(new_parent_id);
+        }
+
+        let Some(local_commit) = local_commit else {
+            tx.repo_mut().add_head(downloaded).await?;
+            let new_commit = if new_parents == downloaded.parent_ids() {
+                downloaded.clone()
+            } else {
+                tx.repo_mut()
+                    .rewrite_commit(downloaded)
+                    .set_parents(new_parents)
+                    .write()
+                    .await?
+            };
+            summaries.push((
+                format!("Added patchset {number}/{patchset} as "),
+                new_commit.clone(),
+            ));
+            new_commit_ids.insert(downloaded.id().clone(), new_commit.id().clone());
+            continue;
+        };
+
+        let gerrit_change_id = gerrit_change_id(downloaded.description()).unwrap();
+        let new_description = strip_change_id_trailer(downloaded.description(), &gerrit_change_id);
+        if downloaded.tree_ids() == local_commit.tree_ids()
+            && new_parents == local_commit.parent_ids()
+            && new_description == local_commit.description()
+        {
+            summaries.push((
+                format!("Already up to date with patchset {number}/{patchset}: "),
+                local_commit.clone(),
+            ));
+            new_commit_ids.insert(downloaded.id().clone(), local_commit.id().clone());
+            new_change_commit_ids
+                .insert(local_commit.change_id().clone(), local_commit.id().clone());
+            continue;
+        }
+
+        // The downloaded commit is recorded as a predecessor of the updated
+        // change, and is hidden.
+        tx.repo_mut().add_head(downloaded).await?;
+        let new_commit = tx
+            .repo_mut()
+            .rewrite_commit(&local_commit)
+            .set_parents(new_parents)
+            .set_tree(downloaded.tree())
+            .set_description(new_description)
+            .set_author(downloaded.author().clone())
+            .set_predecessors(vec![local_commit.id().clone(), downloaded.id().clone()])
+            .write()
+            .await?;
+        tx.repo_mut()
+            .set_rewritten_commit(downloaded.id().clone(), new_commit.id().clone());
+        summaries.push((
+            format!("Updated to patchset {number}/{patchset}: "),
+            new_commit.clone(),
+        ));
+        new_commit_ids.insert(downloaded.id().clone(), new_commit.id().clone());
+        new_change_commit_ids.insert(new_commit.change_id().clone(), new_commit.id().clone());
+    }
+
+    // Rebase before printing the summaries so the replaced commits are hidden.
+    let num_rebased = tx.repo_mut().rebase_descendants().await?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (message, commit) in &summaries {
+            write!(formatter, "{message}")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    if num_rebased > 0 {
+        writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "download Gerrit changes {} from remote {remote}",
+            args.changes.join(", ")
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_change_spec() {
+        assert_eq!(
+            ChangeSpec::parse("1234").unwrap(),
+            ChangeSpec::Number {
+                number: 1234,
+                patchset: None
+            }
+        );
+        assert_eq!(
+            ChangeSpec::parse("1234/2").unwrap(),
+            ChangeSpec::Number {
+                number: 1234,
+                patchset: Some(2)
+            }
+        );
+        let change_id = "I0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(
+            ChangeSpec::parse(change_id).unwrap(),
+            ChangeSpec::ChangeId(change_id.to_owned())
+        );
+        assert!(ChangeSpec::parse("0").is_err());
+        assert!(ChangeSpec::parse("1234/").is_err());
+        assert!(ChangeSpec::parse("I0123").is_err());
+    }
+
+    #[test]
+    fn test_change_ref_pattern() {
+        assert_eq!(change_ref_pattern(1234, None), "refs/changes/34/1234/*");
+        assert_eq!(change_ref_pattern(5, Some(3)), "refs/changes/05/5/3");
+    }
+
+    #[test]
+    fn test_parse_change_query_response() {
+        let response = b")]}'\n[{\"id\":\"a\",\"_number\":1234},{\"_number\":5}]";
+        assert_eq!(parse_change_query_response(response), Some(vec![1234, 5]));
+        assert_eq!(parse_change_query_response(b"[]"), Some(vec![]));
+        assert_eq!(parse_change_query_response(b"[{\"id\":\"a\"}]"), None);
+        assert_eq!(parse_change_query_response(b"<html>"), None);
+    }
+
+    #[test]
+    fn test_strip_change_id_trailer() {
+        let id = "I0123456789abcdef0123456789abcdef6a6a6964";
+        assert_eq!(
+            strip_change_id_trailer(&format!("one\n\nChange-Id: {id}\n"), id),
+            "one\n"
+        );
+        assert_eq!(
+            strip_change_id_trailer(
+                &format!("one\n\nFoo: bar\nLink: https://example.com/id/{id}\n"),
+                id
+            ),
+            "one\n\nFoo: bar\n"
+        );
+        assert_eq!(
+            strip_change_id_trailer("one\n\nChange-Id: Iother\n", id),
+            "one\n\nChange-Id: Iother\n"
+        );
+    }
+}
@@ -15,6 +15,7 @@
 use std::fmt::Debug;
 
 use clap::Subcommand;
+use jj_lib::trailer::parse_description_trailers;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -24,7 +25,8 @@ use crate::ui::Ui;
 /// Interact with Gerrit Code Review.
 #[derive(Subcommand, Clone, Debug)]
 pub enum GerritCommand {
-    Upload(gerrit::upload::UploadArgs),
+    Download(gerrit::download::DownloadArgs),
+    Upload(Box<gerrit::upload::UploadArgs>),
 }
 
 pub async fn cmd_gerrit(
@@ -33,10 +35,34 @@ pub async fn cmd_gerrit(
     subcommand: &GerritCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        GerritCommand::Download(args) => {
+            gerrit::download::cmd_gerrit_download(ui, command, args).await
+        }
         GerritCommand::Upload(review) => {
             gerrit::upload::cmd_gerrit_upload(ui, command, review).await
         }
     }
 }
 
-mod upload;
+/// Returns whether the text is a Gerrit `Change-Id`.
+pub(crate) fn is_gerrit_change_id(text: &str) -> bool {
+    text.len() == 41 && text.starts_with('I') && text[1..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Returns the Gerrit `Change-Id` from the `Change-Id` or `Link` trailer.
+pub(crate) fn gerrit_change_id(description: &str) -> Option<String> {
+    parse_description_trailers(description)
+        .into_iter()
+        .find_map(|trailer| match trailer.key.as_str() {
+            "Change-Id" => Some(trailer.value),
+            "Link" => trailer
+                .value
+                .rsplit_once("/id/")
+                .map(|(_url, id)| id.to_owned()),
+            _ => None,
+        })
+        .filter(|id| is_gerrit_change_id(id))
+}
+
+mod download;
+pub(super) mod upload;
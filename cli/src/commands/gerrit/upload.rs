@@ -32,6 +32,7 @@ use jj_lib::revset::RevsetExpression;
 use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
 use jj_lib::trailer::Trailer;
+use jj_lib::trailer::gerrit_change_id;
 use jj_lib::trailer::parse_description_trailers;
 
 use crate::cli_util::CommandHelper;
@@ -205,6 +206,25 @@ pub struct UploadArgs {
     // See https://gerrit-review.googlesource.com/Documentation/user-upload.html#patch_set_description
 }
 
+impl UploadArgs {
+    /// Arguments to upload the given revisions without any Gerrit-specific
+    /// options.
+    pub(crate) fn for_revisions(
+        revisions: Vec<RevisionArg>,
+        remote: Option<String>,
+        remote_branch: Option<String>,
+        dry_run: bool,
+    ) -> Self {
+        Self {
+            revisions,
+            remote,
+            remote_branch,
+            dry_run,
+            ..Default::default()
+        }
+    }
+}
+
 /// Which emails receive an email notification about an update to the change.
 #[derive(clap::ValueEnum, Clone, Debug)]
 #[value(rename_all = "kebab_case")]
@@ -221,10 +241,12 @@ pub enum EmailNotification {
     All,
 }
 
-fn calculate_push_remote(
+/// Determines the Gerrit remote to push to or fetch from.
+pub(super) fn calculate_remote(
     store: &Arc<Store>,
     settings: &UserSettings,
     remote: Option<&str>,
+    direction: gix::remote::Direction,
 ) -> Result<String, CommandError> {
     let git_repo = git::get_git_repo(store)?; // will fail if not a git repo
     let remotes = git_repo.remote_names();
@@ -249,8 +271,8 @@ fn calculate_push_remote(
         )));
     }
 
-    // If a general push remote was configured, use that
-    if let Some(remote) = git_repo.remote_default_name(gix::remote::Direction::Push) {
+    // If a general push (or fetch) remote was configured, use that
+    if let Some(remote) = git_repo.remote_default_name(direction) {
         return Ok(remote.to_string());
     }
 
@@ -383,6 +405,7 @@ pub async fn cmd_gerrit_upload(
         // TODO: migrate push_options() away from extra_args?
         extra_args: push_options(args)?,
         remote_push_options: vec![],
+        atomic: false,
     };
 
     let mut workspace_command = command.workspace_helper(ui)?;
@@ -472,7 +495,12 @@ pub async fn cmd_gerrit_upload(
         .map_err(internal_error)?;
 
     let subprocess_options = GitSubprocessOptions::from_settings(command.settings())?;
-    let remote = calculate_push_remote(&store, command.settings(), args.remote.as_deref())?;
+    let remote = calculate_remote(
+        &store,
+        command.settings(),
+        args.remote.as_deref(),
+        gix::remote::Direction::Push,
+    )?;
     let remote_branch = calculate_push_ref(command.settings(), args.remote_branch.clone())?;
 
     // Immediately error and reject any commits that shouldn't be uploaded.
@@ -540,10 +568,7 @@ pub async fn cmd_gerrit_upload(
 
             original_commit.description().to_owned()
         } else {
-            // Gerrit change id is 40 chars, jj change id is 32, so we need padding.
-            // To be consistent with `format_gerrit_change_id_trailer``, we pad with
-            // 6a6a6964 (hex of "jjid").
-            let gerrit_change_id = format!("I{}6a6a6964", original_commit.change_id().hex());
+            let gerrit_change_id = gerrit_change_id(original_commit.change_id());
 
             let change_id_trailer =
                 if let Ok(review_url) = command.settings().get_string("gerrit.review-url") {
@@ -12,10 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
 use std::io;
 
 use clap_complete::ArgValueCandidates;
+use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::FetchTagsOverride;
@@ -24,24 +28,35 @@ use jj_lib::git::GitFetchRefExpression;
 use jj_lib::git::GitSettings;
 use jj_lib::git::IgnoredRefspec;
 use jj_lib::git::IgnoredRefspecs;
+use jj_lib::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO;
 use jj_lib::git::expand_fetch_refspecs;
 use jj_lib::git::get_git_backend;
 use jj_lib::git::load_default_fetch_bookmarks;
+use jj_lib::id_prefix::IdPrefixContext;
+use jj_lib::landed::abandon_landed_commits;
+use jj_lib::landed::find_landed_commits;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RemoteName;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset;
+use jj_lib::revset::RevsetDiagnostics;
+use jj_lib::revset::RevsetExpression;
 use jj_lib::str_util::StringExpression;
+use jj_lib::view::View;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::cli_util::print_updated_commits;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::commands::git::get_single_remote;
+use crate::commands::git::push_bookmarks::delete_merged_push_bookmarks;
 use crate::complete;
 use crate::git_util::GitSubprocessUi;
 use crate::git_util::load_git_import_options;
 use crate::git_util::print_git_import_stats;
+use crate::revset_util::RevsetExpressionEvaluator;
 use crate::revset_util::parse_union_name_patterns;
 use crate::ui::Ui;
 
@@ -232,6 +247,22 @@ pub async fn cmd_git_fetch(
     let import_stats = git_fetch.import_refs().await?;
     print_git_import_stats(ui, &tx, &import_stats)?;
 
+    if tx.settings().get_bool("git.fetch-notes")? {
+        for remote in &matching_remotes {
+            let mut callback = GitSubprocessUi::new(ui);
+            git::fetch_git_notes(
+                tx.repo_mut(),
+                git_settings.to_subprocess_options(),
+                remote,
+                &mut callback,
+            )?;
+        }
+    }
+
+    if tx.settings().get_bool("git.abandon-landed-changes")? {
+        abandon_landed_changes(ui, command, &mut tx).await?;
+    }
+
     if let Some(bookmark_expr) = &common_bookmark_expr {
         warn_if_branches_not_found(ui, &tx, bookmark_expr, &matching_remotes)?;
     }
@@ -244,9 +275,113 @@ pub async fn cmd_git_fetch(
         ),
     )
     .await?;
+    if workspace_command
+        .settings()
+        .get_bool("git.delete-merged-push-bookmarks")?
+    {
+        delete_merged_push_bookmarks(ui, &mut workspace_command, false).await?;
+    }
     Ok(())
 }
 
+/// Abandons mutable commits whose changes were fetched as different commits
+/// in `trunk()`, and rebases their descendants onto the fetched commits.
+///
+/// Only commits which had been pushed, i.e. which were reachable from tracked
+/// remote bookmarks before fetching, are considered.
+async fn abandon_landed_changes(
+    ui: &Ui,
+    command: &CommandHelper,
+    tx: &mut WorkspaceCommandTransaction<'_>,
+) -> Result<(), CommandError> {
+    let old_heads = remote_bookmark_heads(tx.base_repo().view());
+    let new_heads = remote_bookmark_heads(tx.repo().view());
+    if old_heads == new_heads {
+        return Ok(());
+    }
+    let env = tx.base_workspace_helper().env();
+    let trunk_expr = revset::parse(
+        &mut RevsetDiagnostics::new(),
+        "::trunk()",
+        &env.revset_parse_context(),
+    )?;
+    let upstream_expr = trunk_expr
+        .intersection(&RevsetExpression::commits(new_heads.into_iter().collect()).ancestors())
+        .minus(&RevsetExpression::commits(old_heads.into_iter().collect()).ancestors());
+    let pushed_heads = tracked_remote_bookmark_heads(tx.base_repo().view());
+    if pushed_heads.is_empty() {
+        return Ok(());
+    }
+    let local_expr = env
+        .immutable_expression()
+        .negated()
+        .intersection(&RevsetExpression::commits(pushed_heads.into_iter().collect()).ancestors());
+    let id_prefix_context = IdPrefixContext::new(command.revset_extensions().clone());
+    let evaluate = |expression| {
+        RevsetExpressionEvaluator::new(
+            tx.repo(),
+            command.revset_extensions().clone(),
+            &id_prefix_context,
+            expression,
+        )
+        .evaluate_to_commits()
+    };
+    let local: Vec<Commit> = evaluate(local_expr)?.try_collect().await?;
+    if local.is_empty() {
+        return Ok(());
+    }
+    let upstream: Vec<Commit> = evaluate(upstream_expr)?.try_collect().await?;
+    let match_patch_ids = tx
+        .settings()
+        .get_bool("git.abandon-landed-changes-by-patch-id")?;
+    let landed = find_landed_commits(tx.repo(), &local, &upstream, match_patch_ids).await?;
+    if landed.is_empty() {
+        return Ok(());
+    }
+    let num_rebased = abandon_landed_commits(tx.repo_mut(), &landed).await?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Abandoned {} commits that landed upstream:",
+            landed.len()
+        )?;
+        print_updated_commits(
+            formatter.as_mut(),
+            &tx.base_workspace_helper().commit_summary_template(),
+            landed.iter().map(|landed| &landed.local),
+        )?;
+        if num_rebased > 0 {
+            writeln!(
+                formatter,
+                "Rebased {num_rebased} descendant commits onto landed commits"
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the commits pointed to by remote bookmarks, excluding the ones of
+/// the underlying Git repo.
+fn remote_bookmark_heads(view: &View) -> BTreeSet<CommitId> {
+    view.all_remote_bookmarks()
+        .filter(|(symbol, _)| symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO)
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect()
+}
+
+/// Returns the commits pointed to by tracked remote bookmarks, excluding the
+/// ones of the underlying Git repo.
+fn tracked_remote_bookmark_heads(view: &View) -> BTreeSet<CommitId> {
+    view.all_remote_bookmarks()
+        .filter(|(symbol, remote_ref)| {
+            symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO && remote_ref.is_tracked()
+        })
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect()
+}
+
 const DEFAULT_REMOTE: &RemoteName = RemoteName::new("origin");
 
 fn get_default_fetch_remotes(
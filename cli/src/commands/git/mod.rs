@@ -18,7 +18,8 @@ mod export;
 mod fetch;
 mod import;
 mod init;
-mod push;
+pub(super) mod push;
+mod push_bookmarks;
 mod remote;
 mod root;
 
@@ -49,6 +50,8 @@ use self::init::cmd_git_init;
 use self::push::GitPushArgs;
 use self::push::cmd_git_push;
 pub use self::push::is_push_operation;
+use self::push_bookmarks::GitPushBookmarksCommand;
+use self::push_bookmarks::cmd_git_push_bookmarks;
 use self::remote::RemoteCommand;
 use self::remote::cmd_git_remote;
 use self::root::GitRootArgs;
@@ -80,6 +83,8 @@ pub enum GitCommand {
     Init(GitInitArgs),
     Push(GitPushArgs),
     #[command(subcommand)]
+    PushBookmarks(GitPushBookmarksCommand),
+    #[command(subcommand)]
     Remote(RemoteCommand),
     Root(GitRootArgs),
 }
@@ -97,6 +102,9 @@ pub async fn cmd_git(
         GitCommand::Import(args) => cmd_git_import(ui, command, args).await,
         GitCommand::Init(args) => cmd_git_init(ui, command, args).await,
         GitCommand::Push(args) => cmd_git_push(ui, command, args).await,
+        GitCommand::PushBookmarks(subcommand) => {
+            cmd_git_push_bookmarks(ui, command, subcommand).await
+        }
         GitCommand::Remote(args) => cmd_git_remote(ui, command, args).await,
         GitCommand::Root(args) => cmd_git_root(ui, command, args).await,
     }
@@ -34,11 +34,14 @@ use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitPushOptions;
 use jj_lib::git::GitPushRefTargets;
+use jj_lib::git::GitPushStats;
 use jj_lib::git::GitSettings;
 use jj_lib::index::IndexResult;
 use jj_lib::merge::Diff;
 use jj_lib::op_store::RefTarget;
 use jj_lib::operation::Operation;
+use jj_lib::ref_name::GitRefName;
+use jj_lib::ref_name::GitRefNameBuf;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RefNameBuf;
 use jj_lib::ref_name::RemoteName;
@@ -235,8 +238,47 @@ pub struct GitPushArgs {
     dry_run: bool,
 
     /// Git push options
+    ///
+    /// These are sent in addition to the `remotes.<name>.push-options` and
+    /// `remotes.<name>.push-options-template` settings.
     #[arg(long, short)]
     option: Vec<String>,
+
+    /// Request an atomic transaction on the remote
+    ///
+    /// Either all bookmarks and tags are updated on the remote, or none of
+    /// them are. Without this flag, a rejected update doesn't prevent other
+    /// updates from being pushed.
+    #[arg(long)]
+    atomic: bool,
+}
+
+impl GitPushArgs {
+    /// Arguments to push the given revisions by creating bookmarks for them,
+    /// like `--change` does.
+    pub(crate) fn for_changes(
+        remote: Option<RemoteNameBuf>,
+        changes: Vec<RevisionArg>,
+        dry_run: bool,
+    ) -> Self {
+        Self {
+            remote,
+            bookmark: vec![],
+            tag: vec![],
+            all: false,
+            tracked: false,
+            deleted: false,
+            allow_new: false,
+            allow_empty_description: false,
+            allow_private: false,
+            revisions: vec![],
+            change: changes,
+            named: vec![],
+            dry_run,
+            option: vec![],
+            atomic: false,
+        }
+    }
 }
 
 fn make_updates_term(ref_updates: &GitPushRefTargets) -> String {
@@ -257,6 +299,32 @@ fn make_updates_term(ref_updates: &GitPushRefTargets) -> String {
         .join(", ")
 }
 
+/// Returns the subset of `ref_updates` that were accepted by the remote.
+pub(super) fn retain_pushed_updates(
+    ref_updates: &GitPushRefTargets,
+    push_stats: &GitPushStats,
+) -> GitPushRefTargets {
+    let pushed: HashSet<&GitRefName> = push_stats.pushed.iter().map(AsRef::as_ref).collect();
+    let is_pushed = |prefix: &str, name: &RefName| {
+        let qualified_name: GitRefNameBuf = format!("{prefix}{name}", name = name.as_str()).into();
+        pushed.contains(&*qualified_name)
+    };
+    GitPushRefTargets {
+        bookmarks: ref_updates
+            .bookmarks
+            .iter()
+            .filter(|(name, _)| is_pushed("refs/heads/", name))
+            .cloned()
+            .collect(),
+        tags: ref_updates
+            .tags
+            .iter()
+            .filter(|(name, _)| is_pushed("refs/tags/", name))
+            .cloned()
+            .collect(),
+    }
+}
+
 const DEFAULT_REMOTE: &RemoteName = RemoteName::new("origin");
 
 const TX_DESC_PUSH: &str = "push ";
@@ -292,8 +360,10 @@ pub async fn cmd_git_push(
 
     let mut tx = workspace_command.start_transaction();
     let view = tx.repo().view();
-    let tx_description;
+    let mut tx_description;
     let mut ref_updates = GitPushRefTargets::default();
+    // Local bookmarks created by --change and --named
+    let mut created_bookmark_names: Vec<RefNameBuf> = vec![];
     if args.all {
         let mut commits_validator =
             CommitsValidator::new(ui, tx.base_workspace_helper(), remote, args)?;
@@ -424,17 +494,19 @@ pub async fn cmd_git_push(
             tx.repo_mut()
                 .set_local_bookmark_target(name, RefTarget::normal(commit.id().clone()));
         }
-        let created_bookmarks = change_bookmark_names
-            .iter()
-            .chain(named_bookmark_commits.iter().map(|(name, _)| name))
-            .map(|name| {
-                let remote_symbol = name.to_remote_symbol(remote);
-                let targets = LocalAndRemoteRef {
-                    local_target: tx.repo().view().get_local_bookmark(name),
-                    remote_ref: tx.repo().view().get_remote_bookmark(remote_symbol),
-                };
-                (remote_symbol, targets)
-            });
+        created_bookmark_names = itertools::chain(
+            change_bookmark_names,
+            named_bookmark_commits.into_iter().map(|(name, _)| name),
+        )
+        .collect();
+        let created_bookmarks = created_bookmark_names.iter().map(|name| {
+            let remote_symbol = name.to_remote_symbol(remote);
+            let targets = LocalAndRemoteRef {
+                local_target: tx.repo().view().get_local_bookmark(name),
+                remote_ref: tx.repo().view().get_remote_bookmark(remote_symbol),
+            };
+            (remote_symbol, targets)
+        });
         for (remote_symbol, targets) in created_bookmarks {
             let name = remote_symbol.name;
             if !seen_bookmarks.insert(name) {
@@ -558,10 +630,16 @@ pub async fn cmd_git_push(
         return Ok(());
     }
 
-    if !args.dry_run && tx.settings().get_bool("git.sign-on-push")? {
+    let sign_on_push = tx.settings().get_bool("git.sign-on-push")?;
+    if !args.dry_run && sign_on_push {
         let to_push_expr = ready_to_push_revset_expression(&tx, remote, &ref_updates);
         ref_updates = sign_commits_before_push(ui, &mut tx, to_push_expr, ref_updates).await?;
     }
+    // Commits which would be signed on push can't be validated in dry-run mode
+    if tx.settings().get_bool("git.require-trusted-signatures")? && !(args.dry_run && sign_on_push)
+    {
+        validate_trusted_signatures(&tx, remote, &ref_updates).await?;
+    }
 
     if let Some(mut formatter) = ui.status_formatter() {
         writeln!(
@@ -572,6 +650,9 @@ pub async fn cmd_git_push(
         print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &ref_updates)?;
     }
 
+    // Evaluate the push options template even in dry-run mode so errors are
+    // reported before pushing
+    let remote_push_options = get_remote_push_options(ui, &tx, remote, &ref_updates, args)?;
     if args.dry_run {
         writeln!(ui.status(), "Dry-run requested, not pushing.")?;
         return Ok(());
@@ -580,7 +661,8 @@ pub async fn cmd_git_push(
     let git_settings = GitSettings::from_settings(tx.settings())?;
     let options = GitPushOptions {
         extra_args: vec![],
-        remote_push_options: args.option.clone(),
+        remote_push_options,
+        atomic: args.atomic,
     };
     let push_stats = git::push_refs(
         tx.repo_mut(),
@@ -591,8 +673,44 @@ pub async fn cmd_git_push(
         &options,
     )?;
     print_push_stats(ui, &push_stats)?;
-    // TODO: On partial success, locally-created --change/--named bookmarks will
-    // be committed. It's probably better to remove failed local bookmarks.
+    if !push_stats.all_ok() && push_stats.some_exported() {
+        // Record only the updates that made it to the remote so that the
+        // operation log tells which remote refs actually moved. Locally-created
+        // --change/--named bookmarks that failed to push are removed, so that
+        // the same command can be run again. Bookmarks that existed before
+        // this command are left alone.
+        let pushed_updates = retain_pushed_updates(&ref_updates, &push_stats);
+        let base_view = tx.base_repo().view();
+        let rolled_back_names = created_bookmark_names
+            .iter()
+            .filter(|name| base_view.get_local_bookmark(name).is_absent())
+            .cloned()
+            .collect_vec();
+        for name in &rolled_back_names {
+            if !pushed_updates
+                .bookmarks
+                .iter()
+                .any(|(pushed, _)| pushed == name)
+            {
+                tx.repo_mut()
+                    .set_local_bookmark_target(name, RefTarget::absent());
+            }
+        }
+        tx_description = format!(
+            "{TX_DESC_PUSH}{names} to git remote {remote}",
+            names = make_updates_term(&pushed_updates),
+            remote = remote.as_symbol()
+        );
+    }
+    if push_stats.all_ok() && tx.settings().get_bool("git.push-notes")? {
+        let notes_stats = git::push_git_notes(
+            tx.repo_mut(),
+            git_settings.to_subprocess_options(),
+            remote,
+            &mut GitSubprocessUi::new(ui),
+        )?;
+        print_push_stats(ui, &notes_stats)?;
+    }
     if push_stats.all_ok() || push_stats.some_exported() {
         tx.finish(ui, tx_description).await?;
     }
@@ -603,6 +721,47 @@ pub async fn cmd_git_push(
     }
 }
 
+/// Collects push options configured for the `remote` followed by the ones
+/// specified on the command line. Repeated options are passed through as is.
+///
+/// The push options template is evaluated for each pushed commit. Since push
+/// options apply to the whole push, the options generated for the commits are
+/// merged, and identical options are sent only once. Options with the same key
+/// but different values are all sent, since some keys (such as GitLab's
+/// `merge_request.label`) are meant to be repeated.
+fn get_remote_push_options(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    remote: &RemoteName,
+    ref_updates: &GitPushRefTargets,
+    args: &GitPushArgs,
+) -> Result<Vec<String>, CommandError> {
+    let mut push_options = vec![];
+    if let Some(settings) = tx.settings().remote_settings()?.remove(remote) {
+        push_options.extend(settings.push_options);
+        if let Some(template_text) = &settings.push_options_template {
+            let template = tx.parse_commit_template(ui, template_text)?;
+            let new_targets: IndexSet<&CommitId> =
+                itertools::chain(&ref_updates.bookmarks, &ref_updates.tags)
+                    .filter_map(|(_, update)| update.after.as_ref())
+                    .collect();
+            let mut template_options: IndexSet<String> = IndexSet::new();
+            for id in new_targets {
+                let commit = tx.repo().store().get_commit(id)?;
+                let output = template.format_plain_text(&commit);
+                let text = String::from_utf8(output).map_err(|err| {
+                    user_error_with_message("Invalid character in push option", err.utf8_error())
+                })?;
+                let options = text.lines().map(str::trim).filter(|line| !line.is_empty());
+                template_options.extend(options.map(str::to_owned));
+            }
+            push_options.extend(template_options);
+        }
+    }
+    push_options.extend(args.option.iter().cloned());
+    Ok(push_options)
+}
+
 #[derive(Clone, Debug)]
 struct RejectedCommitReason {
     commit: Commit,
@@ -804,6 +963,50 @@ fn ready_to_push_revset_expression(
         .range(&RevsetExpression::commits(new_heads))
 }
 
+/// Checks that the commits to push are signed by keys trusted for their
+/// authors.
+async fn validate_trusted_signatures(
+    tx: &WorkspaceCommandTransaction<'_>,
+    remote: &RemoteName,
+    ref_updates: &GitPushRefTargets,
+) -> Result<(), CommandError> {
+    let to_push_expr = ready_to_push_revset_expression(tx, remote, ref_updates);
+    let to_push = tx
+        .base_workspace_helper()
+        .attach_revset_evaluator(to_push_expr)
+        .resolve()?;
+    let mut commit_stream = to_push
+        .evaluate(tx.repo())?
+        .stream()
+        .commits(tx.repo().store());
+    while let Some(commit) = commit_stream.try_next().await? {
+        let trusted = commit.has_trusted_signature().map_err(|err| {
+            user_error_with_message(
+                format!(
+                    "Failed to verify signature of commit {}",
+                    short_commit_hash(commit.id())
+                ),
+                err,
+            )
+        })?;
+        if trusted {
+            continue;
+        }
+        let mut error = user_error(format!(
+            "Won't push commit {id} since it is not signed by a key trusted for its author",
+            id = short_commit_hash(commit.id()),
+        ));
+        error.add_formatted_hint_with(|formatter| {
+            write!(formatter, "Rejected commit: ")?;
+            tx.write_commit_summary(formatter, &commit)?;
+            Ok(())
+        });
+        error.add_hint("Trusted keys can be configured by the `signing.trust` setting.");
+        return Err(error);
+    }
+    Ok(())
+}
+
 /// Signs commits before pushing.
 ///
 /// Returns the updated list of bookmark names and corresponding
@@ -1214,6 +1417,7 @@ async fn create_change_bookmarks(
             change_id = commit.change_id()
         )?;
         tx.repo_mut().set_local_bookmark_target(name, target);
+        tx.repo_mut().add_push_bookmark(name.clone());
     }
     Ok(bookmark_names)
 }
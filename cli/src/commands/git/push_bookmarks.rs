@@ -0,0 +1,297 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::git;
+use jj_lib::git::GitPushOptions;
+use jj_lib::git::GitPushRefTargets;
+use jj_lib::git::GitSettings;
+use jj_lib::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO;
+use jj_lib::merge::Diff;
+use jj_lib::op_store::RefTarget;
+use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteNameBuf;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::commands::git::push::retain_pushed_updates;
+use crate::formatter::FormatterExt as _;
+use crate::git_util::GitSubprocessUi;
+use crate::git_util::print_push_stats;
+use crate::ui::Ui;
+
+/// List bookmarks created by `jj git push --change`
+///
+/// Bookmarks which have been merged into `trunk()` are marked as such.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitPushBookmarksListArgs {}
+
+/// Delete push bookmarks which have been merged into `trunk()`
+///
+/// A push bookmark is considered merged once the commits it and its tracked
+/// remote bookmarks point to are all ancestors of `trunk()`. Merged bookmarks
+/// are deleted locally and from the remotes they are tracking.
+///
+/// This can be run automatically after `jj git fetch` by setting
+/// `git.delete-merged-push-bookmarks = true`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitPushBookmarksGcArgs {
+    /// Only display the bookmarks that would be deleted
+    #[arg(long, short = 'n')]
+    dry_run: bool,
+}
+
+/// Manage bookmarks created by `jj git push --change`
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum GitPushBookmarksCommand {
+    Gc(GitPushBookmarksGcArgs),
+    List(GitPushBookmarksListArgs),
+}
+
+pub async fn cmd_git_push_bookmarks(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &GitPushBookmarksCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        GitPushBookmarksCommand::Gc(args) => cmd_git_push_bookmarks_gc(ui, command, args).await,
+        GitPushBookmarksCommand::List(args) => cmd_git_push_bookmarks_list(ui, command, args).await,
+    }
+}
+
+async fn cmd_git_push_bookmarks_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &GitPushBookmarksListArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let push_bookmarks = find_push_bookmarks(ui, &workspace_command)?;
+    let mut formatter = ui.stdout_formatter();
+    for bookmark in &push_bookmarks {
+        write!(
+            formatter.labeled("bookmark"),
+            "{}",
+            bookmark.name.as_symbol()
+        )?;
+        if bookmark.merged {
+            write!(formatter, " (merged)")?;
+        }
+        if let Some(id) = bookmark.local_target.as_normal() {
+            write!(formatter, ": ")?;
+            let commit = repo.store().get_commit_async(id).await?;
+            workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+            writeln!(formatter)?;
+        } else if bookmark.local_target.is_absent() {
+            writeln!(formatter, " (deleted)")?;
+        } else {
+            writeln!(formatter, " (conflicted)")?;
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_git_push_bookmarks_gc(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitPushBookmarksGcArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let num_deleted =
+        delete_merged_push_bookmarks(ui, &mut workspace_command, args.dry_run).await?;
+    if num_deleted == 0 {
+        writeln!(ui.status(), "No merged push bookmarks to delete.")?;
+    }
+    Ok(())
+}
+
+/// Bookmark created by `jj git push --change` and its current state.
+struct PushBookmark {
+    name: RefNameBuf,
+    local_target: RefTarget,
+    /// Targets of the tracked remote bookmarks, excluding the Git repo.
+    remote_targets: Vec<(RemoteNameBuf, RefTarget)>,
+    merged: bool,
+}
+
+fn find_push_bookmarks(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<Vec<PushBookmark>, CommandError> {
+    let view = workspace_command.repo().view();
+    if view.push_bookmarks().is_empty() {
+        return Ok(vec![]);
+    }
+    let trunk_ancestors = workspace_command
+        .parse_revset(ui, &RevisionArg::from("::trunk()".to_owned()))?
+        .evaluate()?;
+    let is_merged = trunk_ancestors.containing_fn();
+    let mut push_bookmarks = vec![];
+    for name in view.push_bookmarks() {
+        let local_target = view.get_local_bookmark(name).clone();
+        let remote_targets = view
+            .all_remote_bookmarks()
+            .filter(|(symbol, remote_ref)| {
+                *symbol.name == **name
+                    && symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                    && remote_ref.is_tracked()
+                    && remote_ref.is_present()
+            })
+            .map(|(symbol, remote_ref)| (symbol.remote.to_owned(), remote_ref.target.clone()))
+            .collect_vec();
+        let target_ids: Vec<&CommitId> = local_target
+            .added_ids()
+            .chain(
+                remote_targets
+                    .iter()
+                    .flat_map(|(_, target)| target.added_ids()),
+            )
+            .collect();
+        let merged = !local_target.has_conflict()
+            && !target_ids.is_empty()
+            && target_ids
+                .into_iter()
+                .map(&is_merged)
+                .process_results(|mut results| results.all(|merged| merged))?;
+        push_bookmarks.push(PushBookmark {
+            name: name.clone(),
+            local_target,
+            remote_targets,
+            merged,
+        });
+    }
+    Ok(push_bookmarks)
+}
+
+/// Deletes push bookmarks merged into `trunk()`, locally and from the remotes.
+///
+/// Push bookmarks which no longer exist anywhere are forgotten. Returns the
+/// number of merged bookmarks.
+pub(super) async fn delete_merged_push_bookmarks(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    dry_run: bool,
+) -> Result<usize, CommandError> {
+    let push_bookmarks = find_push_bookmarks(ui, workspace_command)?;
+    let (merged, unmerged): (Vec<_>, Vec<_>) = push_bookmarks
+        .into_iter()
+        .partition(|bookmark| bookmark.merged);
+    let stale = unmerged
+        .iter()
+        .filter(|bookmark| bookmark.local_target.is_absent() && bookmark.remote_targets.is_empty())
+        .collect_vec();
+    for bookmark in &merged {
+        if dry_run {
+            writeln!(
+                ui.status(),
+                "Would delete merged bookmark {}",
+                bookmark.name.as_symbol()
+            )?;
+        } else {
+            writeln!(
+                ui.status(),
+                "Deleting merged bookmark {}",
+                bookmark.name.as_symbol()
+            )?;
+        }
+    }
+    if dry_run || (merged.is_empty() && stale.is_empty()) {
+        return Ok(merged.len());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let mut remote_deletions: BTreeMap<&RemoteNameBuf, Vec<_>> = BTreeMap::new();
+    for bookmark in &merged {
+        for (remote, target) in &bookmark.remote_targets {
+            let diff = Diff::new(target.as_normal().cloned(), None);
+            remote_deletions
+                .entry(remote)
+                .or_default()
+                .push((bookmark.name.clone(), diff));
+        }
+    }
+    // Delete the bookmarks from the remotes first, so the local bookmarks of
+    // which some remote deletion failed can be kept for the next attempt.
+    let git_settings = GitSettings::from_settings(tx.settings())?;
+    let mut undeleted_names: HashSet<RefNameBuf> = HashSet::new();
+    let mut push_result = Ok(());
+    for (remote, bookmarks) in remote_deletions {
+        let targets = GitPushRefTargets {
+            bookmarks,
+            tags: vec![],
+        };
+        if push_result.is_err() {
+            undeleted_names.extend(targets.bookmarks.into_iter().map(|(name, _)| name));
+            continue;
+        }
+        match git::push_refs(
+            tx.repo_mut(),
+            git_settings.to_subprocess_options(),
+            remote,
+            &targets,
+            &mut GitSubprocessUi::new(ui),
+            &GitPushOptions::default(),
+        ) {
+            Ok(push_stats) => {
+                print_push_stats(ui, &push_stats)?;
+                let pushed = retain_pushed_updates(&targets, &push_stats);
+                undeleted_names.extend(
+                    targets
+                        .bookmarks
+                        .into_iter()
+                        .filter(|update| !pushed.bookmarks.contains(update))
+                        .map(|(name, _)| name),
+                );
+            }
+            // The deletions pushed to the previous remotes are still recorded.
+            Err(err) => {
+                undeleted_names.extend(targets.bookmarks.into_iter().map(|(name, _)| name));
+                push_result = Err(err);
+            }
+        }
+    }
+    for bookmark in &merged {
+        if !undeleted_names.contains(&bookmark.name) {
+            tx.repo_mut()
+                .set_local_bookmark_target(&bookmark.name, RefTarget::absent());
+        }
+    }
+    for bookmark in stale {
+        tx.repo_mut().remove_push_bookmark(&bookmark.name);
+    }
+    if tx.repo().has_changes() {
+        tx.finish(ui, "delete merged push bookmarks").await?;
+    }
+    push_result?;
+    if !undeleted_names.is_empty() {
+        return Err(user_error(format!(
+            "Failed to delete some merged bookmarks from the remotes: {}",
+            undeleted_names
+                .iter()
+                .sorted()
+                .map(|name| name.as_symbol())
+                .join(", ")
+        )));
+    }
+    Ok(merged.len())
+}
@@ -38,13 +38,19 @@ use tracing::instrument;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
-use crate::cli_util::format_template;
+use crate::cli_util::format_template_with;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::formatter::DocumentFormat;
 use crate::formatter::FormatterExt as _;
+use crate::formatter::FormatterFactory;
+use crate::formatter::write_html_epilogue;
+use crate::formatter::write_html_prologue;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -123,6 +129,20 @@ pub(crate) struct LogArgs {
     #[arg(long, short = 'p')]
     patch: bool,
 
+    /// Output format
+    ///
+    /// `html` renders the output as a standalone HTML document, where labels
+    /// are turned into CSS classes and `hyperlink()` in templates produces
+    /// links. Use `--output json` for machine-readable output.
+    #[arg(
+        long = "format",
+        value_name = "FORMAT",
+        default_value = "text",
+        value_parser = DocumentFormat::value_parser(&[DocumentFormat::Text, DocumentFormat::Html]),
+        conflicts_with = "count"
+    )]
+    output_format: DocumentFormat,
+
     /// Print the number of commits instead of showing them
     #[arg(long, conflicts_with_all = ["DiffFormatArgs", "no_graph", "patch", "reversed", "template"])]
     count: bool,
@@ -197,17 +217,31 @@ pub(crate) async fn cmd_log(
     let use_elided_nodes = settings.get_bool("ui.log-synthetic-elided-nodes")?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
 
+    let json_output = command.is_json_output();
+    if json_output {
+        if args.template.is_some() {
+            return Err(text_only_arg_error("`--template`"));
+        } else if args.output_format == DocumentFormat::Html {
+            return Err(text_only_arg_error("`--format html`"));
+        } else if diff_renderer.is_some() {
+            return Err(text_only_arg_error("Diff format options"));
+        }
+    }
     let template: TemplateRenderer<Commit>;
     let node_template: TemplateRenderer<Option<Commit>>;
     {
         let language = workspace_command.commit_template_language();
-        let template_string = match &args.template {
-            Some(value) => value.clone(),
-            None => settings.get_string("templates.log")?,
+        template = if json_output {
+            parse_json_template(&language, "commit", "self")?
+        } else {
+            let template_string = match &args.template {
+                Some(value) => value.clone(),
+                None => settings.get_string("templates.log")?,
+            };
+            workspace_command
+                .parse_template(ui, &language, &template_string)?
+                .labeled(["log", "commit"])
         };
-        template = workspace_command
-            .parse_template(ui, &language, &template_string)?
-            .labeled(["log", "commit"]);
         node_template = workspace_command
             .parse_template(ui, &language, &settings.get_string("templates.log_node")?)?
             .labeled(["log", "commit", "node"]);
@@ -215,11 +249,20 @@ pub(crate) async fn cmd_log(
 
     {
         ui.request_pager();
-        let mut formatter = ui.stdout_formatter();
+        let is_html = args.output_format == DocumentFormat::Html;
+        let formatter_factory = if is_html {
+            FormatterFactory::html(settings.config())?
+        } else {
+            ui.formatter_factory().clone()
+        };
+        let mut formatter = formatter_factory.new_formatter(ui.stdout());
         let formatter = formatter.as_mut();
+        if is_html {
+            write_html_prologue(formatter.raw_formatted()?.as_mut())?;
+        }
 
-        if !args.no_graph {
-            let mut raw_output = formatter.raw()?;
+        if !args.no_graph && !json_output {
+            let mut raw_output = formatter.raw_formatted()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
             let iter: Box<dyn Iterator<Item = _>> = {
                 let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph(), |id| id);
@@ -274,11 +317,12 @@ pub(crate) async fn cmd_log(
                 let commit = store.get_commit(&key.0)?;
                 let within_graph =
                     with_content_format.sub_width(graph.width(&key, &graphlog_edges));
-                within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                    template.format(&commit, formatter)
-                })?;
+                within_graph.write(
+                    formatter_factory.new_formatter(&mut buffer).as_mut(),
+                    |formatter| template.format(&commit, formatter),
+                )?;
                 if let Some(renderer) = &diff_renderer {
-                    let mut formatter = ui.new_formatter(&mut buffer);
+                    let mut formatter = formatter_factory.new_formatter(&mut buffer);
                     renderer
                         .show_patch(
                             ui,
@@ -291,7 +335,7 @@ pub(crate) async fn cmd_log(
                 }
 
                 let commit = Some(commit);
-                let node_symbol = format_template(ui, &commit, &node_template);
+                let node_symbol = format_template_with(&formatter_factory, &commit, &node_template);
                 graph.add_node(
                     &key,
                     &graphlog_edges,
@@ -311,10 +355,12 @@ pub(crate) async fn cmd_log(
                     let mut buffer = vec![];
                     let within_graph =
                         with_content_format.sub_width(graph.width(&elided_key, &edges));
-                    within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                        writeln!(formatter.labeled("elided"), "(elided revisions)")
-                    })?;
-                    let node_symbol = format_template(ui, &None, &node_template);
+                    within_graph.write(
+                        formatter_factory.new_formatter(&mut buffer).as_mut(),
+                        |formatter| writeln!(formatter.labeled("elided"), "(elided revisions)"),
+                    )?;
+                    let node_symbol =
+                        format_template_with(&formatter_factory, &None, &node_template);
                     graph.add_node(
                         &elided_key,
                         &edges,
@@ -335,8 +381,12 @@ pub(crate) async fn cmd_log(
             };
             let mut commit_stream = id_stream.commits(store);
             while let Some(commit) = commit_stream.try_next().await? {
-                with_content_format
-                    .write(formatter, |formatter| template.format(&commit, formatter))?;
+                if json_output {
+                    template.format(&commit, formatter)?;
+                } else {
+                    with_content_format
+                        .write(formatter, |formatter| template.format(&commit, formatter))?;
+                }
                 if let Some(renderer) = &diff_renderer {
                     let width = ui.term_width();
                     renderer
@@ -350,6 +400,9 @@ pub(crate) async fn cmd_log(
                     .retain(|&path| tree.path_value(path).block_on().unwrap().is_absent());
             }
         }
+        if is_html {
+            write_html_epilogue(formatter.raw_formatted()?.as_mut())?;
+        }
 
         if !explicit_paths.is_empty() {
             let ui_paths = explicit_paths
@@ -15,6 +15,7 @@
 mod abandon;
 mod absorb;
 mod arrange;
+mod backport;
 #[cfg(feature = "bench")]
 mod bench;
 mod bisect;
@@ -42,14 +43,18 @@ mod new;
 mod next;
 mod operation;
 mod parallelize;
+mod patch;
 mod prev;
 mod rebase;
 mod redo;
 mod resolve;
 mod restore;
 mod revert;
+#[cfg(feature = "git")]
+mod review;
 mod root;
 mod run;
+mod shelve;
 mod show;
 mod sign;
 mod simplify_parents;
@@ -65,6 +70,7 @@ mod version;
 mod workspace;
 
 use std::fmt::Debug;
+use std::iter;
 
 use clap::CommandFactory as _;
 use clap::FromArgMatches as _;
@@ -72,11 +78,13 @@ use clap::Subcommand as _;
 use clap::builder::Styles;
 use clap::builder::styling::AnsiColor;
 use clap_complete::engine::SubcommandCandidates;
+use itertools::Itertools as _;
 use tracing::instrument;
 
 use crate::cli_util::Args;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
+use crate::command_error::user_error;
 use crate::complete;
 use crate::ui::Ui;
 
@@ -95,6 +103,7 @@ enum Command {
     Abandon(abandon::AbandonArgs),
     Absorb(absorb::AbsorbArgs),
     Arrange(arrange::ArrangeArgs),
+    Backport(backport::BackportArgs),
     #[cfg(feature = "bench")]
     #[command(subcommand)]
     Bench(bench::BenchCommand),
@@ -133,16 +142,23 @@ enum Command {
     #[command(visible_alias = "op")]
     Operation(operation::OperationCommand),
     Parallelize(parallelize::ParallelizeArgs),
+    #[command(subcommand)]
+    Patch(patch::PatchCommand),
     Prev(prev::PrevArgs),
     Rebase(rebase::RebaseArgs),
     Redo(redo::RedoArgs),
     Resolve(resolve::ResolveArgs),
     Restore(restore::RestoreArgs),
     Revert(revert::RevertArgs),
+    #[cfg(feature = "git")]
+    #[command(subcommand)]
+    Review(review::ReviewCommand),
     Root(root::RootArgs),
     #[command(hide = true)]
     // TODO: Flesh out.
     Run(run::RunArgs),
+    #[command(subcommand)]
+    Shelve(shelve::ShelveCommand),
     Show(show::ShowArgs),
     Sign(sign::SignArgs),
     SimplifyParents(simplify_parents::SimplifyParentsArgs),
@@ -166,12 +182,54 @@ pub fn default_app() -> clap::Command {
     Command::augment_subcommands(Args::command())
 }
 
+/// Commands which can print JSON records with `--output json`.
+const JSON_OUTPUT_COMMANDS: &[&[&str]] = &[
+    &["bookmark", "list"],
+    &["diff"],
+    &["evolog"],
+    &["file", "list"],
+    &["log"],
+    &["operation", "log"],
+    &["show"],
+    &["status"],
+    &["tag", "list"],
+    &["workspace", "list"],
+];
+
+fn check_output_format(command_helper: &CommandHelper) -> Result<(), CommandError> {
+    if !command_helper.is_json_output() {
+        return Ok(());
+    }
+    let names = iter::successors(command_helper.matches().subcommand(), |(_, matches)| {
+        matches.subcommand()
+    })
+    .map(|(name, _)| name)
+    .collect_vec();
+    if JSON_OUTPUT_COMMANDS.contains(&names.as_slice()) {
+        Ok(())
+    } else {
+        let supported_commands = JSON_OUTPUT_COMMANDS
+            .iter()
+            .map(|names| format!("`jj {}`", names.join(" ")))
+            .join(", ");
+        Err(user_error(format!(
+            "`--output json` cannot be used with `jj {}`",
+            names.join(" ")
+        ))
+        .hinted(format!(
+            "`--output json` is supported by {supported_commands}"
+        )))
+    }
+}
+
 #[instrument(skip_all)]
 pub async fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), CommandError> {
+    check_output_format(command_helper)?;
     let subcommand = Command::from_arg_matches(command_helper.matches()).unwrap();
     match &subcommand {
         Command::Abandon(args) => abandon::cmd_abandon(ui, command_helper, args).await,
         Command::Absorb(args) => absorb::cmd_absorb(ui, command_helper, args).await,
+        Command::Backport(args) => backport::cmd_backport(ui, command_helper, args).await,
         #[cfg(feature = "bench")]
         Command::Bench(args) => bench::cmd_bench(ui, command_helper, args).await,
         Command::Bisect(args) => bisect::cmd_bisect(ui, command_helper, args).await,
@@ -200,17 +258,21 @@ pub async fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<
         Command::Next(args) => next::cmd_next(ui, command_helper, args).await,
         Command::Operation(args) => operation::cmd_operation(ui, command_helper, args).await,
         Command::Parallelize(args) => parallelize::cmd_parallelize(ui, command_helper, args).await,
+        Command::Patch(args) => patch::cmd_patch(ui, command_helper, args).await,
         Command::Prev(args) => prev::cmd_prev(ui, command_helper, args).await,
         Command::Rebase(args) => rebase::cmd_rebase(ui, command_helper, args).await,
         Command::Redo(args) => redo::cmd_redo(ui, command_helper, args).await,
         Command::Resolve(args) => resolve::cmd_resolve(ui, command_helper, args).await,
         Command::Restore(args) => restore::cmd_restore(ui, command_helper, args).await,
         Command::Revert(args) => revert::cmd_revert(ui, command_helper, args).await,
+        #[cfg(feature = "git")]
+        Command::Review(args) => review::cmd_review(ui, command_helper, args).await,
         Command::Root(args) => root::cmd_root(ui, command_helper, args).await,
         Command::Run(args) => run::cmd_run(ui, command_helper, args).await,
         Command::SimplifyParents(args) => {
             simplify_parents::cmd_simplify_parents(ui, command_helper, args).await
         }
+        Command::Shelve(args) => shelve::cmd_shelve(ui, command_helper, args).await,
         Command::Show(args) => show::cmd_show(ui, command_helper, args).await,
         Command::Sign(args) => sign::cmd_sign(ui, command_helper, args).await,
         Command::Sparse(args) => sparse::cmd_sparse(ui, command_helper, args).await,
@@ -17,6 +17,7 @@ use jj_lib::operation::Operation;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::start_repo_transaction;
+use crate::cli_util::warn_unverified_operations;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -75,6 +76,7 @@ pub async fn cmd_op_integrate(
                     )?;
                 }
             }
+            warn_unverified_operations(ui, &tx)?;
             writeln!(
                 ui.status(),
                 "The specified operation has been integrated with other existing operations."
@@ -39,6 +39,8 @@ use crate::diff_util::diff_formats_for_log;
 use crate::formatter::Formatter;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::operation_templater::OperationTemplateLanguage;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
@@ -112,7 +114,15 @@ pub async fn cmd_op_log(
         let workspace_command = command.workspace_helper(ui)?;
         let current_op = workspace_command.repo().operation();
         let repo_loader = workspace_command.workspace().repo_loader();
-        do_op_log(ui, workspace_command.env(), repo_loader, current_op, args).await
+        do_op_log(
+            ui,
+            command,
+            workspace_command.env(),
+            repo_loader,
+            current_op,
+            args,
+        )
+        .await
     } else {
         // Don't load the repo so that the operation history can be inspected
         // even with a corrupted repo state. For example, you can find the first
@@ -122,12 +132,13 @@ pub async fn cmd_op_log(
         let repo_loader = workspace.repo_loader();
         let current_op =
             command.resolve_operation(ui, workspace.repo_loader(), workspace.workspace_name())?;
-        do_op_log(ui, &workspace_env, repo_loader, &current_op, args).await
+        do_op_log(ui, command, &workspace_env, repo_loader, &current_op, args).await
     }
 }
 
 async fn do_op_log(
     ui: &mut Ui,
+    command: &CommandHelper,
     workspace_env: &WorkspaceCommandEnvironment,
     repo_loader: &RepoLoader,
     current_op: &Operation,
@@ -137,6 +148,14 @@ async fn do_op_log(
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
 
+    let json_output = command.is_json_output();
+    if json_output {
+        if args.template.is_some() {
+            return Err(text_only_arg_error("`--template`"));
+        } else if args.op_diff || args.patch {
+            return Err(text_only_arg_error("`--op-diff` and `--patch`"));
+        }
+    }
     let template: TemplateRenderer<Operation>;
     let op_node_template: TemplateRenderer<Operation>;
     {
@@ -145,13 +164,17 @@ async fn do_op_log(
             Some(current_op.id()),
             workspace_env.operation_template_extensions(),
         );
-        let text = match &args.template {
-            Some(value) => value.to_owned(),
-            None => settings.get_string("templates.op_log")?,
+        template = if json_output {
+            parse_json_template(&language, "operation", "self")?
+        } else {
+            let text = match &args.template {
+                Some(value) => value.to_owned(),
+                None => settings.get_string("templates.op_log")?,
+            };
+            workspace_env
+                .parse_template(ui, &language, &text)?
+                .labeled(["op_log", "operation"])
         };
-        template = workspace_env
-            .parse_template(ui, &language, &text)?
-            .labeled(["op_log", "operation"]);
         op_node_template = workspace_env
             .parse_template(
                 ui,
@@ -227,7 +250,7 @@ async fn do_op_log(
     let stream =
         op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
 
-    if !args.no_graph {
+    if !args.no_graph && !json_output {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
         let stream = stream.map_ok(|op| {
@@ -271,7 +294,12 @@ async fn do_op_log(
             stream.boxed_local()
         };
         while let Some(op) = stream.try_next().await? {
-            with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
+            if json_output {
+                template.format(&op, formatter)?;
+            } else {
+                with_content_format
+                    .write(formatter, |formatter| template.format(&op, formatter))?;
+            }
             if let Some(show) = &maybe_show_op_diff {
                 show(ui, formatter, &op, &with_content_format).await?;
             }
@@ -113,5 +113,9 @@ pub(crate) fn view_with_desired_portions_restored(
         git_refs: current_view.git_refs.clone(),
         git_head: current_view.git_head.clone(),
         wc_commit_ids: repo_source.wc_commit_ids.clone(),
+        shelves: repo_source.shelves.clone(),
+        push_bookmarks: repo_source.push_bookmarks.clone(),
+        git_notes: repo_source.git_notes.clone(),
+        git_notes_ref: current_view.git_notes_ref.clone(),
     }
 }
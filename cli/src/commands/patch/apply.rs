@@ -0,0 +1,144 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::patch::Patch;
+use jj_lib::patch::apply_patch;
+use jj_lib::patch::parse_patches;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Create revisions from patch files
+///
+/// The patches can be email messages in the format of `git format-patch`, mbox
+/// files containing a series of such messages, or plain unified diffs. Each
+/// patch becomes a new revision on top of the previous one. The author and the
+/// description are taken from the message headers and body.
+///
+/// If the lines changed by a patch don't match the destination file, the file
+/// is merged with the contents of the patch. This may result in a conflict,
+/// which can be resolved later.
+#[derive(clap::Args, Clone, Debug)]
+pub struct PatchApplyArgs {
+    /// Patch files to apply (default: read from standard input)
+    ///
+    /// Use `-` to read from standard input.
+    #[arg(value_name = "FILES", value_hint = clap::ValueHint::FilePath)]
+    paths: Vec<PathBuf>,
+
+    /// The revision to apply the first patch onto
+    #[arg(long, short, default_value = "@", value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    onto: RevisionArg,
+}
+
+#[instrument(skip_all)]
+pub async fn cmd_patch_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &PatchApplyArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut parent = workspace_command.resolve_single_rev(ui, &args.onto).await?;
+
+    let mut patches = vec![];
+    if args.paths.is_empty() {
+        patches.extend(read_patches(command.cwd(), Path::new("-"))?);
+    }
+    for path in &args.paths {
+        patches.extend(read_patches(command.cwd(), path)?);
+    }
+    if patches.is_empty() {
+        return Err(user_error("No patches found"));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let mut new_commits = vec![];
+    for patch in &patches {
+        let subject = patch.description.lines().next().unwrap_or("");
+        let applied = apply_patch(
+            &parent.tree(),
+            parent.conflict_label(),
+            patch,
+            format!("\"{subject}\""),
+        )
+        .await?;
+        let mut commit_builder = tx
+            .repo_mut()
+            .new_commit(vec![parent.id().clone()], applied.tree)
+            .set_description(&patch.description);
+        let mut author = commit_builder.author().clone();
+        if let Some(name) = &patch.author_name {
+            author.name = name.clone();
+        }
+        if let Some(email) = &patch.author_email {
+            author.email = email.clone();
+        }
+        if let Some(timestamp) = patch.author_timestamp {
+            author.timestamp = timestamp;
+        }
+        commit_builder = commit_builder.set_author(author);
+        let commit = commit_builder.write().await?;
+        for path in &applied.mismatched_paths {
+            writeln!(
+                ui.warning_default(),
+                "Patch \"{subject}\" did not apply cleanly to {}",
+                tx.base_workspace_helper().format_file_path(path)
+            )?;
+        }
+        new_commits.push(commit.clone());
+        parent = commit;
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Applied {} patches:", new_commits.len())?;
+        for commit in &new_commits {
+            write!(formatter, "  ")?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    tx.finish(ui, format!("apply {} patches", new_commits.len()))
+        .await?;
+    Ok(())
+}
+
+fn read_patches(cwd: &Path, path: &Path) -> Result<Vec<Patch>, CommandError> {
+    let (name, content) = if path == Path::new("-") {
+        let mut content = vec![];
+        io::stdin().read_to_end(&mut content)?;
+        ("standard input".to_owned(), content)
+    } else {
+        let content = fs::read(cwd.join(path)).map_err(|err| {
+            user_error_with_message(format!("Failed to read {}", path.display()), err)
+        })?;
+        (path.display().to_string(), content)
+    };
+    parse_patches(&content)
+        .map_err(|err| user_error_with_message(format!("Failed to parse patch {name}"), err))
+}
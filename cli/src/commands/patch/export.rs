@@ -0,0 +1,212 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCompleter;
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::patch::encode_header_value;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error_with_message;
+use crate::complete;
+use crate::diff_util::DiffFormat;
+use crate::diff_util::DiffRenderer;
+use crate::diff_util::LineDiffOptions;
+use crate::diff_util::UnifiedDiffOptions;
+use crate::formatter::PlainTextFormatter;
+use crate::ui::Ui;
+
+/// Write revisions as patch files
+///
+/// Each revision is written to a file named after its description, in the
+/// format of `git format-patch`. The revisions are numbered from the oldest to
+/// the newest. Merge revisions are skipped.
+///
+/// The paths of the written files are printed.
+#[derive(clap::Args, Clone, Debug)]
+pub struct PatchExportArgs {
+    /// The revisions to export
+    #[arg(long, short, default_value = "@", value_name = "REVSETS")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revisions: Vec<RevisionArg>,
+
+    /// Directory to write the patch files to (default: current directory)
+    #[arg(long, short, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    output_directory: Option<PathBuf>,
+
+    /// Write all patches to standard output as a single mbox instead
+    #[arg(long, conflicts_with = "output_directory")]
+    stdout: bool,
+}
+
+#[instrument(skip_all)]
+pub async fn cmd_patch_export(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &PatchExportArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let mut commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()
+        .await?;
+    commits.reverse();
+    let (merges, commits): (Vec<_>, Vec<_>) = commits
+        .into_iter()
+        .partition(|commit| commit.parent_ids().len() > 1);
+    for commit in &merges {
+        writeln!(
+            ui.warning_default(),
+            "Skipping merge revision {}",
+            commit.change_id().reverse_hex()
+        )?;
+    }
+
+    // Patch files are read by other tools, so the user's diff settings don't
+    // apply.
+    let diff_options = UnifiedDiffOptions {
+        context: 3,
+        show_path_prefix: true,
+        line_diff: LineDiffOptions::default(),
+        color_moved: false,
+    };
+    let renderer = workspace_command.diff_renderer(vec![DiffFormat::Git(Box::new(diff_options))]);
+
+    let total = commits.len();
+    let mut stdout = ui.stdout_formatter();
+    for (index, commit) in commits.iter().enumerate() {
+        let number = index + 1;
+        let patch = format_patch(ui, &renderer, commit, number, total).await?;
+        if args.stdout {
+            stdout.write_all(&patch)?;
+            continue;
+        }
+        let file_name = patch_file_name(number, commit.description());
+        let path = match &args.output_directory {
+            Some(dir) => dir.join(file_name),
+            None => PathBuf::from(file_name),
+        };
+        let full_path = command.cwd().join(&path);
+        if let Some(dir) = full_path.parent() {
+            fs::create_dir_all(dir).map_err(|err| {
+                user_error_with_message(format!("Failed to create {}", dir.display()), err)
+            })?;
+        }
+        fs::write(&full_path, &patch).map_err(|err| {
+            user_error_with_message(format!("Failed to write {}", path.display()), err)
+        })?;
+        writeln!(stdout, "{}", path.display())?;
+    }
+    Ok(())
+}
+
+/// Formats the commit as an email message in the `git format-patch` format.
+async fn format_patch(
+    ui: &Ui,
+    renderer: &DiffRenderer<'_>,
+    commit: &Commit,
+    number: usize,
+    total: usize,
+) -> Result<Vec<u8>, CommandError> {
+    let (subject, body) = commit
+        .description()
+        .split_once('\n')
+        .unwrap_or((commit.description(), ""));
+    let body = body.trim();
+    let subject_prefix = if total > 1 {
+        format!("[PATCH {number}/{total}]")
+    } else {
+        "[PATCH]".to_owned()
+    };
+    let author = commit.author();
+
+    let mut output = vec![];
+    // Same fixed date as `git format-patch`, which marks the message as a patch
+    writeln!(
+        output,
+        "From {} Mon Sep 17 00:00:00 2001",
+        commit.id().hex()
+    )?;
+    writeln!(
+        output,
+        "From: {} <{}>",
+        encode_header_value(&author.name),
+        author.email
+    )?;
+    if let Ok(datetime) = author.timestamp.to_datetime() {
+        writeln!(output, "Date: {}", datetime.to_rfc2822())?;
+    }
+    writeln!(
+        output,
+        "Subject: {}",
+        encode_header_value(&format!("{subject_prefix} {subject}"))
+    )?;
+    if !(author.name.is_ascii() && commit.description().is_ascii()) {
+        writeln!(output, "MIME-Version: 1.0")?;
+        writeln!(output, "Content-Type: text/plain; charset=UTF-8")?;
+        writeln!(output, "Content-Transfer-Encoding: 8bit")?;
+    }
+    writeln!(output)?;
+    for line in body.lines() {
+        // Escape lines that would otherwise start a new message in an mbox
+        if line.trim_start_matches('>').starts_with("From ") {
+            write!(output, ">")?;
+        }
+        writeln!(output, "{line}")?;
+    }
+    writeln!(output, "---")?;
+    {
+        let mut formatter = PlainTextFormatter::new(&mut output);
+        renderer
+            .show_patch(
+                ui,
+                &mut formatter,
+                commit,
+                &EverythingMatcher,
+                ui.term_width(),
+            )
+            .await?;
+    }
+    writeln!(output)?;
+    Ok(output)
+}
+
+/// Returns the name of the patch file like `0001-Fix-a-bug.patch`.
+fn patch_file_name(number: usize, description: &str) -> String {
+    const MAX_SLUG_LEN: usize = 52;
+    let subject = description.lines().next().unwrap_or("");
+    let slug = subject
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '_'))
+        .filter(|word| !word.is_empty())
+        .join("-");
+    let mut slug = slug.trim_matches('.').to_owned();
+    slug.truncate(MAX_SLUG_LEN);
+    let slug = slug.trim_end_matches(['-', '.']);
+    if slug.is_empty() {
+        format!("{number:04}.patch")
+    } else {
+        format!("{number:04}-{slug}.patch")
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod apply;
+mod export;
+
+use self::apply::PatchApplyArgs;
+use self::apply::cmd_patch_apply;
+use self::export::PatchExportArgs;
+use self::export::cmd_patch_export;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Exchange changes as patch files
+///
+/// Patches are written in the format of `git format-patch`, which can be sent
+/// by email or applied by `git am`. Patches created by Git or other tools can
+/// be applied as new revisions.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum PatchCommand {
+    Apply(PatchApplyArgs),
+    Export(PatchExportArgs),
+}
+
+pub async fn cmd_patch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &PatchCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        PatchCommand::Apply(args) => cmd_patch_apply(ui, command, args).await,
+        PatchCommand::Export(args) => cmd_patch_export(ui, command, args).await,
+    }
+}
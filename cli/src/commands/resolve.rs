@@ -15,7 +15,20 @@
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflict_resolvers::ConflictResolver;
+use jj_lib::conflict_resolvers::ConflictSide;
+use jj_lib::conflict_resolvers::ResolveStrategy;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::matchers::IntersectionMatcher;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::resolution_cache::ResolutionCache;
+use jj_lib::store::Store;
+use tokio::io::AsyncReadExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -24,8 +37,10 @@ use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::complete;
 use crate::formatter::FormatterExt as _;
+use crate::merge_tools::MergeEditor;
 use crate::ui::Ui;
 
 /// Resolve conflicted files with an external merge tool
@@ -36,6 +51,9 @@ use crate::ui::Ui;
 /// resolved. To stop resolving conflicts, exit the merge tool without making
 /// any changes.
 ///
+/// Conflicts can also be resolved non-interactively with `--take`, `--union`,
+/// or `--resolver`. These apply to all matching conflicted files at once.
+///
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
@@ -66,6 +84,29 @@ pub(crate) struct ResolveArgs {
     #[arg(add = ArgValueCandidates::new(complete::merge_editors))]
     tool: Option<String>,
 
+    /// Resolve conflicts by taking the given side of each conflicting hunk
+    ///
+    /// The side can be specified by its number (starting from 1), or by its
+    /// conflict label. A label matches if it's the whole label or the
+    /// parenthesized part at its end, such as "rebase destination" or "rebased
+    /// revision".
+    #[arg(long, value_name = "SIDE", conflicts_with_all = ["list", "tool"])]
+    take: Option<String>,
+
+    /// Resolve conflicts by taking all sides of each conflicting hunk, one
+    /// after another
+    #[arg(long, conflicts_with_all = ["list", "tool", "take"])]
+    union: bool,
+
+    /// Resolve conflicts in the files matching the patterns of the resolver
+    /// of this name configured in the `conflict-resolvers` table
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = ["list", "tool", "take", "union"]
+    )]
+    resolver: Option<String>,
+
     /// Only resolve conflicts in these paths. You can use the `--list` argument
     /// to find paths to use here.
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
@@ -73,6 +114,14 @@ pub(crate) struct ResolveArgs {
     paths: Vec<String>,
 }
 
+/// How the conflicts are resolved.
+enum ResolveMethod {
+    /// Resolves the conflicts non-interactively.
+    Strategy(ResolveStrategy),
+    /// Resolves the conflicts by running a merge tool.
+    MergeEditor(MergeEditor),
+}
+
 #[instrument(skip_all)]
 pub(crate) async fn cmd_resolve(
     ui: &mut Ui,
@@ -110,10 +159,40 @@ pub(crate) async fn cmd_resolve(
         .map(|(path, _)| path.as_ref())
         .collect_vec();
     workspace_command.check_rewritable([commit.id()]).await?;
-    let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+    let store = workspace_command.repo().store().clone();
+    let resolver = args
+        .resolver
+        .as_deref()
+        .map(|name| get_conflict_resolver(&store, name))
+        .transpose()?;
+    let method = if let Some(side) = &args.take {
+        ResolveMethod::Strategy(ResolveStrategy::TakeSide(ConflictSide::parse(side)))
+    } else if args.union {
+        ResolveMethod::Strategy(ResolveStrategy::Union)
+    } else if let Some(resolver) = resolver {
+        ResolveMethod::Strategy(resolver.strategy().clone())
+    } else {
+        ResolveMethod::MergeEditor(workspace_command.merge_editor(ui, args.tool.as_deref())?)
+    };
     let mut tx = workspace_command.start_transaction();
-    let (new_tree, partial_resolution_error) =
-        merge_editor.edit_files(ui, &tree, &repo_paths).await?;
+    let (new_tree, partial_resolution_error) = match method {
+        ResolveMethod::Strategy(strategy) => {
+            let resolver_matcher = resolver
+                .map_or(&EverythingMatcher as &dyn Matcher, |resolver| {
+                    resolver.matcher()
+                });
+            let matcher = IntersectionMatcher::new(&matcher, resolver_matcher);
+            (strategy.resolve_tree(&tree, &matcher).await?, None)
+        }
+        ResolveMethod::MergeEditor(merge_editor) => {
+            let (new_tree, partial_resolution_error) =
+                merge_editor.edit_files(ui, &tree, &repo_paths).await?;
+            if let Some(cache) = tx.repo().store().resolution_cache() {
+                record_resolutions(cache, &tree, &new_tree, &repo_paths).await?;
+            }
+            (new_tree, partial_resolution_error)
+        }
+    };
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(&commit)
@@ -147,3 +226,45 @@ pub(crate) async fn cmd_resolve(
     }
     Ok(())
 }
+
+fn get_conflict_resolver<'a>(
+    store: &'a Store,
+    name: &str,
+) -> Result<&'a ConflictResolver, CommandError> {
+    store
+        .merge_options()
+        .conflict_resolvers
+        .get(name)
+        .ok_or_else(|| {
+            user_error(format!("No conflict resolver named \"{name}\""))
+                .hinted("Conflict resolvers are configured in the `conflict-resolvers` table.")
+        })
+}
+
+/// Remembers how the conflicts at `repo_paths` in `tree` were resolved in
+/// `new_tree`, so the same conflicts can be resolved automatically later.
+async fn record_resolutions(
+    cache: &ResolutionCache,
+    tree: &MergedTree,
+    new_tree: &MergedTree,
+    repo_paths: &[&RepoPath],
+) -> Result<(), CommandError> {
+    let store = tree.store();
+    for &repo_path in repo_paths {
+        let Ok(Some(TreeValue::File { id, .. })) =
+            new_tree.path_value(repo_path).await?.into_resolved()
+        else {
+            continue;
+        };
+        let Some(conflict) = tree.path_value(repo_path).await?.to_file_merge() else {
+            continue;
+        };
+        let mut content = vec![];
+        let mut reader = store.read_file(repo_path, &id).await?;
+        reader.read_to_end(&mut content).await?;
+        cache
+            .record_file_resolution(store, repo_path, &conflict, &content)
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,434 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Review providers for forges with REST APIs, such as GitHub and GitLab.
+
+use std::env;
+
+use jj_lib::settings::UserSettings;
+use serde_json::Value;
+use serde_json::json;
+
+use super::provider::PushMode;
+use super::provider::ReviewChange;
+use super::provider::ReviewError;
+use super::provider::ReviewProvider;
+use super::provider::ReviewRequest;
+use crate::command_error::CommandError;
+use crate::http_util::HttpProgram;
+use crate::http_util::HttpRequest;
+
+/// Sends JSON requests to the REST API of a forge.
+pub trait HttpClient {
+    fn send(&self, method: &str, url: &str, body: &Value) -> Result<Value, ReviewError>;
+}
+
+/// Sends JSON requests by running the program configured by
+/// `review.http-program`.
+pub struct RestClient {
+    program: HttpProgram,
+    token: Option<String>,
+}
+
+impl RestClient {
+    pub fn new(program: HttpProgram, token: Option<String>) -> Self {
+        Self { program, token }
+    }
+
+    /// Creates a client which authenticates with the token stored in the
+    /// environment variable named by the `review.<provider>.token-env`
+    /// setting.
+    fn from_settings(settings: &UserSettings, provider: &str) -> Result<Self, CommandError> {
+        let token_env = settings.get_string(["review", provider, "token-env"])?;
+        Ok(Self::new(
+            HttpProgram::from_settings(settings)?,
+            env::var(token_env).ok(),
+        ))
+    }
+}
+
+impl HttpClient for RestClient {
+    fn send(&self, method: &str, url: &str, body: &Value) -> Result<Value, ReviewError> {
+        let body = body.to_string();
+        let mut request = HttpRequest::new(method, url);
+        request.headers.push("Accept: application/json".to_owned());
+        request
+            .headers
+            .push("Content-Type: application/json".to_owned());
+        if let Some(token) = &self.token {
+            request
+                .headers
+                .push(format!("Authorization: Bearer {token}"));
+        }
+        request.body = Some(body.as_bytes());
+        let response = self.program.send(&request)?;
+        serde_json::from_slice(&response).map_err(|err| ReviewError::InvalidResponse {
+            url: url.to_owned(),
+            message: err.to_string(),
+        })
+    }
+}
+
+/// Extracts a string or number field from a JSON response.
+fn response_field(response: &Value, url: &str, name: &str) -> Result<String, ReviewError> {
+    match response.get(name) {
+        Some(Value::String(value)) => Ok(value.clone()),
+        Some(Value::Number(value)) => Ok(value.to_string()),
+        _ => Err(ReviewError::InvalidResponse {
+            url: url.to_owned(),
+            message: format!("missing field `{name}`"),
+        }),
+    }
+}
+
+/// Publishes changes as GitHub pull requests.
+pub struct GitHubProvider {
+    client: Box<dyn HttpClient>,
+    api_url: String,
+    repository: String,
+}
+
+impl GitHubProvider {
+    pub fn new(client: Box<dyn HttpClient>, api_url: &str, repository: &str) -> Self {
+        Self {
+            client,
+            api_url: api_url.trim_end_matches('/').to_owned(),
+            repository: repository.to_owned(),
+        }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, CommandError> {
+        Ok(Self::new(
+            Box::new(RestClient::from_settings(settings, "github")?),
+            &settings.get_string("review.github.api-url")?,
+            &settings.get_string("review.github.repository")?,
+        ))
+    }
+}
+
+impl ReviewProvider for GitHubProvider {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn repository(&self) -> String {
+        self.repository.clone()
+    }
+
+    fn request_kind(&self) -> &'static str {
+        "pull request"
+    }
+
+    fn push_mode(&self) -> PushMode {
+        PushMode::Bookmarks
+    }
+
+    fn publish(
+        &self,
+        change: &ReviewChange,
+        existing: Option<&ReviewRequest>,
+    ) -> Result<ReviewRequest, ReviewError> {
+        let pulls_url = format!("{}/repos/{}/pulls", self.api_url, self.repository);
+        let (url, response) = if let Some(existing) = existing {
+            let url = format!("{pulls_url}/{}", existing.id);
+            let body = json!({ "base": change.base });
+            let response = self.client.send("PATCH", &url, &body)?;
+            (url, response)
+        } else {
+            let body = json!({
+                "title": change.title(),
+                "body": change.body(),
+                "head": change.bookmark,
+                "base": change.base,
+            });
+            let response = self.client.send("POST", &pulls_url, &body)?;
+            (pulls_url, response)
+        };
+        Ok(ReviewRequest {
+            id: response_field(&response, &url, "number")?,
+            url: response_field(&response, &url, "html_url")?,
+        })
+    }
+}
+
+/// Publishes changes as GitLab merge requests.
+pub struct GitLabProvider {
+    client: Box<dyn HttpClient>,
+    api_url: String,
+    project: String,
+}
+
+impl GitLabProvider {
+    pub fn new(client: Box<dyn HttpClient>, api_url: &str, project: &str) -> Self {
+        Self {
+            client,
+            api_url: api_url.trim_end_matches('/').to_owned(),
+            // The project path has to be URL-encoded to be used as an ID.
+            project: project.replace('/', "%2F"),
+        }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, CommandError> {
+        Ok(Self::new(
+            Box::new(RestClient::from_settings(settings, "gitlab")?),
+            &settings.get_string("review.gitlab.api-url")?,
+            &settings.get_string("review.gitlab.project")?,
+        ))
+    }
+}
+
+impl ReviewProvider for GitLabProvider {
+    fn name(&self) -> &'static str {
+        "gitlab"
+    }
+
+    fn repository(&self) -> String {
+        self.project.replace("%2F", "/")
+    }
+
+    fn request_kind(&self) -> &'static str {
+        "merge request"
+    }
+
+    fn push_mode(&self) -> PushMode {
+        PushMode::Bookmarks
+    }
+
+    fn publish(
+        &self,
+        change: &ReviewChange,
+        existing: Option<&ReviewRequest>,
+    ) -> Result<ReviewRequest, ReviewError> {
+        let requests_url = format!("{}/projects/{}/merge_requests", self.api_url, self.project);
+        let (url, response) = if let Some(existing) = existing {
+            let url = format!("{requests_url}/{}", existing.id);
+            let body = json!({ "target_branch": change.base });
+            let response = self.client.send("PUT", &url, &body)?;
+            (url, response)
+        } else {
+            let body = json!({
+                "title": change.title(),
+                "description": change.body(),
+                "source_branch": change.bookmark,
+                "target_branch": change.base,
+            });
+            let response = self.client.send("POST", &requests_url, &body)?;
+            (requests_url, response)
+        };
+        Ok(ReviewRequest {
+            id: response_field(&response, &url, "iid")?,
+            url: response_field(&response, &url, "web_url")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use assert_matches::assert_matches;
+    use jj_lib::backend::ChangeId;
+    use testutils::ensure_running_outside_ci;
+    use testutils::http::serve_json;
+    use testutils::is_external_tool_installed;
+
+    use super::*;
+    use crate::http_util::HttpError;
+
+    type SentRequests = Rc<RefCell<Vec<(String, String, Value)>>>;
+
+    /// Records the requests and replies with canned responses.
+    struct MockClient {
+        requests: SentRequests,
+        responses: RefCell<VecDeque<Value>>,
+    }
+
+    impl MockClient {
+        fn new(responses: impl IntoIterator<Item = Value>) -> (Box<Self>, SentRequests) {
+            let requests = SentRequests::default();
+            let client = Self {
+                requests: requests.clone(),
+                responses: RefCell::new(responses.into_iter().collect()),
+            };
+            (Box::new(client), requests)
+        }
+    }
+
+    impl HttpClient for MockClient {
+        fn send(&self, method: &str, url: &str, body: &Value) -> Result<Value, ReviewError> {
+            self.requests
+                .borrow_mut()
+                .push((method.to_owned(), url.to_owned(), body.clone()));
+            Ok(self.responses.borrow_mut().pop_front().unwrap())
+        }
+    }
+
+    fn review_change(bookmark: &str, base: &str) -> ReviewChange {
+        ReviewChange {
+            change_id: ChangeId::from_hex("0123"),
+            description: "Add feature\n\nDetails\n".to_owned(),
+            bookmark: Some(bookmark.to_owned()),
+            base: base.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_github_create_and_update() {
+        let (client, requests) = MockClient::new([
+            json!({ "number": 12, "html_url": "https://github.com/o/r/pull/12" }),
+            json!({ "number": 12, "html_url": "https://github.com/o/r/pull/12" }),
+        ]);
+        let provider = GitHubProvider::new(client, "https://api.github.com/", "o/r");
+
+        let change = review_change("push-a", "main");
+        let request = provider.publish(&change, None).unwrap();
+        assert_eq!(
+            request,
+            ReviewRequest {
+                id: "12".to_owned(),
+                url: "https://github.com/o/r/pull/12".to_owned(),
+            }
+        );
+        let change = review_change("push-a", "push-b");
+        provider.publish(&change, Some(&request)).unwrap();
+
+        assert_eq!(
+            *requests.borrow(),
+            [
+                (
+                    "POST".to_owned(),
+                    "https://api.github.com/repos/o/r/pulls".to_owned(),
+                    json!({
+                        "title": "Add feature",
+                        "body": "Details",
+                        "head": "push-a",
+                        "base": "main",
+                    }),
+                ),
+                (
+                    "PATCH".to_owned(),
+                    "https://api.github.com/repos/o/r/pulls/12".to_owned(),
+                    json!({ "base": "push-b" }),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gitlab_create_and_update() {
+        let (client, requests) = MockClient::new([
+            json!({ "iid": 3, "web_url": "https://gitlab.com/g/p/-/merge_requests/3" }),
+            json!({ "iid": 3, "web_url": "https://gitlab.com/g/p/-/merge_requests/3" }),
+        ]);
+        let provider = GitLabProvider::new(client, "https://gitlab.com/api/v4", "g/p");
+
+        let change = review_change("push-a", "main");
+        let request = provider.publish(&change, None).unwrap();
+        assert_eq!(request.id, "3");
+        assert_eq!(request.url, "https://gitlab.com/g/p/-/merge_requests/3");
+        let change = review_change("push-a", "push-b");
+        provider.publish(&change, Some(&request)).unwrap();
+
+        assert_eq!(
+            *requests.borrow(),
+            [
+                (
+                    "POST".to_owned(),
+                    "https://gitlab.com/api/v4/projects/g%2Fp/merge_requests".to_owned(),
+                    json!({
+                        "title": "Add feature",
+                        "description": "Details",
+                        "source_branch": "push-a",
+                        "target_branch": "main",
+                    }),
+                ),
+                (
+                    "PUT".to_owned(),
+                    "https://gitlab.com/api/v4/projects/g%2Fp/merge_requests/3".to_owned(),
+                    json!({ "target_branch": "push-b" }),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        let (client, _requests) = MockClient::new([json!({ "number": 12 })]);
+        let provider = GitHubProvider::new(client, "https://api.github.com", "o/r");
+        let result = provider.publish(&review_change("push-a", "main"), None);
+        assert_matches!(
+            result,
+            Err(ReviewError::InvalidResponse { message, .. }) if message == "missing field `html_url`"
+        );
+    }
+
+    #[test]
+    fn test_rest_client_sends_request() {
+        if !is_external_tool_installed("curl") {
+            ensure_running_outside_ci("`curl` must be in the PATH");
+            eprintln!("Skipping test because curl is not installed on the system");
+            return;
+        }
+        let (url, handle) = serve_json([("201 Created", json!({ "number": 1 }).to_string())]);
+        let client = RestClient::new(
+            HttpProgram::new("curl"),
+            Some("secret \"token\"".to_owned()),
+        );
+        let response = client
+            .send(
+                "POST",
+                &format!("{url}/pulls"),
+                &json!({ "title": "a\\b\n" }),
+            )
+            .unwrap();
+        assert_eq!(response, json!({ "number": 1 }));
+
+        let [request] = handle.join().unwrap().try_into().unwrap();
+        assert_eq!(request.request_line, "POST /pulls HTTP/1.1");
+        let headers = &request.headers;
+        assert!(headers.contains(&r#"Authorization: Bearer secret "token""#.to_owned()));
+        assert!(headers.contains(&"Content-Type: application/json".to_owned()));
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body, json!({ "title": "a\\b\n" }));
+    }
+
+    #[test]
+    fn test_rest_client_reports_error() {
+        if !is_external_tool_installed("curl") {
+            ensure_running_outside_ci("`curl` must be in the PATH");
+            eprintln!("Skipping test because curl is not installed on the system");
+            return;
+        }
+        let (url, handle) = serve_json([(
+            "422 Unprocessable Entity",
+            json!({ "message": "Validation Failed" }).to_string(),
+        )]);
+        let client = RestClient::new(HttpProgram::new("curl"), None);
+        let result = client.send("PATCH", &format!("{url}/pulls/1"), &json!({}));
+        let [request] = handle.join().unwrap().try_into().unwrap();
+        assert!(
+            !request
+                .headers
+                .iter()
+                .any(|header| header.starts_with("Authorization"))
+        );
+        assert_matches!(
+            result,
+            Err(ReviewError::Http(HttpError::Request { message, .. })) if message.contains("422")
+                && message.ends_with("(Validation Failed)")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::settings::UserSettings;
+use jj_lib::trailer;
+
+use super::provider::PushMode;
+use super::provider::ReviewChange;
+use super::provider::ReviewError;
+use super::provider::ReviewProvider;
+use super::provider::ReviewRequest;
+use crate::commands::gerrit::gerrit_change_id;
+
+/// Publishes changes to Gerrit by uploading them like `jj gerrit upload`.
+///
+/// Gerrit creates or updates the review requests when the changes are
+/// uploaded, so publishing only looks up the `Change-Id` of each change.
+pub struct GerritProvider {
+    review_url: Option<String>,
+}
+
+impl GerritProvider {
+    pub fn new(review_url: Option<String>) -> Self {
+        Self { review_url }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Self {
+        Self::new(settings.get_string("gerrit.review-url").ok())
+    }
+}
+
+impl ReviewProvider for GerritProvider {
+    fn name(&self) -> &'static str {
+        "gerrit"
+    }
+
+    fn repository(&self) -> String {
+        self.review_url.clone().unwrap_or_default()
+    }
+
+    fn request_kind(&self) -> &'static str {
+        "change"
+    }
+
+    fn push_mode(&self) -> PushMode {
+        PushMode::Gerrit
+    }
+
+    fn publish(
+        &self,
+        change: &ReviewChange,
+        _existing: Option<&ReviewRequest>,
+    ) -> Result<ReviewRequest, ReviewError> {
+        // Same derivation as `jj gerrit upload` if there's no explicit trailer.
+        let id = gerrit_change_id(&change.description)
+            .unwrap_or_else(|| trailer::gerrit_change_id(&change.change_id));
+        let url = self.review_url.as_ref().map_or_else(String::new, |url| {
+            format!("{}/id/{id}", url.trim_end_matches('/'))
+        });
+        Ok(ReviewRequest { id, url })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jj_lib::backend::ChangeId;
+
+    use super::*;
+
+    #[test]
+    fn test_gerrit_publish() {
+        let change = |description: &str| ReviewChange {
+            change_id: ChangeId::from_hex("0123456789abcdef0123456789abcdef"),
+            description: description.to_owned(),
+            bookmark: None,
+            base: "main".to_owned(),
+        };
+        let provider = GerritProvider::new(None);
+        assert_eq!(
+            provider.publish(&change("Fix bug\n"), None).unwrap(),
+            ReviewRequest {
+                id: "I0123456789abcdef0123456789abcdef6a6a6964".to_owned(),
+                url: "".to_owned(),
+            }
+        );
+
+        let provider = GerritProvider::new(Some("https://review.example.com/".to_owned()));
+        let explicit_id = "Ifedcba9876543210fedcba9876543210fedcba98";
+        assert_eq!(
+            provider
+                .publish(
+                    &change(&format!("Fix bug\n\nChange-Id: {explicit_id}\n")),
+                    None
+                )
+                .unwrap(),
+            ReviewRequest {
+                id: explicit_id.to_owned(),
+                url: format!("https://review.example.com/id/{explicit_id}"),
+            }
+        );
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod forge;
+mod gerrit;
+mod provider;
+mod push;
+
+use clap::Subcommand;
+
+use self::push::ReviewPushArgs;
+use self::push::cmd_review_push;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Publish stacks of changes for code review
+///
+/// Each change in a stack is published as its own review request, such as a
+/// GitHub pull request, a GitLab merge request, or a Gerrit change. The review
+/// system is selected by the `review.provider` setting.
+#[derive(Subcommand, Clone, Debug)]
+pub enum ReviewCommand {
+    Push(ReviewPushArgs),
+}
+
+pub async fn cmd_review(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &ReviewCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        ReviewCommand::Push(args) => cmd_review_push(ui, command, args).await,
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::backend::ChangeId;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::file_util::persist_temp_file;
+use jj_lib::lock::FileLock;
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::command_error::CommandError;
+use crate::command_error::user_error_with_message;
+use crate::http_util::HttpError;
+
+/// How the changes of a stack are sent to the remote.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PushMode {
+    /// Each change is pushed to its own bookmark, and the review requests are
+    /// then created from the bookmarks.
+    Bookmarks,
+    /// The stack is uploaded to Gerrit's `refs/for/<base>` ref, which creates
+    /// the review requests.
+    Gerrit,
+}
+
+/// A change to be published for review.
+#[derive(Clone, Debug)]
+pub struct ReviewChange {
+    pub change_id: ChangeId,
+    pub description: String,
+    /// The bookmark the change was pushed to, if pushed to its own bookmark.
+    pub bookmark: Option<String>,
+    /// The branch the change should be merged into. For a change stacked on
+    /// top of another change, this is the bookmark of the parent change.
+    pub base: String,
+}
+
+impl ReviewChange {
+    /// Returns the first line of the description.
+    pub fn title(&self) -> &str {
+        self.description.lines().next().unwrap_or_default()
+    }
+
+    /// Returns the description without the first line.
+    pub fn body(&self) -> &str {
+        self.description
+            .split_once('\n')
+            .map_or("", |(_, body)| body.trim())
+    }
+}
+
+/// A review request created for a change, such as a pull request.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ReviewRequest {
+    /// Identifier of the request in the review system.
+    pub id: String,
+    /// Web URL of the request, if known.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+}
+
+impl ReviewRequest {
+    /// Returns the URL of the request, or its identifier if the URL is
+    /// unknown.
+    pub fn display(&self) -> &str {
+        if self.url.is_empty() {
+            &self.id
+        } else {
+            &self.url
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReviewError {
+    #[error(transparent)]
+    Http(#[from] HttpError),
+    #[error("Unexpected response from {url}: {message}")]
+    InvalidResponse { url: String, message: String },
+}
+
+/// A code review system which stacks of changes can be published to.
+pub trait ReviewProvider {
+    /// Name of the provider, which is also the key of the recorded review
+    /// requests.
+    fn name(&self) -> &'static str;
+
+    /// Identifies the repository the review requests are created in. The
+    /// recorded review requests are keyed by this as well, so requests in
+    /// different repositories don't get mixed up.
+    fn repository(&self) -> String;
+
+    /// What a review request is called in this review system.
+    fn request_kind(&self) -> &'static str;
+
+    /// How the changes are sent to the remote.
+    fn push_mode(&self) -> PushMode;
+
+    /// Creates the review request for a change which has been pushed, or
+    /// updates the `existing` request so it targets the new base.
+    fn publish(
+        &self,
+        change: &ReviewChange,
+        existing: Option<&ReviewRequest>,
+    ) -> Result<ReviewRequest, ReviewError>;
+}
+
+/// Review requests published for changes, keyed by provider name, repository,
+/// and change ID.
+///
+/// This is stored in the repo directory so that publishing a stack again
+/// updates the existing requests instead of creating new ones.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ReviewState {
+    providers: BTreeMap<String, BTreeMap<String, BTreeMap<String, ReviewRequest>>>,
+}
+
+impl ReviewState {
+    pub fn path(repo_path: &Path) -> PathBuf {
+        repo_path.join("review").join("state.json")
+    }
+
+    /// Locks the review state of the repo. The lock should be held from
+    /// loading the state until the last change is saved so that concurrent
+    /// commands don't lose each other's requests.
+    pub fn lock(repo_path: &Path) -> Result<FileLock, CommandError> {
+        let dir = repo_path.join("review");
+        fs::create_dir_all(&dir).context(&dir)?;
+        FileLock::lock(dir.join("state.lock"))
+            .map_err(|err| user_error_with_message("Failed to take lock for review state", err))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CommandError> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err).context(path)?,
+        };
+        serde_json::from_slice(&content).map_err(|err| {
+            user_error_with_message(
+                format!("Failed to parse review state {}", path.display()),
+                err,
+            )
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CommandError> {
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir).context(dir)?;
+        let mut temp_file = NamedTempFile::new_in(dir).context(dir)?;
+        let content = serde_json::to_vec_pretty(self).unwrap();
+        temp_file.write_all(&content).context(temp_file.path())?;
+        persist_temp_file(temp_file, path).context(path)?;
+        Ok(())
+    }
+
+    pub fn get(
+        &self,
+        provider: &str,
+        repository: &str,
+        change_id: &ChangeId,
+    ) -> Option<&ReviewRequest> {
+        self.providers
+            .get(provider)?
+            .get(repository)?
+            .get(&change_id.reverse_hex())
+    }
+
+    pub fn insert(
+        &mut self,
+        provider: &str,
+        repository: &str,
+        change_id: &ChangeId,
+        request: ReviewRequest,
+    ) {
+        self.providers
+            .entry(provider.to_owned())
+            .or_default()
+            .entry(repository.to_owned())
+            .or_default()
+            .insert(change_id.reverse_hex(), request);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_change_title_and_body() {
+        let change = |description: &str| ReviewChange {
+            change_id: ChangeId::from_hex("0123"),
+            description: description.to_owned(),
+            bookmark: None,
+            base: "main".to_owned(),
+        };
+        assert_eq!(change("").title(), "");
+        assert_eq!(change("").body(), "");
+        assert_eq!(change("title\n").title(), "title");
+        assert_eq!(change("title\n").body(), "");
+        assert_eq!(change("title\n\nbody\nmore\n").body(), "body\nmore");
+    }
+
+    #[test]
+    fn test_review_state_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = ReviewState::path(temp_dir.path());
+        let change_id = ChangeId::from_hex("0123");
+        let request = ReviewRequest {
+            id: "12".to_owned(),
+            url: "https://example.com/12".to_owned(),
+        };
+
+        let _lock = ReviewState::lock(temp_dir.path()).unwrap();
+        let mut state = ReviewState::load(&path).unwrap();
+        assert_eq!(state.get("github", "owner/repo", &change_id), None);
+        state.insert("github", "owner/repo", &change_id, request.clone());
+        state.save(&path).unwrap();
+
+        let state = ReviewState::load(&path).unwrap();
+        assert_eq!(
+            state.get("github", "owner/repo", &change_id),
+            Some(&request)
+        );
+        assert_eq!(state.get("github", "owner/other", &change_id), None);
+        assert_eq!(state.get("gitlab", "owner/repo", &change_id), None);
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use serde::Deserialize;
+
+use super::forge::GitHubProvider;
+use super::forge::GitLabProvider;
+use super::gerrit::GerritProvider;
+use super::provider::PushMode;
+use super::provider::ReviewChange;
+use super::provider::ReviewProvider;
+use super::provider::ReviewState;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::short_change_hash;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::commands::gerrit::upload::UploadArgs;
+use crate::commands::gerrit::upload::cmd_gerrit_upload;
+use crate::commands::git::push::GitPushArgs;
+use crate::commands::git::push::cmd_git_push;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Push a stack of changes and create or update their review requests
+///
+/// The changes between the immutable heads and the given revisions must form
+/// a linear stack.
+///
+/// With GitHub and GitLab, each change is pushed to its own bookmark like `jj
+/// git push --change` does. The request of the bottom change targets the base
+/// branch, and the request of every other change targets the bookmark of its
+/// parent, so the requests are chained in the same order as the changes. With
+/// Gerrit, the stack is uploaded like `jj gerrit upload` does.
+///
+/// The requests created for the changes are recorded in the repo. Pushing the
+/// stack again after rewriting it updates the existing requests instead of
+/// creating new ones.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ReviewPushArgs {
+    /// The revisions at the top of the stack to publish
+    ///
+    /// If this is not provided, @ is published if it has a description, and @-
+    /// otherwise.
+    #[arg(long = "revision", short, value_name = "REVSETS", alias = "revisions")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
+    revisions: Vec<RevisionArg>,
+
+    /// The review system to publish to
+    ///
+    /// This defaults to the `review.provider` setting.
+    #[arg(long, value_enum)]
+    provider: Option<ReviewProviderKind>,
+
+    /// The remote to push to
+    ///
+    /// This defaults to the remote used by `jj git push`, or by `jj gerrit
+    /// upload` for Gerrit.
+    #[arg(long)]
+    remote: Option<String>,
+
+    /// The branch the stack is intended to land on
+    ///
+    /// This defaults to the `review.base-branch` setting.
+    #[arg(long, short)]
+    base: Option<String>,
+
+    /// Only display what would be pushed and published
+    #[arg(long, short = 'n')]
+    dry_run: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ReviewProviderKind {
+    /// GitHub pull requests
+    Github,
+    /// GitLab merge requests
+    Gitlab,
+    /// Gerrit changes
+    Gerrit,
+}
+
+pub async fn cmd_review_push(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ReviewPushArgs,
+) -> Result<(), CommandError> {
+    let settings = command.settings();
+    let provider_kind = match args.provider {
+        Some(kind) => kind,
+        None => settings.get("review.provider").optional()?.ok_or_else(|| {
+            user_error(
+                "No review provider specified via --provider, and no 'review.provider' was found",
+            )
+        })?,
+    };
+    let provider: Box<dyn ReviewProvider> = match provider_kind {
+        ReviewProviderKind::Github => Box::new(GitHubProvider::from_settings(settings)?),
+        ReviewProviderKind::Gitlab => Box::new(GitLabProvider::from_settings(settings)?),
+        ReviewProviderKind::Gerrit => Box::new(GerritProvider::from_settings(settings)),
+    };
+    let base = match &args.base {
+        Some(base) => base.clone(),
+        None => settings
+            .get_string("review.base-branch")
+            .optional()?
+            .ok_or_else(|| {
+                user_error(
+                    "No base branch specified via --base, and no 'review.base-branch' was found",
+                )
+            })?,
+    };
+
+    let workspace_command = command.workspace_helper(ui)?;
+    let heads = resolve_heads(ui, &workspace_command, &args.revisions).await?;
+    let stack = find_stack(&workspace_command, heads).await?;
+    if stack.is_empty() {
+        writeln!(ui.status(), "No revisions to publish.")?;
+        return Ok(());
+    }
+
+    let bookmarks: Vec<Option<String>> = match provider.push_mode() {
+        PushMode::Bookmarks => {
+            let template_text = settings.get_string("templates.git_push_bookmark")?;
+            let template = workspace_command.parse_commit_template(ui, &template_text)?;
+            stack
+                .iter()
+                .map(|commit| {
+                    let output = template.format_plain_text(commit);
+                    let name = String::from_utf8(output).map_err(|err| {
+                        user_error_with_message(
+                            "Invalid character in bookmark name",
+                            err.utf8_error(),
+                        )
+                    })?;
+                    if name.is_empty() {
+                        return Err(user_error("Empty bookmark name generated"));
+                    }
+                    Ok(Some(name))
+                })
+                .try_collect()?
+        }
+        PushMode::Gerrit => vec![None; stack.len()],
+    };
+    let mut changes: Vec<ReviewChange> = vec![];
+    for (commit, bookmark) in stack.iter().zip(bookmarks) {
+        // Chain each request onto the bookmark of the parent change.
+        let base = changes
+            .last()
+            .and_then(|parent| parent.bookmark.clone())
+            .unwrap_or_else(|| base.clone());
+        changes.push(ReviewChange {
+            change_id: commit.change_id().clone(),
+            description: commit.description().to_owned(),
+            bookmark,
+            base,
+        });
+    }
+
+    let _state_lock = ReviewState::lock(workspace_command.repo_path())?;
+    let state_path = ReviewState::path(workspace_command.repo_path());
+    let mut state = ReviewState::load(&state_path)?;
+    let repository = provider.repository();
+    let revisions: Vec<RevisionArg> = stack
+        .iter()
+        .map(|commit| RevisionArg::from(commit.id().hex()))
+        .collect();
+    // The push commands load the workspace again.
+    drop(workspace_command);
+    match provider.push_mode() {
+        PushMode::Bookmarks => {
+            let push_args = GitPushArgs::for_changes(
+                args.remote.clone().map(Into::into),
+                revisions,
+                args.dry_run,
+            );
+            cmd_git_push(ui, command, &push_args).await?;
+        }
+        PushMode::Gerrit => {
+            // Uploading the top of the stack uploads the whole stack.
+            let upload_args = UploadArgs::for_revisions(
+                revisions.last().cloned().into_iter().collect(),
+                args.remote.clone(),
+                Some(base.clone()),
+                args.dry_run,
+            );
+            cmd_gerrit_upload(ui, command, &upload_args).await?;
+        }
+    }
+
+    let request_kind = provider.request_kind();
+    for change in &changes {
+        let existing = state.get(provider.name(), &repository, &change.change_id);
+        let change_hash = short_change_hash(&change.change_id);
+        if args.dry_run {
+            let action = if existing.is_some() {
+                "update"
+            } else {
+                "create"
+            };
+            writeln!(
+                ui.status(),
+                "Dry-run: Would {action} {request_kind} for {change_hash} targeting {base}",
+                base = change.base,
+            )?;
+            continue;
+        }
+        let action = if existing.is_some() {
+            "Updated"
+        } else {
+            "Created"
+        };
+        let request = provider.publish(change, existing).map_err(|err| {
+            user_error_with_message(
+                format!("Failed to publish {request_kind} for {change_hash}"),
+                err,
+            )
+        })?;
+        writeln!(
+            ui.status(),
+            "{action} {request_kind} {request} for {change_hash} targeting {base}",
+            request = request.display(),
+            base = change.base,
+        )?;
+        state.insert(provider.name(), &repository, &change.change_id, request);
+        // Save the state after each request so that the created requests are
+        // remembered even if publishing a later change fails.
+        state.save(&state_path)?;
+    }
+    Ok(())
+}
+
+/// Returns the revisions at the top of the stack, which are `@` or `@-` by
+/// default.
+async fn resolve_heads(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    revisions: &[RevisionArg],
+) -> Result<Vec<Commit>, CommandError> {
+    if !revisions.is_empty() {
+        return workspace_command
+            .parse_union_revsets(ui, revisions)?
+            .evaluate_to_commits()?
+            .try_collect()
+            .await
+            .map_err(Into::into);
+    }
+    let Some(wc_commit_id) = workspace_command.get_wc_commit_id() else {
+        return Err(user_error("No revision provided")
+            .hinted("Explicitly specify a revision to publish with `-r`"));
+    };
+    let wc_commit = workspace_command.repo().store().get_commit(wc_commit_id)?;
+    if wc_commit.description().is_empty() {
+        wc_commit.parents().await.map_err(Into::into)
+    } else {
+        Ok(vec![wc_commit])
+    }
+}
+
+/// Returns the mutable ancestors of the heads ordered from the bottom, after
+/// checking that they form a linear stack which can be published.
+async fn find_stack(
+    workspace_command: &WorkspaceCommandHelper,
+    heads: Vec<Commit>,
+) -> Result<Vec<Commit>, CommandError> {
+    let head_ids = heads.iter().map(|commit| commit.id().clone()).collect();
+    let mut stack: Vec<Commit> = workspace_command
+        .attach_revset_evaluator(
+            workspace_command
+                .env()
+                .immutable_expression()
+                .range(&RevsetExpression::commits(head_ids)),
+        )
+        .evaluate_to_commits()?
+        .try_collect()
+        .await?;
+    stack.reverse();
+
+    for (i, commit) in stack.iter().enumerate() {
+        let change_hash = short_change_hash(commit.change_id());
+        if commit.parent_ids().len() != 1 {
+            return Err(user_error(format!(
+                "Refusing to publish revision {change_hash} because it is a merge commit"
+            )));
+        }
+        if i > 0 && commit.parent_ids()[0] != *stack[i - 1].id() {
+            return Err(
+                user_error("The revisions to publish do not form a linear stack").hinted(
+                    "Publish each stack separately, or rebase the revisions onto each other",
+                ),
+            );
+        }
+        if commit.description().is_empty() {
+            return Err(user_error(format!(
+                "Refusing to publish revision {change_hash} because it has no description"
+            )));
+        }
+    }
+    Ok(stack)
+}
@@ -0,0 +1,87 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::shelf;
+use tracing::instrument;
+
+use super::resolve_shelf;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Apply the changes in a shelf to a revision
+///
+/// The shelved changes are merged into the destination revision. If the
+/// changes can't be merged cleanly, the conflicts are recorded in the
+/// destination revision. The shelf is kept unless `--drop` is specified.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ShelveApplyArgs {
+    /// Name of the shelf to apply
+    name: String,
+
+    /// Revision to apply the changes to
+    #[arg(
+        long,
+        short,
+        visible_alias = "to",
+        default_value = "@",
+        value_name = "REVSET"
+    )]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
+    into: RevisionArg,
+
+    /// Delete the shelf after applying it
+    #[arg(long)]
+    drop: bool,
+}
+
+#[instrument(skip_all)]
+pub async fn cmd_shelve_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ShelveApplyArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let shelf_commit = resolve_shelf(&workspace_command, &args.name).await?;
+    let destination = workspace_command.resolve_single_rev(ui, &args.into).await?;
+    workspace_command
+        .check_rewritable([destination.id()])
+        .await?;
+
+    let mut tx = workspace_command.start_transaction();
+    let new_commit = shelf::apply_shelf(tx.repo_mut(), &shelf_commit, &destination).await?;
+    tx.repo_mut().rebase_descendants().await?;
+    if args.drop {
+        tx.repo_mut().remove_shelf(&args.name);
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Applied shelf {} to ", args.name)?;
+        tx.write_commit_summary(formatter.as_mut(), &new_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "apply shelf {} to commit {}",
+            args.name,
+            destination.id().hex()
+        ),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+/// Delete shelves
+///
+/// The shelved changes will be lost, but they can still be recovered by
+/// restoring an earlier operation.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ShelveDropArgs {
+    /// Names of the shelves to delete
+    #[arg(required = true)]
+    names: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub async fn cmd_shelve_drop(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ShelveDropArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let view = workspace_command.repo().view();
+    if let Some(name) = args
+        .names
+        .iter()
+        .find(|name| view.get_shelf(name).is_none())
+    {
+        return Err(user_error(format!("No such shelf: {name}")));
+    }
+    let names = args.names.iter().unique().collect_vec();
+
+    let mut tx = workspace_command.start_transaction();
+    for name in &names {
+        tx.repo_mut().remove_shelf(name);
+    }
+    writeln!(ui.status(), "Dropped {} shelves.", names.len())?;
+    tx.finish(ui, format!("drop shelf {}", names.iter().join(", ")))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::git;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Import Git stash entries as shelves
+///
+/// Each entry of the Git stash is imported as a shelf named `stash@{N}`,
+/// replacing the existing shelf of the same name. Only the working-tree
+/// changes are imported. The staged and untracked parts of the stash entries
+/// are ignored.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ShelveImportGitStashArgs {}
+
+#[instrument(skip_all)]
+pub async fn cmd_shelve_import_git_stash(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &ShelveImportGitStashArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut tx = workspace_command.start_transaction();
+    let names = git::import_git_stashes(tx.repo_mut()).await?;
+    if names.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    writeln!(
+        ui.status(),
+        "Imported {} shelves from Git stash.",
+        names.len()
+    )?;
+    tx.finish(
+        ui,
+        format!("import git stash as {}", names.iter().join(", ")),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::formatter::FormatterExt as _;
+use crate::ui::Ui;
+
+/// List shelves
+#[derive(clap::Args, Clone, Debug)]
+pub struct ShelveListArgs {}
+
+#[instrument(skip_all)]
+pub async fn cmd_shelve_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &ShelveListArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let template = workspace_command.commit_summary_template();
+    let mut formatter = ui.stdout_formatter();
+    for (name, commit_id) in repo.view().shelves() {
+        let commit = repo.store().get_commit_async(commit_id).await?;
+        write!(formatter.labeled("shelf"), "{name}")?;
+        write!(formatter, ": ")?;
+        template.format(&commit, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod apply;
+mod drop;
+#[cfg(feature = "git")]
+mod import_git_stash;
+mod list;
+mod push;
+
+use jj_lib::commit::Commit;
+use jj_lib::repo::Repo as _;
+
+use self::apply::ShelveApplyArgs;
+use self::apply::cmd_shelve_apply;
+use self::drop::ShelveDropArgs;
+use self::drop::cmd_shelve_drop;
+#[cfg(feature = "git")]
+use self::import_git_stash::ShelveImportGitStashArgs;
+#[cfg(feature = "git")]
+use self::import_git_stash::cmd_shelve_import_git_stash;
+use self::list::ShelveListArgs;
+use self::list::cmd_shelve_list;
+use self::push::ShelvePushArgs;
+use self::push::cmd_shelve_push;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+/// Set changes aside and apply them later
+///
+/// `jj shelve push` moves changes out of a revision (the working-copy revision
+/// by default) into a named shelf. Shelves are stored as hidden commits, so
+/// they don't show up in `jj log`. They can be applied onto any revision by
+/// `jj shelve apply`.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum ShelveCommand {
+    Apply(ShelveApplyArgs),
+    Drop(ShelveDropArgs),
+    #[cfg(feature = "git")]
+    ImportGitStash(ShelveImportGitStashArgs),
+    #[command(visible_alias("l"))]
+    List(ShelveListArgs),
+    Push(ShelvePushArgs),
+}
+
+pub async fn cmd_shelve(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &ShelveCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        ShelveCommand::Apply(args) => cmd_shelve_apply(ui, command, args).await,
+        ShelveCommand::Drop(args) => cmd_shelve_drop(ui, command, args).await,
+        #[cfg(feature = "git")]
+        ShelveCommand::ImportGitStash(args) => cmd_shelve_import_git_stash(ui, command, args).await,
+        ShelveCommand::List(args) => cmd_shelve_list(ui, command, args).await,
+        ShelveCommand::Push(args) => cmd_shelve_push(ui, command, args).await,
+    }
+}
+
+async fn resolve_shelf(
+    workspace_command: &WorkspaceCommandHelper,
+    name: &str,
+) -> Result<Commit, CommandError> {
+    let repo = workspace_command.repo();
+    let commit_id = repo
+        .view()
+        .get_shelf(name)
+        .ok_or_else(|| user_error(format!("No such shelf: {name}")))?;
+    Ok(repo.store().get_commit_async(commit_id).await?)
+}
@@ -0,0 +1,170 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use indoc::formatdoc;
+use jj_lib::merge::Diff;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::rewrite::CommitWithSelection;
+use jj_lib::shelf;
+use jj_lib::view::View;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::print_unmatched_explicit_paths;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::description_util::join_message_paragraphs;
+use crate::ui::Ui;
+
+/// Move changes from a revision into a new shelf
+///
+/// Without any options, moves all changes from the working-copy revision into
+/// the shelf, so the working-copy revision becomes empty. The shelf is created
+/// on top of the parents of the revision.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ShelvePushArgs {
+    /// Revision to move changes from
+    #[arg(long, short, default_value = "@", value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
+    revision: RevisionArg,
+
+    /// Name of the shelf (default: `shelf-<N>`)
+    #[arg(long, short)]
+    name: Option<String>,
+
+    /// The description of the shelf (default: the description of the
+    /// revision)
+    #[arg(long = "message", short, value_name = "MESSAGE")]
+    message_paragraphs: Option<Vec<String>>,
+
+    /// Interactively choose which parts to shelve
+    #[arg(long, short)]
+    interactive: bool,
+
+    /// Specify diff editor to be used (implies --interactive)
+    #[arg(long, value_name = "NAME")]
+    #[arg(add = ArgValueCandidates::new(complete::diff_editors))]
+    tool: Option<String>,
+
+    /// Shelve only changes to these paths (instead of all paths)
+    #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    #[arg(add = ArgValueCompleter::new(complete::modified_revision_files))]
+    paths: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub async fn cmd_shelve_push(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ShelvePushArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let source = workspace_command
+        .resolve_single_rev(ui, &args.revision)
+        .await?;
+    workspace_command.check_rewritable([source.id()]).await?;
+    let name = match &args.name {
+        Some(name) => {
+            if workspace_command.repo().view().get_shelf(name).is_some() {
+                return Err(user_error(format!("Shelf already exists: {name}"))
+                    .hinted("Use `jj shelve drop` to delete the existing shelf."));
+            }
+            name.clone()
+        }
+        None => next_shelf_name(workspace_command.repo().view()),
+    };
+
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let matcher = fileset_expression.to_matcher();
+    let diff_selector =
+        workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
+    let mut tx = workspace_command.start_transaction();
+    let parent_tree = source.parent_tree(tx.repo()).await?;
+    let format_instructions = || {
+        formatdoc! {"
+            You are moving changes from: {source}
+            into a new shelf.
+
+            The left side of the diff shows the contents of the parent commit. The
+            right side initially shows the contents of the commit you're moving
+            changes from.
+
+            Adjust the right side until the diff shows the changes you want to
+            shelve. If you don't make any changes, then all the changes from the
+            source will be shelved.
+            ",
+            source = tx.format_commit_summary(&source),
+        }
+    };
+    let selected_tree = diff_selector
+        .select(
+            ui,
+            Diff::new(&parent_tree, &source.tree()),
+            Diff::new(
+                source.parents_conflict_label().await?,
+                source.conflict_label(),
+            ),
+            matcher.as_ref(),
+            format_instructions,
+        )
+        .await?;
+    print_unmatched_explicit_paths(
+        ui,
+        tx.base_workspace_helper(),
+        &fileset_expression,
+        [&selected_tree],
+    )?;
+    let selection = CommitWithSelection {
+        commit: source.clone(),
+        selected_tree,
+        parent_tree,
+    };
+    if selection.is_empty_selection() {
+        writeln!(ui.status(), "No changes to shelve.")?;
+        return Ok(());
+    }
+
+    let description = match &args.message_paragraphs {
+        Some(paragraphs) => join_message_paragraphs(paragraphs),
+        None => source.description().to_owned(),
+    };
+    let shelf_commit =
+        shelf::shelve_changes(tx.repo_mut(), &selection, name.clone(), description).await?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Created shelf {name}: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &shelf_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!("shelve changes from commit {} as {name}", source.id().hex()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Returns `shelf-<N>` name which is greater than the existing ones.
+fn next_shelf_name(view: &View) -> String {
+    let max_index = view
+        .shelves()
+        .keys()
+        .filter_map(|name| name.strip_prefix("shelf-")?.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("shelf-{}", max_index + 1)
+}
@@ -22,6 +22,9 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::diff_util::json_diff_formats_for;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::ui::Ui;
 
 /// Show commit description and changes in a revision
@@ -77,14 +80,28 @@ pub(crate) async fn cmd_show(
     let commit = workspace_command
         .resolve_single_rev(ui, revision_arg)
         .await?;
-    let template_string = match &args.template {
-        Some(value) => value.clone(),
-        None => workspace_command.settings().get_string("templates.show")?,
+    let json_output = command.is_json_output();
+    if json_output && args.template.is_some() {
+        return Err(text_only_arg_error("`--template`"));
+    }
+    let template = if json_output {
+        let language = workspace_command.commit_template_language();
+        parse_json_template(&language, "commit", "self")?
+    } else {
+        let template_string = match &args.template {
+            Some(value) => value.clone(),
+            None => workspace_command.settings().get_string("templates.show")?,
+        };
+        workspace_command
+            .parse_commit_template(ui, &template_string)?
+            .labeled(["show", "commit"])
+    };
+    let diff_renderer = if json_output {
+        let formats = json_diff_formats_for(workspace_command.settings(), &args.format)?;
+        workspace_command.diff_renderer(formats)
+    } else {
+        workspace_command.diff_renderer_for(&args.format)?
     };
-    let template = workspace_command
-        .parse_commit_template(ui, &template_string)?
-        .labeled(["show", "commit"]);
-    let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+
 use clap_complete::ArgValueCompleter;
 use futures::TryStreamExt as _;
 use indexmap::IndexSet;
@@ -26,8 +28,10 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::print_updated_commits;
 use crate::cli_util::short_change_hash;
+use crate::cli_util::short_commit_hash;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::complete;
 use crate::progress::ProgressWriter;
 use crate::ui::Ui;
@@ -45,14 +49,8 @@ pub struct SignArgs {
     /// If no revisions are specified, this defaults to the `revsets.sign`
     /// setting.
     ///
-    /// Note that revisions are always re-signed.
-    ///
-    /// While that leads to discomfort for users, which sign with hardware
-    /// devices, as of now we cannot reliably check if a commit is already
-    /// signed by the user without creating a signature (see [#5786]).
-    ///
-    /// [#5786]:
-    ///     https://github.com/jj-vcs/jj/issues/5786
+    /// Revisions which are already signed by the key are left unchanged,
+    /// unless their ancestors have to be re-signed.
     #[arg(long = "revision", short, value_name = "REVSETS", alias = "revisions")]
     #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
     revisions: Vec<RevisionArg>,
@@ -95,9 +93,30 @@ pub async fn cmd_sign(
         .try_collect()
         .await?;
 
+    let key = args
+        .key
+        .clone()
+        .or_else(|| workspace_command.settings().sign_settings().key);
+    let mut already_signed = HashSet::new();
+    for commit in &to_sign {
+        let is_signed_by_key = commit.is_signed_by(key.as_deref()).map_err(|err| {
+            user_error_with_message(
+                format!(
+                    "Failed to verify signature of commit {}",
+                    short_commit_hash(commit.id())
+                ),
+                err,
+            )
+        })?;
+        if is_signed_by_key {
+            already_signed.insert(commit.id().clone());
+        }
+    }
+
     let mut tx = workspace_command.start_transaction();
 
     let mut signed_commits = vec![];
+    let mut num_already_signed = 0;
     let mut num_reparented = 0;
 
     {
@@ -108,7 +127,6 @@ pub async fn cmd_sign(
                 to_sign.iter().ids().cloned().collect_vec(),
                 async |rewriter| {
                     let old_commit = rewriter.old_commit().clone();
-                    let mut commit_builder = rewriter.reparent();
 
                     if let Some(writer) = &mut progress_writer {
                         writer
@@ -116,6 +134,16 @@ pub async fn cmd_sign(
                             .ok();
                     }
 
+                    if !rewriter.parents_changed() {
+                        if already_signed.contains(old_commit.id()) {
+                            num_already_signed += 1;
+                            return Ok(());
+                        } else if !to_sign.contains(&old_commit) {
+                            return Ok(());
+                        }
+                    }
+
+                    let mut commit_builder = rewriter.reparent();
                     if to_sign.contains(&old_commit) {
                         if let Some(key) = &args.key {
                             commit_builder = commit_builder.set_sign_key(key.clone());
@@ -160,6 +188,13 @@ pub async fn cmd_sign(
         )?;
     }
 
+    if num_already_signed > 0 {
+        writeln!(
+            ui.status(),
+            "Skipped {num_already_signed} commits already signed by the key"
+        )?;
+    }
+
     if num_reparented > 0 {
         writeln!(ui.status(), "Rebased {num_reparented} descendant commits")?;
     }
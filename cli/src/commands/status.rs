@@ -12,16 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use futures::StreamExt as _;
 use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopyRecords;
+use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::working_copy::SnapshotStats;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -30,8 +36,10 @@ use crate::cli_util::print_snapshot_stats;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::diff_util::DiffFormat;
+use crate::diff_util::diff_status;
 use crate::diff_util::get_copy_records;
 use crate::formatter::FormatterExt as _;
+use crate::json_output::write_json_record;
 use crate::ui::Ui;
 
 /// Show high-level repo status [default alias: st]
@@ -76,6 +84,18 @@ pub(crate) async fn cmd_status(
         .transpose()?;
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
     let matcher = fileset_expression.to_matcher();
+    if command.is_json_output() {
+        let status = collect_json_status(
+            repo.as_ref(),
+            maybe_wc_commit.as_ref(),
+            &snapshot_stats,
+            matcher.as_ref(),
+        )
+        .await?;
+        let mut formatter = ui.stdout_formatter();
+        write_json_record(formatter.as_mut(), "status", &status)?;
+        return Ok(());
+    }
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -240,6 +260,91 @@ pub(crate) async fn cmd_status(
     Ok(())
 }
 
+/// Status of the working copy in the JSON output.
+#[derive(Debug, serde::Serialize)]
+struct JsonStatus {
+    working_copy: Option<Commit>,
+    parents: Vec<Commit>,
+    changes: Vec<JsonStatusChange>,
+    untracked_paths: Vec<RepoPathBuf>,
+    conflicted_paths: Vec<RepoPathBuf>,
+    conflicted_bookmarks: Vec<String>,
+    conflicted_remote_bookmarks: Vec<JsonRemoteBookmark>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonRemoteBookmark {
+    name: String,
+    remote: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonStatusChange {
+    path: RepoPathBuf,
+    status: &'static str,
+    /// Source path if the file was copied or renamed.
+    source_path: Option<RepoPathBuf>,
+}
+
+async fn collect_json_status(
+    repo: &ReadonlyRepo,
+    maybe_wc_commit: Option<&Commit>,
+    snapshot_stats: &SnapshotStats,
+    matcher: &dyn Matcher,
+) -> Result<JsonStatus, CommandError> {
+    let mut status = JsonStatus {
+        working_copy: maybe_wc_commit.cloned(),
+        parents: vec![],
+        changes: vec![],
+        untracked_paths: snapshot_stats
+            .untracked_paths
+            .keys()
+            .filter(|path| matcher.matches(path))
+            .cloned()
+            .collect(),
+        conflicted_paths: vec![],
+        conflicted_bookmarks: repo
+            .view()
+            .local_bookmarks()
+            .filter(|(_, target)| target.has_conflict())
+            .map(|(name, _)| name.as_str().to_owned())
+            .collect(),
+        conflicted_remote_bookmarks: repo
+            .view()
+            .all_remote_bookmarks()
+            .filter(|(_, remote_ref)| remote_ref.target.has_conflict())
+            .map(|(symbol, _)| JsonRemoteBookmark {
+                name: symbol.name.as_str().to_owned(),
+                remote: symbol.remote.as_str().to_owned(),
+            })
+            .collect(),
+    };
+    if let Some(wc_commit) = maybe_wc_commit {
+        status.parents = wc_commit.parents().await?;
+        let parent_tree = wc_commit.parent_tree(repo).await?;
+        let tree = wc_commit.tree();
+        let mut copy_records = CopyRecords::default();
+        for parent in wc_commit.parent_ids() {
+            let records = get_copy_records(repo.store(), parent, wc_commit.id(), matcher).await?;
+            copy_records.add_records(records);
+        }
+        let mut tree_diff = parent_tree.diff_stream_with_copies(&tree, matcher, &copy_records);
+        while let Some(CopiesTreeDiffEntry { path, values }) = tree_diff.next().await {
+            let status_kind = diff_status(&path, &values?);
+            status.changes.push(JsonStatusChange {
+                path: path.target().to_owned(),
+                status: status_kind.label(),
+                source_path: path.copy_operation().map(|_| path.source().to_owned()),
+            });
+        }
+        status.conflicted_paths = tree
+            .conflicts_matching(matcher)
+            .map(|(path, _)| path)
+            .collect();
+    }
+    Ok(status)
+}
+
 async fn visit_collapsed_untracked_files(
     untracked_paths: impl IntoIterator<Item = impl AsRef<RepoPath>>,
     tree: MergedTree,
@@ -28,9 +28,12 @@ use crate::cli_util::default_ignored_remote_name;
 use crate::command_error::CommandError;
 use crate::commit_ref_list;
 use crate::commit_ref_list::RefFilterPredicates;
+use crate::commit_ref_list::RefListItem;
 use crate::commit_ref_list::SortKey;
 use crate::commit_templater::CommitRef;
 use crate::complete;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::revset_util::parse_union_name_patterns;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
@@ -163,13 +166,20 @@ pub async fn cmd_tag_list(
     };
     let template: TemplateRenderer<Rc<CommitRef>> = {
         let language = workspace_command.commit_template_language();
-        let text = match &args.template {
-            Some(value) => value.to_owned(),
-            None => settings.get("templates.tag_list")?,
-        };
-        workspace_command
-            .parse_template(ui, &language, &text)?
-            .labeled(["tag_list"])
+        if command.is_json_output() {
+            if args.template.is_some() {
+                return Err(text_only_arg_error("`--template`"));
+            }
+            parse_json_template(&language, "tag", "self")?
+        } else {
+            let text = match &args.template {
+                Some(value) => value.to_owned(),
+                None => settings.get("templates.tag_list")?,
+            };
+            workspace_command
+                .parse_template(ui, &language, &text)?
+                .labeled(["tag_list"])
+        }
     };
     let sort_keys = if args.sort.is_empty() {
         settings.get_value_with("ui.tag-list-sort-keys", commit_ref_list::parse_sort_keys)?
@@ -186,7 +196,10 @@ pub async fn cmd_tag_list(
         include_synced_remotes: args.tracked || args.all_remotes || args.remotes.is_some(),
         include_untracked_remotes: !args.tracked && (args.all_remotes || args.remotes.is_some()),
     };
-    let mut list_items = commit_ref_list::collect_items(view.tags(), &predicates);
+    let mut list_items: Vec<_> = commit_ref_list::collect_items(view.tags(), &predicates)
+        .into_iter()
+        .map(RefListItem::into_tag)
+        .collect();
     commit_ref_list::sort(repo.store(), &mut list_items, &sort_keys)?;
 
     ui.request_pager();
@@ -15,18 +15,37 @@
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+#[cfg(feature = "git")]
+use jj_lib::backend::SigningFn;
+#[cfg(feature = "git")]
+use jj_lib::git;
+#[cfg(feature = "git")]
+use jj_lib::git::GitRefKind;
 use jj_lib::op_store::RefTarget;
 use jj_lib::ref_name::RefNameBuf;
+#[cfg(feature = "git")]
+use jj_lib::repo::Repo as _;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::complete;
+use crate::description_util::edit_description;
+use crate::description_util::join_message_paragraphs;
+#[cfg(feature = "git")]
+use crate::git_util::print_git_export_stats;
 use crate::revset_util;
 use crate::ui::Ui;
 
 /// Create or update tags
+///
+/// By default, lightweight tags are created. If `--message`, `--edit`, or
+/// `--sign` is specified, annotated tag objects are created instead. This
+/// requires the Git backend.
 #[derive(clap::Args, Clone, Debug)]
 pub struct TagSetArgs {
     /// Target revision to point to
@@ -44,6 +63,24 @@ pub struct TagSetArgs {
     #[arg(long)]
     allow_move: bool,
 
+    /// The tag message (creates annotated tags)
+    #[arg(long = "message", short, value_name = "MESSAGE")]
+    message_paragraphs: Option<Vec<String>>,
+
+    /// Open an editor to edit the tag message (creates annotated tags)
+    #[arg(long)]
+    edit: bool,
+
+    /// Cryptographically sign the tags (creates annotated tags)
+    ///
+    /// This requires configuring a [commit signing] backend. If no message is
+    /// specified, an editor will be opened.
+    ///
+    /// [commit signing]:
+    ///     https://docs.jj-vcs.dev/latest/config/#commit-signing
+    #[arg(long)]
+    sign: bool,
+
     /// Tag names to create or update
     #[arg(required = true, value_parser = revset_util::parse_tag_name)]
     #[arg(add = ArgValueCandidates::new(complete::local_tags))]
@@ -84,10 +121,27 @@ pub async fn cmd_tag_set(
         writeln!(ui.warning_default(), "Target revision is empty.")?;
     }
 
+    let annotation = if args.message_paragraphs.is_some() || args.edit || args.sign {
+        Some(get_tag_message(&workspace_command, args)?)
+    } else {
+        None
+    };
+
     let mut tx = workspace_command.start_transaction();
-    for name in &args.names {
-        tx.repo_mut()
-            .set_local_tag_target(name, RefTarget::normal(target_commit.id().clone()));
+    if let Some(message) = &annotation {
+        set_annotated_tags(
+            ui,
+            &mut tx,
+            &args.names,
+            target_commit.id(),
+            message,
+            args.sign,
+        )?;
+    } else {
+        for name in &args.names {
+            tx.repo_mut()
+                .set_local_tag_target(name, RefTarget::normal(target_commit.id().clone()));
+        }
     }
 
     if let Some(mut formatter) = ui.status_formatter() {
@@ -114,3 +168,72 @@ pub async fn cmd_tag_set(
     .await?;
     Ok(())
 }
+
+fn get_tag_message(
+    workspace_command: &WorkspaceCommandHelper,
+    args: &TagSetArgs,
+) -> Result<String, CommandError> {
+    let message = args
+        .message_paragraphs
+        .as_deref()
+        .map(join_message_paragraphs)
+        .unwrap_or_default();
+    if !args.edit && (args.message_paragraphs.is_some() || !args.sign) {
+        return Ok(message);
+    }
+    let names = args.names.iter().map(|n| n.as_symbol()).join(", ");
+    let text = format!("{message}\nJJ: Enter the message for tag {names}.\n");
+    let message = edit_description(&workspace_command.text_editor()?, &text)?;
+    if message.is_empty() {
+        return Err(user_error("Aborting because the tag message is empty"));
+    }
+    Ok(message)
+}
+
+#[cfg(feature = "git")]
+fn set_annotated_tags(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    names: &[RefNameBuf],
+    target: &CommitId,
+    message: &str,
+    sign: bool,
+) -> Result<(), CommandError> {
+    let store = tx.repo().store().clone();
+    if sign && !store.signer().can_sign() {
+        return Err(user_error("No signing backend configured").hinted(
+            "For configuring a signing backend, see \
+             https://docs.jj-vcs.dev/latest/config/#commit-signing",
+        ));
+    }
+    let tagger = tx.settings().signature();
+    let key = tx.settings().sign_settings().key;
+    for name in names {
+        let mut sign_fn = |data: &[u8]| store.signer().sign(data, key.as_deref());
+        let sign_with: Option<&mut SigningFn> = sign.then_some(&mut sign_fn);
+        git::set_annotated_tag(tx.repo_mut(), name, target, message, &tagger, sign_with)?;
+    }
+    // The tag objects are only reachable from the Git refs, so they have to be
+    // exported even if the workspace isn't colocated.
+    let stats = git::export_some_refs(tx.repo_mut(), |kind, symbol| {
+        kind == GitRefKind::Tag
+            && symbol.remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+            && names.iter().any(|name| name == symbol.name)
+    })?;
+    print_git_export_stats(ui, &stats)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "git"))]
+fn set_annotated_tags(
+    _ui: &Ui,
+    _tx: &mut WorkspaceCommandTransaction,
+    _names: &[RefNameBuf],
+    _target: &CommitId,
+    _message: &str,
+    _sign: bool,
+) -> Result<(), CommandError> {
+    Err(user_error(
+        "Annotated tags are only supported with the Git backend",
+    ))
+}
@@ -62,6 +62,14 @@ pub async fn cmd_util_gc(
     repo.op_store()
         .gc(slice::from_ref(repo.op_id()), keep_newer)
         .await?;
+    #[cfg(feature = "git")]
+    if let Ok(git_backend) = jj_lib::git::get_git_backend(repo.store()) {
+        let notes_heads =
+            jj_lib::git::git_notes_heads_for_gc(repo.store(), slice::from_ref(repo.operation()))
+                .await?;
+        git_backend.gc_with_extra_heads(repo.index(), &notes_heads, keep_newer)?;
+        return Ok(());
+    }
     repo.store().gc(repo.index(), keep_newer)?;
     Ok(())
 }
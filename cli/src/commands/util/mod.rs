@@ -18,6 +18,7 @@ mod exec;
 mod gc;
 mod install_man_pages;
 mod markdown_help;
+mod resolutions;
 mod snapshot;
 
 use clap::Subcommand;
@@ -35,6 +36,8 @@ use self::install_man_pages::UtilInstallManPagesArgs;
 use self::install_man_pages::cmd_util_install_man_pages;
 use self::markdown_help::UtilMarkdownHelp;
 use self::markdown_help::cmd_util_markdown_help;
+use self::resolutions::UtilResolutionsCommand;
+use self::resolutions::cmd_util_resolutions;
 use self::snapshot::UtilSnapshotArgs;
 use self::snapshot::cmd_util_snapshot;
 use crate::cli_util::CommandHelper;
@@ -50,6 +53,8 @@ pub(crate) enum UtilCommand {
     Gc(UtilGcArgs),
     InstallManPages(UtilInstallManPagesArgs),
     MarkdownHelp(UtilMarkdownHelp),
+    #[command(subcommand)]
+    Resolutions(UtilResolutionsCommand),
     Snapshot(UtilSnapshotArgs),
 }
 
@@ -66,6 +71,7 @@ pub(crate) async fn cmd_util(
         UtilCommand::Gc(args) => cmd_util_gc(ui, command, args).await,
         UtilCommand::InstallManPages(args) => cmd_util_install_man_pages(ui, command, args).await,
         UtilCommand::MarkdownHelp(args) => cmd_util_markdown_help(ui, command, args).await,
+        UtilCommand::Resolutions(subcommand) => cmd_util_resolutions(ui, command, subcommand).await,
         UtilCommand::Snapshot(args) => cmd_util_snapshot(ui, command, args).await,
     }
 }
@@ -0,0 +1,79 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId as _;
+use tracing::instrument;
+
+use super::load_resolution_cache;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+/// Forget remembered conflict resolutions
+#[derive(clap::Args, Clone, Debug)]
+#[command(group(clap::ArgGroup::new("target").args(&["ids", "all"]).required(true)))]
+pub struct UtilResolutionsForgetArgs {
+    /// Ids of the resolutions to forget, as shown by `jj util resolutions
+    /// list`
+    ///
+    /// A unique prefix of the id is accepted.
+    #[arg(value_name = "ID")]
+    ids: Vec<String>,
+    /// Forget all remembered resolutions
+    #[arg(long)]
+    all: bool,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_util_resolutions_forget(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilResolutionsForgetArgs,
+) -> Result<(), CommandError> {
+    let cache = load_resolution_cache(ui, command)?;
+    let resolutions = cache.list()?;
+    let to_forget = if args.all {
+        resolutions
+            .iter()
+            .map(|resolution| &resolution.id)
+            .collect_vec()
+    } else {
+        args.ids
+            .iter()
+            .map(|prefix| {
+                let matches = resolutions
+                    .iter()
+                    .filter(|resolution| resolution.id.hex().starts_with(prefix.as_str()))
+                    .collect_vec();
+                match matches.as_slice() {
+                    [resolution] => Ok(&resolution.id),
+                    [] => Err(user_error(format!("No such resolution: {prefix}"))),
+                    _ => Err(user_error(format!(
+                        "Resolution id prefix is ambiguous: {prefix}"
+                    ))),
+                }
+            })
+            .try_collect()?
+    };
+    let mut count = 0;
+    for id in to_forget.into_iter().unique() {
+        if cache.remove(id)? {
+            count += 1;
+        }
+    }
+    writeln!(ui.status(), "Forgot {count} resolutions.")?;
+    Ok(())
+}
@@ -0,0 +1,46 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::object_id::ObjectId as _;
+use tracing::instrument;
+
+use super::load_resolution_cache;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::formatter::FormatterExt as _;
+use crate::ui::Ui;
+
+/// List remembered conflict resolutions
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilResolutionsListArgs {}
+
+#[instrument(skip_all)]
+pub fn cmd_util_resolutions_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &UtilResolutionsListArgs,
+) -> Result<(), CommandError> {
+    let cache = load_resolution_cache(ui, command)?;
+    let mut formatter = ui.stdout_formatter();
+    for resolution in cache.list()? {
+        write!(formatter.labeled("id"), "{}", &resolution.id.hex()[..12])?;
+        writeln!(
+            formatter,
+            " {} ({}-sided conflict)",
+            resolution.path,
+            resolution.conflict.num_sides()
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod forget;
+mod list;
+
+use clap::Subcommand;
+use jj_lib::resolution_cache::ResolutionCache;
+use tracing::instrument;
+
+use self::forget::UtilResolutionsForgetArgs;
+use self::forget::cmd_util_resolutions_forget;
+use self::list::UtilResolutionsListArgs;
+use self::list::cmd_util_resolutions_list;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage remembered conflict resolutions
+///
+/// If `merge.remember-resolutions = true` is set, the resolution of each
+/// conflicting hunk resolved with `jj resolve` is remembered in the repo. If
+/// the same conflict hunks appear again, for example when rebasing a stack of
+/// commits repeatedly, the conflict is resolved automatically by using the
+/// remembered resolutions.
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum UtilResolutionsCommand {
+    Forget(UtilResolutionsForgetArgs),
+    List(UtilResolutionsListArgs),
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_util_resolutions(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &UtilResolutionsCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        UtilResolutionsCommand::Forget(args) => cmd_util_resolutions_forget(ui, command, args),
+        UtilResolutionsCommand::List(args) => cmd_util_resolutions_list(ui, command, args),
+    }
+}
+
+fn load_resolution_cache(
+    ui: &Ui,
+    command: &CommandHelper,
+) -> Result<ResolutionCache, CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    // Not using the store's cache, which is unavailable if remembering
+    // resolutions is disabled.
+    Ok(ResolutionCache::new(workspace_command.repo_path()))
+}
@@ -20,6 +20,8 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::commit_templater::WorkspaceRef;
 use crate::complete;
+use crate::json_output::parse_json_template;
+use crate::json_output::text_only_arg_error;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -52,17 +54,22 @@ pub async fn cmd_workspace_list(
 
     let template: TemplateRenderer<WorkspaceRef> = {
         let language = workspace_command.commit_template_language();
-
-        let text = match &args.template {
-            Some(value) => value.to_owned(),
-            None => workspace_command
-                .settings()
-                .get("templates.workspace_list")?,
-        };
-
-        workspace_command
-            .parse_template(ui, &language, &text)?
-            .labeled(["workspace_list"])
+        if command.is_json_output() {
+            if args.template.is_some() {
+                return Err(text_only_arg_error("`--template`"));
+            }
+            parse_json_template(&language, "workspace", "self")?
+        } else {
+            let text = match &args.template {
+                Some(value) => value.to_owned(),
+                None => workspace_command
+                    .settings()
+                    .get("templates.workspace_list")?,
+            };
+            workspace_command
+                .parse_template(ui, &language, &text)?
+                .labeled(["workspace_list"])
+        }
     };
 
     let repo = workspace_command.repo();
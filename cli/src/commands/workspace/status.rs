@@ -0,0 +1,188 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::op_store::OperationId;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::working_copy::WorkingCopyFreshness;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceStore as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::commit_templater::WorkspaceRef;
+use crate::commit_templater::WorkspaceStatus;
+use crate::complete;
+use crate::templater::TemplateRenderer;
+use crate::ui::Ui;
+
+/// Show the state of the working copies of all workspaces
+///
+/// For each workspace, this shows the path to the workspace root, the
+/// operation the working copy was last updated to, whether the working copy is
+/// stale, whether it has changes that haven't been snapshotted yet, and the
+/// number of conflicted files in its working-copy commit.
+///
+/// The files in every workspace are scanned with the workspace's own
+/// configuration to find changes that haven't been snapshotted, but the working
+/// copies aren't updated.
+///
+/// Use `-T 'json(self) ++ "\n"'` to print the status of each workspace as a
+/// JSON object.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceStatusArgs {
+    /// Render each workspace using the given template
+    ///
+    /// All 0-argument methods of the [`WorkspaceStatus` type] are available as
+    /// keywords in the template expression. See [`jj help -k templates`] for
+    /// more information.
+    ///
+    /// [`WorkspaceStatus` type]:
+    ///     https://docs.jj-vcs.dev/latest/templates/#workspacestatus-type
+    ///
+    /// [`jj help -k templates`]:
+    ///     https://docs.jj-vcs.dev/latest/templates/
+    #[arg(long, short = 'T')]
+    #[arg(add = ArgValueCandidates::new(complete::template_aliases))]
+    template: Option<String>,
+}
+
+#[instrument(skip_all)]
+pub async fn cmd_workspace_status(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceStatusArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+
+    let template: TemplateRenderer<WorkspaceStatus> = {
+        let language = workspace_command.commit_template_language();
+
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => workspace_command
+                .settings()
+                .get("templates.workspace_status")?,
+        };
+
+        workspace_command
+            .parse_template(ui, &language, &text)?
+            .labeled(["workspace_status"])
+    };
+
+    let repo = workspace_command.repo();
+    let repo_path = workspace_command.repo_path();
+    let workspace_store = SimpleWorkspaceStore::load(repo_path)?;
+
+    let mut statuses = Vec::new();
+    for (name, wc_commit_id) in repo.view().wc_commit_ids() {
+        let commit = repo.store().get_commit(wc_commit_id)?;
+        let ws_ref = WorkspaceRef::new(name.clone(), commit);
+        let root = match workspace_store.get_workspace_path(name)? {
+            Some(path) => {
+                let full_path = repo_path.join(path);
+                dunce::canonicalize(&full_path).map_err(|err| {
+                    format!(
+                        "Cannot resolve absolute workspace path: {}: {err}",
+                        full_path.display()
+                    )
+                })
+            }
+            None => Err("Workspace has no recorded path".to_owned()),
+        };
+        let status = match root {
+            Ok(root) => {
+                let root_str = root.to_string_lossy().into_owned();
+                match load_working_copy_state(ui, command, repo, repo_path, &ws_ref, &root).await {
+                    Ok((operation_id, stale, dirty)) => {
+                        WorkspaceStatus::new(ws_ref, root_str, operation_id, stale, dirty)
+                    }
+                    Err(err) => WorkspaceStatus::unavailable(ws_ref, Some(root_str), err),
+                }
+            }
+            Err(err) => WorkspaceStatus::unavailable(ws_ref, None, err),
+        };
+        statuses.push(status);
+    }
+
+    let mut formatter = ui.stdout_formatter();
+    for status in &statuses {
+        template.format(status, formatter.as_mut())?;
+    }
+
+    Ok(())
+}
+
+/// Loads the working copy at `root`, and returns its operation id, whether
+/// it's stale, and whether it has unsnapshotted changes.
+///
+/// The working copy is snapshotted with the settings of the workspace to find
+/// unsnapshotted changes, but the result isn't saved.
+async fn load_working_copy_state(
+    ui: &Ui,
+    command: &CommandHelper,
+    repo: &Arc<ReadonlyRepo>,
+    repo_path: &Path,
+    ws_ref: &WorkspaceRef,
+    root: &Path,
+) -> Result<(OperationId, bool, bool), String> {
+    let to_string = |err: CommandError| err.error.to_string();
+    let (settings, _) = command
+        .settings_for_workspace(ui, root, repo_path)
+        .map_err(to_string)?;
+    let workspace = command
+        .load_workspace_at(root, &settings)
+        .map_err(to_string)?;
+    if workspace.workspace_name() != ws_ref.name() {
+        return Err(format!(
+            "Workspace at {} is named {}",
+            root.display(),
+            workspace.workspace_name().as_symbol()
+        ));
+    }
+    let workspace_command = command
+        .for_workable_repo(ui, workspace, repo.clone())
+        .map_err(to_string)?;
+    let auto_tracking_matcher = workspace_command
+        .auto_tracking_matcher(ui)
+        .map_err(to_string)?;
+    let options = workspace_command
+        .snapshot_options_with_start_tracking_matcher(&*auto_tracking_matcher)
+        .map_err(to_string)?;
+    let mut locked_wc = workspace_command
+        .working_copy()
+        .start_mutation()
+        .map_err(|err| err.to_string())?;
+    let operation_id = locked_wc.old_operation_id().clone();
+    let stale = match WorkingCopyFreshness::check_stale(locked_wc.as_ref(), ws_ref.target(), repo)
+        .await
+        .map_err(|err| err.to_string())?
+    {
+        WorkingCopyFreshness::Fresh | WorkingCopyFreshness::Updated(_) => false,
+        WorkingCopyFreshness::WorkingCopyStale | WorkingCopyFreshness::SiblingOperation => true,
+    };
+    let (new_tree, _stats) = locked_wc
+        .snapshot(&options)
+        .await
+        .map_err(|err| err.to_string())?;
+    let dirty = new_tree.tree_ids_and_labels() != locked_wc.old_tree().tree_ids_and_labels();
+    // The lock is released without writing the snapshotted state.
+    drop(locked_wc);
+    Ok((operation_id, stale, dirty))
+}
@@ -42,6 +42,16 @@ pub struct RefListItem {
     pub tracked: Vec<Rc<CommitRef>>,
 }
 
+impl RefListItem {
+    /// Marks the refs as tags.
+    pub fn into_tag(self) -> Self {
+        Self {
+            primary: self.primary.into_tag(),
+            tracked: self.tracked.into_iter().map(CommitRef::into_tag).collect(),
+        }
+    }
+}
+
 /// Conditions to select local/remote refs.
 pub struct RefFilterPredicates {
     /// Matches local names.
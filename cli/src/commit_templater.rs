@@ -32,6 +32,8 @@ use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::SecureSig;
+use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
@@ -290,6 +292,11 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::WorkspaceStatus(property) => {
+                let table = &self.build_fn_table.workspace_status_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
             CommitTemplatePropertyKind::RefSymbol(property) => {
                 let table = &self.build_fn_table.core.string_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -447,6 +454,7 @@ pub enum CommitTemplatePropertyKind<'repo> {
     WorkspaceRef(BoxedTemplateProperty<'repo, WorkspaceRef>),
     WorkspaceRefOpt(BoxedTemplateProperty<'repo, Option<WorkspaceRef>>),
     WorkspaceRefList(BoxedTemplateProperty<'repo, Vec<WorkspaceRef>>),
+    WorkspaceStatus(BoxedTemplateProperty<'repo, WorkspaceStatus>),
     RefSymbol(BoxedTemplateProperty<'repo, RefSymbolBuf>),
     RefSymbolOpt(BoxedTemplateProperty<'repo, Option<RefSymbolBuf>>),
     RepoPath(BoxedTemplateProperty<'repo, RepoPathBuf>),
@@ -481,6 +489,7 @@ template_builder::impl_property_wrappers!(<'repo> CommitTemplatePropertyKind<'re
     WorkspaceRef(WorkspaceRef),
     WorkspaceRefOpt(Option<WorkspaceRef>),
     WorkspaceRefList(Vec<WorkspaceRef>),
+    WorkspaceStatus(WorkspaceStatus),
     RefSymbol(RefSymbolBuf),
     RefSymbolOpt(Option<RefSymbolBuf>),
     RepoPath(RepoPathBuf),
@@ -529,6 +538,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::WorkspaceRef(_) => "WorkspaceRef",
             Self::WorkspaceRefOpt(_) => "Option<WorkspaceRef>",
             Self::WorkspaceRefList(_) => "List<WorkspaceRef>",
+            Self::WorkspaceStatus(_) => "WorkspaceStatus",
             Self::RefSymbol(_) => "RefSymbol",
             Self::RefSymbolOpt(_) => "Option<RefSymbol>",
             Self::RepoPath(_) => "RepoPath",
@@ -585,6 +595,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::WorkspaceRef(_) => Err(self),
             Self::WorkspaceRefOpt(property) => Ok(property.map(|opt| opt.is_some()).into_dyn()),
             Self::WorkspaceRefList(property) => Ok(property.map(|l| !l.is_empty()).into_dyn()),
+            Self::WorkspaceStatus(_) => Err(self),
             Self::RefSymbol(_) => Err(self),
             Self::RefSymbolOpt(property) => Ok(property.map(|opt| opt.is_some()).into_dyn()),
             Self::RepoPath(_) => Err(self),
@@ -641,6 +652,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::WorkspaceRef(property) => Some(property.into_serialize()),
             Self::WorkspaceRefOpt(property) => Some(property.into_serialize()),
             Self::WorkspaceRefList(property) => Some(property.into_serialize()),
+            Self::WorkspaceStatus(property) => Some(property.into_serialize()),
             Self::RefSymbol(property) => Some(property.into_serialize()),
             Self::RefSymbolOpt(property) => Some(property.into_serialize()),
             Self::RepoPath(property) => Some(property.into_serialize()),
@@ -651,8 +663,8 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::TreeDiff(_) => None,
             Self::TreeDiffEntry(_) => None,
             Self::TreeDiffEntryList(_) => None,
-            Self::TreeEntry(_) => None,
-            Self::TreeEntryList(_) => None,
+            Self::TreeEntry(property) => Some(property.into_serialize()),
+            Self::TreeEntryList(property) => Some(property.into_serialize()),
             Self::DiffStats(_) => None,
             Self::DiffStatEntry(_) => None,
             Self::DiffStatEntryList(_) => None,
@@ -677,6 +689,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::WorkspaceRef(property) => Some(property.into_template()),
             Self::WorkspaceRefOpt(property) => Some(property.into_template()),
             Self::WorkspaceRefList(property) => Some(property.into_template()),
+            Self::WorkspaceStatus(_) => None,
             Self::RefSymbol(property) => Some(property.into_template()),
             Self::RefSymbolOpt(property) => Some(property.into_template()),
             Self::RepoPath(property) => Some(property.into_template()),
@@ -746,6 +759,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::WorkspaceRef(_), _) => None,
             (Self::WorkspaceRefOpt(_), _) => None,
             (Self::WorkspaceRefList(_), _) => None,
+            (Self::WorkspaceStatus(_), _) => None,
             (Self::RefSymbol(_), _) => None,
             (Self::RefSymbolOpt(_), _) => None,
             (Self::RepoPath(_), _) => None,
@@ -788,6 +802,7 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::WorkspaceRef(_), _) => None,
             (Self::WorkspaceRefOpt(_), _) => None,
             (Self::WorkspaceRefList(_), _) => None,
+            (Self::WorkspaceStatus(_), _) => None,
             (Self::RefSymbol(_), _) => None,
             (Self::RefSymbolOpt(_), _) => None,
             (Self::RepoPath(_), _) => None,
@@ -828,6 +843,7 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub commit_ref_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<Rc<CommitRef>>>,
     pub workspace_ref_methods: CommitTemplateBuildMethodFnMap<'repo, WorkspaceRef>,
     pub workspace_ref_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<WorkspaceRef>>,
+    pub workspace_status_methods: CommitTemplateBuildMethodFnMap<'repo, WorkspaceStatus>,
     pub repo_path_methods: CommitTemplateBuildMethodFnMap<'repo, RepoPathBuf>,
     pub change_id_methods: CommitTemplateBuildMethodFnMap<'repo, ChangeId>,
     pub commit_id_methods: CommitTemplateBuildMethodFnMap<'repo, CommitId>,
@@ -859,6 +875,7 @@ impl CommitTemplateBuildFnTable<'_> {
             commit_ref_list_methods: HashMap::new(),
             workspace_ref_methods: HashMap::new(),
             workspace_ref_list_methods: HashMap::new(),
+            workspace_status_methods: HashMap::new(),
             repo_path_methods: HashMap::new(),
             change_id_methods: HashMap::new(),
             commit_id_methods: HashMap::new(),
@@ -889,6 +906,7 @@ impl CommitTemplateBuildFnTable<'_> {
             commit_ref_list_methods,
             workspace_ref_methods,
             workspace_ref_list_methods,
+            workspace_status_methods,
             repo_path_methods,
             change_id_methods,
             commit_id_methods,
@@ -922,6 +940,7 @@ impl CommitTemplateBuildFnTable<'_> {
             &mut self.workspace_ref_list_methods,
             workspace_ref_list_methods,
         );
+        merge_fn_map(&mut self.workspace_status_methods, workspace_status_methods);
         merge_fn_map(&mut self.repo_path_methods, repo_path_methods);
         merge_fn_map(&mut self.change_id_methods, change_id_methods);
         merge_fn_map(&mut self.commit_id_methods, commit_id_methods);
@@ -966,6 +985,7 @@ impl CommitTemplateBuildFnTable<'_> {
             commit_ref_list_methods: template_builder::builtin_formattable_list_methods(),
             workspace_ref_methods: builtin_workspace_ref_methods(),
             workspace_ref_list_methods: template_builder::builtin_formattable_list_methods(),
+            workspace_status_methods: builtin_workspace_status_methods(),
             repo_path_methods: builtin_repo_path_methods(),
             change_id_methods: builtin_change_id_methods(),
             commit_id_methods: builtin_commit_id_methods(),
@@ -993,17 +1013,22 @@ pub struct CommitKeywordCache<'repo> {
     tags_index: OnceCell<Rc<CommitRefsIndex>>,
     git_refs_index: OnceCell<Rc<CommitRefsIndex>>,
     is_immutable_fn: OnceCell<Rc<RevsetContainingFn<'repo>>>,
+    git_notes: OnceCell<Rc<git_util::GitNotes>>,
 }
 
 impl<'repo> CommitKeywordCache<'repo> {
     pub fn bookmarks_index(&self, repo: &dyn Repo) -> &Rc<CommitRefsIndex> {
-        self.bookmarks_index
-            .get_or_init(|| Rc::new(build_local_remote_refs_index(repo.view().bookmarks())))
+        self.bookmarks_index.get_or_init(|| {
+            Rc::new(build_local_remote_refs_index(
+                repo.view().bookmarks(),
+                false,
+            ))
+        })
     }
 
     pub fn tags_index(&self, repo: &dyn Repo) -> &Rc<CommitRefsIndex> {
         self.tags_index
-            .get_or_init(|| Rc::new(build_local_remote_refs_index(repo.view().tags())))
+            .get_or_init(|| Rc::new(build_local_remote_refs_index(repo.view().tags(), true)))
     }
 
     pub fn git_refs_index(&self, repo: &dyn Repo) -> &Rc<CommitRefsIndex> {
@@ -1011,6 +1036,11 @@ impl<'repo> CommitKeywordCache<'repo> {
             .get_or_init(|| Rc::new(build_commit_refs_index(repo.view().git_refs())))
     }
 
+    pub fn git_notes(&self, repo: &dyn Repo) -> &Rc<git_util::GitNotes> {
+        self.git_notes
+            .get_or_init(|| Rc::new(git_util::GitNotes::load(repo)))
+    }
+
     pub fn is_immutable_fn(
         &self,
         language: &CommitTemplateLanguage<'repo>,
@@ -1070,6 +1100,16 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "notes",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let notes = language.keyword_cache.git_notes(language.repo).clone();
+            let out_property = self_property
+                .and_then(move |commit| Ok(notes.get(commit.id())?.unwrap_or_default()));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "change_id",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -1539,7 +1579,7 @@ fn builtin_commit_evolution_entry_methods<'repo>()
 }
 
 /// Bookmark or tag name with metadata.
-#[derive(Debug, serde::Serialize)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct CommitRef {
     // Not using Ref/GitRef/RemoteName types here because it would be overly
     // complex to generalize the name type as T: RefName|GitRefName.
@@ -1559,9 +1599,15 @@ pub struct CommitRef {
     /// ref is synchronized with the local.
     #[serde(skip)] // internal state used mainly for Template impl
     synced: bool,
+    /// Whether this is a tag, which may point to an annotated tag object.
+    #[serde(skip)]
+    is_tag: bool,
+    /// Lazily loaded annotated tag object.
+    #[serde(skip)]
+    tag_annotation: OnceCell<Option<Rc<TagAnnotation>>>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct TrackingRef {
     /// Local ref target which tracks the other remote ref.
     target: RefTarget,
@@ -1591,6 +1637,8 @@ impl CommitRef {
             target,
             tracking_ref: None,
             synced,
+            is_tag: false,
+            tag_annotation: OnceCell::new(),
         })
     }
 
@@ -1626,6 +1674,8 @@ impl CommitRef {
             target: remote_ref.target,
             tracking_ref,
             synced,
+            is_tag: false,
+            tag_annotation: OnceCell::new(),
         })
     }
 
@@ -1641,9 +1691,18 @@ impl CommitRef {
             target,
             tracking_ref: None,
             synced: false, // has no local counterpart
+            is_tag: false,
+            tag_annotation: OnceCell::new(),
         })
     }
 
+    /// Marks this ref as a tag, which may point to an annotated tag object.
+    pub fn into_tag(self: Rc<Self>) -> Rc<Self> {
+        let mut commit_ref = Rc::unwrap_or_clone(self);
+        commit_ref.is_tag = true;
+        Rc::new(commit_ref)
+    }
+
     /// Local name.
     pub fn name(&self) -> &str {
         self.name.as_ref()
@@ -1727,6 +1786,49 @@ impl CommitRef {
             })
             .copied()
     }
+
+    /// Annotated tag object if this is a tag pointing to one.
+    fn tag_annotation(&self, repo: &dyn Repo) -> Option<&Rc<TagAnnotation>> {
+        if !self.is_tag {
+            return None;
+        }
+        self.tag_annotation
+            .get_or_init(|| TagAnnotation::load(repo, self.name(), self.remote_name()).map(Rc::new))
+            .as_ref()
+    }
+}
+
+/// Message, tagger, and signature of an annotated tag.
+#[derive(Debug)]
+struct TagAnnotation {
+    message: String,
+    tagger: Option<Signature>,
+    secure_sig: Option<SecureSig>,
+    verification: OnceCell<Verification>,
+}
+
+impl TagAnnotation {
+    /// Reads the annotated tag object from the Git repo. Returns `None` if the
+    /// repo isn't backed by Git, or the tag is a lightweight tag.
+    #[cfg(feature = "git")]
+    fn load(repo: &dyn Repo, name: &str, remote: Option<&str>) -> Option<Self> {
+        let git_repo = jj_lib::git::get_git_repo(repo.store()).ok()?;
+        let remote = remote.map(jj_lib::ref_name::RemoteName::new);
+        let signer = repo.store().signer();
+        let tag =
+            jj_lib::git::get_git_tag(&git_repo, RefName::new(name), remote, signer).ok()??;
+        Some(Self {
+            message: tag.message,
+            tagger: tag.tagger,
+            secure_sig: tag.secure_sig,
+            verification: OnceCell::new(),
+        })
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn load(_repo: &dyn Repo, _name: &str, _remote: Option<&str>) -> Option<Self> {
+        None
+    }
 }
 
 // If wrapping with Rc<T> becomes common, add generic impl for Rc<T>.
@@ -1858,6 +1960,153 @@ fn builtin_workspace_ref_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'rep
     map
 }
 
+/// Working-copy state of a workspace on disk for templating.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorkspaceStatus {
+    #[serde(flatten)]
+    workspace: WorkspaceRef,
+    /// Absolute path to the workspace root.
+    root: Option<String>,
+    /// Operation the working copy was last updated to.
+    operation_id: Option<OperationId>,
+    /// Whether the working copy is behind the repo's view.
+    stale: Option<bool>,
+    /// Whether the working copy has changes that aren't snapshotted yet.
+    dirty: Option<bool>,
+    /// Number of conflicted paths in the working-copy commit.
+    conflict_count: usize,
+    /// Reason why the working-copy state couldn't be loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl WorkspaceStatus {
+    /// Creates status of the workspace whose working copy was loaded.
+    pub fn new(
+        workspace: WorkspaceRef,
+        root: String,
+        operation_id: OperationId,
+        stale: bool,
+        dirty: bool,
+    ) -> Self {
+        let conflict_count = workspace.target.tree().conflicts().count();
+        Self {
+            workspace,
+            root: Some(root),
+            operation_id: Some(operation_id),
+            stale: Some(stale),
+            dirty: Some(dirty),
+            conflict_count,
+            error: None,
+        }
+    }
+
+    /// Creates status of the workspace whose working copy couldn't be loaded.
+    pub fn unavailable(workspace: WorkspaceRef, root: Option<String>, error: String) -> Self {
+        let conflict_count = workspace.target.tree().conflicts().count();
+        Self {
+            workspace,
+            root,
+            operation_id: None,
+            stale: None,
+            dirty: None,
+            conflict_count,
+            error: Some(error),
+        }
+    }
+
+    /// Returns the workspace reference.
+    pub fn workspace(&self) -> &WorkspaceRef {
+        &self.workspace
+    }
+
+    fn get<T>(&self, value: Option<T>) -> Result<T, TemplatePropertyError> {
+        value.ok_or_else(|| {
+            let message = self.error.as_deref().unwrap_or("unknown error");
+            TemplatePropertyError(
+                format!(
+                    "Failed to load workspace {}: {message}",
+                    self.workspace.name().as_symbol()
+                )
+                .into(),
+            )
+        })
+    }
+}
+
+fn builtin_workspace_status_methods<'repo>()
+-> CommitTemplateBuildMethodFnMap<'repo, WorkspaceStatus> {
+    let mut map = CommitTemplateBuildMethodFnMap::<WorkspaceStatus>::new();
+    map.insert(
+        "workspace",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.workspace);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "name",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|status| RefSymbolBuf(status.workspace.name.into()));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "target",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|status| status.workspace.target);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "root",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|status| status.get(status.root.clone()));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "operation_id",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|status| status.get(status.operation_id.clone()));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "stale",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|status| status.get(status.stale));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "dirty",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|status| status.get(status.dirty));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "conflict_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|status| Ok(i64::try_from(status.conflict_count)?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
 #[expect(clippy::ref_option)]
 fn serialize_tracking_target<S>(
     tracking_ref: &Option<TrackingRef>,
@@ -1988,6 +2237,45 @@ fn builtin_commit_ref_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "tag_message",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property.map(|commit_ref| {
+                commit_ref
+                    .tag_annotation(repo)
+                    .map(|annotation| annotation.message.clone())
+                    .unwrap_or_default()
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "tagger",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property.map(|commit_ref| {
+                commit_ref
+                    .tag_annotation(repo)
+                    .and_then(|annotation| annotation.tagger.clone())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "tag_signature",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property.map(|commit_ref| {
+                let annotation = commit_ref.tag_annotation(repo)?;
+                CryptographicSignature::from_tag(repo.store().clone(), annotation.clone())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -2012,7 +2300,15 @@ impl CommitRefsIndex {
 
 fn build_local_remote_refs_index<'a>(
     local_remote_refs: impl IntoIterator<Item = (&'a RefName, LocalRemoteRefTarget<'a>)>,
+    is_tag: bool,
 ) -> CommitRefsIndex {
+    let with_kind = |commit_ref: Rc<CommitRef>| {
+        if is_tag {
+            commit_ref.into_tag()
+        } else {
+            commit_ref
+        }
+    };
     let mut index = CommitRefsIndex::default();
     for (name, target) in local_remote_refs {
         let local_target = target.local_target;
@@ -2023,11 +2319,11 @@ fn build_local_remote_refs_index<'a>(
                 local_target.clone(),
                 remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
             );
-            index.insert(local_target.added_ids(), commit_ref);
+            index.insert(local_target.added_ids(), with_kind(commit_ref));
         }
         for &(remote_name, remote_ref) in &remote_refs {
             let commit_ref = CommitRef::remote(name, remote_name, remote_ref.clone(), local_target);
-            index.insert(remote_ref.target.added_ids(), commit_ref);
+            index.insert(remote_ref.target.added_ids(), with_kind(commit_ref));
         }
     }
     index
@@ -2460,6 +2756,9 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                             conflict_marker_style,
                         )
                         .block_on()
+                        // Templates can't report tokenizer errors, but they
+                        // are logged.
+                        .map(drop)
                     })
                 })
                 .into_template();
@@ -2511,7 +2810,8 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
     map.insert(
         "stat",
         |language, diagnostics, build_ctx, self_property, function| {
-            let ([], [width_node]) = function.expect_arguments()?;
+            let ([], [width_node, moved_node]) =
+                function.expect_named_arguments(&["width", "moved"])?;
             let width_property = width_node
                 .map(|node| {
                     template_builder::expect_usize_expression(
@@ -2522,23 +2822,40 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     )
                 })
                 .transpose()?;
+            let moved_property = moved_node
+                .map(|node| {
+                    template_builder::expect_boolean_expression(
+                        language,
+                        diagnostics,
+                        build_ctx,
+                        node,
+                    )
+                })
+                .transpose()?;
             let path_converter = language.path_converter;
-            // No user configuration exists for diff stat.
-            let options = diff_util::DiffStatOptions::default();
             let conflict_marker_style = language.conflict_marker_style;
             // TODO: cache and reuse stats within the current evaluation?
-            let out_property = (self_property, width_property).and_then(move |(diff, width)| {
-                let store = diff.from_tree.store();
-                let tree_diff = diff.diff_stream();
-                let stats = DiffStats::calculate(store, tree_diff, &options, conflict_marker_style)
-                    .block_on()?;
-                Ok(DiffStatsFormatted {
-                    stats,
-                    path_converter,
-                    // TODO: fall back to current available width
-                    width: width.unwrap_or(80),
-                })
-            });
+            let out_property = (self_property, width_property, moved_property).and_then(
+                move |(diff, width, moved)| {
+                    let store = diff.from_tree.store();
+                    let tree_diff = diff.diff_stream();
+                    // No user configuration exists for diff stat. Finding moved
+                    // lines is expensive, so it's done only if requested.
+                    let options = diff_util::DiffStatOptions {
+                        moved_lines: moved.unwrap_or(false),
+                        ..Default::default()
+                    };
+                    let stats =
+                        DiffStats::calculate(store, tree_diff, &options, conflict_marker_style)
+                            .block_on()?;
+                    Ok(DiffStatsFormatted {
+                        stats,
+                        path_converter,
+                        // TODO: fall back to current available width
+                        width: width.unwrap_or(80),
+                    })
+                },
+            );
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -2671,6 +2988,22 @@ pub struct TreeEntry {
     pub value: MergedTreeValue,
 }
 
+impl serde::Serialize for TreeEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+        let mut state = serializer.serialize_struct("TreeEntry", 5)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("file_type", describe_file_type(&self.value))?;
+        state.serialize_field(
+            "executable",
+            &is_executable_file(&self.value).unwrap_or_default(),
+        )?;
+        state.serialize_field("conflict", &!self.value.is_resolved())?;
+        state.serialize_field("conflict_side_count", &self.value.simplify().num_sides())?;
+        state.end()
+    }
+}
+
 fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeEntry> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -2786,6 +3119,15 @@ fn builtin_diff_stats_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "total_moved",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|stats| Ok(i64::try_from(stats.count_total_moved())?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -2852,6 +3194,24 @@ fn builtin_diff_stat_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'r
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "moved_lines_added",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|entry| Ok(i64::try_from(entry.moved_added_removed().0)?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "moved_lines_removed",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|entry| Ok(i64::try_from(entry.moved_added_removed().1)?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "bytes_delta",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -2866,19 +3226,45 @@ fn builtin_diff_stat_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'r
 
 #[derive(Debug)]
 pub struct CryptographicSignature {
-    commit: Commit,
+    source: SignatureSource,
+}
+
+#[derive(Debug)]
+enum SignatureSource {
+    Commit(Commit),
+    Tag {
+        store: Arc<Store>,
+        annotation: Rc<TagAnnotation>,
+    },
 }
 
 impl CryptographicSignature {
     fn new(commit: Commit) -> Option<Self> {
-        commit.is_signed().then_some(Self { commit })
+        commit.is_signed().then(|| Self {
+            source: SignatureSource::Commit(commit),
+        })
+    }
+
+    fn from_tag(store: Arc<Store>, annotation: Rc<TagAnnotation>) -> Option<Self> {
+        annotation.secure_sig.is_some().then(|| Self {
+            source: SignatureSource::Tag { store, annotation },
+        })
     }
 
     fn verify(&self) -> SignResult<Verification> {
-        self.commit
-            .verification()
-            .transpose()
-            .expect("must have signature")
+        match &self.source {
+            SignatureSource::Commit(commit) => commit
+                .verification()
+                .transpose()
+                .expect("must have signature"),
+            SignatureSource::Tag { store, annotation } => {
+                let sig = annotation.secure_sig.as_ref().expect("must have signature");
+                annotation
+                    .verification
+                    .get_or_try_init(|| store.signer().verify_data(&sig.data, &sig.sig))
+                    .cloned()
+            }
+        }
     }
 
     fn status(&self) -> SignResult<SigStatus> {
@@ -2896,6 +3282,21 @@ impl CryptographicSignature {
         self.verify()
             .map(|verification| verification.display.unwrap_or_default())
     }
+
+    /// Whether the signature was made by a key trusted for the author (or the
+    /// tagger.)
+    fn trusted(&self) -> SignResult<bool> {
+        let verification = self.verify()?;
+        let (store, email) = match &self.source {
+            SignatureSource::Commit(commit) => (commit.store(), commit.author().email.as_str()),
+            SignatureSource::Tag { store, annotation } => {
+                let tagger = annotation.tagger.as_ref();
+                (store, tagger.map_or("", |tagger| tagger.email.as_str()))
+            }
+        };
+        let trust = store.signer().trust_store();
+        Ok(trust.is_trusted(&verification, email))
+    }
 }
 
 fn builtin_cryptographic_signature_methods<'repo>()
@@ -2931,6 +3332,18 @@ fn builtin_cryptographic_signature_methods<'repo>()
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "trusted",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| match sig.trusted() {
+                Ok(trusted) => Ok(trusted),
+                Err(SignError::InvalidSignatureFormat) => Ok(false),
+                Err(err) => Err(err.into()),
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
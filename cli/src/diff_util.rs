@@ -16,13 +16,17 @@ use std::borrow::Cow;
 use std::cmp::max;
 use std::future;
 use std::io;
+use std::io::Write as _;
 use std::iter;
+use std::mem;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::pin::pin;
 
 use bstr::BStr;
 use bstr::BString;
+use bstr::ByteSlice as _;
 use clap_complete::ArgValueCandidates;
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
@@ -41,6 +45,7 @@ use jj_lib::conflicts::ConflictMaterializeOptions;
 use jj_lib::conflicts::MaterializedTreeDiffEntry;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_merge_result_to_bytes_with_tokenizer;
 use jj_lib::conflicts::materialized_diff_stream;
 use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopiesTreeDiffEntryPath;
@@ -54,7 +59,11 @@ use jj_lib::diff_presentation::FileContent;
 use jj_lib::diff_presentation::LineCompareMode;
 use jj_lib::diff_presentation::diff_by_line;
 use jj_lib::diff_presentation::file_content_for_diff;
+use jj_lib::diff_presentation::moved::ChangedLines;
+use jj_lib::diff_presentation::moved::MovedLines;
+use jj_lib::diff_presentation::moved::find_moved_lines;
 use jj_lib::diff_presentation::unified::DiffLineType;
+use jj_lib::diff_presentation::unified::GitDiffPart;
 use jj_lib::diff_presentation::unified::UnifiedDiffError;
 use jj_lib::diff_presentation::unified::git_diff_part;
 use jj_lib::diff_presentation::unified::unified_diff_hunks;
@@ -77,16 +86,25 @@ use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
+use jj_lib::word_tokenizer::ContentsWordTokenizer;
+use jj_lib::word_tokenizer::DefaultWordTokenizer;
+use jj_lib::word_tokenizer::WordTokenizer;
+use jj_lib::word_tokenizer::WordTokenizerError;
 use thiserror::Error;
 use tracing::instrument;
+use unicode_width::UnicodeWidthChar as _;
 use unicode_width::UnicodeWidthStr as _;
 
+use crate::cli_util::print_word_tokenizer_errors;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
 use crate::commit_templater;
 use crate::config::CommandNameAndArgs;
+use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
+use crate::json_output::text_only_arg_error;
+use crate::json_output::write_json_record;
 use crate::merge_tools;
 use crate::merge_tools::DiffGenerateError;
 use crate::merge_tools::DiffToolMode;
@@ -101,7 +119,7 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 #[command(next_help_heading = "Diff Formatting Options")]
 #[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
-#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words"])))]
+#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "side_by_side"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or deleted
     #[arg(long, short)]
@@ -136,6 +154,13 @@ pub struct DiffFormatArgs {
     #[arg(long)]
     pub color_words: bool,
 
+    /// Show changed lines side by side in two columns
+    ///
+    /// The columns are sized to fit the terminal width. Long lines are
+    /// wrapped, and changed words are highlighted.
+    #[arg(long)]
+    pub side_by_side: bool,
+
     /// Generate diff by external command
     ///
     /// A builtin format can also be specified as `:<name>`. For example,
@@ -148,6 +173,12 @@ pub struct DiffFormatArgs {
     #[arg(long)]
     context: Option<usize>,
 
+    /// Highlight blocks of lines moved within or between files
+    ///
+    /// This applies to the `--git` and `--color-words` formats.
+    #[arg(long)]
+    color_moved: bool,
+
     // Short flags are set by command to avoid future conflicts.
     /// Ignore whitespace when comparing lines.
     #[arg(long)] // short = 'w'
@@ -167,6 +198,8 @@ pub enum DiffFormat {
     NameOnly,
     Git(Box<UnifiedDiffOptions>),
     ColorWords(Box<ColorWordsDiffOptions>),
+    SideBySide(Box<SideBySideDiffOptions>),
+    Json(Box<JsonDiffOptions>),
     Tool(Box<ExternalMergeTool>),
 }
 
@@ -178,6 +211,8 @@ enum BuiltinFormatKind {
     NameOnly,
     Git,
     ColorWords,
+    SideBySide,
+    Json,
 }
 
 impl BuiltinFormatKind {
@@ -191,6 +226,8 @@ impl BuiltinFormatKind {
         Self::NameOnly,
         Self::Git,
         Self::ColorWords,
+        Self::SideBySide,
+        // Json isn't selectable by name. It's requested by `--output json`.
     ];
 
     fn from_name(name: &str) -> Result<Self, String> {
@@ -201,6 +238,7 @@ impl BuiltinFormatKind {
             "name-only" => Ok(Self::NameOnly),
             "git" => Ok(Self::Git),
             "color-words" => Ok(Self::ColorWords),
+            "side-by-side" => Ok(Self::SideBySide),
             _ => Err(format!("Invalid builtin diff format: {name}")),
         }
     }
@@ -224,6 +262,8 @@ impl BuiltinFormatKind {
             Some(Self::Git)
         } else if args.color_words {
             Some(Self::ColorWords)
+        } else if args.side_by_side {
+            Some(Self::SideBySide)
         } else {
             None
         }
@@ -232,7 +272,7 @@ impl BuiltinFormatKind {
     fn is_short(self) -> bool {
         match self {
             Self::Summary | Self::Stat | Self::Types | Self::NameOnly => true,
-            Self::Git | Self::ColorWords => false,
+            Self::Git | Self::ColorWords | Self::SideBySide | Self::Json => false,
         }
     }
 
@@ -244,6 +284,8 @@ impl BuiltinFormatKind {
             Self::NameOnly => "name-only",
             Self::Git => "git",
             Self::ColorWords => "color-words",
+            Self::SideBySide => "side-by-side",
+            Self::Json => "json",
         }
     }
 
@@ -271,6 +313,16 @@ impl BuiltinFormatKind {
                 options.merge_args(args);
                 Ok(DiffFormat::ColorWords(Box::new(options)))
             }
+            Self::SideBySide => {
+                let mut options = SideBySideDiffOptions::from_settings(settings)?;
+                options.merge_args(args);
+                Ok(DiffFormat::SideBySide(Box::new(options)))
+            }
+            Self::Json => {
+                let mut options = JsonDiffOptions::from_settings(settings)?;
+                options.merge_args(args);
+                Ok(DiffFormat::Json(Box::new(options)))
+            }
         }
     }
 }
@@ -311,6 +363,19 @@ pub fn diff_formats_for(
     }
 }
 
+/// Returns the JSON diff format requested by `--output json`. Other diff
+/// formats can't be requested at the same time.
+pub fn json_diff_formats_for(
+    settings: &UserSettings,
+    args: &DiffFormatArgs,
+) -> Result<Vec<DiffFormat>, CommandError> {
+    let formats = diff_formats_from_args(settings, args)?;
+    if formats.iter().any(|f| f.is_some()) {
+        return Err(text_only_arg_error("Other diff formats"));
+    }
+    Ok(vec![BuiltinFormatKind::Json.to_format(settings, args)?])
+}
+
 /// Returns a list of requested diff formats for log-like commands, which may be
 /// empty.
 pub fn diff_formats_for_log(
@@ -477,6 +542,7 @@ impl<'a> DiffRenderer<'a> {
 
         let store = self.repo.store();
         let path_converter = self.path_converter;
+        let mut tokenizer_errors = vec![];
         for format in &self.formats {
             match format {
                 DiffFormat::Summary => {
@@ -513,7 +579,7 @@ impl<'a> DiffRenderer<'a> {
                 }
                 DiffFormat::ColorWords(options) => {
                     let tree_diff = diff_stream();
-                    show_color_words_diff(
+                    let errors = show_color_words_diff(
                         *formatter.labeled("color_words"),
                         store,
                         tree_diff,
@@ -523,6 +589,34 @@ impl<'a> DiffRenderer<'a> {
                         self.conflict_marker_style,
                     )
                     .await?;
+                    tokenizer_errors.extend(errors);
+                }
+                DiffFormat::SideBySide(options) => {
+                    let tree_diff = diff_stream();
+                    let errors = show_side_by_side_diff(
+                        *formatter.labeled("side_by_side"),
+                        store,
+                        tree_diff,
+                        conflict_labels,
+                        path_converter,
+                        options,
+                        self.conflict_marker_style,
+                        width,
+                    )
+                    .await?;
+                    tokenizer_errors.extend(errors);
+                }
+                DiffFormat::Json(options) => {
+                    let tree_diff = diff_stream();
+                    show_json_diff(
+                        formatter,
+                        store,
+                        tree_diff,
+                        conflict_labels,
+                        options,
+                        self.conflict_marker_style,
+                    )
+                    .await?;
                 }
                 DiffFormat::Tool(tool) => {
                     match tool.diff_invocation_mode {
@@ -559,6 +653,7 @@ impl<'a> DiffRenderer<'a> {
                 }
             }
         }
+        print_word_tokenizer_errors(ui, &tokenizer_errors)?;
         Ok(())
     }
 
@@ -566,6 +661,7 @@ impl<'a> DiffRenderer<'a> {
         &self,
         formatter: &mut dyn Formatter,
         descriptions: Diff<&Merge<&str>>,
+        width: usize,
     ) -> Result<(), DiffRenderError> {
         if !descriptions.is_changed() {
             return Ok(());
@@ -597,13 +693,30 @@ impl<'a> DiffRenderer<'a> {
                 DiffFormat::ColorWords(options) => {
                     writeln!(formatter.labeled("header"), "Modified commit description:")?;
                     show_color_words_diff_hunks(
+                        formatter,
+                        descriptions,
+                        Diff::new(&ConflictLabels::unlabeled(), &ConflictLabels::unlabeled()),
+                        &MovedLines::default(),
+                        options,
+                        &materialize_options,
+                        &DefaultWordTokenizer,
+                    )?;
+                }
+                DiffFormat::SideBySide(options) => {
+                    writeln!(formatter.labeled("header"), "Modified commit description:")?;
+                    show_side_by_side_diff_hunks(
                         formatter,
                         descriptions,
                         Diff::new(&ConflictLabels::unlabeled(), &ConflictLabels::unlabeled()),
                         options,
                         &materialize_options,
+                        &DefaultWordTokenizer,
+                        SideBySideLayout::new(width),
                     )?;
                 }
+                // Descriptions aren't files, so they can't be represented in
+                // the per-path JSON records.
+                DiffFormat::Json(_) => {}
                 DiffFormat::Tool(_) => {
                     // TODO: materialize commit description as file?
                 }
@@ -643,6 +756,7 @@ impl<'a> DiffRenderer<'a> {
         self.show_diff_commit_descriptions(
             *formatter,
             Diff::new(&from_description, &to_description),
+            width,
         )?;
         self.show_diff_trees(
             ui,
@@ -739,6 +853,8 @@ pub struct ColorWordsDiffOptions {
     pub line_diff: LineDiffOptions,
     /// Maximum number of removed/added word alternation to inline.
     pub max_inline_alternation: Option<usize>,
+    /// Whether to highlight moved lines.
+    pub color_moved: bool,
 }
 
 impl ColorWordsDiffOptions {
@@ -759,6 +875,7 @@ impl ColorWordsDiffOptions {
             context: settings.get("diff.color-words.context")?,
             line_diff: LineDiffOptions::default(),
             max_inline_alternation,
+            color_moved: settings.get("diff.color-words.color-moved")?,
         })
     }
 
@@ -767,6 +884,9 @@ impl ColorWordsDiffOptions {
             self.context = context;
         }
         self.line_diff.merge_args(args);
+        if args.color_moved {
+            self.color_moved = true;
+        }
     }
 }
 
@@ -774,42 +894,81 @@ fn show_color_words_diff_hunks<T: AsRef<[u8]>>(
     formatter: &mut dyn Formatter,
     contents: Diff<&Merge<T>>,
     conflict_labels: Diff<&ConflictLabels>,
+    moved_lines: &MovedLines,
     options: &ColorWordsDiffOptions,
     materialize_options: &ConflictMaterializeOptions,
-) -> io::Result<()> {
+    word_tokenizer: &dyn WordTokenizer,
+) -> io::Result<Vec<WordTokenizerError>> {
     let line_number = DiffLineNumber { left: 1, right: 1 };
     let labels = Diff::new("removed", "added");
     if let (Some(left), Some(right)) = (contents.before.as_resolved(), contents.after.as_resolved())
     {
-        let contents = Diff::new(left, right).map(BStr::new);
-        show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
-        return Ok(());
+        let contents = Diff::new(left.as_ref(), right.as_ref());
+        let word_tokenizer = ContentsWordTokenizer::new(word_tokenizer, contents.into_array());
+        show_color_words_resolved_hunks(
+            formatter,
+            Diff::new((0, 0..left.as_ref().len()), (1, 0..right.as_ref().len())),
+            line_number,
+            moved_lines,
+            labels,
+            options,
+            &word_tokenizer,
+        )?;
+        return Ok(word_tokenizer.into_errors());
     }
+    let mut tokenizer_errors = vec![];
     match options.conflict {
         ConflictDiffMethod::Materialize => {
             let contents = contents.zip(conflict_labels).map(|(side, labels)| {
-                materialize_merge_result_to_bytes(side, labels, materialize_options)
+                let materialized = materialize_merge_result_to_bytes_with_tokenizer(
+                    side,
+                    labels,
+                    materialize_options,
+                    word_tokenizer,
+                );
+                tokenizer_errors.extend(materialized.tokenizer_errors);
+                materialized.merged
             });
+            let word_tokenizer = ContentsWordTokenizer::new(
+                word_tokenizer,
+                contents.as_ref().map(|c| c.as_bytes()).into_array(),
+            );
             show_color_words_resolved_hunks(
                 formatter,
-                contents.as_ref().map(BStr::new),
+                Diff::new((0, 0..contents.before.len()), (1, 0..contents.after.len())),
                 line_number,
+                &MovedLines::default(),
                 labels,
                 options,
+                &word_tokenizer,
             )?;
+            tokenizer_errors.extend(word_tokenizer.into_errors());
         }
         ConflictDiffMethod::Pair => {
-            let contents = contents.map(|side| files::merge(side, &materialize_options.merge));
+            let contents = contents.map(|side| {
+                let merged =
+                    files::merge_with_tokenizer(side, &materialize_options.merge, word_tokenizer);
+                tokenizer_errors.extend(merged.tokenizer_errors);
+                merged.merged
+            });
+            let word_tokenizer = ContentsWordTokenizer::new(
+                word_tokenizer,
+                [&contents.before, &contents.after]
+                    .into_iter()
+                    .flat_map(|side| side.iter().map(|c| c.as_bytes())),
+            );
             show_color_words_conflict_hunks(
                 formatter,
                 contents.as_ref(),
                 line_number,
                 labels,
                 options,
+                &word_tokenizer,
             )?;
+            tokenizer_errors.extend(word_tokenizer.into_errors());
         }
     }
-    Ok(())
+    Ok(tokenizer_errors)
 }
 
 fn show_color_words_conflict_hunks(
@@ -818,6 +977,7 @@ fn show_color_words_conflict_hunks(
     mut line_number: DiffLineNumber,
     labels: Diff<&str>,
     options: &ColorWordsDiffOptions,
+    word_tokenizer: &ContentsWordTokenizer,
 ) -> io::Result<DiffLineNumber> {
     let num_lefts = contents.before.as_slice().len();
     let line_diff = diff_by_line(
@@ -830,7 +990,13 @@ fn show_color_words_conflict_hunks(
     let mut contexts: Vec<Diff<&BStr>> = Vec::new();
     let mut emitted = false;
 
-    for hunk in files::conflict_diff_hunks(line_diff.hunks(), num_lefts) {
+    // The terms of both sides are indexed in the tokenizer in the same order as
+    // the diff inputs.
+    let hunks = iter::zip(
+        files::conflict_diff_hunks(line_diff.hunks(), num_lefts),
+        line_diff.hunk_ranges(),
+    );
+    for (hunk, hunk_ranges) in hunks {
         match hunk.kind {
             // There may be conflicts in matching hunk, but just pick one. It
             // would be too verbose to show all conflict pairs as context.
@@ -851,13 +1017,20 @@ fn show_color_words_conflict_hunks(
                 )?;
                 contexts.clear();
                 emitted = true;
-                line_number = if let (Some(&left), Some(&right)) =
-                    (hunk.lefts.as_resolved(), hunk.rights.as_resolved())
-                {
+                let ranges = &hunk_ranges.ranges;
+                line_number = if hunk.lefts.is_resolved() && hunk.rights.is_resolved() {
+                    let word_diff = ContentDiff::by_word_in_contents(
+                        word_tokenizer,
+                        [
+                            (0, ranges[0].clone()),
+                            (num_lefts, ranges[num_lefts].clone()),
+                        ],
+                    );
                     show_color_words_diff_lines(
                         formatter,
-                        Diff::new(left, right),
+                        &word_diff,
                         line_number,
+                        &MovedLines::default(),
                         labels,
                         options,
                     )?
@@ -865,9 +1038,11 @@ fn show_color_words_conflict_hunks(
                     show_color_words_unresolved_hunk(
                         formatter,
                         &hunk,
+                        Diff::new(&ranges[..num_lefts], &ranges[num_lefts..]),
                         line_number,
                         labels,
                         options,
+                        word_tokenizer,
                     )?
                 }
             }
@@ -887,12 +1062,17 @@ fn show_color_words_conflict_hunks(
     )
 }
 
+/// Shows the conflict `hunk`. The `ranges` are the positions of the terms of
+/// both sides in the contents of the `word_tokenizer`, where the left terms
+/// come first.
 fn show_color_words_unresolved_hunk(
     formatter: &mut dyn Formatter,
     hunk: &ConflictDiffHunk,
+    ranges: Diff<&[Range<usize>]>,
     line_number: DiffLineNumber,
     labels: Diff<&str>,
     options: &ColorWordsDiffOptions,
+    word_tokenizer: &ContentsWordTokenizer,
 ) -> io::Result<DiffLineNumber> {
     let hunk_desc = if hunk.lefts.is_resolved() {
         "Created conflict"
@@ -916,7 +1096,18 @@ fn show_color_words_unresolved_hunk(
     )
     .take(num_terms);
     let mut max_line_number = line_number;
-    for (i, ((left_index, &left_content), (right_index, &right_content))) in padded.enumerate() {
+    // Resolved sides are collapsed to a single term, so any of the ranges
+    // points to the content.
+    let left_position = |index: usize| {
+        let index = if hunk.lefts.is_resolved() { 0 } else { index };
+        (index, ranges.before[index].clone())
+    };
+    let num_lefts = ranges.before.len();
+    let right_position = |index: usize| {
+        let index = if hunk.rights.is_resolved() { 0 } else { index };
+        (num_lefts + index, ranges.after[index].clone())
+    };
+    for (i, ((left_index, _), (right_index, _))) in padded.enumerate() {
         let positive = i % 2 == 0;
         writeln!(
             formatter.labeled("hunk_header"),
@@ -928,14 +1119,20 @@ fn show_color_words_unresolved_hunk(
             right_name = if right_index % 2 == 0 { "side" } else { "base" },
             right_index = right_index / 2 + 1,
         )?;
-        let contents = Diff::new(left_content, right_content);
         let labels = match positive {
             true => labels,
             false => labels.invert(),
         };
         // Individual hunk pair may be largely the same, so diff it again.
-        let new_line_number =
-            show_color_words_resolved_hunks(formatter, contents, line_number, labels, options)?;
+        let new_line_number = show_color_words_resolved_hunks(
+            formatter,
+            Diff::new(left_position(left_index), right_position(right_index)),
+            line_number,
+            &MovedLines::default(),
+            labels,
+            options,
+            word_tokenizer,
+        )?;
         // Take max to assign unique line numbers to trailing hunks. The line
         // numbers can't be real anyway because preceding conflict hunks might
         // have been resolved.
@@ -947,19 +1144,26 @@ fn show_color_words_unresolved_hunk(
     Ok(max_line_number)
 }
 
+/// Shows the diff of the `inputs`, which are pairs of the index of the content
+/// in the `word_tokenizer` and the range in it.
 fn show_color_words_resolved_hunks(
     formatter: &mut dyn Formatter,
-    contents: Diff<&BStr>,
+    inputs: Diff<(usize, Range<usize>)>,
     mut line_number: DiffLineNumber,
+    moved_lines: &MovedLines,
     labels: Diff<&str>,
     options: &ColorWordsDiffOptions,
+    word_tokenizer: &ContentsWordTokenizer,
 ) -> io::Result<DiffLineNumber> {
+    let contents = inputs
+        .as_ref()
+        .map(|(index, range)| BStr::new(&word_tokenizer.content(*index)[range.clone()]));
     let line_diff = diff_by_line(contents.into_array(), &options.line_diff.compare_mode);
     // Matching entries shouldn't appear consecutively in diff of two inputs.
     let mut context: Option<Diff<&BStr>> = None;
     let mut emitted = false;
 
-    for hunk in line_diff.hunks() {
+    for (hunk, hunk_ranges) in iter::zip(line_diff.hunks(), line_diff.hunk_ranges()) {
         let &[left, right] = hunk.contents.as_slice() else {
             panic!("hunk contents should have two sides")
         };
@@ -982,10 +1186,17 @@ fn show_color_words_resolved_hunks(
                 )?;
                 context = None;
                 emitted = true;
+                let word_inputs = iter::zip(inputs.as_ref().into_array(), &hunk_ranges.ranges).map(
+                    |((index, base), range)| {
+                        (*index, base.start + range.start..base.start + range.end)
+                    },
+                );
+                let word_diff = ContentDiff::by_word_in_contents(word_tokenizer, word_inputs);
                 line_number = show_color_words_diff_lines(
                     formatter,
-                    hunk_contents,
+                    &word_diff,
                     line_number,
+                    moved_lines,
                     labels,
                     options,
                 )?;
@@ -1059,10 +1270,13 @@ fn show_color_words_context_lines(
         } else {
             let left = left_lines.concat();
             let right = right_lines.concat();
+            // The lines differ only in whitespace, so the choice of tokenizer
+            // doesn't matter.
             show_color_words_diff_lines(
                 *formatter,
-                Diff::new(&left, &right).map(BStr::new),
+                &ContentDiff::by_word([&left, &right]),
                 line_number,
+                &MovedLines::default(),
                 labels,
                 options,
             )
@@ -1093,14 +1307,13 @@ fn show_color_words_context_lines(
 
 fn show_color_words_diff_lines(
     formatter: &mut dyn Formatter,
-    contents: Diff<&BStr>,
+    word_diff: &ContentDiff,
     mut line_number: DiffLineNumber,
+    moved_lines: &MovedLines,
     labels: Diff<&str>,
     options: &ColorWordsDiffOptions,
 ) -> io::Result<DiffLineNumber> {
-    let word_diff_hunks = ContentDiff::by_word(contents.into_array())
-        .hunks()
-        .collect_vec();
+    let word_diff_hunks = word_diff.hunks().collect_vec();
     let can_inline = match options.max_inline_alternation {
         None => true,     // unlimited
         Some(0) => false, // no need to count alternation
@@ -1109,49 +1322,73 @@ fn show_color_words_diff_lines(
             groups.map(count_diff_alternation).max().unwrap_or(0) <= max_num
         }
     };
+    let is_moved = |line_numbers: Diff<Option<u32>>| match line_numbers.into_array() {
+        [Some(left), None] => moved_lines.removed.contains(&(left as usize - 1)),
+        [None, Some(right)] => moved_lines.added.contains(&(right as usize - 1)),
+        _ => false,
+    };
     if can_inline {
         let mut diff_line_iter =
             DiffLineIterator::with_line_number(word_diff_hunks.iter(), line_number);
         for diff_line in diff_line_iter.by_ref() {
-            show_color_words_line_number(
-                formatter,
-                Diff::new(
-                    diff_line
-                        .has_left_content()
-                        .then_some(diff_line.line_number.left),
-                    diff_line
-                        .has_right_content()
-                        .then_some(diff_line.line_number.right),
-                ),
-                labels,
-            )?;
-            show_color_words_inline_hunks(formatter, &diff_line.hunks, labels)?;
+            let line_numbers = Diff::new(
+                diff_line
+                    .has_left_content()
+                    .then_some(diff_line.line_number.left),
+                diff_line
+                    .has_right_content()
+                    .then_some(diff_line.line_number.right),
+            );
+            let moved_label = if !is_moved(line_numbers) {
+                None
+            } else if line_numbers.before.is_some() {
+                Some(labels.before)
+            } else {
+                Some(labels.after)
+            };
+            with_moved_label(formatter, moved_label, |formatter| {
+                show_color_words_line_number(formatter, line_numbers, labels)?;
+                show_color_words_inline_hunks(formatter, &diff_line.hunks, labels)
+            })?;
         }
         line_number = diff_line_iter.next_line_number();
     } else {
         let lines = unzip_diff_hunks_to_lines(&word_diff_hunks);
         for tokens in &lines.before {
-            show_color_words_line_number(
-                formatter,
-                Diff::new(Some(line_number.left), None),
-                labels,
-            )?;
-            show_color_words_single_sided_line(formatter, tokens, labels.before)?;
+            let line_numbers = Diff::new(Some(line_number.left), None);
+            let moved_label = is_moved(line_numbers).then_some(labels.before);
+            with_moved_label(formatter, moved_label, |formatter| {
+                show_color_words_line_number(formatter, line_numbers, labels)?;
+                show_color_words_single_sided_line(formatter, tokens, labels.before)
+            })?;
             line_number.left += 1;
         }
         for tokens in &lines.after {
-            show_color_words_line_number(
-                formatter,
-                Diff::new(None, Some(line_number.right)),
-                labels,
-            )?;
-            show_color_words_single_sided_line(formatter, tokens, labels.after)?;
+            let line_numbers = Diff::new(None, Some(line_number.right));
+            let moved_label = is_moved(line_numbers).then_some(labels.after);
+            with_moved_label(formatter, moved_label, |formatter| {
+                show_color_words_line_number(formatter, line_numbers, labels)?;
+                show_color_words_single_sided_line(formatter, tokens, labels.after)
+            })?;
             line_number.right += 1;
         }
     }
     Ok(line_number)
 }
 
+/// Calls `show` with the formatter labeled as a moved line of the
+/// `moved_label` side.
+fn with_moved_label<T>(
+    formatter: &mut dyn Formatter,
+    moved_label: Option<&str>,
+    show: impl FnOnce(&mut dyn Formatter) -> io::Result<T>,
+) -> io::Result<T> {
+    match moved_label {
+        Some(label) => show(*formatter.labeled(label).labeled("moved")),
+        None => show(formatter),
+    }
+}
+
 fn show_color_words_line_number(
     formatter: &mut dyn Formatter,
     line_numbers: Diff<Option<u32>>,
@@ -1366,6 +1603,86 @@ fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
     }
 }
 
+/// Writes a line describing how the file changed, such as `Modified regular
+/// file path:`.
+fn show_file_change_header(
+    formatter: &mut dyn Formatter,
+    ui_paths: Diff<&str>,
+    values: Diff<&MaterializedTreeValue>,
+) -> io::Result<()> {
+    let Diff {
+        before: left_ui_path,
+        after: right_ui_path,
+    } = ui_paths;
+    let Diff {
+        before: left_value,
+        after: right_value,
+    } = values;
+    if left_value.is_absent() {
+        let description = basic_diff_file_type(right_value);
+        return writeln!(
+            formatter.labeled("header"),
+            "Added {description} {right_ui_path}:"
+        );
+    } else if right_value.is_absent() {
+        let description = basic_diff_file_type(left_value);
+        return writeln!(
+            formatter.labeled("header"),
+            "Removed {description} {right_ui_path}:"
+        );
+    }
+    let description = match (left_value, right_value) {
+        (MaterializedTreeValue::File(left), MaterializedTreeValue::File(right)) => {
+            if left.executable && right.executable {
+                "Modified executable file".to_string()
+            } else if left.executable {
+                "Executable file became non-executable at".to_string()
+            } else if right.executable {
+                "Non-executable file became executable at".to_string()
+            } else {
+                "Modified regular file".to_string()
+            }
+        }
+        (
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+        ) => "Modified conflict in".to_string(),
+        (
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+            _,
+        ) => "Resolved conflict in".to_string(),
+        (
+            _,
+            MaterializedTreeValue::FileConflict(_) | MaterializedTreeValue::OtherConflict { .. },
+        ) => "Created conflict in".to_string(),
+        (MaterializedTreeValue::Symlink { .. }, MaterializedTreeValue::Symlink { .. }) => {
+            "Symlink target changed at".to_string()
+        }
+        (_, _) => {
+            let left_type = basic_diff_file_type(left_value);
+            let right_type = basic_diff_file_type(right_value);
+            let (first, rest) = left_type.split_at(1);
+            format!(
+                "{}{} became {} at",
+                first.to_ascii_uppercase(),
+                rest,
+                right_type
+            )
+        }
+    };
+    if left_ui_path == right_ui_path {
+        writeln!(
+            formatter.labeled("header"),
+            "{description} {right_ui_path}:"
+        )
+    } else {
+        writeln!(
+            formatter.labeled("header"),
+            "{description} {right_ui_path} ({left_ui_path} => {right_ui_path}):"
+        )
+    }
+}
+
 pub async fn show_color_words_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
@@ -1374,234 +1691,946 @@ pub async fn show_color_words_diff(
     path_converter: &RepoPathUiConverter,
     options: &ColorWordsDiffOptions,
     marker_style: ConflictMarkerStyle,
-) -> Result<(), DiffRenderError> {
+) -> Result<Vec<WordTokenizerError>, DiffRenderError> {
     let materialize_options = ConflictMaterializeOptions {
         marker_style,
         marker_len: None,
         merge: store.merge_options().clone(),
     };
-    let empty_content = || Merge::resolved(BString::default());
-    let mut diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels);
-    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
-        let left_path = path.source();
-        let right_path = path.target();
-        let left_ui_path = path_converter.format_file_path(left_path);
-        let right_ui_path = path_converter.format_file_path(right_path);
-        let Diff {
-            before: left_value,
-            after: right_value,
-        } = values?;
-
-        match (&left_value, &right_value) {
-            (MaterializedTreeValue::AccessDenied(source), _) => {
-                write!(
-                    formatter.labeled("access-denied"),
-                    "Access denied to {left_ui_path}:"
-                )?;
-                writeln!(formatter, " {source}")?;
-                continue;
-            }
-            (_, MaterializedTreeValue::AccessDenied(source)) => {
-                write!(
-                    formatter.labeled("access-denied"),
-                    "Access denied to {right_ui_path}:"
-                )?;
-                writeln!(formatter, " {source}")?;
-                continue;
-            }
-            _ => {}
-        }
-        if left_value.is_absent() {
-            let description = basic_diff_file_type(&right_value);
-            writeln!(
-                formatter.labeled("header"),
-                "Added {description} {right_ui_path}:"
-            )?;
-            let right_content = diff_content_as_merge(right_path, right_value).await?;
-            if right_content.contents.is_empty() {
-                writeln!(formatter.labeled("empty"), "    (empty)")?;
-            } else if right_content.is_binary {
-                writeln!(formatter.labeled("binary"), "    (binary)")?;
-            } else {
-                show_color_words_diff_hunks(
-                    formatter,
-                    Diff::new(&empty_content(), &right_content.contents.file_content),
-                    Diff::new(
-                        &ConflictLabels::unlabeled(),
-                        &right_content.contents.conflict_labels,
-                    ),
-                    options,
-                    &materialize_options,
-                )?;
-            }
-        } else if right_value.is_present() {
-            let description = match (&left_value, &right_value) {
-                (MaterializedTreeValue::File(left), MaterializedTreeValue::File(right)) => {
-                    if left.executable && right.executable {
-                        "Modified executable file".to_string()
-                    } else if left.executable {
-                        "Executable file became non-executable at".to_string()
-                    } else if right.executable {
-                        "Non-executable file became executable at".to_string()
-                    } else {
-                        "Modified regular file".to_string()
-                    }
-                }
-                (
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                ) => "Modified conflict in".to_string(),
-                (
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                    _,
-                ) => "Resolved conflict in".to_string(),
-                (
-                    _,
-                    MaterializedTreeValue::FileConflict(_)
-                    | MaterializedTreeValue::OtherConflict { .. },
-                ) => "Created conflict in".to_string(),
-                (MaterializedTreeValue::Symlink { .. }, MaterializedTreeValue::Symlink { .. }) => {
-                    "Symlink target changed at".to_string()
+    let maybe_color = formatter.maybe_color();
+    let diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels).then(
+        async |MaterializedTreeDiffEntry { path, values }| {
+            let left_path = path.source();
+            let right_path = path.target();
+            let left_ui_path = path_converter.format_file_path(left_path);
+            let right_ui_path = path_converter.format_file_path(right_path);
+            let Diff {
+                before: left_value,
+                after: right_value,
+            } = values?;
+
+            let mut header = FormatRecorder::new(maybe_color);
+            match (&left_value, &right_value) {
+                (MaterializedTreeValue::AccessDenied(source), _) => {
+                    write!(
+                        header.labeled("access-denied"),
+                        "Access denied to {left_ui_path}:"
+                    )?;
+                    writeln!(header, " {source}")?;
+                    return Ok((header, None));
                 }
-                (_, _) => {
-                    let left_type = basic_diff_file_type(&left_value);
-                    let right_type = basic_diff_file_type(&right_value);
-                    let (first, rest) = left_type.split_at(1);
-                    format!(
-                        "{}{} became {} at",
-                        first.to_ascii_uppercase(),
-                        rest,
-                        right_type
-                    )
+                (_, MaterializedTreeValue::AccessDenied(source)) => {
+                    write!(
+                        header.labeled("access-denied"),
+                        "Access denied to {right_ui_path}:"
+                    )?;
+                    writeln!(header, " {source}")?;
+                    return Ok((header, None));
                 }
-            };
+                _ => {}
+            }
+            show_file_change_header(
+                &mut header,
+                Diff::new(&left_ui_path, &right_ui_path),
+                Diff::new(&left_value, &right_value),
+            )?;
+            let presence = Diff::new(left_value.is_present(), right_value.is_present());
+            // Absent side is loaded as empty content.
             let left_content = diff_content_as_merge(left_path, left_value).await?;
             let right_content = diff_content_as_merge(right_path, right_value).await?;
-            if left_path == right_path {
-                writeln!(
-                    formatter.labeled("header"),
-                    "{description} {right_ui_path}:"
-                )?;
-            } else {
-                writeln!(
-                    formatter.labeled("header"),
-                    "{description} {right_ui_path} ({left_ui_path} => {right_ui_path}):"
-                )?;
-            }
-            if left_content.is_binary || right_content.is_binary {
-                writeln!(formatter.labeled("binary"), "    (binary)")?;
-            } else if left_content.contents != right_content.contents {
-                show_color_words_diff_hunks(
+            let contents = Diff::new(left_content, right_content);
+            let path = right_path.to_owned();
+            Ok::<_, DiffRenderError>((header, Some((path, presence, contents))))
+        },
+    );
+    let mut diff_stream = pin!(diff_stream);
+    let mut tokenizer_errors = vec![];
+    if options.color_moved {
+        // Moved lines are detected across all files, so load them first.
+        let files: Vec<_> = diff_stream.try_collect().await?;
+        let changed_lines = files
+            .iter()
+            .map(|(_, file)| {
+                let Some((_, _, contents)) = file else {
+                    return ChangedLines::default();
+                };
+                if contents.before.is_binary || contents.after.is_binary {
+                    return ChangedLines::default();
+                }
+                // Line numbers of conflicts depend on how they're rendered.
+                let (Some(left), Some(right)) = (
+                    contents.before.contents.file_content.as_resolved(),
+                    contents.after.contents.file_content.as_resolved(),
+                ) else {
+                    return ChangedLines::default();
+                };
+                ChangedLines::from_contents(
+                    Diff::new(left, right).map(BStr::new),
+                    &options.line_diff.compare_mode,
+                )
+            })
+            .collect_vec();
+        let moved_lines = find_moved_lines(&changed_lines);
+        for ((header, file), moved_lines) in iter::zip(&files, &moved_lines) {
+            header.replay(formatter)?;
+            if let Some((path, presence, contents)) = file {
+                let errors = show_color_words_file_contents(
                     formatter,
-                    Diff::new(
-                        &left_content.contents.file_content,
-                        &right_content.contents.file_content,
-                    ),
-                    Diff::new(
-                        &left_content.contents.conflict_labels,
-                        &right_content.contents.conflict_labels,
-                    ),
+                    path,
+                    *presence,
+                    contents.as_ref(),
+                    moved_lines,
                     options,
                     &materialize_options,
                 )?;
+                tokenizer_errors.extend(errors);
             }
-        } else {
-            let description = basic_diff_file_type(&left_value);
-            writeln!(
-                formatter.labeled("header"),
-                "Removed {description} {right_ui_path}:"
-            )?;
-            let left_content = diff_content_as_merge(left_path, left_value).await?;
-            if left_content.contents.is_empty() {
-                writeln!(formatter.labeled("empty"), "    (empty)")?;
-            } else if left_content.is_binary {
-                writeln!(formatter.labeled("binary"), "    (binary)")?;
-            } else {
-                show_color_words_diff_hunks(
+        }
+    } else {
+        while let Some((header, file)) = diff_stream.try_next().await? {
+            header.replay(formatter)?;
+            if let Some((path, presence, contents)) = &file {
+                let errors = show_color_words_file_contents(
                     formatter,
-                    Diff::new(&left_content.contents.file_content, &empty_content()),
-                    Diff::new(
-                        &left_content.contents.conflict_labels,
-                        &ConflictLabels::unlabeled(),
-                    ),
+                    path,
+                    *presence,
+                    contents.as_ref(),
+                    &MovedLines::default(),
                     options,
                     &materialize_options,
                 )?;
+                tokenizer_errors.extend(errors);
             }
         }
     }
-    Ok(())
+    Ok(tokenizer_errors)
 }
 
-#[expect(clippy::too_many_arguments)]
-pub async fn show_file_by_file_diff(
-    ui: &Ui,
+fn show_color_words_file_contents(
     formatter: &mut dyn Formatter,
-    store: &Store,
-    tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
-    conflict_labels: Diff<&ConflictLabels>,
-    path_converter: &RepoPathUiConverter,
-    tool: &ExternalMergeTool,
-    marker_style: ConflictMarkerStyle,
-    width: usize,
-) -> Result<(), DiffRenderError> {
-    let materialize_options = ConflictMaterializeOptions {
-        marker_style,
-        marker_len: None,
-        merge: store.merge_options().clone(),
-    };
-    let create_file = async |path: &RepoPath,
-                             wc_dir: &Path,
-                             value: MaterializedTreeValue|
-           -> Result<PathBuf, DiffRenderError> {
-        let fs_path = path.to_fs_path(wc_dir)?;
-        std::fs::create_dir_all(fs_path.parent().unwrap())?;
-        let content = diff_content(path, value, &materialize_options).await?;
-        std::fs::write(&fs_path, content.contents)?;
-        Ok(fs_path)
-    };
+    path: &RepoPath,
+    presence: Diff<bool>,
+    contents: Diff<&FileContent<DiffContentAsMerge>>,
+    moved_lines: &MovedLines,
+    options: &ColorWordsDiffOptions,
+    materialize_options: &ConflictMaterializeOptions,
+) -> io::Result<Vec<WordTokenizerError>> {
+    let is_binary = contents.before.is_binary || contents.after.is_binary;
+    if presence.before && presence.after {
+        if is_binary {
+            writeln!(formatter.labeled("binary"), "    (binary)")?;
+            return Ok(vec![]);
+        } else if contents.before.contents == contents.after.contents {
+            return Ok(vec![]);
+        }
+    } else if contents.before.contents.is_empty() && contents.after.contents.is_empty() {
+        writeln!(formatter.labeled("empty"), "    (empty)")?;
+        return Ok(vec![]);
+    } else if is_binary {
+        writeln!(formatter.labeled("binary"), "    (binary)")?;
+        return Ok(vec![]);
+    }
+    show_color_words_diff_hunks(
+        formatter,
+        contents.map(|content| &content.contents.file_content),
+        contents.map(|content| &content.contents.conflict_labels),
+        moved_lines,
+        options,
+        materialize_options,
+        materialize_options
+            .merge
+            .word_tokenizers
+            .tokenizer_for_path(path),
+    )
+}
 
-    let temp_dir = new_utf8_temp_dir("jj-diff-")?;
-    let left_wc_dir = temp_dir.path().join("left");
-    let right_wc_dir = temp_dir.path().join("right");
-    let mut diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels);
-    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
-        let Diff {
-            before: left_value,
-            after: right_value,
-        } = values?;
-        let left_path = path.source();
-        let right_path = path.target();
-        let left_ui_path = path_converter.format_file_path(left_path);
-        let right_ui_path = path_converter.format_file_path(right_path);
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SideBySideDiffOptions {
+    /// Number of context lines to show.
+    pub context: usize,
+    /// How lines are tokenized and compared.
+    pub line_diff: LineDiffOptions,
+}
 
-        match (&left_value, &right_value) {
-            (_, MaterializedTreeValue::AccessDenied(source)) => {
-                write!(
-                    formatter.labeled("access-denied"),
-                    "Access denied to {right_ui_path}:"
-                )?;
-                writeln!(formatter, " {source}")?;
-                continue;
-            }
-            (MaterializedTreeValue::AccessDenied(source), _) => {
-                write!(
-                    formatter.labeled("access-denied"),
-                    "Access denied to {left_ui_path}:"
-                )?;
-                writeln!(formatter, " {source}")?;
-                continue;
-            }
-            _ => {}
+impl SideBySideDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            context: settings.get("diff.side-by-side.context")?,
+            line_diff: LineDiffOptions::default(),
+        })
+    }
+
+    fn merge_args(&mut self, args: &DiffFormatArgs) {
+        if let Some(context) = args.context {
+            self.context = context;
         }
-        let left_path = create_file(left_path, &left_wc_dir, left_value).await?;
-        let right_path = create_file(right_path, &right_wc_dir, right_value).await?;
-        let patterns = &maplit::hashmap! {
-            "left" => left_path
-                .strip_prefix(temp_dir.path())
+        self.line_diff.merge_args(args);
+    }
+}
+
+/// Column layout of side-by-side diff.
+#[derive(Clone, Copy, Debug)]
+struct SideBySideLayout {
+    /// Total display width.
+    width: usize,
+    /// Display width of the line numbers, excluding the space after them.
+    line_number_width: usize,
+    /// Display width of the content column of each side.
+    column_width: usize,
+}
+
+impl SideBySideLayout {
+    const MIN_LINE_NUMBER_WIDTH: usize = 4;
+    const SEPARATOR: &str = " │ ";
+    const MIN_COLUMN_WIDTH: usize = 20;
+    const TAB_WIDTH: usize = 4;
+
+    fn new(width: usize) -> Self {
+        Self::with_line_number_width(width, Self::MIN_LINE_NUMBER_WIDTH)
+    }
+
+    fn with_line_number_width(width: usize, line_number_width: usize) -> Self {
+        let fixed_width = 2 * (line_number_width + 1) + Self::SEPARATOR.width();
+        let column_width = max(
+            width.saturating_sub(fixed_width) / 2,
+            Self::MIN_COLUMN_WIDTH,
+        );
+        Self {
+            width,
+            line_number_width,
+            column_width,
+        }
+    }
+
+    /// Returns the layout whose line number column fits `max_line_number`.
+    fn for_max_line_number(self, max_line_number: usize) -> Self {
+        let digits = max_line_number.checked_ilog10().unwrap_or(0) as usize + 1;
+        Self::with_line_number_width(self.width, max(digits, Self::MIN_LINE_NUMBER_WIDTH))
+    }
+}
+
+/// Line of one side, split into tokens.
+type SideBySideLine<'a> = Vec<(DiffTokenType, &'a [u8])>;
+
+fn show_side_by_side_diff_hunks<T: AsRef<[u8]>>(
+    formatter: &mut dyn Formatter,
+    contents: Diff<&Merge<T>>,
+    conflict_labels: Diff<&ConflictLabels>,
+    options: &SideBySideDiffOptions,
+    materialize_options: &ConflictMaterializeOptions,
+    word_tokenizer: &dyn WordTokenizer,
+    layout: SideBySideLayout,
+) -> io::Result<Vec<WordTokenizerError>> {
+    let mut tokenizer_errors = vec![];
+    let contents = contents
+        .zip(conflict_labels)
+        .map(|(side, labels)| match side.as_resolved() {
+            Some(content) => BString::from(content.as_ref()),
+            None => {
+                let materialized = materialize_merge_result_to_bytes_with_tokenizer(
+                    side,
+                    labels,
+                    materialize_options,
+                    word_tokenizer,
+                );
+                tokenizer_errors.extend(materialized.tokenizer_errors);
+                materialized.merged
+            }
+        });
+    let word_tokenizer = ContentsWordTokenizer::new(
+        word_tokenizer,
+        contents.as_ref().map(|c| c.as_bytes()).into_array(),
+    );
+    show_side_by_side_resolved_hunks(
+        formatter,
+        contents.as_ref().map(BStr::new),
+        options,
+        &word_tokenizer,
+        layout,
+    )?;
+    tokenizer_errors.extend(word_tokenizer.into_errors());
+    Ok(tokenizer_errors)
+}
+
+/// Shows the diff of the two contents of the `word_tokenizer`.
+fn show_side_by_side_resolved_hunks(
+    formatter: &mut dyn Formatter,
+    contents: Diff<&BStr>,
+    options: &SideBySideDiffOptions,
+    word_tokenizer: &ContentsWordTokenizer,
+    layout: SideBySideLayout,
+) -> io::Result<()> {
+    let max_line_number = max(
+        contents.before.split_inclusive(|b| *b == b'\n').count(),
+        contents.after.split_inclusive(|b| *b == b'\n').count(),
+    );
+    let layout = layout.for_max_line_number(max_line_number);
+    let line_diff = diff_by_line(contents.into_array(), &options.line_diff.compare_mode);
+    // Matching entries shouldn't appear consecutively in diff of two inputs.
+    let mut context: Option<Diff<&BStr>> = None;
+    let mut line_number = DiffLineNumber { left: 1, right: 1 };
+    let mut emitted = false;
+
+    for (hunk, hunk_ranges) in iter::zip(line_diff.hunks(), line_diff.hunk_ranges()) {
+        let &[left, right] = hunk.contents.as_slice() else {
+            panic!("hunk contents should have two sides")
+        };
+        let hunk_contents = Diff::new(left, right);
+        match hunk.kind {
+            DiffHunkKind::Matching => {
+                context = Some(hunk_contents);
+            }
+            DiffHunkKind::Different => {
+                let num_after = if emitted { options.context } else { 0 };
+                let num_before = options.context;
+                if let Some(context) = context.take() {
+                    line_number = show_side_by_side_context_lines(
+                        formatter,
+                        context,
+                        line_number,
+                        num_after,
+                        num_before,
+                        layout,
+                    )?;
+                }
+                emitted = true;
+                let word_diff = ContentDiff::by_word_in_contents(
+                    word_tokenizer,
+                    hunk_ranges.ranges.iter().cloned().enumerate(),
+                );
+                line_number =
+                    show_side_by_side_diff_lines(formatter, &word_diff, line_number, layout)?;
+            }
+        }
+    }
+
+    if let Some(context) = context
+        && emitted
+    {
+        show_side_by_side_context_lines(
+            formatter,
+            context,
+            line_number,
+            options.context,
+            0,
+            layout,
+        )?;
+    }
+    Ok(())
+}
+
+/// Prints `num_after` lines, ellipsis, and `num_before` lines.
+fn show_side_by_side_context_lines(
+    formatter: &mut dyn Formatter,
+    contents: Diff<&BStr>,
+    mut line_number: DiffLineNumber,
+    num_after: usize,
+    num_before: usize,
+    layout: SideBySideLayout,
+) -> io::Result<DiffLineNumber> {
+    const SKIPPED_CONTEXT_LINE: &str = "...\n";
+    // Lines of matching hunk are paired one to one, but may differ in
+    // whitespace if it is ignored.
+    let lines = iter::zip(
+        contents.before.split_inclusive(|b| *b == b'\n'),
+        contents.after.split_inclusive(|b| *b == b'\n'),
+    )
+    .collect_vec();
+    let (shown_after, shown_before) = if lines.len() > num_after + num_before + 1 {
+        (&lines[..num_after], &lines[lines.len() - num_before..])
+    } else {
+        (&lines[..], &lines[..0])
+    };
+    let mut formatter = formatter.labeled("context");
+    let show = |formatter: &mut dyn Formatter,
+                &(left, right): &(&[u8], &[u8]),
+                line_number: &mut DiffLineNumber| {
+        show_side_by_side_row(
+            formatter,
+            Diff::new(
+                Some((line_number.left, vec![(DiffTokenType::Matching, left)])),
+                Some((line_number.right, vec![(DiffTokenType::Matching, right)])),
+            ),
+            Diff::new(None, None),
+            layout,
+        )?;
+        line_number.left += 1;
+        line_number.right += 1;
+        io::Result::Ok(())
+    };
+    for line in shown_after {
+        show(*formatter, line, &mut line_number)?;
+    }
+    let num_skipped = lines.len() - shown_after.len() - shown_before.len();
+    if num_skipped > 0 {
+        write!(
+            formatter,
+            "{:width$}{SKIPPED_CONTEXT_LINE}",
+            "",
+            width = layout.line_number_width
+        )?;
+        let num_skipped: u32 = num_skipped.try_into().unwrap();
+        line_number.left += num_skipped;
+        line_number.right += num_skipped;
+    }
+    for line in shown_before {
+        show(*formatter, line, &mut line_number)?;
+    }
+    Ok(line_number)
+}
+
+fn show_side_by_side_diff_lines(
+    formatter: &mut dyn Formatter,
+    word_diff: &ContentDiff,
+    mut line_number: DiffLineNumber,
+    layout: SideBySideLayout,
+) -> io::Result<DiffLineNumber> {
+    let word_diff_hunks = word_diff.hunks().collect_vec();
+    let lines = unzip_diff_hunks_to_lines(&word_diff_hunks);
+    for (left, right) in lines
+        .before
+        .into_iter()
+        .map(Some)
+        .zip_longest(lines.after.into_iter().map(Some))
+        .map(|pair| pair.or(None, None))
+    {
+        let left = left.map(|tokens| {
+            line_number.left += 1;
+            (line_number.left - 1, tokens)
+        });
+        let right = right.map(|tokens| {
+            line_number.right += 1;
+            (line_number.right - 1, tokens)
+        });
+        show_side_by_side_row(
+            formatter,
+            Diff::new(left, right),
+            Diff::new(Some("removed"), Some("added")),
+            layout,
+        )?;
+    }
+    Ok(line_number)
+}
+
+/// Prints a pair of lines, wrapping them at the column width.
+fn show_side_by_side_row(
+    formatter: &mut dyn Formatter,
+    lines: Diff<Option<(u32, SideBySideLine)>>,
+    labels: Diff<Option<&str>>,
+    layout: SideBySideLayout,
+) -> io::Result<()> {
+    let wrap = |line: Option<(u32, SideBySideLine)>| match line {
+        Some((line_number, tokens)) => (Some(line_number), wrap_side_by_side_line(&tokens, layout)),
+        None => (None, vec![]),
+    };
+    let (left_number, left_rows) = wrap(lines.before);
+    let (right_number, right_rows) = wrap(lines.after);
+    let num_rows = max(max(left_rows.len(), right_rows.len()), 1);
+    for i in 0..num_rows {
+        show_side_by_side_cell(
+            formatter,
+            left_number.filter(|_| i == 0),
+            left_rows.get(i),
+            labels.before.filter(|_| i < left_rows.len()),
+            layout.line_number_width,
+            Some(layout.column_width),
+        )?;
+        if i < right_rows.len() {
+            write!(formatter, "{}", SideBySideLayout::SEPARATOR)?;
+            show_side_by_side_cell(
+                formatter,
+                right_number.filter(|_| i == 0),
+                right_rows.get(i),
+                labels.after,
+                layout.line_number_width,
+                None,
+            )?;
+        } else {
+            // Don't leave trailing whitespace
+            write!(formatter, "{}", SideBySideLayout::SEPARATOR.trim_end())?;
+        }
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
+
+/// Prints line number and tokens of one side. The line number is
+/// right-aligned in `line_number_width`. If `pad_width` is specified, the
+/// content is padded with spaces up to the width.
+fn show_side_by_side_cell(
+    formatter: &mut dyn Formatter,
+    line_number: Option<u32>,
+    row: Option<&Vec<(DiffTokenType, String)>>,
+    label: Option<&str>,
+    line_number_width: usize,
+    pad_width: Option<usize>,
+) -> io::Result<()> {
+    if let Some(label) = label {
+        return show_side_by_side_cell(
+            *formatter.labeled(label),
+            line_number,
+            row,
+            None,
+            line_number_width,
+            pad_width,
+        );
+    }
+    match line_number {
+        Some(line_number) => {
+            write!(
+                formatter.labeled("line_number"),
+                "{line_number:>line_number_width$}"
+            )?;
+            write!(formatter, " ")?;
+        }
+        None => write!(formatter, "{:width$}", "", width = line_number_width + 1)?,
+    }
+    let mut content_width = 0;
+    for (token_type, text) in row.into_iter().flatten() {
+        match token_type {
+            DiffTokenType::Matching => write!(formatter, "{text}")?,
+            DiffTokenType::Different => write!(formatter.labeled("token"), "{text}")?,
+        }
+        content_width += text.width();
+    }
+    if let Some(pad_width) = pad_width {
+        write!(
+            formatter,
+            "{:pad$}",
+            "",
+            pad = pad_width.saturating_sub(content_width)
+        )?;
+    }
+    Ok(())
+}
+
+/// Splits line tokens into rows that fit in the column width. Tabs are
+/// expanded to the next tab stop of the row and the trailing newline is
+/// removed.
+fn wrap_side_by_side_line(
+    tokens: &[(DiffTokenType, &[u8])],
+    layout: SideBySideLayout,
+) -> Vec<Vec<(DiffTokenType, String)>> {
+    let mut rows = vec![];
+    let mut row: Vec<(DiffTokenType, String)> = vec![];
+    let mut row_width = 0;
+    for &(token_type, data) in tokens {
+        let text = String::from_utf8_lossy(data);
+        let text = text.trim_end_matches(['\n', '\r']);
+        let mut chunk = String::new();
+        for c in text.chars() {
+            let tab_width = |row_width: usize| {
+                SideBySideLayout::TAB_WIDTH - row_width % SideBySideLayout::TAB_WIDTH
+            };
+            let (c, mut c_width) = match c {
+                '\t' => (None, tab_width(row_width)),
+                _ => (Some(c), c.width().unwrap_or(0)),
+            };
+            if row_width + c_width > layout.column_width && row_width > 0 {
+                if !chunk.is_empty() {
+                    row.push((token_type, mem::take(&mut chunk)));
+                }
+                rows.push(mem::take(&mut row));
+                row_width = 0;
+                if c.is_none() {
+                    c_width = tab_width(row_width);
+                }
+            }
+            match c {
+                Some(c) => chunk.push(c),
+                None => chunk.extend(iter::repeat_n(' ', c_width)),
+            }
+            row_width += c_width;
+        }
+        if !chunk.is_empty() {
+            row.push((token_type, chunk));
+        }
+    }
+    if !row.is_empty() || rows.is_empty() {
+        rows.push(row);
+    }
+    rows
+}
+
+#[expect(clippy::too_many_arguments)]
+pub async fn show_side_by_side_diff(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+    conflict_labels: Diff<&ConflictLabels>,
+    path_converter: &RepoPathUiConverter,
+    options: &SideBySideDiffOptions,
+    marker_style: ConflictMarkerStyle,
+    width: usize,
+) -> Result<Vec<WordTokenizerError>, DiffRenderError> {
+    let materialize_options = ConflictMaterializeOptions {
+        marker_style,
+        marker_len: None,
+        merge: store.merge_options().clone(),
+    };
+    let layout = SideBySideLayout::new(width);
+    let mut tokenizer_errors = vec![];
+    let mut diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels);
+    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+        let left_path = path.source();
+        let right_path = path.target();
+        let left_ui_path = path_converter.format_file_path(left_path);
+        let right_ui_path = path_converter.format_file_path(right_path);
+        let Diff {
+            before: left_value,
+            after: right_value,
+        } = values?;
+
+        match (&left_value, &right_value) {
+            (MaterializedTreeValue::AccessDenied(source), _) => {
+                write!(
+                    formatter.labeled("access-denied"),
+                    "Access denied to {left_ui_path}:"
+                )?;
+                writeln!(formatter, " {source}")?;
+                continue;
+            }
+            (_, MaterializedTreeValue::AccessDenied(source)) => {
+                write!(
+                    formatter.labeled("access-denied"),
+                    "Access denied to {right_ui_path}:"
+                )?;
+                writeln!(formatter, " {source}")?;
+                continue;
+            }
+            _ => {}
+        }
+        show_file_change_header(
+            formatter,
+            Diff::new(&left_ui_path, &right_ui_path),
+            Diff::new(&left_value, &right_value),
+        )?;
+        let left_content = diff_content_as_merge(left_path, left_value).await?;
+        let right_content = diff_content_as_merge(right_path, right_value).await?;
+        if left_content.is_binary || right_content.is_binary {
+            writeln!(formatter.labeled("binary"), "    (binary)")?;
+        } else if left_content.contents.is_empty() && right_content.contents.is_empty() {
+            writeln!(formatter.labeled("empty"), "    (empty)")?;
+        } else if left_content.contents != right_content.contents {
+            let word_tokenizer = materialize_options
+                .merge
+                .word_tokenizers
+                .tokenizer_for_path(right_path);
+            let errors = show_side_by_side_diff_hunks(
+                formatter,
+                Diff::new(
+                    &left_content.contents.file_content,
+                    &right_content.contents.file_content,
+                ),
+                Diff::new(
+                    &left_content.contents.conflict_labels,
+                    &right_content.contents.conflict_labels,
+                ),
+                options,
+                &materialize_options,
+                word_tokenizer,
+                layout,
+            )?;
+            tokenizer_errors.extend(errors);
+        }
+    }
+    Ok(tokenizer_errors)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsonDiffOptions {
+    /// Number of context lines to include in hunks.
+    pub context: usize,
+    /// How lines are tokenized and compared.
+    pub line_diff: LineDiffOptions,
+}
+
+impl JsonDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            context: settings.get("diff.json.context")?,
+            line_diff: LineDiffOptions::default(),
+        })
+    }
+
+    fn merge_args(&mut self, args: &DiffFormatArgs) {
+        if let Some(context) = args.context {
+            self.context = context;
+        }
+        self.line_diff.merge_args(args);
+    }
+}
+
+/// Changed path in JSON diff output.
+#[derive(Debug, serde::Serialize)]
+struct JsonDiffFile {
+    path: String,
+    status: &'static str,
+    /// Source path if the file was copied or renamed.
+    source_path: Option<String>,
+    old: Option<JsonDiffFileSide>,
+    new: Option<JsonDiffFileSide>,
+    binary: bool,
+    hunks: Vec<JsonDiffHunk>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonDiffFileSide {
+    #[serde(rename = "type")]
+    file_type: &'static str,
+    /// Octal Git mode string, or `None` if it can't be determined.
+    mode: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict: Option<JsonDiffConflict>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonDiffConflict {
+    sides: Vec<JsonDiffConflictTerm>,
+    bases: Vec<JsonDiffConflictTerm>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonDiffConflictTerm {
+    label: Option<String>,
+    /// `None` if the content isn't valid UTF-8.
+    content: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonDiffHunk {
+    old_start: usize,
+    old_count: usize,
+    new_start: usize,
+    new_count: usize,
+    lines: Vec<JsonDiffLine>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonDiffLine {
+    kind: &'static str,
+    /// 1-based line number in the old contents.
+    old_line: Option<usize>,
+    /// 1-based line number in the new contents.
+    new_line: Option<usize>,
+    content: String,
+}
+
+fn json_diff_file_side(value: &MaterializedTreeValue) -> Option<JsonDiffFileSide> {
+    let to_mode = |executable: bool| if executable { "100755" } else { "100644" };
+    let (file_type, mode) = match value {
+        MaterializedTreeValue::Absent => return None,
+        MaterializedTreeValue::AccessDenied(_) => ("access_denied", None),
+        MaterializedTreeValue::File(file) => ("file", Some(to_mode(file.executable))),
+        MaterializedTreeValue::Symlink { .. } => ("symlink", Some("120000")),
+        MaterializedTreeValue::GitSubmodule(_) => ("git_submodule", Some("160000")),
+        MaterializedTreeValue::FileConflict(file) => ("conflict", file.executable.map(to_mode)),
+        MaterializedTreeValue::OtherConflict { .. } => ("conflict", None),
+        MaterializedTreeValue::Tree(id) => {
+            panic!("Unexpected tree with id {id:?} in diff");
+        }
+    };
+    let conflict = match value {
+        MaterializedTreeValue::FileConflict(file) => {
+            let to_term = |label: Option<&str>, content: &BString| JsonDiffConflictTerm {
+                label: label.map(str::to_owned),
+                content: str::from_utf8(content).ok().map(str::to_owned),
+            };
+            Some(JsonDiffConflict {
+                sides: (file.contents.adds().enumerate())
+                    .map(|(i, content)| to_term(file.labels.get_add(i), content))
+                    .collect(),
+                bases: (file.contents.removes().enumerate())
+                    .map(|(i, content)| to_term(file.labels.get_remove(i), content))
+                    .collect(),
+            })
+        }
+        _ => None,
+    };
+    let error = match value {
+        MaterializedTreeValue::AccessDenied(err) => Some(err.to_string()),
+        _ => None,
+    };
+    Some(JsonDiffFileSide {
+        file_type,
+        mode,
+        conflict,
+        error,
+    })
+}
+
+fn json_diff_hunks(contents: Diff<&BStr>, options: &JsonDiffOptions) -> Vec<JsonDiffHunk> {
+    unified_diff_hunks(contents, options.context, options.line_diff.compare_mode)
+        .into_iter()
+        .map(|hunk| {
+            let mut left_index = hunk.left_line_range.start;
+            let mut right_index = hunk.right_line_range.start;
+            let lines = hunk
+                .lines
+                .iter()
+                .map(|(line_type, tokens)| {
+                    let (kind, old_line, new_line) = match line_type {
+                        DiffLineType::Context => {
+                            left_index += 1;
+                            right_index += 1;
+                            ("context", Some(left_index), Some(right_index))
+                        }
+                        DiffLineType::Removed => {
+                            left_index += 1;
+                            ("removed", Some(left_index), None)
+                        }
+                        DiffLineType::Added => {
+                            right_index += 1;
+                            ("added", None, Some(right_index))
+                        }
+                    };
+                    let content = tokens
+                        .iter()
+                        .map(|(_, content)| *content)
+                        .collect_vec()
+                        .concat();
+                    JsonDiffLine {
+                        kind,
+                        old_line,
+                        new_line,
+                        content: String::from_utf8(content)
+                            .expect("non-UTF-8 contents should be treated as binary"),
+                    }
+                })
+                .collect();
+            JsonDiffHunk {
+                old_start: to_line_number(hunk.left_line_range.clone()),
+                old_count: hunk.left_line_range.len(),
+                new_start: to_line_number(hunk.right_line_range.clone()),
+                new_count: hunk.right_line_range.len(),
+                lines,
+            }
+        })
+        .collect()
+}
+
+/// Prints a `file_diff` record per changed path.
+pub async fn show_json_diff(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+    conflict_labels: Diff<&ConflictLabels>,
+    options: &JsonDiffOptions,
+    marker_style: ConflictMarkerStyle,
+) -> Result<(), DiffRenderError> {
+    let materialize_options = ConflictMaterializeOptions {
+        marker_style,
+        marker_len: None,
+        merge: store.merge_options().clone(),
+    };
+    let mut diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels);
+    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+        let values = values?;
+        let status =
+            diff_status_inner(&path, values.before.is_present(), values.after.is_present());
+        let sides = values.as_ref().map(json_diff_file_side);
+        let is_access_denied = [&values.before, &values.after]
+            .iter()
+            .any(|value| matches!(value, MaterializedTreeValue::AccessDenied(_)));
+        let left_content = diff_content(path.source(), values.before, &materialize_options).await?;
+        let right_content = diff_content(path.target(), values.after, &materialize_options).await?;
+        // Contents which can't be represented as JSON strings are treated as
+        // binary.
+        let binary = [&left_content, &right_content]
+            .iter()
+            .any(|content| content.is_binary || str::from_utf8(&content.contents).is_err());
+        let hunks = if binary || is_access_denied {
+            vec![]
+        } else {
+            json_diff_hunks(
+                Diff::new(&left_content.contents, &right_content.contents).map(BStr::new),
+                options,
+            )
+        };
+        let file = JsonDiffFile {
+            path: path.target().as_internal_file_string().to_owned(),
+            status: status.label(),
+            source_path: path
+                .copy_operation()
+                .map(|_| path.source().as_internal_file_string().to_owned()),
+            old: sides.before,
+            new: sides.after,
+            binary,
+            hunks,
+        };
+        write_json_record(formatter, "file_diff", &file)?;
+    }
+    Ok(())
+}
+
+#[expect(clippy::too_many_arguments)]
+pub async fn show_file_by_file_diff(
+    ui: &Ui,
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+    conflict_labels: Diff<&ConflictLabels>,
+    path_converter: &RepoPathUiConverter,
+    tool: &ExternalMergeTool,
+    marker_style: ConflictMarkerStyle,
+    width: usize,
+) -> Result<(), DiffRenderError> {
+    let materialize_options = ConflictMaterializeOptions {
+        marker_style,
+        marker_len: None,
+        merge: store.merge_options().clone(),
+    };
+    let create_file = async |path: &RepoPath,
+                             wc_dir: &Path,
+                             value: MaterializedTreeValue|
+           -> Result<PathBuf, DiffRenderError> {
+        let fs_path = path.to_fs_path(wc_dir)?;
+        std::fs::create_dir_all(fs_path.parent().unwrap())?;
+        let content = diff_content(path, value, &materialize_options).await?;
+        std::fs::write(&fs_path, content.contents)?;
+        Ok(fs_path)
+    };
+
+    let temp_dir = new_utf8_temp_dir("jj-diff-")?;
+    let left_wc_dir = temp_dir.path().join("left");
+    let right_wc_dir = temp_dir.path().join("right");
+    let mut diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels);
+    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+        let Diff {
+            before: left_value,
+            after: right_value,
+        } = values?;
+        let left_path = path.source();
+        let right_path = path.target();
+        let left_ui_path = path_converter.format_file_path(left_path);
+        let right_ui_path = path_converter.format_file_path(right_path);
+
+        match (&left_value, &right_value) {
+            (_, MaterializedTreeValue::AccessDenied(source)) => {
+                write!(
+                    formatter.labeled("access-denied"),
+                    "Access denied to {right_ui_path}:"
+                )?;
+                writeln!(formatter, " {source}")?;
+                continue;
+            }
+            (MaterializedTreeValue::AccessDenied(source), _) => {
+                write!(
+                    formatter.labeled("access-denied"),
+                    "Access denied to {left_ui_path}:"
+                )?;
+                writeln!(formatter, " {source}")?;
+                continue;
+            }
+            _ => {}
+        }
+        let left_path = create_file(left_path, &left_wc_dir, left_value).await?;
+        let right_path = create_file(right_path, &right_wc_dir, right_value).await?;
+        let patterns = &maplit::hashmap! {
+            "left" => left_path
+                .strip_prefix(temp_dir.path())
                 .expect("path should be relative to temp_dir")
                 .to_str()
                 .expect("temp_dir should be valid utf-8")
@@ -1630,6 +2659,8 @@ pub struct UnifiedDiffOptions {
     pub show_path_prefix: bool,
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to highlight moved lines.
+    pub color_moved: bool,
 }
 
 impl UnifiedDiffOptions {
@@ -1638,6 +2669,7 @@ impl UnifiedDiffOptions {
             context: settings.get("diff.git.context")?,
             show_path_prefix: settings.get("diff.git.show-path-prefix")?,
             line_diff: LineDiffOptions::default(),
+            color_moved: settings.get("diff.git.color-moved")?,
         })
     }
 
@@ -1646,29 +2678,35 @@ impl UnifiedDiffOptions {
             self.context = context;
         }
         self.line_diff.merge_args(args);
+        if args.color_moved {
+            self.color_moved = true;
+        }
+    }
+}
+
+/// Converts 0-based line range to the start line number of unified diff hunk.
+///
+/// "If the chunk size is 0, the first number is one lower than one would
+/// expect." - <https://www.artima.com/weblogs/viewpost.jsp?thread=164293>
+///
+/// The POSIX spec also states that "the ending line number of an empty range
+/// shall be the number of the preceding line, or 0 if the range is at the
+/// start of the file."
+/// - <https://pubs.opengroup.org/onlinepubs/9799919799/utilities/diff.html>
+fn to_line_number(range: Range<usize>) -> usize {
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
     }
 }
 
 fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     contents: Diff<&BStr>,
+    moved_lines: &MovedLines,
     options: &UnifiedDiffOptions,
 ) -> io::Result<()> {
-    // "If the chunk size is 0, the first number is one lower than one would
-    // expect." - https://www.artima.com/weblogs/viewpost.jsp?thread=164293
-    //
-    // The POSIX spec also states that "the ending line number of an empty range
-    // shall be the number of the preceding line, or 0 if the range is at the
-    // start of the file."
-    // - https://pubs.opengroup.org/onlinepubs/9799919799/utilities/diff.html
-    fn to_line_number(range: Range<usize>) -> usize {
-        if range.is_empty() {
-            range.start
-        } else {
-            range.start + 1
-        }
-    }
-
     for hunk in unified_diff_hunks(contents, options.context, options.line_diff.compare_mode) {
         writeln!(
             formatter.labeled("hunk_header"),
@@ -1678,14 +2716,35 @@ fn show_unified_diff_hunks(
             to_line_number(hunk.right_line_range.clone()),
             hunk.right_line_range.len()
         )?;
+        let mut left_index = hunk.left_line_range.start;
+        let mut right_index = hunk.right_line_range.start;
         for (line_type, tokens) in &hunk.lines {
-            let (label, sigil) = match line_type {
-                DiffLineType::Context => ("context", " "),
-                DiffLineType::Removed => ("removed", "-"),
-                DiffLineType::Added => ("added", "+"),
+            let (label, sigil, moved) = match line_type {
+                DiffLineType::Context => {
+                    left_index += 1;
+                    right_index += 1;
+                    ("context", " ", false)
+                }
+                DiffLineType::Removed => {
+                    let moved = moved_lines.removed.contains(&left_index);
+                    left_index += 1;
+                    ("removed", "-", moved)
+                }
+                DiffLineType::Added => {
+                    let moved = moved_lines.added.contains(&right_index);
+                    right_index += 1;
+                    ("added", "+", moved)
+                }
             };
-            write!(formatter.labeled(label), "{sigil}")?;
-            show_diff_line_tokens(*formatter.labeled(label), tokens)?;
+            let show_line = |formatter: &mut dyn Formatter| {
+                write!(formatter, "{sigil}")?;
+                show_diff_line_tokens(formatter, tokens)
+            };
+            if moved {
+                show_line(*formatter.labeled(label).labeled("moved"))?;
+            } else {
+                show_line(*formatter.labeled(label))?;
+            }
             let (_, content) = tokens.last().expect("hunk line must not be empty");
             if !content.ends_with(b"\n") {
                 write!(formatter, "\n\\ No newline at end of file\n")?;
@@ -1708,6 +2767,25 @@ fn show_diff_line_tokens(
     Ok(())
 }
 
+/// Detects lines moved within or between the files. Binary files are ignored.
+fn find_moved_lines_in_files<'a>(
+    contents: impl IntoIterator<Item = Diff<&'a FileContent<BString>>>,
+    compare_mode: &LineCompareMode,
+) -> Vec<MovedLines> {
+    let changed_lines = contents
+        .into_iter()
+        .map(|contents| {
+            if contents.before.is_binary || contents.after.is_binary {
+                ChangedLines::default()
+            } else {
+                let contents = contents.map(|content| BStr::new(&content.contents));
+                ChangedLines::from_contents(contents, compare_mode)
+            }
+        })
+        .collect_vec();
+    find_moved_lines(&changed_lines)
+}
+
 pub async fn show_git_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
@@ -1721,87 +2799,124 @@ pub async fn show_git_diff(
         marker_len: None,
         merge: store.merge_options().clone(),
     };
-    let mut diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels);
-    while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
-        let left_path = path.source();
-        let right_path = path.target();
-        let left_prefix = if options.show_path_prefix { "a/" } else { "" };
-        let right_prefix = if options.show_path_prefix { "b/" } else { "" };
-        let left_path_string = left_path.as_internal_file_string();
-        let right_path_string = right_path.as_internal_file_string();
-        let values = values?;
+    let diff_stream = materialized_diff_stream(store, tree_diff, conflict_labels).then(
+        async |MaterializedTreeDiffEntry { path, values }| {
+            let values = values?;
+            let left_part =
+                git_diff_part(path.source(), values.before, &materialize_options).await?;
+            let right_part =
+                git_diff_part(path.target(), values.after, &materialize_options).await?;
+            Ok::<_, DiffRenderError>((path, Diff::new(left_part, right_part)))
+        },
+    );
+    let mut diff_stream = pin!(diff_stream);
+    if options.color_moved {
+        // Moved lines are detected across all files, so load them first.
+        let files: Vec<_> = diff_stream.try_collect().await?;
+        let moved_lines = find_moved_lines_in_files(
+            files
+                .iter()
+                .map(|(_, parts)| parts.as_ref().map(|part| &part.content)),
+            &options.line_diff.compare_mode,
+        );
+        for ((path, parts), moved_lines) in iter::zip(&files, &moved_lines) {
+            show_git_diff_file(formatter, path, parts.as_ref(), moved_lines, options)?;
+        }
+    } else {
+        while let Some((path, parts)) = diff_stream.try_next().await? {
+            let moved_lines = MovedLines::default();
+            show_git_diff_file(formatter, &path, parts.as_ref(), &moved_lines, options)?;
+        }
+    }
+    Ok(())
+}
 
-        let left_part = git_diff_part(left_path, values.before, &materialize_options).await?;
-        let right_part = git_diff_part(right_path, values.after, &materialize_options).await?;
+fn show_git_diff_file(
+    formatter: &mut dyn Formatter,
+    path: &CopiesTreeDiffEntryPath,
+    parts: Diff<&GitDiffPart>,
+    moved_lines: &MovedLines,
+    options: &UnifiedDiffOptions,
+) -> io::Result<()> {
+    let left_path = path.source();
+    let right_path = path.target();
+    let left_prefix = if options.show_path_prefix { "a/" } else { "" };
+    let right_prefix = if options.show_path_prefix { "b/" } else { "" };
+    let left_path_string = left_path.as_internal_file_string();
+    let right_path_string = right_path.as_internal_file_string();
+    let Diff {
+        before: left_part,
+        after: right_part,
+    } = parts;
 
-        {
-            let mut formatter = formatter.labeled("file_header");
-            writeln!(
-                formatter,
-                "diff --git {left_prefix}{left_path_string} {right_prefix}{right_path_string}"
-            )?;
-            let left_hash = &left_part.hash;
-            let right_hash = &right_part.hash;
-            match (left_part.mode, right_part.mode) {
-                (None, Some(right_mode)) => {
-                    writeln!(formatter, "new file mode {right_mode}")?;
-                    writeln!(formatter, "index {left_hash}..{right_hash}")?;
-                }
-                (Some(left_mode), None) => {
-                    writeln!(formatter, "deleted file mode {left_mode}")?;
-                    writeln!(formatter, "index {left_hash}..{right_hash}")?;
+    {
+        let mut formatter = formatter.labeled("file_header");
+        writeln!(
+            formatter,
+            "diff --git {left_prefix}{left_path_string} {right_prefix}{right_path_string}"
+        )?;
+        let left_hash = &left_part.hash;
+        let right_hash = &right_part.hash;
+        match (left_part.mode, right_part.mode) {
+            (None, Some(right_mode)) => {
+                writeln!(formatter, "new file mode {right_mode}")?;
+                writeln!(formatter, "index {left_hash}..{right_hash}")?;
+            }
+            (Some(left_mode), None) => {
+                writeln!(formatter, "deleted file mode {left_mode}")?;
+                writeln!(formatter, "index {left_hash}..{right_hash}")?;
+            }
+            (Some(left_mode), Some(right_mode)) => {
+                if let Some(op) = path.copy_operation() {
+                    let operation = match op {
+                        CopyOperation::Copy => "copy",
+                        CopyOperation::Rename => "rename",
+                    };
+                    // TODO: include similarity index?
+                    writeln!(formatter, "{operation} from {left_path_string}")?;
+                    writeln!(formatter, "{operation} to {right_path_string}")?;
                 }
-                (Some(left_mode), Some(right_mode)) => {
-                    if let Some(op) = path.copy_operation() {
-                        let operation = match op {
-                            CopyOperation::Copy => "copy",
-                            CopyOperation::Rename => "rename",
-                        };
-                        // TODO: include similarity index?
-                        writeln!(formatter, "{operation} from {left_path_string}")?;
-                        writeln!(formatter, "{operation} to {right_path_string}")?;
-                    }
-                    if left_mode != right_mode {
-                        writeln!(formatter, "old mode {left_mode}")?;
-                        writeln!(formatter, "new mode {right_mode}")?;
-                        if left_hash != right_hash {
-                            writeln!(formatter, "index {left_hash}..{right_hash}")?;
-                        }
-                    } else if left_hash != right_hash {
-                        writeln!(formatter, "index {left_hash}..{right_hash} {left_mode}")?;
+                if left_mode != right_mode {
+                    writeln!(formatter, "old mode {left_mode}")?;
+                    writeln!(formatter, "new mode {right_mode}")?;
+                    if left_hash != right_hash {
+                        writeln!(formatter, "index {left_hash}..{right_hash}")?;
                     }
+                } else if left_hash != right_hash {
+                    writeln!(formatter, "index {left_hash}..{right_hash} {left_mode}")?;
                 }
-                (None, None) => panic!("either left or right part should be present"),
             }
+            (None, None) => panic!("either left or right part should be present"),
         }
+    }
 
-        if left_part.content.contents == right_part.content.contents {
-            continue; // no content hunks
-        }
+    if left_part.content.contents == right_part.content.contents {
+        return Ok(()); // no content hunks
+    }
 
-        let left_path = match left_part.mode {
-            Some(_) => format!("{left_prefix}{left_path_string}"),
-            None => "/dev/null".to_owned(),
-        };
-        let right_path = match right_part.mode {
-            Some(_) => format!("{right_prefix}{right_path_string}"),
-            None => "/dev/null".to_owned(),
-        };
-        if left_part.content.is_binary || right_part.content.is_binary {
-            // TODO: add option to emit Git binary diff
-            writeln!(
-                formatter,
-                "Binary files {left_path} and {right_path} differ"
-            )?;
-        } else {
-            writeln!(formatter.labeled("file_header"), "--- {left_path}")?;
-            writeln!(formatter.labeled("file_header"), "+++ {right_path}")?;
-            show_unified_diff_hunks(
-                formatter,
-                Diff::new(&left_part.content.contents, &right_part.content.contents).map(BStr::new),
-                options,
-            )?;
-        }
+    let left_path = match left_part.mode {
+        Some(_) => format!("{left_prefix}{left_path_string}"),
+        None => "/dev/null".to_owned(),
+    };
+    let right_path = match right_part.mode {
+        Some(_) => format!("{right_prefix}{right_path_string}"),
+        None => "/dev/null".to_owned(),
+    };
+    if left_part.content.is_binary || right_part.content.is_binary {
+        // TODO: add option to emit Git binary diff
+        writeln!(
+            formatter,
+            "Binary files {left_path} and {right_path} differ"
+        )?;
+    } else {
+        writeln!(formatter.labeled("file_header"), "--- {left_path}")?;
+        writeln!(formatter.labeled("file_header"), "+++ {right_path}")?;
+        show_unified_diff_hunks(
+            formatter,
+            Diff::new(&left_part.content.contents, &right_part.content.contents).map(BStr::new),
+            moved_lines,
+            options,
+        )?;
     }
     Ok(())
 }
@@ -1832,7 +2947,12 @@ fn show_git_diff_texts<T: AsRef<[u8]>>(
             materialize_options,
         )),
     });
-    show_unified_diff_hunks(formatter, contents.as_ref().map(Cow::as_ref), options)
+    show_unified_diff_hunks(
+        formatter,
+        contents.as_ref().map(Cow::as_ref),
+        &MovedLines::default(),
+        options,
+    )
 }
 
 #[instrument(skip_all)]
@@ -1885,6 +3005,8 @@ pub fn diff_status(
 pub struct DiffStatOptions {
     /// How lines are tokenized and compared.
     pub line_diff: LineDiffOptions,
+    /// Whether to count lines moved within or between files.
+    pub moved_lines: bool,
 }
 
 impl DiffStatOptions {
@@ -1912,7 +3034,7 @@ impl DiffStats {
             merge: store.merge_options().clone(),
         };
         let conflict_labels = ConflictLabels::unlabeled();
-        let entries = materialized_diff_stream(
+        let (mut entries, changed_lines): (Vec<_>, Vec<_>) = materialized_diff_stream(
             store,
             tree_diff,
             Diff::new(&conflict_labels, &conflict_labels),
@@ -1925,16 +3047,31 @@ impl DiffStats {
                 diff_content(path.source(), values.before, &materialize_options).await?;
             let right_content =
                 diff_content(path.target(), values.after, &materialize_options).await?;
-            let stat = get_diff_stat_entry(
-                path,
-                status,
-                Diff::new(&left_content, &right_content),
-                options,
-            );
-            BackendResult::Ok(stat)
+            let contents = Diff::new(&left_content, &right_content);
+            let changed_lines = if left_content.is_binary || right_content.is_binary {
+                None
+            } else {
+                let contents = contents.map(|content| BStr::new(&content.contents));
+                Some(ChangedLines::from_contents(
+                    contents,
+                    &options.line_diff.compare_mode,
+                ))
+            };
+            let stat = get_diff_stat_entry(path, status, contents, changed_lines.as_ref());
+            // Keep only the changed lines to detect moves across files.
+            let changed_lines = changed_lines
+                .filter(|_| options.moved_lines)
+                .map(|lines| lines.to_owned_lines());
+            BackendResult::Ok((stat, changed_lines.unwrap_or_default()))
         })
         .try_collect()
         .await?;
+        if options.moved_lines {
+            let moved_lines = find_moved_lines(&changed_lines);
+            for (entry, moved_lines) in iter::zip(&mut entries, &moved_lines) {
+                entry.moved_added_removed = (moved_lines.added.len(), moved_lines.removed.len());
+            }
+        }
         Ok(Self { entries })
     }
 
@@ -1958,6 +3095,14 @@ impl DiffStats {
             .filter_map(|stat| stat.added_removed.map(|(_, removed)| removed))
             .sum()
     }
+
+    /// Total number of lines moved within or between files.
+    pub fn count_total_moved(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|stat| stat.moved_added_removed().0)
+            .sum()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -1999,42 +3144,32 @@ pub struct DiffStatEntry {
     /// Change in file size in bytes.
     pub bytes_delta: isize,
     pub status: DiffEntryStatus,
+    moved_added_removed: (usize, usize),
 }
 
-fn get_diff_stat_entry(
+impl DiffStatEntry {
+    /// Lines moved to and from other places in the diff. These lines are
+    /// also counted in `added_removed`. Zero unless moved lines were
+    /// requested by [`DiffStatOptions::moved_lines`].
+    pub fn moved_added_removed(&self) -> (usize, usize) {
+        self.moved_added_removed
+    }
+}
+
+fn get_diff_stat_entry<T>(
     path: CopiesTreeDiffEntryPath,
     status: DiffEntryStatus,
     contents: Diff<&FileContent<BString>>,
-    options: &DiffStatOptions,
+    changed_lines: Option<&ChangedLines<T>>,
 ) -> DiffStatEntry {
-    let added_removed = if contents.before.is_binary || contents.after.is_binary {
-        None
-    } else {
-        let diff = diff_by_line(
-            contents.map(|content| &content.contents).into_array(),
-            &options.line_diff.compare_mode,
-        );
-        let mut added = 0;
-        let mut removed = 0;
-        for hunk in diff.hunks() {
-            match hunk.kind {
-                DiffHunkKind::Matching => {}
-                DiffHunkKind::Different => {
-                    let [left, right] = hunk.contents[..].try_into().unwrap();
-                    removed += left.split_inclusive(|b| *b == b'\n').count();
-                    added += right.split_inclusive(|b| *b == b'\n').count();
-                }
-            }
-        }
-        Some((added, removed))
-    };
-
+    let added_removed = changed_lines.map(|lines| (lines.added.len(), lines.removed.len()));
     DiffStatEntry {
         path,
         added_removed,
         bytes_delta: contents.after.contents.len() as isize
             - contents.before.contents.len() as isize,
         status,
+        moved_added_removed: (0, 0),
     }
 }
 
@@ -23,6 +23,9 @@ use std::ops::DerefMut;
 use std::ops::Range;
 use std::sync::Arc;
 
+use clap::ValueEnum as _;
+use clap::builder::PossibleValuesParser;
+use clap::builder::TypedValueParser;
 use crossterm::queue;
 use crossterm::style::Attribute;
 use crossterm::style::Color;
@@ -42,11 +45,29 @@ pub trait Formatter: Write {
     /// already formatted, such as in the graphical log.
     fn raw(&mut self) -> io::Result<Box<dyn Write + '_>>;
 
+    /// Returns the backing `Write` for data that was already rendered by a
+    /// formatter of the same kind, such as the graphical log. Unlike
+    /// [`Formatter::raw()`], the data is never escaped.
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        self.raw()
+    }
+
     fn push_label(&mut self, label: &str);
 
     fn pop_label(&mut self);
 
     fn maybe_color(&self) -> bool;
+
+    /// Starts a hyperlink to the `url`. By default, this emits an OSC 8 escape
+    /// sequence.
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        write!(self.raw()?, "\x1b]8;;{url}\x1b\\")
+    }
+
+    /// Ends the hyperlink started by [`Formatter::push_hyperlink()`].
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        write!(self.raw()?, "\x1b]8;;\x1b\\")
+    }
 }
 
 impl<T: Formatter + ?Sized> Formatter for &mut T {
@@ -54,6 +75,10 @@ impl<T: Formatter + ?Sized> Formatter for &mut T {
         <T as Formatter>::raw(self)
     }
 
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        <T as Formatter>::raw_formatted(self)
+    }
+
     fn push_label(&mut self, label: &str) {
         <T as Formatter>::push_label(self, label);
     }
@@ -65,6 +90,14 @@ impl<T: Formatter + ?Sized> Formatter for &mut T {
     fn maybe_color(&self) -> bool {
         <T as Formatter>::maybe_color(self)
     }
+
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        <T as Formatter>::push_hyperlink(self, url)
+    }
+
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        <T as Formatter>::pop_hyperlink(self)
+    }
 }
 
 impl<T: Formatter + ?Sized> Formatter for Box<T> {
@@ -72,6 +105,10 @@ impl<T: Formatter + ?Sized> Formatter for Box<T> {
         <T as Formatter>::raw(self)
     }
 
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        <T as Formatter>::raw_formatted(self)
+    }
+
     fn push_label(&mut self, label: &str) {
         <T as Formatter>::push_label(self, label);
     }
@@ -83,6 +120,14 @@ impl<T: Formatter + ?Sized> Formatter for Box<T> {
     fn maybe_color(&self) -> bool {
         <T as Formatter>::maybe_color(self)
     }
+
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        <T as Formatter>::push_hyperlink(self, url)
+    }
+
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        <T as Formatter>::pop_hyperlink(self)
+    }
 }
 
 /// [`Formatter`] adapters.
@@ -183,6 +228,7 @@ enum FormatterFactoryKind {
     PlainText,
     Sanitized,
     Color { rules: Arc<Rules>, debug: bool },
+    Html { rules: Arc<Rules> },
 }
 
 impl FormatterFactory {
@@ -202,6 +248,13 @@ impl FormatterFactory {
         Ok(Self { kind })
     }
 
+    /// Creates factory of formatters which render labels as HTML elements.
+    pub fn html(config: &StackedConfig) -> Result<Self, ConfigGetError> {
+        let rules = Arc::new(rules_from_config(config)?);
+        let kind = FormatterFactoryKind::Html { rules };
+        Ok(Self { kind })
+    }
+
     pub fn new_formatter<'output, W: Write + 'output>(
         &self,
         output: W,
@@ -212,11 +265,17 @@ impl FormatterFactory {
             FormatterFactoryKind::Color { rules, debug } => {
                 Box::new(ColorFormatter::new(output, rules.clone(), *debug))
             }
+            FormatterFactoryKind::Html { rules } => {
+                Box::new(HtmlFormatter::new(output, rules.clone()))
+            }
         }
     }
 
     pub fn maybe_color(&self) -> bool {
-        matches!(self.kind, FormatterFactoryKind::Color { .. })
+        matches!(
+            self.kind,
+            FormatterFactoryKind::Color { .. } | FormatterFactoryKind::Html { .. }
+        )
     }
 }
 
@@ -355,35 +414,7 @@ impl<W: Write> ColorFormatter<W> {
         if let Some(cached) = self.cached_styles.get(&self.labels) {
             cached.clone()
         } else {
-            // We use the reverse list of matched indices as a measure of how well the rule
-            // matches the actual labels. For example, for rule "a d" and the actual labels
-            // "a b c d", we'll get [3,0]. We compare them by Rust's default Vec comparison.
-            // That means "a d" will trump both rule "d" (priority [3]) and rule
-            // "a b c" (priority [2,1,0]).
-            let mut matched_styles = vec![];
-            for (labels, style) in self.rules.as_ref() {
-                let mut labels_iter = self.labels.iter().enumerate();
-                // The indexes in the current label stack that match the required label.
-                let mut matched_indices = vec![];
-                for required_label in labels {
-                    for (label_index, label) in &mut labels_iter {
-                        if label == required_label {
-                            matched_indices.push(label_index);
-                            break;
-                        }
-                    }
-                }
-                if matched_indices.len() == labels.len() {
-                    matched_indices.reverse();
-                    matched_styles.push((style, matched_indices));
-                }
-            }
-            matched_styles.sort_by_key(|(_, indices)| indices.clone());
-
-            let mut style = Style::default();
-            for (matched_style, _) in matched_styles {
-                style.merge(matched_style);
-            }
+            let style = style_for_labels(&self.rules, &self.labels);
             self.cached_styles
                 .insert(self.labels.clone(), style.clone());
             style
@@ -474,6 +505,40 @@ impl<W: Write> ColorFormatter<W> {
     }
 }
 
+/// Computes the style of text labeled with `current_labels`.
+fn style_for_labels(rules: &Rules, current_labels: &[String]) -> Style {
+    // We use the reverse list of matched indices as a measure of how well the rule
+    // matches the actual labels. For example, for rule "a d" and the actual labels
+    // "a b c d", we'll get [3,0]. We compare them by Rust's default Vec comparison.
+    // That means "a d" will trump both rule "d" (priority [3]) and rule
+    // "a b c" (priority [2,1,0]).
+    let mut matched_styles = vec![];
+    for (labels, style) in rules {
+        let mut labels_iter = current_labels.iter().enumerate();
+        // The indexes in the current label stack that match the required label.
+        let mut matched_indices = vec![];
+        for required_label in labels {
+            for (label_index, label) in &mut labels_iter {
+                if label == required_label {
+                    matched_indices.push(label_index);
+                    break;
+                }
+            }
+        }
+        if matched_indices.len() == labels.len() {
+            matched_indices.reverse();
+            matched_styles.push((style, matched_indices));
+        }
+    }
+    matched_styles.sort_by_key(|(_, indices)| indices.clone());
+
+    let mut style = Style::default();
+    for (matched_style, _) in matched_styles {
+        style.merge(matched_style);
+    }
+    style
+}
+
 fn rules_from_config(config: &StackedConfig) -> Result<Rules, ConfigGetError> {
     config
         .table_keys("colors")
@@ -649,6 +714,336 @@ impl<W: Write> Drop for ColorFormatter<W> {
     }
 }
 
+/// Formatter that renders labels as HTML `<span>` elements.
+///
+/// Each span has the current labels as CSS classes, and the style from the
+/// color rules as inline CSS. The output is a fragment meant to be embedded in
+/// a `<pre>` element, such as the one written by [`write_html_prologue()`].
+pub struct HtmlFormatter<W: Write> {
+    output: W,
+    rules: Arc<Rules>,
+    /// The stack of currently applied labels.
+    labels: Vec<String>,
+    /// The URL of the current hyperlink.
+    hyperlink: Option<String>,
+    cached_styles: HashMap<Vec<String>, Style>,
+    /// The labels and hyperlink of the elements we last opened in the output,
+    /// or `None` if no elements are open.
+    current: Option<(Vec<String>, Option<String>)>,
+}
+
+impl<W: Write> HtmlFormatter<W> {
+    pub fn new(output: W, rules: Arc<Rules>) -> Self {
+        Self {
+            output,
+            rules,
+            labels: vec![],
+            hyperlink: None,
+            cached_styles: HashMap::new(),
+            current: None,
+        }
+    }
+
+    pub fn for_config(output: W, config: &StackedConfig) -> Result<Self, ConfigGetError> {
+        let rules = rules_from_config(config)?;
+        Ok(Self::new(output, Arc::new(rules)))
+    }
+
+    fn requested_style(&mut self) -> Style {
+        if let Some(cached) = self.cached_styles.get(&self.labels) {
+            cached.clone()
+        } else {
+            let style = style_for_labels(&self.rules, &self.labels);
+            self.cached_styles
+                .insert(self.labels.clone(), style.clone());
+            style
+        }
+    }
+
+    fn open_elements(&mut self) -> io::Result<()> {
+        if let Some((labels, hyperlink)) = &self.current
+            && *labels == self.labels
+            && *hyperlink == self.hyperlink
+        {
+            return Ok(());
+        }
+        self.close_elements()?;
+        if let Some(url) = &self.hyperlink {
+            write!(self.output, "<a href=\"")?;
+            write_html_escaped(&mut self.output, url.as_bytes())?;
+            write!(self.output, "\">")?;
+        }
+        if !self.labels.is_empty() {
+            let css = css_for_style(&self.requested_style());
+            write!(self.output, "<span class=\"")?;
+            write_html_escaped(&mut self.output, self.labels.join(" ").as_bytes())?;
+            if !css.is_empty() {
+                write!(self.output, "\" style=\"{css}")?;
+            }
+            write!(self.output, "\">")?;
+        }
+        self.current = Some((self.labels.clone(), self.hyperlink.clone()));
+        Ok(())
+    }
+
+    fn close_elements(&mut self) -> io::Result<()> {
+        if let Some((labels, hyperlink)) = self.current.take() {
+            if !labels.is_empty() {
+                write!(self.output, "</span>")?;
+            }
+            if hyperlink.is_some() {
+                write!(self.output, "</a>")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for HtmlFormatter<W> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        // Like ColorFormatter, we close the elements at the end of each line so
+        // the graph drawn in front of the next line isn't styled.
+        for line in data.split_inclusive(|b| *b == b'\n') {
+            let (text, newline) = match line.strip_suffix(b"\n") {
+                Some(text) => (text, true),
+                None => (line, false),
+            };
+            if !text.is_empty() {
+                self.open_elements()?;
+                write_html_escaped(&mut self.output, text)?;
+            }
+            if newline {
+                self.close_elements()?;
+                self.output.write_all(b"\n")?;
+            }
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.output.flush()
+    }
+}
+
+impl<W: Write> Formatter for HtmlFormatter<W> {
+    /// Returns the backing `Write` which escapes the data as HTML text. This
+    /// is used for the output of external tools and raw escape sequences,
+    /// which must not inject markup into the document.
+    fn raw(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        self.open_elements()?;
+        Ok(Box::new(HtmlEscapingWriter(self.output.by_ref())))
+    }
+
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        self.open_elements()?;
+        Ok(Box::new(self.output.by_ref()))
+    }
+
+    fn push_label(&mut self, label: &str) {
+        self.labels.push(label.to_owned());
+    }
+
+    fn pop_label(&mut self) {
+        self.labels.pop();
+    }
+
+    fn maybe_color(&self) -> bool {
+        true
+    }
+
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        // Links to other schemes (such as "javascript:") could run code when
+        // the document is opened in a browser, so they are rendered as text.
+        self.hyperlink = is_web_url(url).then(|| url.to_owned());
+        Ok(())
+    }
+
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        self.hyperlink = None;
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for HtmlFormatter<W> {
+    fn drop(&mut self) {
+        self.close_elements().ok();
+    }
+}
+
+/// Format of the document printed by commands with a `--format` option.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DocumentFormat {
+    /// Text for the terminal
+    #[default]
+    Text,
+    /// Standalone HTML document styled according to the `colors` settings
+    Html,
+}
+
+impl DocumentFormat {
+    /// Returns a parser of `--format` values which accepts only the `formats`
+    /// supported by the command.
+    pub fn value_parser(formats: &[Self]) -> impl TypedValueParser<Value = Self> {
+        let values = formats
+            .iter()
+            .filter_map(|format| format.to_possible_value());
+        PossibleValuesParser::new(values).map(|name| Self::from_str(&name, false).unwrap())
+    }
+}
+
+/// Writes the beginning of a standalone HTML document. The output of
+/// [`HtmlFormatter`] should follow, and then [`write_html_epilogue()`].
+pub fn write_html_prologue(output: &mut dyn Write) -> io::Result<()> {
+    output.write_all(
+        b"<!DOCTYPE html>\n\
+          <html>\n\
+          <head>\n\
+          <meta charset=\"utf-8\">\n\
+          <style>\n\
+          pre.jj a { color: inherit; }\n\
+          </style>\n\
+          </head>\n\
+          <body>\n\
+          <pre class=\"jj\">\n",
+    )
+}
+
+/// Writes the end of the document started by [`write_html_prologue()`].
+pub fn write_html_epilogue(output: &mut dyn Write) -> io::Result<()> {
+    output.write_all(b"</pre>\n</body>\n</html>\n")
+}
+
+/// `Write` adapter that escapes all data as HTML text.
+struct HtmlEscapingWriter<W>(W);
+
+impl<W: Write> Write for HtmlEscapingWriter<W> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        write_html_escaped(&mut self.0, data)?;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.0.flush()
+    }
+}
+
+fn is_web_url(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+    scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+fn write_html_escaped(output: &mut impl Write, buf: &[u8]) -> io::Result<()> {
+    let mut start = 0;
+    for (i, b) in buf.iter().enumerate() {
+        let escaped: &[u8] = match b {
+            b'&' => b"&amp;",
+            b'<' => b"&lt;",
+            b'>' => b"&gt;",
+            b'"' => b"&quot;",
+            b'\'' => b"&#39;",
+            b'\x1b' => "␛".as_bytes(),
+            _ => continue,
+        };
+        output.write_all(&buf[start..i])?;
+        output.write_all(escaped)?;
+        start = i + 1;
+    }
+    output.write_all(&buf[start..])
+}
+
+fn css_for_style(style: &Style) -> String {
+    let mut fg = style.fg.and_then(css_color);
+    let mut bg = style.bg.and_then(css_color);
+    if style.reverse.unwrap_or_default() {
+        // Swap the colors, using the system colors of the page as defaults.
+        (fg, bg) = (
+            Some(bg.unwrap_or_else(|| "Canvas".to_owned())),
+            Some(fg.unwrap_or_else(|| "CanvasText".to_owned())),
+        );
+    }
+    let mut declarations = vec![];
+    if let Some(color) = fg {
+        declarations.push(format!("color:{color}"));
+    }
+    if let Some(color) = bg {
+        declarations.push(format!("background-color:{color}"));
+    }
+    if style.bold.unwrap_or_default() {
+        declarations.push("font-weight:bold".to_owned());
+    }
+    if style.dim.unwrap_or_default() {
+        declarations.push("opacity:0.7".to_owned());
+    }
+    if style.italic.unwrap_or_default() {
+        declarations.push("font-style:italic".to_owned());
+    }
+    if style.underline.unwrap_or_default() {
+        declarations.push("text-decoration:underline".to_owned());
+    }
+    declarations.join(";")
+}
+
+fn css_color(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Black => ansi256_rgb(0),
+        Color::DarkRed => ansi256_rgb(1),
+        Color::DarkGreen => ansi256_rgb(2),
+        Color::DarkYellow => ansi256_rgb(3),
+        Color::DarkBlue => ansi256_rgb(4),
+        Color::DarkMagenta => ansi256_rgb(5),
+        Color::DarkCyan => ansi256_rgb(6),
+        Color::Grey => ansi256_rgb(7),
+        Color::DarkGrey => ansi256_rgb(8),
+        Color::Red => ansi256_rgb(9),
+        Color::Green => ansi256_rgb(10),
+        Color::Yellow => ansi256_rgb(11),
+        Color::Blue => ansi256_rgb(12),
+        Color::Magenta => ansi256_rgb(13),
+        Color::Cyan => ansi256_rgb(14),
+        Color::White => ansi256_rgb(15),
+        Color::AnsiValue(index) => ansi256_rgb(index),
+        Color::Rgb { r, g, b } => (r, g, b),
+    };
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+/// Returns the RGB value of the color in xterm's default 256-color palette.
+fn ansi256_rgb(index: u8) -> (u8, u8, u8) {
+    const BASIC_COLORS: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+    match index {
+        0..16 => BASIC_COLORS[usize::from(index)],
+        16..232 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        232.. => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
 /// Like buffered formatter, but records `push`/`pop_label()` calls.
 ///
 /// This allows you to manipulate the recorded data without losing labels.
@@ -667,6 +1062,8 @@ pub struct FormatRecorder {
 enum FormatOp {
     PushLabel(String),
     PopLabel,
+    PushHyperlink(String),
+    PopHyperlink,
     RawEscapeSequence(Vec<u8>),
 }
 
@@ -720,6 +1117,8 @@ impl FormatRecorder {
             match op {
                 FormatOp::PushLabel(label) => formatter.push_label(label),
                 FormatOp::PopLabel => formatter.pop_label(),
+                FormatOp::PushHyperlink(url) => formatter.push_hyperlink(url)?,
+                FormatOp::PopHyperlink => formatter.pop_hyperlink()?,
                 FormatOp::RawEscapeSequence(raw_escape_sequence) => {
                     formatter.raw()?.write_all(raw_escape_sequence)?;
                 }
@@ -769,6 +1168,16 @@ impl Formatter for FormatRecorder {
     fn maybe_color(&self) -> bool {
         self.maybe_color
     }
+
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        self.push_op(FormatOp::PushHyperlink(url.to_owned()));
+        Ok(())
+    }
+
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        self.push_op(FormatOp::PopHyperlink);
+        Ok(())
+    }
 }
 
 fn write_sanitized(output: &mut impl Write, buf: &[u8]) -> Result<(), Error> {
@@ -1686,4 +2095,113 @@ mod tests {
             to_snapshot_string(output), @" outer1 [38;5;1m inner1  inner2 [39m outer2 [EOF]");
         Ok(())
     }
+
+    #[test]
+    fn test_html_formatter() -> TestResult {
+        let config = config_from_string(indoc! {"
+            colors.outer = 'blue'
+            colors.'outer inner' = { fg = 'ansi-color-46', bold = true }
+            colors.reversed = { reverse = true }
+        "});
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        write!(formatter, "<plain> & ")?;
+        formatter.push_label("outer");
+        write!(formatter, "outer ")?;
+        formatter.push_label("inner");
+        write!(formatter, "inner\nnext line\n")?;
+        formatter.pop_label();
+        formatter.pop_label();
+        formatter.push_label("reversed");
+        write!(formatter, "\x1b[1m")?;
+        formatter.pop_label();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r#"
+        &lt;plain&gt; &amp; <span class="outer" style="color:#0000ee">outer </span><span class="outer inner" style="color:#00ff00;font-weight:bold">inner</span>
+        <span class="outer inner" style="color:#00ff00;font-weight:bold">next line</span>
+        <span class="reversed" style="color:Canvas;background-color:CanvasText">␛[1m</span>[EOF]
+        "#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_formatter_hyperlink() -> TestResult {
+        let config = config_from_string(r#" colors.link = "red" "#);
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        formatter.push_hyperlink("https://example.com/?a=1&b=\"2\"")?;
+        write!(formatter, "plain ")?;
+        formatter.push_label("link");
+        write!(formatter, "labeled")?;
+        formatter.pop_label();
+        formatter.pop_hyperlink()?;
+        write!(formatter, " after")?;
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r#"<a href="https://example.com/?a=1&amp;b=&quot;2&quot;">plain </a><a href="https://example.com/?a=1&amp;b=&quot;2&quot;"><span class="link" style="color:#cd0000">labeled</span></a> after[EOF]"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_formatter_unsafe_hyperlink() -> TestResult {
+        let config = config_from_string("");
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        formatter.push_hyperlink("javascript:alert(1)")?;
+        write!(formatter, "script")?;
+        formatter.pop_hyperlink()?;
+        formatter.push_hyperlink("HTTP://example.com/")?;
+        write!(formatter, " web")?;
+        formatter.pop_hyperlink()?;
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r#"script<a href="HTTP://example.com/"> web</a>[EOF]"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_formatter_raw() -> TestResult {
+        let config = config_from_string("colors.outer = 'blue'");
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        formatter.push_label("outer");
+        write!(formatter.raw()?, "<b>\x1b[1m</b>")?;
+        write!(formatter.raw_formatted()?, "<b>bold</b>")?;
+        formatter.pop_label();
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r#"<span class="outer" style="color:#0000ee">&lt;b&gt;␛[1m&lt;/b&gt;<b>bold</b></span>[EOF]"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_recorder_hyperlink() -> TestResult {
+        let mut recorder = FormatRecorder::new(true);
+        recorder.push_hyperlink("https://example.com/")?;
+        write!(recorder, "link")?;
+        recorder.pop_hyperlink()?;
+
+        let config = config_from_string("");
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false)?;
+        recorder.replay(&mut formatter)?;
+        drop(formatter);
+        insta::assert_snapshot!(
+            to_snapshot_string(output), @r"]8;;https://example.com/\link]8;;\[EOF]");
+
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        recorder.replay(&mut formatter)?;
+        drop(formatter);
+        insta::assert_snapshot!(
+            to_snapshot_string(output), @r#"<a href="https://example.com/">link</a>[EOF]"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ansi256_rgb() {
+        assert_eq!(ansi256_rgb(1), (0xcd, 0x00, 0x00));
+        assert_eq!(ansi256_rgb(16), (0, 0, 0));
+        assert_eq!(ansi256_rgb(46), (0, 255, 0));
+        assert_eq!(ansi256_rgb(231), (255, 255, 255));
+        assert_eq!(ansi256_rgb(232), (8, 8, 8));
+        assert_eq!(ansi256_rgb(255), (238, 238, 238));
+    }
 }
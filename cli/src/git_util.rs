@@ -20,6 +20,7 @@ use std::io::Write as _;
 use std::iter;
 use std::mem;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -28,12 +29,14 @@ use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use indoc::writedoc;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::git;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitExportStats;
 use jj_lib::git::GitImportOptions;
 use jj_lib::git::GitImportStats;
+use jj_lib::git::GitNotesError;
 use jj_lib::git::GitProgress;
 use jj_lib::git::GitPushStats;
 use jj_lib::git::GitRefKind;
@@ -43,6 +46,7 @@ use jj_lib::git::GitSubprocessCallback;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::ref_name::RemoteRefSymbol;
+use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::settings::RemoteSettingsMap;
@@ -125,6 +129,40 @@ pub fn get_remote_web_url(repo: &ReadonlyRepo, remote_name: &str) -> Option<Stri
     git_remote_url_to_web(url)
 }
 
+/// Git notes recorded in the view, loaded once per template.
+///
+/// The loading error is kept so it can be reported for each commit.
+pub struct GitNotes(Result<Option<git::GitNotes>, Arc<GitNotesError>>);
+
+impl GitNotes {
+    /// Loads the Git notes. Nothing is loaded if the repo isn't backed by Git.
+    pub fn load(repo: &dyn Repo) -> Self {
+        if git::get_git_backend(repo.store()).is_err() {
+            return Self(Ok(None));
+        }
+        Self(git::GitNotes::load(repo).map(Some).map_err(Arc::new))
+    }
+
+    /// Returns the Git note attached to the commit.
+    pub fn get(&self, commit_id: &CommitId) -> Result<Option<String>, Arc<GitNotesError>> {
+        let Some(notes) = self.0.as_ref().map_err(Arc::clone)? else {
+            return Ok(None);
+        };
+        let note = notes.get(commit_id).map_err(Arc::new)?;
+        Ok(note.map(|note| note.to_str_lossy().into_owned()))
+    }
+}
+
+/// Warns if Git notes couldn't be copied to the commits rewritten in the
+/// transaction.
+pub fn print_git_notes_copy_error(ui: &Ui, mut_repo: &MutableRepo) -> io::Result<()> {
+    if let Some(err) = mut_repo.git_notes_copy_error() {
+        writeln!(ui.warning_default(), "Failed to copy Git notes: {err}")?;
+        crate::command_error::print_error_sources(ui, error::Error::source(err))?;
+    }
+    Ok(())
+}
+
 /// [`Ui`] adapter to forward Git command outputs.
 pub struct GitSubprocessUi<'a> {
     // Don't hold locked ui.status() which could block tracing output in
@@ -501,6 +539,14 @@ pub fn print_git_export_stats(ui: &Ui, stats: &GitExportStats) -> Result<(), std
             writeln!(formatter)?;
         }
     }
+    if let Some(reason) = &stats.failed_notes {
+        write!(ui.warning_default(), "Failed to export Git notes")?;
+        let mut formatter = ui.stderr_formatter();
+        for err in iter::successors(Some(reason as &dyn error::Error), |err| err.source()) {
+            write!(formatter, ": {err}")?;
+        }
+        writeln!(formatter)?;
+    }
     if itertools::chain(&stats.failed_bookmarks, &stats.failed_tags)
         .any(|(_, reason)| matches!(reason, FailedRefExportReason::FailedToSet(_)))
     {
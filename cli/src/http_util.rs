@@ -0,0 +1,145 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sends HTTP requests to the REST APIs of code review systems.
+//!
+//! Requests are sent by running the `curl`-compatible program configured by
+//! `review.http-program`, so no HTTP client is linked into jj.
+
+use std::fmt::Write as _;
+use std::io;
+use std::io::Write as _;
+use std::process::Command;
+use std::process::Stdio;
+
+use jj_lib::config::ConfigGetError;
+use jj_lib::settings::UserSettings;
+use thiserror::Error;
+
+/// Error of an HTTP request.
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("Failed to run {program}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Request to {url} failed: {message}")]
+    Request { url: String, message: String },
+}
+
+/// An HTTP request.
+#[derive(Clone, Debug)]
+pub struct HttpRequest<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub headers: Vec<String>,
+    pub body: Option<&'a [u8]>,
+}
+
+impl<'a> HttpRequest<'a> {
+    pub fn new(method: &'a str, url: &'a str) -> Self {
+        Self {
+            method,
+            url,
+            headers: vec![],
+            body: None,
+        }
+    }
+}
+
+/// Runs a `curl`-compatible program to send HTTP requests.
+///
+/// The options are passed through the standard input so header values such as
+/// access tokens don't show up in the process list.
+#[derive(Clone, Debug)]
+pub struct HttpProgram {
+    program: String,
+}
+
+impl HttpProgram {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self::new(settings.get_string("review.http-program")?))
+    }
+
+    /// Sends the request, and returns the body of the response if the server
+    /// responded with a success status.
+    pub fn send(&self, request: &HttpRequest) -> Result<Vec<u8>, HttpError> {
+        let mut config = String::new();
+        let mut add_option = |name: &str, value: &str| {
+            let value = value
+                .replace('\\', r"\\")
+                .replace('"', r#"\""#)
+                .replace('\n', r"\n");
+            writeln!(config, r#"{name} = "{value}""#).unwrap();
+        };
+        add_option("url", request.url);
+        add_option("request", request.method);
+        for header in &request.headers {
+            add_option("header", header);
+        }
+        if let Some(body) = request.body {
+            add_option("data-binary", &String::from_utf8_lossy(body));
+        }
+
+        let spawn_error = |source| HttpError::Spawn {
+            program: self.program.clone(),
+            source,
+        };
+        let mut child = Command::new(&self.program)
+            .args([
+                "--silent",
+                "--show-error",
+                "--location",
+                "--fail-with-body",
+                "--config",
+                "-",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+        // Dropping the pipe closes the standard input of the program.
+        let mut stdin = child.stdin.take().unwrap();
+        stdin.write_all(config.as_bytes()).map_err(spawn_error)?;
+        drop(stdin);
+        let output = child.wait_with_output().map_err(spawn_error)?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let mut message = stderr.trim().trim_start_matches("curl: ").to_owned();
+            // REST APIs usually explain errors in a `message` field.
+            let response: Option<serde_json::Value> = serde_json::from_slice(&output.stdout).ok();
+            if let Some(detail) = response.as_ref().and_then(|r| r.get("message")) {
+                match detail.as_str() {
+                    Some(detail) => write!(message, " ({detail})").unwrap(),
+                    None => write!(message, " ({detail})").unwrap(),
+                }
+            }
+            return Err(HttpError::Request {
+                url: request.url.to_owned(),
+                message,
+            });
+        }
+        Ok(output.stdout)
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stable JSON Lines output of listing commands and diffs.
+//!
+//! Each record is a JSON object on its own line, with the schema `version` and
+//! a single key naming the kind of the record:
+//!
+//! ```json
+//! {"version":1,"commit":{"commit_id":"...", ...}}
+//! ```
+//!
+//! The records are rendered by built-in templates which don't expand user
+//! aliases, so the output doesn't change with the user configuration.
+
+use std::io;
+
+use serde::Serialize;
+use serde::ser::SerializeMap as _;
+
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::formatter::Formatter;
+use crate::template_builder;
+use crate::template_builder::TemplateLanguage;
+use crate::template_parser::TemplateAliasesMap;
+use crate::template_parser::TemplateDiagnostics;
+use crate::templater::TemplateRenderer;
+use crate::templater::WrapTemplateProperty;
+
+/// Version of the JSON output schema. Bumped on incompatible changes.
+pub const JSON_OUTPUT_VERSION: u32 = 1;
+
+/// Output format of listing commands and diffs.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// Human-readable output rendered by the configured templates
+    #[default]
+    Text,
+    /// One JSON object per line in a stable schema
+    Json,
+}
+
+/// Parses the built-in template which renders `value_expr` as a record of the
+/// given `kind`.
+///
+/// `value_expr` is a template expression of `Serialize` type.
+pub fn parse_json_template<'a, C, L>(
+    language: &L,
+    kind: &str,
+    value_expr: &str,
+) -> Result<TemplateRenderer<'a, C>, CommandError>
+where
+    C: Clone + 'a,
+    L: TemplateLanguage<'a> + ?Sized,
+    L::Property: WrapTemplateProperty<'a, C>,
+{
+    let text =
+        format!(r#"'{{"version":{JSON_OUTPUT_VERSION},"{kind}":' ++ json({value_expr}) ++ "}}\n""#);
+    // Aliases aren't expanded so that user configuration can't change the
+    // schema. Built-in functions can't be shadowed either.
+    let mut diagnostics = TemplateDiagnostics::new();
+    let template = template_builder::parse(
+        language,
+        &mut diagnostics,
+        &text,
+        &TemplateAliasesMap::new(),
+    )?;
+    Ok(template)
+}
+
+/// Writes `value` as a record of the given `kind`.
+pub fn write_json_record<T: Serialize + ?Sized>(
+    formatter: &mut dyn Formatter,
+    kind: &str,
+    value: &T,
+) -> io::Result<()> {
+    let record = JsonRecord { kind, value };
+    serde_json::to_writer(&mut *formatter, &record)?;
+    writeln!(formatter)
+}
+
+/// Returns an error for arguments which only apply to the text output.
+pub fn text_only_arg_error(args: &str) -> CommandError {
+    user_error(format!("{args} cannot be used with `--output json`"))
+}
+
+struct JsonRecord<'a, T: ?Sized> {
+    kind: &'a str,
+    value: &'a T,
+}
+
+impl<T: Serialize + ?Sized> Serialize for JsonRecord<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("version", &JSON_OUTPUT_VERSION)?;
+        map.serialize_entry(self.kind, self.value)?;
+        map.end()
+    }
+}
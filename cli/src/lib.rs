@@ -32,7 +32,9 @@ pub mod git_util;
 /// A stub module that provides a no-op implementation of some of the functions
 /// in the `git` module.
 pub mod git_util {
+    use jj_lib::backend::CommitId;
     use jj_lib::repo::ReadonlyRepo;
+    use jj_lib::repo::Repo;
     use jj_lib::workspace::Workspace;
 
     pub fn is_colocated_git_workspace(_workspace: &Workspace, _repo: &ReadonlyRepo) -> bool {
@@ -42,8 +44,22 @@ pub mod git_util {
     pub fn get_remote_web_url(_repo: &ReadonlyRepo, _remote_name: &str) -> Option<String> {
         None
     }
+
+    pub struct GitNotes;
+
+    impl GitNotes {
+        pub fn load(_repo: &dyn Repo) -> Self {
+            Self
+        }
+
+        pub fn get(&self, _commit_id: &CommitId) -> Option<String> {
+            None
+        }
+    }
 }
 pub mod graphlog;
+pub mod http_util;
+pub mod json_output;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
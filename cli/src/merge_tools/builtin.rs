@@ -50,6 +50,10 @@ pub enum BuiltinToolError {
     Unimplemented { item: &'static str, id: String },
     #[error("Backend error")]
     BackendError(#[from] jj_lib::backend::BackendError),
+    #[error("Failed to run the merge editor")]
+    Tui(#[source] std::io::Error),
+    #[error("Canceled by user")]
+    Canceled,
 }
 
 #[derive(Clone, Debug)]
@@ -392,7 +396,7 @@ async fn make_diff_files(
     Ok((changed_files, files))
 }
 
-fn resolve_file_copy_id(tree_value: &MergedTreeValue) -> Option<CopyId> {
+pub(super) fn resolve_file_copy_id(tree_value: &MergedTreeValue) -> Option<CopyId> {
     tree_value.to_copy_id_merge().and_then(|copy_id| {
         copy_id
             .resolve_trivial(SameChange::Accept)
@@ -16,7 +16,7 @@ use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::ConflictMaterializeOptions;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
 use jj_lib::conflicts::choose_materialized_conflict_marker_len;
-use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_merge_result_to_bytes_with_tokenizer;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
@@ -210,7 +210,14 @@ async fn run_mergetool_external_single_file(
             marker_len: Some(conflict_marker_len),
             merge: store.merge_options().clone(),
         };
-        materialize_merge_result_to_bytes(&file.contents, &file.labels, &options)
+        let tokenizer = options.merge.word_tokenizers.tokenizer_for_path(repo_path);
+        materialize_merge_result_to_bytes_with_tokenizer(
+            &file.contents,
+            &file.labels,
+            &options,
+            tokenizer,
+        )
+        .merged
     } else {
         BString::default()
     };
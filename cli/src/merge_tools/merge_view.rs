@@ -0,0 +1,1210 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Multi-pane merge view of the `:merge-view` builtin merge tool.
+//!
+//! Each conflicted hunk is shown with one pane per term of the conflict (the
+//! sides and bases, in the same order as in conflict markers), and a result
+//! pane below them. Lines can be copied from the terms to the result, and the
+//! result can be edited inline.
+
+use std::borrow::Cow;
+use std::io;
+use std::iter::zip;
+use std::panic;
+use std::panic::PanicHookInfo;
+use std::sync::Arc;
+
+use bstr::BString;
+use bstr::ByteSlice as _;
+use bstr::ByteVec as _;
+use crossterm::ExecutableCommand as _;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use crossterm::event::{self};
+use crossterm::terminal::EnterAlternateScreen;
+use crossterm::terminal::LeaveAlternateScreen;
+use crossterm::terminal::disable_raw_mode;
+use crossterm::terminal::enable_raw_mode;
+use futures::future::try_join_all;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::CopyId;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflict_labels::ConflictLabels;
+use jj_lib::files;
+use jj_lib::files::MergeResult;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree_builder::MergedTreeBuilder;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::store::Store;
+use jj_lib::tree_merge::MergeOptions;
+use ratatui::Terminal;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Position;
+use ratatui::layout::Rect;
+use ratatui::prelude::CrosstermBackend;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Paragraph;
+
+use super::MergeToolFile;
+use super::builtin::BuiltinToolError;
+use super::builtin::resolve_file_copy_id;
+
+/// A conflicted hunk and its resolution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ConflictHunk {
+    /// Lines of each term of the conflict, including the line terminators.
+    terms: Merge<Vec<BString>>,
+    /// Lines of the result, or `None` if the hunk is unresolved.
+    result: Option<Vec<BString>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Hunk {
+    Resolved(BString),
+    Conflict(ConflictHunk),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FileState {
+    path: RepoPathBuf,
+    /// Labels of the terms of the conflict.
+    labels: Merge<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl FileState {
+    fn new(merge_tool_file: &MergeToolFile, options: &MergeOptions) -> Self {
+        let file = &merge_tool_file.file;
+        let path = merge_tool_file.repo_path.clone();
+        let labels = term_labels(&file.labels, file.contents.num_sides());
+        let tokenizer = options.word_tokenizers.tokenizer_for_path(&path);
+        let merged = files::merge_hunks_with_tokenizer(&file.contents, options, tokenizer).merged;
+        let hunks = match merged {
+            MergeResult::Resolved(contents) => vec![Hunk::Resolved(contents)],
+            MergeResult::Conflict(hunks) => hunks
+                .into_iter()
+                .map(|hunk| match hunk.into_resolved() {
+                    Ok(contents) => Hunk::Resolved(contents),
+                    Err(terms) => Hunk::Conflict(ConflictHunk {
+                        terms: terms.map(|contents| {
+                            contents
+                                .lines_with_terminator()
+                                .map(BString::from)
+                                .collect()
+                        }),
+                        result: None,
+                    }),
+                })
+                .collect(),
+        };
+        Self {
+            path,
+            labels,
+            hunks,
+        }
+    }
+
+    /// Returns the contents of the file with the resolved hunks applied. The
+    /// contents are resolved if all hunks are resolved.
+    fn contents(&self) -> Merge<BString> {
+        let is_resolved = self.hunks.iter().all(|hunk| match hunk {
+            Hunk::Resolved(_) => true,
+            Hunk::Conflict(conflict) => conflict.result.is_some(),
+        });
+        let num_terms = if is_resolved {
+            1
+        } else {
+            self.labels.as_slice().len()
+        };
+        let mut terms = vec![BString::default(); num_terms];
+        for hunk in &self.hunks {
+            for (index, term) in terms.iter_mut().enumerate() {
+                match hunk {
+                    Hunk::Resolved(contents) => term.push_str(contents),
+                    Hunk::Conflict(ConflictHunk {
+                        result: Some(lines),
+                        ..
+                    }) => {
+                        for line in lines {
+                            term.push_str(line);
+                        }
+                    }
+                    Hunk::Conflict(ConflictHunk {
+                        terms: hunk_terms,
+                        result: None,
+                    }) => {
+                        for line in &hunk_terms.as_slice()[index] {
+                            term.push_str(line);
+                        }
+                    }
+                }
+            }
+        }
+        Merge::from_vec(terms)
+    }
+}
+
+/// Returns the labels of the terms, using the same default labels as conflict
+/// markers.
+fn term_labels(labels: &ConflictLabels, num_sides: usize) -> Merge<String> {
+    let num_bases = num_sides - 1;
+    let removes = (0..num_bases).map(|index| {
+        labels.get_remove(index).map_or_else(
+            || {
+                if num_bases == 1 {
+                    "base".to_owned()
+                } else {
+                    format!("base #{}", index + 1)
+                }
+            },
+            ToOwned::to_owned,
+        )
+    });
+    let adds = (0..num_sides).map(|index| {
+        labels
+            .get_add(index)
+            .map_or_else(|| format!("side #{}", index + 1), ToOwned::to_owned)
+    });
+    Merge::from_removes_adds(removes, adds)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pane {
+    /// The pane of the term at the index in the conflict.
+    Term(usize),
+    Result,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    /// Editing the result line at the cursor. The column is counted in
+    /// characters.
+    Insert {
+        column: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct State {
+    files: Vec<FileState>,
+    /// Positions of the conflicted hunks as (file index, hunk index).
+    conflicts: Vec<(usize, usize)>,
+    /// Index into `conflicts` of the hunk being resolved.
+    current: usize,
+    pane: Pane,
+    /// The line of the cursor in the current pane.
+    cursor: usize,
+    mode: Mode,
+}
+
+impl State {
+    fn new(files: Vec<FileState>) -> Self {
+        let conflicts = files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_index, file)| {
+                file.hunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, hunk)| matches!(hunk, Hunk::Conflict(_)))
+                    .map(move |(hunk_index, _)| (file_index, hunk_index))
+            })
+            .collect();
+        Self {
+            files,
+            conflicts,
+            current: 0,
+            pane: Pane::Term(0),
+            cursor: 0,
+            mode: Mode::Normal,
+        }
+    }
+
+    fn current_file(&self) -> &FileState {
+        let (file_index, _) = self.conflicts[self.current];
+        &self.files[file_index]
+    }
+
+    fn current_hunk(&self) -> &ConflictHunk {
+        let (file_index, hunk_index) = self.conflicts[self.current];
+        match &self.files[file_index].hunks[hunk_index] {
+            Hunk::Conflict(conflict) => conflict,
+            Hunk::Resolved(_) => panic!("hunk should be conflicted"),
+        }
+    }
+
+    fn current_hunk_mut(&mut self) -> &mut ConflictHunk {
+        let (file_index, hunk_index) = self.conflicts[self.current];
+        match &mut self.files[file_index].hunks[hunk_index] {
+            Hunk::Conflict(conflict) => conflict,
+            Hunk::Resolved(_) => panic!("hunk should be conflicted"),
+        }
+    }
+
+    fn num_unresolved(&self) -> usize {
+        self.files
+            .iter()
+            .flat_map(|file| &file.hunks)
+            .filter(|hunk| matches!(hunk, Hunk::Conflict(ConflictHunk { result: None, .. })))
+            .count()
+    }
+
+    /// Returns the number of lines in the current pane.
+    fn pane_len(&self) -> usize {
+        let hunk = self.current_hunk();
+        match self.pane {
+            Pane::Term(index) => hunk.terms.as_slice()[index].len(),
+            Pane::Result => hunk.result.as_ref().map_or(0, Vec::len),
+        }
+    }
+
+    fn clamp_cursor(&mut self) {
+        self.cursor = self.cursor.min(self.pane_len().saturating_sub(1));
+    }
+
+    fn move_to_conflict(&mut self, index: usize) {
+        self.current = index;
+        self.pane = Pane::Term(0);
+        self.cursor = 0;
+    }
+
+    fn move_to_pane(&mut self, pane: Pane) {
+        self.pane = pane;
+        self.clamp_cursor();
+    }
+
+    fn next_pane(&mut self) {
+        let num_terms = self.current_hunk().terms.as_slice().len();
+        match self.pane {
+            Pane::Term(index) if index + 1 < num_terms => self.move_to_pane(Pane::Term(index + 1)),
+            Pane::Term(_) => self.move_to_pane(Pane::Result),
+            Pane::Result => {}
+        }
+    }
+
+    fn previous_pane(&mut self) {
+        let num_terms = self.current_hunk().terms.as_slice().len();
+        match self.pane {
+            Pane::Term(index) if index > 0 => self.move_to_pane(Pane::Term(index - 1)),
+            Pane::Term(_) => {}
+            Pane::Result => self.move_to_pane(Pane::Term(num_terms - 1)),
+        }
+    }
+
+    /// Appends lines to the result, which resolves the hunk.
+    fn take_lines(&mut self, lines: impl IntoIterator<Item = BString>) {
+        let result = self.current_hunk_mut().result.get_or_insert_default();
+        for line in lines {
+            // Keep the lines separate if a line without terminator was taken
+            // from the end of a file.
+            if let Some(last) = result.last_mut()
+                && !last.ends_with(b"\n")
+            {
+                last.push(b'\n');
+            }
+            result.push(line);
+        }
+    }
+
+    fn take_line_at_cursor(&mut self) {
+        let Pane::Term(index) = self.pane else {
+            return;
+        };
+        if let Some(line) = self.current_hunk().terms.as_slice()[index].get(self.cursor) {
+            self.take_lines([line.clone()]);
+        }
+    }
+
+    fn take_term(&mut self) {
+        let Pane::Term(index) = self.pane else {
+            return;
+        };
+        let lines = self.current_hunk().terms.as_slice()[index].clone();
+        self.take_lines(lines);
+    }
+
+    fn delete_result_line(&mut self) {
+        let cursor = self.cursor;
+        if let Some(result) = &mut self.current_hunk_mut().result
+            && cursor < result.len()
+        {
+            result.remove(cursor);
+            self.clamp_cursor();
+        }
+    }
+
+    /// Starts editing the result line at the cursor. If `new_line` is true,
+    /// inserts an empty line below the cursor to edit.
+    fn start_editing(&mut self, new_line: bool) {
+        let cursor = self.cursor;
+        let result = self.current_hunk_mut().result.get_or_insert_default();
+        let line_index = if result.is_empty() {
+            result.push(BString::from("\n"));
+            0
+        } else if new_line {
+            result.insert(cursor + 1, BString::from("\n"));
+            cursor + 1
+        } else {
+            cursor
+        };
+        let column = line_text(&result[line_index]).chars().count();
+        self.cursor = line_index;
+        self.mode = Mode::Insert { column };
+    }
+
+    /// Calls `f` with the text of the result line at the cursor and the
+    /// column, and replaces the line with the returned lines.
+    fn edit_line(&mut self, f: impl FnOnce(String, usize) -> (Vec<String>, usize, usize)) {
+        let Mode::Insert { column } = self.mode else {
+            return;
+        };
+        let cursor = self.cursor;
+        let result = self.current_hunk_mut().result.get_or_insert_default();
+        let (text, terminator) = split_line(&result[cursor]);
+        let terminator = if terminator.is_empty() {
+            b"\n".to_vec()
+        } else {
+            terminator.to_vec()
+        };
+        let (lines, line_offset, new_column) = f(text.to_str_lossy().into_owned(), column);
+        let new_lines = lines.into_iter().map(|text| {
+            let mut line = BString::from(text);
+            line.extend_from_slice(&terminator);
+            line
+        });
+        result.splice(cursor..cursor + 1, new_lines);
+        self.cursor = cursor + line_offset;
+        self.mode = Mode::Insert { column: new_column };
+    }
+
+    /// Joins the result line at the cursor with the previous line.
+    fn join_with_previous_line(&mut self) {
+        let cursor = self.cursor;
+        if cursor == 0 {
+            return;
+        }
+        let result = self.current_hunk_mut().result.get_or_insert_default();
+        let line = result.remove(cursor);
+        let previous = &mut result[cursor - 1];
+        let (previous_text, previous_terminator) = split_line(previous);
+        let (text, terminator) = split_line(&line);
+        let column = previous_text.to_str_lossy().chars().count();
+        let mut joined = BString::from(previous_text);
+        joined.push_str(text);
+        joined.push_str(if previous_terminator.is_empty() {
+            terminator
+        } else {
+            previous_terminator
+        });
+        *previous = joined;
+        self.cursor = cursor - 1;
+        self.mode = Mode::Insert { column };
+    }
+
+    fn handle_normal_key(&mut self, event: KeyEvent) {
+        match (event.code, event.modifiers) {
+            (KeyCode::Down | KeyCode::Char('j'), KeyModifiers::NONE)
+                if self.cursor + 1 < self.pane_len() =>
+            {
+                self.cursor += 1;
+            }
+            (KeyCode::Up | KeyCode::Char('k'), KeyModifiers::NONE) if self.cursor > 0 => {
+                self.cursor -= 1;
+            }
+            (KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab, KeyModifiers::NONE) => {
+                self.next_pane();
+            }
+            (KeyCode::Left | KeyCode::Char('h'), KeyModifiers::NONE)
+            | (KeyCode::BackTab, KeyModifiers::SHIFT) => {
+                self.previous_pane();
+            }
+            (KeyCode::Char('n'), KeyModifiers::NONE) if self.current + 1 < self.conflicts.len() => {
+                self.move_to_conflict(self.current + 1);
+            }
+            (KeyCode::Char('p'), KeyModifiers::NONE) if self.current > 0 => {
+                self.move_to_conflict(self.current - 1);
+            }
+            (KeyCode::Char(' ') | KeyCode::Enter, KeyModifiers::NONE) => {
+                self.take_line_at_cursor();
+                if self.cursor + 1 < self.pane_len() {
+                    self.cursor += 1;
+                }
+            }
+            (KeyCode::Char('a'), KeyModifiers::NONE) => self.take_term(),
+            (KeyCode::Char('d' | 'x'), KeyModifiers::NONE) if self.pane == Pane::Result => {
+                self.delete_result_line();
+            }
+            (KeyCode::Char('e'), KeyModifiers::NONE) => {
+                self.move_to_pane(Pane::Result);
+                self.start_editing(false);
+            }
+            (KeyCode::Char('o'), KeyModifiers::NONE) => {
+                self.move_to_pane(Pane::Result);
+                self.start_editing(true);
+            }
+            (KeyCode::Char('u'), KeyModifiers::NONE) => {
+                self.current_hunk_mut().result = None;
+                self.clamp_cursor();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_insert_key(&mut self, event: KeyEvent) {
+        match (event.code, event.modifiers) {
+            (KeyCode::Esc, _) => self.mode = Mode::Normal,
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                self.edit_line(|mut text, column| {
+                    text.insert(char_to_byte_index(&text, column), c);
+                    (vec![text], 0, column + 1)
+                });
+            }
+            (KeyCode::Enter, _) => {
+                self.edit_line(|mut text, column| {
+                    let tail = text.split_off(char_to_byte_index(&text, column));
+                    (vec![text, tail], 1, 0)
+                });
+            }
+            (KeyCode::Backspace, _) if matches!(self.mode, Mode::Insert { column: 0 }) => {
+                self.join_with_previous_line();
+            }
+            (KeyCode::Backspace, _) => {
+                self.edit_line(|mut text, column| {
+                    text.remove(char_to_byte_index(&text, column - 1));
+                    (vec![text], 0, column - 1)
+                });
+            }
+            (KeyCode::Delete, _) => {
+                self.edit_line(|mut text, column| {
+                    let index = char_to_byte_index(&text, column);
+                    if index < text.len() {
+                        text.remove(index);
+                    }
+                    (vec![text], 0, column)
+                });
+            }
+            (KeyCode::Left, _) => {
+                self.edit_line(|text, column| (vec![text], 0, column.saturating_sub(1)));
+            }
+            (KeyCode::Right, _) => {
+                self.edit_line(|text, column| {
+                    let len = text.chars().count();
+                    (vec![text], 0, (column + 1).min(len))
+                });
+            }
+            (KeyCode::Home, _) => self.edit_line(|text, _| (vec![text], 0, 0)),
+            (KeyCode::End, _) => {
+                self.edit_line(|text, _| {
+                    let len = text.chars().count();
+                    (vec![text], 0, len)
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key_event(&mut self, event: KeyEvent) {
+        match self.mode {
+            Mode::Normal => self.handle_normal_key(event),
+            Mode::Insert { .. } => self.handle_insert_key(event),
+        }
+    }
+}
+
+/// Splits the line into the text and the line terminator.
+fn split_line(line: &[u8]) -> (&[u8], &[u8]) {
+    let text_len = line.strip_suffix(b"\n").map_or(line.len(), |text| {
+        text.strip_suffix(b"\r").unwrap_or(text).len()
+    });
+    line.split_at(text_len)
+}
+
+/// Returns the text of the line without the line terminator. Invalid UTF-8
+/// sequences are replaced, which also applies to edited lines.
+fn line_text(line: &[u8]) -> Cow<'_, str> {
+    split_line(line).0.to_str_lossy()
+}
+
+fn char_to_byte_index(text: &str, column: usize) -> usize {
+    text.char_indices()
+        .nth(column)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+fn run_tui<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    mut state: State,
+) -> Result<Option<State>, BuiltinToolError> {
+    loop {
+        terminal
+            .draw(|frame| render(&state, frame, frame.area()))
+            .map_err(|err| BuiltinToolError::Tui(io::Error::other(err.to_string())))?;
+        if let Event::Key(event) = event::read().map_err(BuiltinToolError::Tui)? {
+            // On Windows, we get Press and Release (and maybe Repeat) events, but on Linux
+            // we only get Press.
+            if event.is_release() {
+                continue;
+            }
+            if state.mode == Mode::Normal {
+                match (event.code, event.modifiers) {
+                    (KeyCode::Char('q'), KeyModifiers::NONE)
+                    | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                        return Ok(None);
+                    }
+                    (KeyCode::Char('c'), KeyModifiers::NONE) => {
+                        return Ok(Some(state));
+                    }
+                    _ => {}
+                }
+            }
+            state.handle_key_event(event);
+        }
+    }
+}
+
+fn help_line(mode: Mode) -> Line<'static> {
+    let help_items: &[_] = match mode {
+        Mode::Normal => &[
+            ("←/→", "pane"),
+            ("↑/↓", "line"),
+            ("n/p", "next/prev conflict"),
+            ("space", "take line"),
+            ("a", "take all"),
+            ("e/o", "edit/insert line"),
+            ("d", "delete line"),
+            ("u", "unresolve"),
+            ("c", "confirm"),
+            ("q", "quit"),
+        ],
+        Mode::Insert { .. } => &[("esc", "stop editing")],
+    };
+    let mut help_spans = Vec::new();
+    for (i, (key, desc)) in help_items.iter().enumerate() {
+        if i > 0 {
+            help_spans.push(Span::raw(" • "));
+        }
+        help_spans.push(Span::styled(*key, Style::default().fg(Color::Magenta)));
+        help_spans.push(Span::raw(format!(" {desc}")));
+    }
+    Line::from(help_spans)
+}
+
+fn render(state: &State, frame: &mut ratatui::Frame, area: Rect) {
+    let [header_area, terms_area, result_area, help_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+
+    let file = state.current_file();
+    let hunk = state.current_hunk();
+    let header = Line::from(vec![
+        Span::styled(
+            file.path.as_internal_file_string().to_owned(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(
+            ": conflict {} of {} ({} unresolved)",
+            state.current + 1,
+            state.conflicts.len(),
+            state.num_unresolved()
+        )),
+    ]);
+    frame.render_widget(header, header_area);
+
+    let num_terms = hunk.terms.as_slice().len();
+    let term_areas = Layout::horizontal(vec![Constraint::Fill(1); num_terms]).split(terms_area);
+    for (index, ((lines, label), term_area)) in zip(
+        zip(hunk.terms.iter(), file.labels.iter()),
+        term_areas.iter(),
+    )
+    .enumerate()
+    {
+        // Bases are at odd indices
+        let style = if index % 2 == 0 {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let lines = lines
+            .iter()
+            .map(|line| Line::styled(line_text(line).into_owned(), style))
+            .collect();
+        render_pane(
+            state,
+            frame,
+            *term_area,
+            Pane::Term(index),
+            label.clone(),
+            lines,
+        );
+    }
+
+    let (title, lines) = match &hunk.result {
+        Some(result) => (
+            "result".to_owned(),
+            result
+                .iter()
+                .map(|line| Line::raw(line_text(line).into_owned()))
+                .collect(),
+        ),
+        None => (
+            "result (unresolved)".to_owned(),
+            vec![Line::styled(
+                "Take lines from the panes above, or edit the result",
+                Style::default().add_modifier(Modifier::DIM),
+            )],
+        ),
+    };
+    let inner_area = render_pane(state, frame, result_area, Pane::Result, title, lines);
+    if let Mode::Insert { column } = state.mode {
+        let offset = scroll_offset(state.cursor, inner_area.height);
+        let text = hunk
+            .result
+            .as_ref()
+            .map_or(Cow::Borrowed(""), |result| line_text(&result[state.cursor]));
+        let prefix: String = text.chars().take(column).collect();
+        let x = inner_area.x + u16::try_from(Span::raw(prefix).width()).unwrap_or(u16::MAX);
+        let y = inner_area.y + u16::try_from(state.cursor - offset).unwrap_or(u16::MAX);
+        frame.set_cursor_position(Position::new(
+            x.min(inner_area.right().saturating_sub(1)),
+            y,
+        ));
+    }
+
+    frame.render_widget(help_line(state.mode), help_area);
+}
+
+/// Renders a bordered pane, and returns the area inside the border.
+fn render_pane(
+    state: &State,
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    pane: Pane,
+    title: String,
+    mut lines: Vec<Line<'static>>,
+) -> Rect {
+    let is_focused = state.pane == pane;
+    let border_style = if is_focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let block = Block::bordered().title(title).border_style(border_style);
+    let inner_area = block.inner(area);
+    let mut offset = 0;
+    if is_focused && let Some(line) = lines.get_mut(state.cursor) {
+        line.style = line.style.add_modifier(Modifier::REVERSED);
+        offset = scroll_offset(state.cursor, inner_area.height);
+    }
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block)
+        .scroll((u16::try_from(offset).unwrap_or(u16::MAX), 0));
+    frame.render_widget(paragraph, area);
+    inner_area
+}
+
+/// Returns the first line to show so that the cursor is visible.
+fn scroll_offset(cursor: usize, height: u16) -> usize {
+    cursor.saturating_sub(usize::from(height).saturating_sub(1))
+}
+
+type PanicHook = dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static;
+
+/// Switches the terminal to the alternate screen in raw mode, and restores it
+/// when dropped.
+///
+/// While the guard is alive, a panic hook restores the terminal before the
+/// panic message is printed, so the message isn't lost with the alternate
+/// screen.
+struct TerminalGuard {
+    previous_hook: Option<Arc<PanicHook>>,
+}
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        let previous_hook: Arc<PanicHook> = panic::take_hook().into();
+        panic::set_hook(Box::new({
+            let previous_hook = previous_hook.clone();
+            move |info| {
+                restore_terminal().ok();
+                previous_hook(info);
+            }
+        }));
+        // Create the guard first so the hook is uninstalled on error.
+        let guard = Self {
+            previous_hook: Some(previous_hook),
+        };
+        io::stdout().execute(EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        Ok(guard)
+    }
+
+    /// Restores the terminal, reporting any error.
+    fn leave(mut self) -> io::Result<()> {
+        self.uninstall_hook();
+        restore_terminal()
+    }
+
+    fn uninstall_hook(&mut self) {
+        // The hook can't be replaced while panicking, in which case ours stays
+        // installed. Restoring the terminal again is harmless.
+        if let Some(previous_hook) = self.previous_hook.take()
+            && !std::thread::panicking()
+        {
+            drop(panic::take_hook());
+            panic::set_hook(Box::new(move |info| previous_hook(info)));
+        }
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if self.previous_hook.is_some() {
+            self.uninstall_hook();
+            restore_terminal().ok();
+        }
+    }
+}
+
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    io::stdout().execute(LeaveAlternateScreen)?;
+    Ok(())
+}
+
+pub async fn edit_merge_view(
+    tree: &MergedTree,
+    merge_tool_files: &[MergeToolFile],
+) -> Result<MergedTree, BuiltinToolError> {
+    let store = tree.store();
+    let files = merge_tool_files
+        .iter()
+        .map(|file| FileState::new(file, store.merge_options()))
+        .collect();
+    let mut state = State::new(files);
+    if !state.conflicts.is_empty() {
+        let guard = TerminalGuard::enter().map_err(BuiltinToolError::Tui)?;
+        let result = Terminal::new(CrosstermBackend::new(io::stdout()))
+            .map_err(BuiltinToolError::Tui)
+            .and_then(|mut terminal| {
+                terminal.clear().map_err(BuiltinToolError::Tui)?;
+                run_tui(&mut terminal, state)
+            });
+        guard.leave().map_err(BuiltinToolError::Tui)?;
+        state = result?.ok_or(BuiltinToolError::Canceled)?;
+    }
+    apply_merge_view(store, tree, merge_tool_files, &state.files)
+        .await
+        .map_err(BuiltinToolError::BackendError)
+}
+
+async fn apply_merge_view(
+    store: &Arc<Store>,
+    tree: &MergedTree,
+    merge_tool_files: &[MergeToolFile],
+    files: &[FileState],
+) -> BackendResult<MergedTree> {
+    let mut tree_builder = MergedTreeBuilder::new(tree.clone());
+    for (merge_tool_file, file_state) in zip(merge_tool_files, files) {
+        let MergeToolFile {
+            repo_path,
+            conflict,
+            file,
+        } = merge_tool_file;
+        let new_tree_value = match file_state.contents().into_resolved() {
+            Ok(contents) => {
+                let id = store
+                    .write_file(repo_path, &mut contents.as_slice())
+                    .await?;
+                let copy_id = resolve_file_copy_id(conflict).unwrap_or_else(CopyId::placeholder);
+                Merge::normal(TreeValue::File {
+                    id,
+                    executable: file.executable.expect("should have been resolved"),
+                    copy_id,
+                })
+            }
+            Err(contents) => {
+                let ids = try_join_all(zip(contents.iter(), file.ids.iter()).map(
+                    async |(contents, old_id)| {
+                        if old_id.is_none() && contents.is_empty() {
+                            Ok::<_, BackendError>(None)
+                        } else {
+                            let id = store
+                                .write_file(repo_path, &mut contents.as_slice())
+                                .await?;
+                            Ok(Some(id))
+                        }
+                    },
+                ))
+                .await?;
+                let ids = file
+                    .unsimplified_ids
+                    .clone()
+                    .update_from_simplified(Merge::from_vec(ids));
+                // Update the file ids only, leaving the executable flags unchanged
+                conflict.with_new_file_ids(&ids)
+            }
+        };
+        tree_builder.set_or_remove(repo_path.clone(), new_tree_value);
+    }
+    tree_builder.write_tree().await
+}
+
+#[cfg(test)]
+mod tests {
+    use jj_lib::backend::TreeId;
+    use jj_lib::merge::SameChange;
+    use jj_lib::repo::Repo as _;
+    use jj_lib::repo_path::RepoPath;
+    use pollster::FutureExt as _;
+    use testutils::TestRepo;
+    use testutils::TestResult;
+    use testutils::repo_path;
+
+    use super::*;
+
+    fn create_conflict_tree(
+        test_repo: &TestRepo,
+        path: &RepoPath,
+        contents: &[&str],
+        labels: ConflictLabels,
+    ) -> MergedTree {
+        let tree_ids: Vec<TreeId> = contents
+            .iter()
+            .map(|contents| {
+                testutils::create_single_tree(&test_repo.repo, &[(path, contents)])
+                    .id()
+                    .clone()
+            })
+            .collect();
+        MergedTree::new(
+            test_repo.repo.store().clone(),
+            Merge::from_vec(tree_ids),
+            labels,
+        )
+    }
+
+    fn make_state(tree: &MergedTree, path: &RepoPath) -> TestResult<(MergeToolFile, State)> {
+        let merge_tool_file = MergeToolFile::from_tree_and_path(tree, path).block_on()?;
+        let file_state = FileState::new(&merge_tool_file, tree.store().merge_options());
+        Ok((merge_tool_file, State::new(vec![file_state])))
+    }
+
+    fn press(state: &mut State, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\x08' => KeyCode::Backspace,
+                '\x1b' => KeyCode::Esc,
+                c => KeyCode::Char(c),
+            };
+            state.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    fn read_file(tree: &MergedTree, path: &RepoPath) -> TestResult<Merge<String>> {
+        let value = tree.path_value(path).block_on()?;
+        let contents = value.try_map(|value| -> TestResult<String> {
+            let Some(TreeValue::File { id, .. }) = value else {
+                return Ok("<absent>".to_owned());
+            };
+            Ok(String::from_utf8(testutils::read_file(
+                tree.store(),
+                path,
+                id,
+            ))?)
+        })?;
+        Ok(contents)
+    }
+
+    #[test]
+    fn test_file_state_new() -> TestResult {
+        let test_repo = TestRepo::init();
+        let path = repo_path("file");
+        let tree = create_conflict_tree(
+            &test_repo,
+            path,
+            &["a\nleft\nb\n", "a\nbase\nb\n", "a\nright\nb\n"],
+            ConflictLabels::from_vec(vec!["left".into(), "base".into(), "right".into()]),
+        );
+        let (_, state) = make_state(&tree, path)?;
+        insta::assert_debug_snapshot!(state.files[0], @r#"
+        FileState {
+            path: "file",
+            labels: Conflicted(
+                [
+                    "left",
+                    "base",
+                    "right",
+                ],
+            ),
+            hunks: [
+                Resolved(
+                    "a\n",
+                ),
+                Conflict(
+                    ConflictHunk {
+                        terms: Conflicted(
+                            [
+                                [
+                                    "left\n",
+                                ],
+                                [
+                                    "base\n",
+                                ],
+                                [
+                                    "right\n",
+                                ],
+                            ],
+                        ),
+                        result: None,
+                    },
+                ),
+                Resolved(
+                    "b\n",
+                ),
+            ],
+        }
+        "#);
+        assert_eq!(state.conflicts, [(0, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_term_labels() {
+        let labels = term_labels(&ConflictLabels::unlabeled(), 3);
+        assert_eq!(
+            labels.as_slice(),
+            ["side #1", "base #1", "side #2", "base #2", "side #3"]
+        );
+        let labels = term_labels(&ConflictLabels::unlabeled(), 2);
+        assert_eq!(labels.as_slice(), ["side #1", "base", "side #2"]);
+    }
+
+    #[test]
+    fn test_take_lines() -> TestResult {
+        let test_repo = TestRepo::init();
+        let path = repo_path("file");
+        let tree = create_conflict_tree(
+            &test_repo,
+            path,
+            &["one\ntwo\n", "", "three\nfour"],
+            ConflictLabels::unlabeled(),
+        );
+        let (_, mut state) = make_state(&tree, path)?;
+        // Take the second line of the first side
+        press(&mut state, "j ");
+        assert_eq!(
+            state.current_hunk().result,
+            Some(vec![BString::from("two\n")])
+        );
+        // Take the whole second side, which has no final newline
+        press(&mut state, "lla");
+        assert_eq!(state.pane, Pane::Term(2));
+        assert_eq!(
+            state.current_hunk().result,
+            Some(vec![
+                BString::from("two\n"),
+                "three\n".into(),
+                "four".into()
+            ])
+        );
+        // Delete the first line of the result
+        press(&mut state, "lkkd");
+        assert_eq!(
+            state.files[0].contents(),
+            Merge::resolved("three\nfour".into())
+        );
+        // Unresolve the hunk
+        press(&mut state, "u");
+        assert_eq!(
+            state.files[0].contents(),
+            Merge::from_vec(vec![
+                BString::from("one\ntwo\n"),
+                "".into(),
+                "three\nfour".into()
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_edit_result() -> TestResult {
+        let test_repo = TestRepo::init();
+        let path = repo_path("file");
+        let tree = create_conflict_tree(
+            &test_repo,
+            path,
+            &["left\n", "base\n", "right\n"],
+            ConflictLabels::unlabeled(),
+        );
+        let (_, mut state) = make_state(&tree, path)?;
+        // Edit a new line, and split it in two
+        press(&mut state, "efirst\nsecondd\x08\x1b");
+        assert_eq!(state.mode, Mode::Normal);
+        assert_eq!(state.pane, Pane::Result);
+        assert_eq!(
+            state.files[0].contents(),
+            Merge::resolved("first\nsecond\n".into())
+        );
+        // Insert a line below the first one, then join it with the first line
+        press(&mut state, "ko\x08!\x1b");
+        assert_eq!(
+            state.files[0].contents(),
+            Merge::resolved("first!\nsecond\n".into())
+        );
+        // Append a line from the first side
+        press(&mut state, "hhh ");
+        assert_eq!(
+            state.files[0].contents(),
+            Merge::resolved("first!\nsecond\nleft\n".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_conflicts() -> TestResult {
+        let test_repo = TestRepo::init();
+        let path = repo_path("file");
+        let tree = create_conflict_tree(
+            &test_repo,
+            path,
+            &["a1\nx\na2\n", "b1\nx\nb2\n", "c1\nx\nc2\n"],
+            ConflictLabels::unlabeled(),
+        );
+        let (_, mut state) = make_state(&tree, path)?;
+        assert_eq!(state.conflicts, [(0, 0), (0, 2)]);
+        press(&mut state, "a");
+        assert_eq!(state.num_unresolved(), 1);
+        press(&mut state, "nlla");
+        assert_eq!(state.num_unresolved(), 0);
+        assert_eq!(
+            state.files[0].contents(),
+            Merge::resolved("a1\nx\nc2\n".into())
+        );
+        press(&mut state, "p");
+        assert_eq!(state.current, 0);
+        assert_eq!(state.pane, Pane::Term(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_partial_resolution() -> TestResult {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+        let path = repo_path("file");
+        let tree = create_conflict_tree(
+            &test_repo,
+            path,
+            &[
+                "a1\nx\na2\n",
+                "b1\nx\nb2\n",
+                "c1\nx\nc2\n",
+                "d1\nx\nd2\n",
+                "e1\nx\ne2\n",
+            ],
+            ConflictLabels::unlabeled(),
+        );
+        let (merge_tool_file, mut state) = make_state(&tree, path)?;
+        // Resolve the first conflict only
+        press(&mut state, "lllla");
+        let new_tree =
+            apply_merge_view(store, &tree, &[merge_tool_file], &state.files).block_on()?;
+        assert_eq!(
+            read_file(&new_tree, path)?,
+            Merge::from_vec(vec![
+                "e1\nx\na2\n".to_owned(),
+                "e1\nx\nb2\n".to_owned(),
+                "e1\nx\nc2\n".to_owned(),
+                "e1\nx\nd2\n".to_owned(),
+                "e1\nx\ne2\n".to_owned(),
+            ])
+        );
+
+        // Resolve the remaining conflict
+        let (merge_tool_file, mut state) = make_state(&new_tree, path)?;
+        press(&mut state, "a");
+        let new_tree =
+            apply_merge_view(store, &new_tree, &[merge_tool_file], &state.files).block_on()?;
+        assert_eq!(
+            read_file(&new_tree, path)?,
+            Merge::resolved("e1\nx\na2\n".to_owned())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_should_preserve_copy_id() -> TestResult {
+        let test_repo = TestRepo::init();
+        let store = test_repo.repo.store();
+
+        let file_path = repo_path("test-file.txt");
+        // A random unique copy id that is unlikely to collide.
+        let new_copy_id = CopyId::from_bytes(b"\xa3\x2b\x24\x3b\xda\x42\x6b\xe3\x14\x33");
+        let mut tree_builder = testutils::TestThreeWayMergeTreeBuilder::new(Arc::clone(store));
+        tree_builder
+            .base()
+            .file(file_path, "")
+            // Another random unique copy id that is unlikely to collide.
+            .copy_id(CopyId::from_bytes(
+                b"\x10\xda\x3e\x78\x6f\x62\x83\x97\x0b\x0f",
+            ));
+        tree_builder
+            .parent1()
+            .file(file_path, "left parent1\n")
+            .copy_id(new_copy_id.clone());
+        tree_builder
+            .parent2()
+            .file(file_path, "left parent2\n")
+            .copy_id(new_copy_id.clone());
+        let tree = tree_builder.write_merged_tree();
+
+        let (merge_tool_file, mut state) = make_state(&tree, file_path)?;
+        press(&mut state, "a");
+        let tree = apply_merge_view(store, &tree, &[merge_tool_file], &state.files).block_on()?;
+
+        let actual_copy_ids = tree
+            .path_value(file_path)
+            .block_on()?
+            .into_map(|tree_value| {
+                let Some(TreeValue::File { copy_id, .. }) = tree_value else {
+                    panic!("The path should point to an existing file.");
+                };
+                copy_id
+            });
+        assert_eq!(
+            actual_copy_ids.resolve_trivial(SameChange::Accept),
+            Some(&new_copy_id),
+            "Expect the copy id of the file to be resolved to {new_copy_id:?}, but got \
+             {actual_copy_ids:?}."
+        );
+        Ok(())
+    }
+}
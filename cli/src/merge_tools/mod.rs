@@ -15,6 +15,7 @@
 mod builtin;
 mod diff_working_copies;
 mod external;
+mod merge_view;
 
 use std::sync::Arc;
 
@@ -54,10 +55,12 @@ use self::external::ExternalToolError;
 use self::external::edit_diff_external;
 pub use self::external::generate_diff;
 pub use self::external::invoke_external_diff;
+use self::merge_view::edit_merge_view;
 use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
 const BUILTIN_EDITOR_NAME: &str = ":builtin";
+const MERGE_VIEW_TOOL_NAME: &str = ":merge-view";
 const OURS_TOOL_NAME: &str = ":ours";
 const THEIRS_TOOL_NAME: &str = ":theirs";
 
@@ -136,6 +139,7 @@ pub enum MergeToolConfigError {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MergeTool {
     Builtin,
+    MergeView,
     Ours,
     Theirs,
     // Boxed because ExternalMergeTool is big compared to the Builtin variant.
@@ -155,6 +159,7 @@ impl MergeTool {
     ) -> Result<Option<Self>, MergeToolConfigError> {
         match name {
             BUILTIN_EDITOR_NAME => Ok(Some(Self::Builtin)),
+            MERGE_VIEW_TOOL_NAME => Ok(Some(Self::MergeView)),
             OURS_TOOL_NAME => Ok(Some(Self::Ours)),
             THEIRS_TOOL_NAME => Ok(Some(Self::Theirs)),
             _ => Ok(get_external_tool_config(settings, name)?.map(Self::external)),
@@ -346,13 +351,6 @@ impl MergeToolFile {
                     path: repo_path.to_owned(),
                     summary: conflict.describe(tree.labels()),
                 })?;
-        // We only support conflicts with 2 sides (3-way conflicts)
-        if file.ids.num_sides() > 2 {
-            return Err(ConflictResolveError::ConflictTooComplicated {
-                path: repo_path.to_owned(),
-                sides: file.ids.num_sides(),
-            });
-        }
         if file.executable.is_none() {
             return Err(ConflictResolveError::ExecutableConflict {
                 path: repo_path.to_owned(),
@@ -439,6 +437,17 @@ impl MergeEditor {
                 .map(|&repo_path| MergeToolFile::from_tree_and_path(tree, repo_path)),
         )
         .await?;
+        // Only the merge view supports conflicts with more than 2 sides
+        if !matches!(self.tool, MergeTool::MergeView)
+            && let Some(merge_tool_file) = merge_tool_files
+                .iter()
+                .find(|merge_tool_file| merge_tool_file.file.ids.num_sides() > 2)
+        {
+            return Err(ConflictResolveError::ConflictTooComplicated {
+                path: merge_tool_file.repo_path.clone(),
+                sides: merge_tool_file.file.ids.num_sides(),
+            });
+        }
 
         match &self.tool {
             MergeTool::Builtin => {
@@ -447,6 +456,12 @@ impl MergeEditor {
                     .map_err(Box::new)?;
                 Ok((tree, None))
             }
+            MergeTool::MergeView => {
+                let tree = edit_merge_view(tree, &merge_tool_files)
+                    .await
+                    .map_err(Box::new)?;
+                Ok((tree, None))
+            }
             MergeTool::Ours => {
                 let tree = pick_conflict_side(tree, &merge_tool_files, 0).await?;
                 Ok((tree, None))
@@ -809,6 +824,7 @@ mod tests {
         };
 
         insta::assert_debug_snapshot!(get(":builtin", "").unwrap(), @"Builtin");
+        insta::assert_debug_snapshot!(get(":merge-view", "").unwrap(), @"MergeView");
 
         // Just program name
         insta::assert_debug_snapshot!(get("my diff", "").unwrap_err(), @r#"
@@ -28,6 +28,8 @@ use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::SignError;
+use jj_lib::signing::Verification;
 use pollster::FutureExt as _;
 
 use crate::template_builder;
@@ -159,6 +161,7 @@ where
     Self: WrapTemplateProperty<'a, Option<Operation>>,
     Self: WrapTemplateProperty<'a, Vec<Operation>>,
     Self: WrapTemplateProperty<'a, OperationId>,
+    Self: WrapTemplateProperty<'a, Option<OperationSignature>>,
 {
 }
 
@@ -168,6 +171,7 @@ pub enum OperationTemplatePropertyKind<'a> {
     OperationOpt(BoxedTemplateProperty<'a, Option<Operation>>),
     OperationList(BoxedTemplateProperty<'a, Vec<Operation>>),
     OperationId(BoxedTemplateProperty<'a, OperationId>),
+    OperationSignatureOpt(BoxedTemplateProperty<'a, Option<OperationSignature>>),
 }
 
 /// Implements `WrapTemplateProperty<type>` for operation property types.
@@ -181,6 +185,7 @@ macro_rules! impl_operation_property_wrappers {
             OperationOpt(Option<jj_lib::operation::Operation>),
             OperationList(Vec<jj_lib::operation::Operation>),
             OperationId(jj_lib::op_store::OperationId),
+            OperationSignatureOpt(Option<$crate::operation_templater::OperationSignature>),
         });
     };
 }
@@ -196,6 +201,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(_) => "Option<Operation>",
             Self::OperationList(_) => "List<Operation>",
             Self::OperationId(_) => "OperationId",
+            Self::OperationSignatureOpt(_) => "Option<OperationSignature>",
         }
     }
 
@@ -213,6 +219,9 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(property) => Ok(property.map(|opt| opt.is_some()).into_dyn()),
             Self::OperationList(property) => Ok(property.map(|l| !l.is_empty()).into_dyn()),
             Self::OperationId(_) => Err(self),
+            Self::OperationSignatureOpt(property) => {
+                Ok(property.map(|opt| opt.is_some()).into_dyn())
+            }
         }
     }
 
@@ -230,6 +239,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(property) => Some(property.into_serialize()),
             Self::OperationList(property) => Some(property.into_serialize()),
             Self::OperationId(property) => Some(property.into_serialize()),
+            Self::OperationSignatureOpt(_) => None,
         }
     }
 
@@ -239,6 +249,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(_) => None,
             Self::OperationList(_) => None,
             Self::OperationId(property) => Some(property.into_template()),
+            Self::OperationSignatureOpt(_) => None,
         }
     }
 
@@ -248,6 +259,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 
@@ -260,6 +272,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 
@@ -269,6 +282,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 
@@ -281,6 +295,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 }
@@ -391,6 +406,7 @@ pub struct OperationTemplateBuildFnTable<'a, L: ?Sized, P = <L as TemplateLangua
     pub operation_methods: TemplateBuildMethodFnMap<'a, L, Operation, P>,
     pub operation_list_methods: TemplateBuildMethodFnMap<'a, L, Vec<Operation>, P>,
     pub operation_id_methods: TemplateBuildMethodFnMap<'a, L, OperationId, P>,
+    pub operation_signature_methods: TemplateBuildMethodFnMap<'a, L, OperationSignature, P>,
 }
 
 impl<L: ?Sized, P> OperationTemplateBuildFnTable<'_, L, P> {
@@ -399,6 +415,7 @@ impl<L: ?Sized, P> OperationTemplateBuildFnTable<'_, L, P> {
             operation_methods: HashMap::new(),
             operation_list_methods: HashMap::new(),
             operation_id_methods: HashMap::new(),
+            operation_signature_methods: HashMap::new(),
         }
     }
 
@@ -407,11 +424,16 @@ impl<L: ?Sized, P> OperationTemplateBuildFnTable<'_, L, P> {
             operation_methods,
             operation_list_methods,
             operation_id_methods,
+            operation_signature_methods,
         } = other;
 
         merge_fn_map(&mut self.operation_methods, operation_methods);
         merge_fn_map(&mut self.operation_list_methods, operation_list_methods);
         merge_fn_map(&mut self.operation_id_methods, operation_id_methods);
+        merge_fn_map(
+            &mut self.operation_signature_methods,
+            operation_signature_methods,
+        );
     }
 }
 
@@ -426,6 +448,7 @@ where
             operation_methods: builtin_operation_methods(),
             operation_list_methods: template_builder::builtin_unformattable_list_methods(),
             operation_id_methods: builtin_operation_id_methods(),
+            operation_signature_methods: builtin_operation_signature_methods(),
         }
     }
 
@@ -463,6 +486,13 @@ where
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            OperationTemplatePropertyKind::OperationSignatureOpt(property) => {
+                let type_name = "OperationSignature";
+                let table = &self.operation_signature_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name).into_dyn();
+                build(language, diagnostics, build_ctx, inner_property, function)
+            }
         }
     }
 }
@@ -627,6 +657,82 @@ where
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "signature",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language.repo_loader().store().clone();
+            let out_property =
+                self_property.and_then(move |op| match op.verify_signature(store.signer()) {
+                    Ok(verification) => Ok(verification.map(OperationSignature::new)),
+                    Err(SignError::InvalidSignatureFormat) => {
+                        Ok(Some(OperationSignature::invalid()))
+                    }
+                    Err(err) => Err(err.into()),
+                });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
+/// Verified signature of an operation.
+#[derive(Clone, Debug)]
+pub struct OperationSignature {
+    /// `None` if the signature format is invalid.
+    verification: Option<Verification>,
+}
+
+impl OperationSignature {
+    fn new(verification: Verification) -> Self {
+        Self {
+            verification: Some(verification),
+        }
+    }
+
+    fn invalid() -> Self {
+        Self { verification: None }
+    }
+}
+
+fn builtin_operation_signature_methods<'a, L>()
+-> TemplateBuildMethodFnMap<'a, L, OperationSignature>
+where
+    L: TemplateLanguage<'a> + OperationTemplateEnvironment + ?Sized,
+    L::Property: OperationTemplatePropertyVar<'a>,
+{
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, OperationSignature>::new();
+    map.insert(
+        "status",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|sig| match &sig.verification {
+                Some(verification) => verification.status.to_string(),
+                None => "invalid".to_owned(),
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|sig| sig.verification.and_then(|v| v.key).unwrap_or_default());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "display",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property
+                .map(|sig| sig.verification.and_then(|v| v.display).unwrap_or_default());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -221,6 +221,7 @@ pub enum CoreTemplatePropertyKind<'a> {
     ConfigValue(BoxedTemplateProperty<'a, ConfigValue>),
     ConfigValueOpt(BoxedTemplateProperty<'a, Option<ConfigValue>>),
     Signature(BoxedTemplateProperty<'a, Signature>),
+    SignatureOpt(BoxedTemplateProperty<'a, Option<Signature>>),
     Email(BoxedTemplateProperty<'a, Email>),
     SizeHint(BoxedTemplateProperty<'a, SizeHint>),
     RegexCaptures(BoxedTemplateProperty<'a, RegexCaptures>),
@@ -259,6 +260,7 @@ macro_rules! impl_core_property_wrappers {
             ConfigValue(jj_lib::config::ConfigValue),
             ConfigValueOpt(Option<jj_lib::config::ConfigValue>),
             Signature(jj_lib::backend::Signature),
+            SignatureOpt(Option<jj_lib::backend::Signature>),
             Email($crate::templater::Email),
             SizeHint($crate::templater::SizeHint),
             RegexCaptures($crate::templater::RegexCaptures),
@@ -297,6 +299,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::ConfigValue(_) => "ConfigValue",
             Self::ConfigValueOpt(_) => "Option<ConfigValue>",
             Self::Signature(_) => "Signature",
+            Self::SignatureOpt(_) => "Option<Signature>",
             Self::Email(_) => "Email",
             Self::SizeHint(_) => "SizeHint",
             Self::RegexCaptures(_) => "RegexCaptures",
@@ -334,6 +337,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::ConfigValue(_) => Err(self),
             Self::ConfigValueOpt(property) => Ok(property.map(|opt| opt.is_some()).into_dyn()),
             Self::Signature(_) => Err(self),
+            Self::SignatureOpt(property) => Ok(property.map(|opt| opt.is_some()).into_dyn()),
             Self::Email(property) => Ok(property.map(|e| !e.0.is_empty()).into_dyn()),
             Self::SizeHint(_) => Err(self),
             Self::RegexCaptures(_) => Err(self),
@@ -381,6 +385,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
                     .into_serialize(),
             ),
             Self::Signature(property) => Some(property.into_serialize()),
+            Self::SignatureOpt(property) => Some(property.into_serialize()),
             Self::Email(property) => Some(property.into_serialize()),
             Self::SizeHint(property) => Some(property.into_serialize()),
             Self::RegexCaptures(_) => None,
@@ -404,6 +409,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::ConfigValue(property) => Some(property.into_template()),
             Self::ConfigValueOpt(property) => Some(property.into_template()),
             Self::Signature(property) => Some(property.into_template()),
+            Self::SignatureOpt(property) => Some(property.into_template()),
             Self::Email(property) => Some(property.into_template()),
             Self::SizeHint(_) => None,
             Self::RegexCaptures(_) => None,
@@ -463,6 +469,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::ConfigValue(_), _) => None,
             (Self::ConfigValueOpt(_), _) => None,
             (Self::Signature(_), _) => None,
+            (Self::SignatureOpt(_), _) => None,
             (Self::Email(_), _) => None,
             (Self::SizeHint(_), _) => None,
             (Self::RegexCaptures(_), _) => None,
@@ -498,6 +505,7 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::ConfigValue(_), _) => None,
             (Self::ConfigValueOpt(_), _) => None,
             (Self::Signature(_), _) => None,
+            (Self::SignatureOpt(_), _) => None,
             (Self::Email(_), _) => None,
             (Self::SizeHint(_), _) => None,
             (Self::RegexCaptures(_), _) => None,
@@ -751,6 +759,13 @@ where
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::SignatureOpt(property) => {
+                let type_name = "Signature";
+                let table = &self.signature_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name).into_dyn();
+                build(language, diagnostics, build_ctx, inner_property, function)
+            }
             CoreTemplatePropertyKind::Email(property) => {
                 let table = &self.email_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
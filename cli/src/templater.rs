@@ -258,8 +258,7 @@ impl<T: Template> Template for RawEscapeSequenceTemplate<T> {
     }
 }
 
-/// Renders a hyperlink using OSC 8 escape sequences when supported, or a
-/// fallback otherwise.
+/// Renders a hyperlink if the formatter supports it, or a fallback otherwise.
 pub struct HyperlinkTemplate<U, T, F> {
     url: U,
     text: T,
@@ -296,10 +295,9 @@ where
             return self.text.format(formatter);
         }
 
-        // Write OSC 8 hyperlink via raw()
-        write!(formatter.raw()?, "\x1b]8;;{url_str}\x1b\\")?;
+        formatter.push_hyperlink(&url_str)?;
         self.text.format(formatter)?;
-        write!(formatter.raw()?, "\x1b]8;;\x1b\\")
+        formatter.pop_hyperlink()
     }
 }
 
@@ -1035,6 +1033,14 @@ impl<'a> TemplateFormatter<'a> {
         self.formatter.maybe_color()
     }
 
+    pub fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        self.formatter.push_hyperlink(url)
+    }
+
+    pub fn pop_hyperlink(&mut self) -> io::Result<()> {
+        self.formatter.pop_hyperlink()
+    }
+
     pub fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
         self.formatter.write_fmt(args)
     }
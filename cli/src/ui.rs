@@ -418,6 +418,10 @@ impl Ui {
         self.formatter_factory.maybe_color()
     }
 
+    pub fn formatter_factory(&self) -> &FormatterFactory {
+        &self.formatter_factory
+    }
+
     pub fn new_formatter<'output, W: Write + 'output>(
         &self,
         output: W,
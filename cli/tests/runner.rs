@@ -14,6 +14,7 @@ mod test_acls;
 mod test_advance_bookmarks;
 mod test_alias;
 mod test_arrange_command;
+mod test_backport_command;
 mod test_bisect_command;
 mod test_bookmark_command;
 mod test_builtin_aliases;
@@ -41,6 +42,7 @@ mod test_file_track_untrack_commands;
 mod test_fileset_output;
 mod test_fix_command;
 mod test_generate_md_cli_help;
+mod test_gerrit_download;
 mod test_gerrit_upload;
 mod test_git_clone;
 mod test_git_colocated;
@@ -50,6 +52,7 @@ mod test_git_import_export;
 mod test_git_init;
 mod test_git_private_commits;
 mod test_git_push;
+mod test_git_push_bookmarks;
 mod test_git_remotes;
 mod test_git_root;
 mod test_gitignores;
@@ -58,6 +61,7 @@ mod test_help_command;
 mod test_identical_commits;
 mod test_immutable_commits;
 mod test_interdiff_command;
+mod test_json_output;
 mod test_log_command;
 mod test_metaedit_command;
 mod test_new_command;
@@ -67,13 +71,16 @@ mod test_op_restore_command;
 mod test_op_revert_command;
 mod test_operations;
 mod test_parallelize_command;
+mod test_patch_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
 mod test_restore_command;
 mod test_revert_command;
+mod test_review_push;
 mod test_revset_output;
 mod test_root;
+mod test_shelve_command;
 mod test_show_command;
 mod test_sign_unsign_commands;
 mod test_simplify_parents_command;
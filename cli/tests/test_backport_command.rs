@@ -0,0 +1,225 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::create_commit_with_files;
+
+#[test]
+fn test_backport() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "release-1", &["base"], &[("other", "1\n")]);
+    create_commit_with_files(&work_dir, "release-2", &["base"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "fix", &["base"], &[("file", "c\n")]);
+    work_dir.run_jj(["new", "root()"]).success();
+
+    let output = work_dir.run_jj(["backport", "root()", "--onto", "base"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Cannot backport the root commit
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["backport", "none()", "--onto", "base"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No revisions to backport.
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "backport",
+        "fix",
+        "--onto",
+        "release-1",
+        "--onto",
+        "release-2",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Backported 1 commit onto 2 destinations:
+      clean            zsuskuln 310b188a release-1 | release-1
+      conflicted (1/1) royxmykx c7176e51 release-2 | release-2
+    Warning: 1 of 2 destinations has conflicts
+    New conflicts appeared in 1 commits:
+      mouksmqu cc8a5d98 (conflict) fix
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new mouksmqu
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  2b2f7cb00d53
+    │ ×  cc8a5d9841ec fix (conflict)
+    │ ○  c7176e518a14 release-2
+    │ │ ○  4613dba8b3a1 fix
+    │ │ ○  310b188aa4ef release-1
+    │ ├─╯
+    │ │ ○  a1f6be7884dc fix
+    │ ├─╯
+    │ ○  c5eb7b7fd331 base
+    ├─╯
+    ◆  000000000000
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "children(release-1)",
+        "-T",
+        "description",
+    ]);
+    insta::assert_snapshot!(output, @"
+    fix
+
+    (cherry picked from commit a1f6be7884dc221eb8133904e5c699e37af3728b)
+    [EOF]
+    ");
+
+    // Backporting again onto a destination that contains the commit warns
+    let output = work_dir.run_jj(["backport", "base", "--onto", "release-1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: Destination 310b188aa4ef already contains commit c5eb7b7fd331
+    Backported 1 commit onto 1 destination:
+      clean zsuskuln 310b188a release-1 | release-1
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_backport_counts_new_conflicts() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "release", &["base"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "release2", &["base"], &[("file", "d\n")]);
+    create_commit_with_files(&work_dir, "fix1", &["base"], &[("file", "c\n")]);
+    create_commit_with_files(&work_dir, "fix2", &["fix1"], &[("other", "x\n")]);
+    work_dir.run_jj(["new", "root()"]).success();
+
+    // The conflict of fix1 is inherited by fix2, so only fix1 is counted
+    let output = work_dir.run_jj(["backport", "fix1::fix2", "--onto", "release"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Backported 2 commits onto 1 destination:
+      conflicted (1/2) zsuskuln abc1563c release | release
+    Warning: 1 of 1 destination has conflicts
+    New conflicts appeared in 2 commits:
+      mouksmqu 71756a90 (conflict) fix2
+      wqnwkozp 74b3102d (conflict) fix1
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the first conflicted commit:
+      jj new wqnwkozp
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+
+    // Conflicts which already existed in the source aren't counted
+    let output = work_dir.run_jj(["backport", "children(release)", "--onto", "release2"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Backported 1 commit onto 1 destination:
+      clean royxmykx 4e76b64e release2 | release2
+    New conflicts appeared in 1 commits:
+      lylxulpl 86627ec2 (conflict) fix1
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new lylxulpl
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_backport_trailer_template() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "fix", &["base"], &[("file", "b\n")]);
+    work_dir
+        .run_jj([
+            "describe",
+            "-r",
+            "fix",
+            "-m",
+            "fix\n\nSigned-off-by: Test User <test.user@example.com>",
+        ])
+        .success();
+
+    test_env
+        .add_config(r#"templates.backport_trailer = '"Backported-from: " ++ change_id.short()'"#);
+    work_dir
+        .run_jj(["backport", "fix", "--onto", "root()"])
+        .success();
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "children(root()) ~ base",
+        "-T",
+        "description",
+    ]);
+    insta::assert_snapshot!(output, @"
+    fix
+
+    Signed-off-by: Test User <test.user@example.com>
+    Backported-from: zsuskulnrvyr
+    [EOF]
+    ");
+
+    test_env.add_config(r#"templates.backport_trailer = '""'"#);
+    work_dir
+        .run_jj(["backport", "fix", "--onto", "base"])
+        .success();
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "children(base) ~ fix",
+        "-T",
+        "description",
+    ]);
+    insta::assert_snapshot!(output, @"
+    fix
+
+    Signed-off-by: Test User <test.user@example.com>
+    [EOF]
+    ");
+}
+
+#[must_use]
+fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
+    let template =
+        r#"commit_id.short() ++ " " ++ description.first_line() ++ if(conflict, " (conflict)")"#;
+    work_dir.run_jj(["log", "-T", template])
+}
@@ -817,6 +817,72 @@ fn test_log_git_head() {
     ");
 }
 
+#[test]
+fn test_log_git_notes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m=first"]).success();
+    let commit_id = work_dir
+        .run_jj(["log", "--no-graph", "-r@", "-T=commit_id"])
+        .success()
+        .stdout
+        .into_raw();
+    let output = std::process::Command::new("git")
+        .arg("--git-dir")
+        .arg(work_dir.root().join(".jj/repo/store/git"))
+        .args([
+            "-c",
+            "user.name=Test User",
+            "-c",
+            "user.email=test.user@example.com",
+        ])
+        .args(["notes", "add", "-m", "CI: passed", &commit_id])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Notes aren't visible until imported
+    let template = r#"description.first_line() ++ ": " ++ notes"#;
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  first:
+    ◆  :
+    [EOF]
+    ");
+    work_dir.run_jj(["git", "import"]).success();
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  first: CI: passed
+    ◆  :
+    [EOF]
+    ");
+
+    // Notes are carried over when the commit is rewritten
+    work_dir.run_jj(["describe", "-m=second"]).success();
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  second: CI: passed
+    ◆  :
+    [EOF]
+    ");
+    let rewritten_id = work_dir
+        .run_jj(["log", "--no-graph", "-r@", "-T=commit_id"])
+        .success()
+        .stdout
+        .into_raw();
+
+    // Copied notes are reverted by undo
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["log", "-T", template, "-r", &rewritten_id]);
+    insta::assert_snapshot!(output, @"
+    ○  second:
+    │
+    ~
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_customize_short_id() {
     let test_env = TestEnvironment::default();
@@ -667,18 +667,21 @@ fn test_command_completion(shell: Shell) {
     match shell {
         Shell::Zsh => {
             insta::assert_snapshot!(output, @"
+            backport:Copy changes onto one or more destinations, such as release branches
             bisect:Find a bad revision by bisection
             bookmark:Manage bookmarks [default alias: b][EOF]
             ");
         }
         Shell::Bash => {
             insta::assert_snapshot!(output, @"
+            backport
             bisect
             bookmark[EOF]
             ");
         }
         Shell::Fish => {
             insta::assert_snapshot!(output, @"
+            backport	Copy changes onto one or more destinations, such as release branches
             bisect	Find a bad revision by bisection
             bookmark	Manage bookmarks [default alias: b]
             [EOF]
@@ -723,6 +726,7 @@ fn test_command_completion_short_name() {
     --no-integrate-operation	Run the command as usual but don't integrate any operations
     --ignore-immutable	Allow rewriting immutable commits
     --at-operation	Operation to load the repo at
+    --output	Output format of listing commands
     --debug	Enable debug logging
     --color	When to colorize output
     --quiet	Silence non-primary command output
@@ -1527,6 +1531,7 @@ fn test_merge_tools() {
     :name-only
     :git
     :color-words
+    :side-by-side
     diffedit3
     diffedit3-ssh
     difft
@@ -369,6 +369,7 @@ fn test_diff_basic() {
     error: the argument '--template <TEMPLATE>' cannot be used with:
       --git
       --color-words
+      --side-by-side
 
     Usage: jj diff --template <TEMPLATE> [FILESETS]...
 
@@ -1616,6 +1617,441 @@ fn test_diff_color_words_omit_blank_right_line() {
     ");
 }
 
+#[test]
+fn test_diff_side_by_side() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("COLUMNS", "60");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n");
+    work_dir.write_file("file2", "foo\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file(
+        "file1",
+        "1\n2 modified\n3\n4\n5\n6\n7\n8\n9\n\tindented\n10\n11 a long line which is wrapped at \
+         the width of the column\n",
+    );
+    work_dir.remove_file("file2");
+    work_dir.write_file("file3", "bar\n");
+
+    let output = work_dir.run_jj(["diff", "--side-by-side"]);
+    insta::assert_snapshot!(output, @"
+    Modified regular file file1:
+       1 1                       │    1 1
+       2 2                       │    2 2 modified
+       3 3                       │    3 3
+       4 4                       │    4 4
+       5 5                       │    5 5
+       6 6                       │    6 6
+       7 7                       │    7 7
+       8 8                       │    8 8
+       9 9                       │    9 9
+                                 │   10     indented
+      10 10                      │   11 10
+                                 │   12 11 a long line which is
+                                 │       wrapped at the width o
+                                 │      f the column
+    Removed regular file file2:
+       1 foo                     │
+    Added regular file file3:
+                                 │    1 bar
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--side-by-side", "--color=always", "file1"]);
+    insta::assert_snapshot!(output, @"
+    [38;5;3mModified regular file file1:[39m
+    [2m   1[0m 1                       │ [2m   1[0m 1
+    [38;5;1m   2 2                      [39m │ [38;5;2m   2 2[4m modified[24m[39m
+    [2m   3[0m 3                       │ [2m   3[0m 3
+    [2m   4[0m 4                       │ [2m   4[0m 4
+    [2m   5[0m 5                       │ [2m   5[0m 5
+    [2m   6[0m 6                       │ [2m   6[0m 6
+    [2m   7[0m 7                       │ [2m   7[0m 7
+    [2m   8[0m 8                       │ [2m   8[0m 8
+    [2m   9[0m 9                       │ [2m   9[0m 9
+                                 │ [38;5;2m  10 [4m    indented[24m[39m
+    [2m  10[0m 10                      │ [2m  11[0m 10
+                                 │ [38;5;2m  12 [4m11 a long line which is[24m[39m
+                                 │ [38;5;2m     [4m wrapped at the width o[24m[39m
+                                 │ [38;5;2m     [4mf the column[24m[39m
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--tool=:side-by-side", "--context=0", "file1"]);
+    insta::assert_snapshot!(output, @"
+    Modified regular file file1:
+       1 1                       │    1 1
+       2 2                       │    2 2 modified
+        ...
+                                 │   10     indented
+      10 10                      │   11 10
+                                 │   12 11 a long line which is
+                                 │       wrapped at the width o
+                                 │      f the column
+    [EOF]
+    ");
+
+    // Line numbers are widened to fit, and tabs are expanded to tab stops
+    let contents = (1..=10000).map(|n| format!("{n}\n")).join("");
+    work_dir.write_file("file4", &contents);
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file4", format!("{contents}a\tb\nabcd\te\n"));
+    let output = work_dir.run_jj(["diff", "--side-by-side", "--context=1", "file4"]);
+    insta::assert_snapshot!(output, @"
+    Modified regular file file4:
+         ...
+    10000 10000                  │ 10000 10000
+                                 │ 10001 a   b
+                                 │ 10002 abcd    e
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1\n2\n3\n");
+    work_dir.write_file("file2", "foo\n");
+    work_dir.write_file("file3", "bar\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "1\n2 modified\n3\n4");
+    work_dir.remove_file("file2");
+    work_dir.write_file("file4", "foo\n");
+    work_dir.run_jj(["file", "chmod", "x", "file3"]).success();
+
+    let output = work_dir.run_jj(["--output=json", "diff"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"file_diff":{"path":"file1","status":"modified","source_path":null,"old":{"type":"file","mode":"100644"},"new":{"type":"file","mode":"100644"},"binary":false,"hunks":[{"old_start":1,"old_count":3,"new_start":1,"new_count":4,"lines":[{"kind":"context","old_line":1,"new_line":1,"content":"1\n"},{"kind":"removed","old_line":2,"new_line":null,"content":"2\n"},{"kind":"added","old_line":null,"new_line":2,"content":"2 modified\n"},{"kind":"context","old_line":3,"new_line":3,"content":"3\n"},{"kind":"added","old_line":null,"new_line":4,"content":"4"}]}]}}
+    {"version":1,"file_diff":{"path":"file3","status":"modified","source_path":null,"old":{"type":"file","mode":"100644"},"new":{"type":"file","mode":"100755"},"binary":false,"hunks":[]}}
+    {"version":1,"file_diff":{"path":"file4","status":"renamed","source_path":"file2","old":{"type":"file","mode":"100644"},"new":{"type":"file","mode":"100644"},"binary":false,"hunks":[]}}
+    [EOF]
+    "#);
+
+    // Contents which aren't valid UTF-8 are reported as binary
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file3", b"caf\xe9\n");
+    let output = work_dir.run_jj(["--output=json", "diff"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"file_diff":{"path":"file3","status":"modified","source_path":null,"old":{"type":"file","mode":"100755"},"new":{"type":"file","mode":"100755"},"binary":true,"hunks":[]}}
+    [EOF]
+    "#);
+
+    // The commit is printed as a record before the diff
+    let output = work_dir.run_jj(["--output=json", "show"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"commit":{"commit_id":"4af9158a9527d87258bd813b19dbd58b27f32be4","parents":["9f104e0cb09f7d3f1c98b63ced2f0c9b132de488"],"change_id":"mzvwutvlkqwtuzoztpszkqxkqmqyqyxo","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:12+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:12+07:00"}}}
+    {"version":1,"file_diff":{"path":"file3","status":"modified","source_path":null,"old":{"type":"file","mode":"100755"},"new":{"type":"file","mode":"100755"},"binary":true,"hunks":[]}}
+    [EOF]
+    "#);
+
+    // Conflicted files carry the content of each side
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "base\n")]);
+    create_commit_with_files(&work_dir, "left", &["base"], &[("file", "left\n")]);
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "right\n")]);
+    create_commit_with_files(&work_dir, "merge", &["left", "right"], &[]);
+    create_commit_with_files(&work_dir, "resolved", &["merge"], &[("file", "resolved\n")]);
+
+    let output = work_dir.run_jj(["--output=json", "diff", "--context=0", "-r=resolved"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"file_diff":{"path":"file","status":"modified","source_path":null,"old":{"type":"conflict","mode":"100644","conflict":{"sides":[{"label":"znkkpsqq 816d51a8 \"left\"","content":"left\n"},{"label":"kmkuslsw c48ff47e \"right\"","content":"right\n"}],"bases":[{"label":"vruxwmqv 5a3b7b8d \"base\"","content":"base\n"}]}},"new":{"type":"file","mode":"100644"},"binary":false,"hunks":[{"old_start":1,"old_count":8,"new_start":1,"new_count":1,"lines":[{"kind":"removed","old_line":1,"new_line":null,"content":"<<<<<<< conflict 1 of 1\n"},{"kind":"removed","old_line":2,"new_line":null,"content":"%%%%%%% diff from: vruxwmqv 5a3b7b8d \"base\"\n"},{"kind":"removed","old_line":3,"new_line":null,"content":"\\\\\\\\\\\\\\        to: znkkpsqq 816d51a8 \"left\"\n"},{"kind":"removed","old_line":4,"new_line":null,"content":"-base\n"},{"kind":"removed","old_line":5,"new_line":null,"content":"+left\n"},{"kind":"removed","old_line":6,"new_line":null,"content":"+++++++ kmkuslsw c48ff47e \"right\"\n"},{"kind":"removed","old_line":7,"new_line":null,"content":"right\n"},{"kind":"removed","old_line":8,"new_line":null,"content":">>>>>>> conflict 1 of 1 ends\n"},{"kind":"added","old_line":null,"new_line":1,"content":"resolved\n"}]}]}}
+    [EOF]
+    "#);
+
+    // Other diff formats can't be requested at the same time
+    let output = work_dir.run_jj(["--output=json", "diff", "--git"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Other diff formats cannot be used with `--output json`
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["--output=json", "diff", "-T''"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: `--template` cannot be used with `--output json`
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["--output=json", "diff", "--html"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: `--format html` cannot be used with `--output json`
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // JSON diffs aren't available as a diff format
+    let output = work_dir.run_jj(["diff", "--tool=:json"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Invalid builtin diff format: json
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_diff_html() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "<a> & b\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "<a> & c\n");
+
+    let output = work_dir.run_jj(["diff", "--format", "html"]);
+    insta::assert_snapshot!(output, @r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+    <meta charset="utf-8">
+    <style>
+    pre.jj a { color: inherit; }
+    </style>
+    </head>
+    <body>
+    <pre class="jj">
+    <span class="diff color_words header" style="color:#cdcd00">Modified regular file file1:</span>
+    <span class="diff color_words removed line_number" style="color:#cd0000">   1</span><span class="diff color_words"> </span><span class="diff color_words added line_number" style="color:#00cd00">   1</span><span class="diff color_words">: &lt;a&gt; &amp; </span><span class="diff color_words removed token" style="color:#cd0000;text-decoration:underline">b</span><span class="diff color_words added token" style="color:#00cd00;text-decoration:underline">c</span>
+    </pre>
+    </body>
+    </html>
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["diff", "--html", "--git"]);
+    insta::assert_snapshot!(output, @r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+    <meta charset="utf-8">
+    <style>
+    pre.jj a { color: inherit; }
+    </style>
+    </head>
+    <body>
+    <pre class="jj">
+    <span class="diff git file_header" style="font-weight:bold">diff --git a/file1 b/file1</span>
+    <span class="diff git file_header" style="font-weight:bold">index 537b727395..726aa70803 100644</span>
+    <span class="diff git file_header" style="font-weight:bold">--- a/file1</span>
+    <span class="diff git file_header" style="font-weight:bold">+++ b/file1</span>
+    <span class="diff git hunk_header" style="color:#00cdcd">@@ -1,1 +1,1 @@</span>
+    <span class="diff git removed" style="color:#cd0000">-&lt;a&gt; &amp; </span><span class="diff git removed token" style="color:#cd0000;text-decoration:underline">b</span>
+    <span class="diff git added" style="color:#00cd00">+&lt;a&gt; &amp; </span><span class="diff git added token" style="color:#00cd00;text-decoration:underline">c</span>
+    </pre>
+    </body>
+    </html>
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_diff_color_moved() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(
+        "file1",
+        indoc! {"
+            fn moved() {
+                do_something_important();
+            }
+            fn edited() {
+                other_stuff();
+            }
+        "},
+    );
+    work_dir.write_file("file2", "x\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file(
+        "file1",
+        indoc! {"
+            fn edited() {
+                other_stuff_changed();
+            }
+        "},
+    );
+    work_dir.write_file(
+        "file2",
+        indoc! {"
+            x
+            fn moved() {
+                do_something_important();
+            }
+        "},
+    );
+
+    let output = work_dir.run_jj(["diff", "--color-moved", "--color=debug"]);
+    insta::assert_snapshot!(output, @"
+    [38;5;3m<<diff color_words header::Modified regular file file1:>>[39m
+    [38;5;5m<<diff color_words removed moved removed line_number::   1>><<diff color_words removed moved::     : >>[4m<<diff color_words removed moved removed token::fn moved() {>>[24m[39m
+    [38;5;5m<<diff color_words removed moved removed line_number::   2>><<diff color_words removed moved::     : >>[4m<<diff color_words removed moved removed token::    do_something_important();>>[24m[39m
+    [38;5;5m<<diff color_words removed moved removed line_number::   3>><<diff color_words removed moved::     : >>[4m<<diff color_words removed moved removed token::}>>[24m[39m
+    [2m[38;5;1m<<diff color_words context removed line_number::   4>>[0m<<diff color_words context:: >>[2m[38;5;2m<<diff color_words context added line_number::   1>>[0m<<diff color_words context::: fn edited() {>>
+    [38;5;1m<<diff color_words removed line_number::   5>>[39m<<diff color_words:: >>[38;5;2m<<diff color_words added line_number::   2>>[39m<<diff color_words:::     >>[4m[38;5;1m<<diff color_words removed token::other_stuff>>[38;5;2m<<diff color_words added token::other_stuff_changed>>[24m[39m<<diff color_words::();>>
+    [2m[38;5;1m<<diff color_words context removed line_number::   6>>[0m<<diff color_words context:: >>[2m[38;5;2m<<diff color_words context added line_number::   3>>[0m<<diff color_words context::: }>>
+    [38;5;3m<<diff color_words header::Modified regular file file2:>>[39m
+    [2m[38;5;1m<<diff color_words context removed line_number::   1>>[0m<<diff color_words context:: >>[2m[38;5;2m<<diff color_words context added line_number::   1>>[0m<<diff color_words context::: x>>
+    [38;5;6m<<diff color_words added moved::     >><<diff color_words added moved added line_number::   2>><<diff color_words added moved::: >>[4m<<diff color_words added moved added token::fn moved() {>>[24m[39m
+    [38;5;6m<<diff color_words added moved::     >><<diff color_words added moved added line_number::   3>><<diff color_words added moved::: >>[4m<<diff color_words added moved added token::    do_something_important();>>[24m[39m
+    [38;5;6m<<diff color_words added moved::     >><<diff color_words added moved added line_number::   4>><<diff color_words added moved::: >>[4m<<diff color_words added moved added token::}>>[24m[39m
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git", "--color=debug"]);
+    insta::assert_snapshot!(output, @"
+    [1m<<diff git file_header::diff --git a/file1 b/file1>>[0m
+    [1m<<diff git file_header::index 0a5413188b..7e976a9968 100644>>[0m
+    [1m<<diff git file_header::--- a/file1>>[0m
+    [1m<<diff git file_header::+++ b/file1>>[0m
+    [38;5;6m<<diff git hunk_header::@@ -1,6 +1,3 @@>>[39m
+    [38;5;1m<<diff git removed::->>[4m<<diff git removed token::fn moved() {>>[24m[39m
+    [38;5;1m<<diff git removed::->>[4m<<diff git removed token::    do_something_important();>>[24m[39m
+    [38;5;1m<<diff git removed::->>[4m<<diff git removed token::}>>[24m[39m
+    <<diff git context:: fn edited() {>>
+    [38;5;1m<<diff git removed::-    >>[4m<<diff git removed token::other_stuff>>[24m<<diff git removed::();>>[39m
+    [38;5;2m<<diff git added::+    >>[4m<<diff git added token::other_stuff_changed>>[24m<<diff git added::();>>[39m
+    <<diff git context:: }>>
+    [1m<<diff git file_header::diff --git a/file2 b/file2>>[0m
+    [1m<<diff git file_header::index 587be6b4c3..b3a2fe9335 100644>>[0m
+    [1m<<diff git file_header::--- a/file2>>[0m
+    [1m<<diff git file_header::+++ b/file2>>[0m
+    [38;5;6m<<diff git hunk_header::@@ -1,1 +1,4 @@>>[39m
+    <<diff git context:: x>>
+    [38;5;2m<<diff git added::+>>[4m<<diff git added token::fn moved() {>>[24m[39m
+    [38;5;2m<<diff git added::+>>[4m<<diff git added token::    do_something_important();>>[24m[39m
+    [38;5;2m<<diff git added::+>>[4m<<diff git added token::}>>[24m[39m
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "diff",
+        "--git",
+        "--color=debug",
+        "--config=diff.git.color-moved=true",
+    ]);
+    insta::assert_snapshot!(output, @"
+    [1m<<diff git file_header::diff --git a/file1 b/file1>>[0m
+    [1m<<diff git file_header::index 0a5413188b..7e976a9968 100644>>[0m
+    [1m<<diff git file_header::--- a/file1>>[0m
+    [1m<<diff git file_header::+++ b/file1>>[0m
+    [38;5;6m<<diff git hunk_header::@@ -1,6 +1,3 @@>>[39m
+    [38;5;5m<<diff git removed moved::->>[4m<<diff git removed moved token::fn moved() {>>[24m[39m
+    [38;5;5m<<diff git removed moved::->>[4m<<diff git removed moved token::    do_something_important();>>[24m[39m
+    [38;5;5m<<diff git removed moved::->>[4m<<diff git removed moved token::}>>[24m[39m
+    <<diff git context:: fn edited() {>>
+    [38;5;1m<<diff git removed::-    >>[4m<<diff git removed token::other_stuff>>[24m<<diff git removed::();>>[39m
+    [38;5;2m<<diff git added::+    >>[4m<<diff git added token::other_stuff_changed>>[24m<<diff git added::();>>[39m
+    <<diff git context:: }>>
+    [1m<<diff git file_header::diff --git a/file2 b/file2>>[0m
+    [1m<<diff git file_header::index 587be6b4c3..b3a2fe9335 100644>>[0m
+    [1m<<diff git file_header::--- a/file2>>[0m
+    [1m<<diff git file_header::+++ b/file2>>[0m
+    [38;5;6m<<diff git hunk_header::@@ -1,1 +1,4 @@>>[39m
+    <<diff git context:: x>>
+    [38;5;6m<<diff git added moved::+>>[4m<<diff git added moved token::fn moved() {>>[24m[39m
+    [38;5;6m<<diff git added moved::+>>[4m<<diff git added moved token::    do_something_important();>>[24m[39m
+    [38;5;6m<<diff git added moved::+>>[4m<<diff git added moved token::}>>[24m[39m
+    [EOF]
+    ");
+
+    let template = r#"
+    self.diff().stat(moved=true).files().map(|e| separate(" ",
+      e.path(), e.lines_added(), e.lines_removed(),
+      e.moved_lines_added(), e.moved_lines_removed(),
+    )).join("\n") ++ "\ntotal moved: " ++ self.diff().stat(moved=true).total_moved() ++ "\n"
+    "#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    file1 1 4 0 3
+    file2 3 0 3 0
+    total moved: 3
+    [EOF]
+    ");
+
+    // Moved lines aren't counted by default
+    let template = r#"self.diff().stat().total_moved() ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    0
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_word_tokenizer() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(indoc! {r#"
+        [word-tokenizers.subword]
+        builtin = "subword"
+        patterns = ["glob:'*.java'"]
+    "#});
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("Foo.java", "getUserName();\n");
+    work_dir.write_file("foo.rs", "getUserName();\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("Foo.java", "getUserId();\n");
+    work_dir.write_file("foo.rs", "getUserId();\n");
+
+    // Only the changed part of the identifier is highlighted in Java files
+    let output = work_dir.run_jj(["diff", "--color-words", "--color=debug"]);
+    insta::assert_snapshot!(output, @"
+    [38;5;3m<<diff color_words header::Modified regular file Foo.java:>>[39m
+    [38;5;1m<<diff color_words removed line_number::   1>>[39m<<diff color_words:: >>[38;5;2m<<diff color_words added line_number::   1>>[39m<<diff color_words::: getUser>>[4m[38;5;1m<<diff color_words removed token::Name>>[38;5;2m<<diff color_words added token::Id>>[24m[39m<<diff color_words::();>>
+    [38;5;3m<<diff color_words header::Modified regular file foo.rs:>>[39m
+    [38;5;1m<<diff color_words removed line_number::   1>>[39m<<diff color_words:: >>[38;5;2m<<diff color_words added line_number::   1>>[39m<<diff color_words::: >>[4m[38;5;1m<<diff color_words removed token::getUserName>>[38;5;2m<<diff color_words added token::getUserId>>[24m[39m<<diff color_words::();>>
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--side-by-side", "--color=debug", "Foo.java"]);
+    insta::assert_snapshot!(output, @"
+    [38;5;3m<<diff side_by_side header::Modified regular file Foo.java:>>[39m
+    [38;5;1m<<diff side_by_side removed line_number::   1>><<diff side_by_side removed:: getUser>>[4m<<diff side_by_side removed token::Name>>[24m<<diff side_by_side removed::();                             >>[39m<<diff side_by_side:: │ >>[38;5;2m<<diff side_by_side added line_number::   1>><<diff side_by_side added:: getUser>>[4m<<diff side_by_side added token::Id>>[24m<<diff side_by_side added::();>>[39m<<diff side_by_side::>>
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_word_tokenizer_failure() {
+    let test_env = TestEnvironment::default();
+    let tokenizer_path = assert_cmd::cargo::cargo_bin!("fake-formatter");
+    test_env.add_config(format!(
+        r#"
+        [word-tokenizers.failing]
+        command = [{}, "--stderr", "bad input", "--fail"]
+        patterns = ["all()"]
+        "#,
+        to_toml_value(tokenizer_path.to_str().unwrap())
+    ));
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "foo bar\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file", "foo baz\n");
+
+    // The words are split by the default tokenizer, and the failure is reported
+    let output = work_dir.run_jj(["diff", "--color-words"]);
+    insta::with_settings!({
+        filters => [("exit code", "exit status")], // Windows
+    }, {
+        insta::assert_snapshot!(output, @"
+        Modified regular file file:
+           1    1: foo barbaz
+        [EOF]
+        ------- stderr -------
+        Warning: Failed to split words by external tokenizer. The default tokenizer was used instead.
+        Caused by: Word tokenizer failed with exit status: 1:
+        bad input
+        [EOF]
+        ");
+    });
+}
+
 #[test]
 fn test_diff_missing_newline() {
     let test_env = TestEnvironment::default();
@@ -3205,6 +3641,30 @@ fn test_diff_external_tool() -> TestResult {
     [EOF]
     ");
 
+    // The tool output is escaped in HTML
+    std::fs::write(&edit_script, "print <b>\x1b[1mbold</b>")?;
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--format", "html", "--tool=fake-diff-editor"]), @r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+    <meta charset="utf-8">
+    <style>
+    pre.jj a { color: inherit; }
+    </style>
+    </head>
+    <body>
+    <pre class="jj">
+    <span class="diff">&lt;b&gt;␛[1mbold&lt;/b&gt;
+    </span></pre>
+    </body>
+    </html>
+    [EOF]
+    "#);
+    std::fs::write(
+        &edit_script,
+        "print-files-before\0print --\0print-files-after",
+    )?;
+
     // Enabled by default, looks up the merge-tools table
     let config = "--config=ui.diff-formatter=fake-diff-editor";
     insta::assert_snapshot!(work_dir.run_jj(["diff", config]), @"
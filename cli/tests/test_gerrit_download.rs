@@ -0,0 +1,292 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::thread;
+
+use testutils::ensure_running_outside_ci;
+use testutils::git;
+use testutils::http::HttpRequest;
+use testutils::http::serve_json;
+use testutils::is_external_tool_installed;
+
+use crate::common::TestEnvironment;
+
+/// Serves a single Gerrit REST API request with the given JSON response.
+fn serve_gerrit_once(response: &str) -> (String, thread::JoinHandle<Vec<HttpRequest>>) {
+    // Gerrit prefixes JSON responses to prevent XSSI
+    serve_json([("200 OK", format!(")]}}'\n{response}"))])
+}
+
+#[test]
+fn test_gerrit_download() {
+    let test_env = TestEnvironment::default();
+    // Set up a remote laid out like Gerrit
+    let remote_repo = git::init_bare(test_env.env_root().join("remote"));
+    let base = git::add_commit(
+        &remote_repo,
+        "refs/heads/main",
+        "file",
+        b"base\n",
+        "base",
+        &[],
+    );
+    let one = git::add_commit(
+        &remote_repo,
+        "refs/changes/01/1/1",
+        "file",
+        b"one\n",
+        "one\n\nChange-Id: I1111111111111111111111111111111111111111\n",
+        &[base.commit_id],
+    );
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "gerrit", "../remote"])
+        .success();
+
+    // Changes not derived from local changes are added as new commits
+    let output = work_dir.run_jj(["gerrit", "download", "1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    git: From $TEST_ENV/remote        
+    git:  * [new ref]         refs/changes/01/1/1 -> refs/jj/gerrit/gerrit/changes/01/1/1        
+    Added patchset 1/1 as tyqmnzkn d1d1a6b9 one
+    [EOF]
+    ");
+
+    // Create a local change on top of it, and a child of it
+    work_dir
+        .run_jj(["new", "-m", "two", "description(substring:one)"])
+        .success();
+    work_dir.write_file("file", "two\n");
+    work_dir.run_jj(["new", "-m", "three"]).success();
+    let change_id = work_dir
+        .run_jj(["log", "--no-graph", "-r@-", "-T", "change_id.normal_hex()"])
+        .success()
+        .stdout
+        .into_raw();
+
+    // A reviewer amends the uploaded change in the web UI
+    git::add_commit(
+        &remote_repo,
+        "refs/changes/02/2/1",
+        "file",
+        b"two\n",
+        &format!("two\n\nChange-Id: I{change_id}6a6a6964\n"),
+        &[one.commit_id],
+    );
+    git::add_commit(
+        &remote_repo,
+        "refs/changes/02/2/2",
+        "file",
+        b"two amended\n",
+        &format!("two amended\n\nChange-Id: I{change_id}6a6a6964\n"),
+        &[one.commit_id],
+    );
+
+    // The local change is updated to the latest patchset
+    let output = work_dir.run_jj(["gerrit", "download", "2"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    git: From $TEST_ENV/remote        
+    git:  * [new ref]         refs/changes/02/2/1 -> refs/jj/gerrit/gerrit/changes/02/2/1        
+    git:  * [new ref]         refs/changes/02/2/2 -> refs/jj/gerrit/gerrit/changes/02/2/2        
+    Updated to patchset 2/2: zsuskuln 35933773 two amended
+    Rebased 1 descendant commits
+    Working copy  (@) now at: mzvwutvl 5bb1c9e4 (empty) three
+    Parent commit (@-)      : zsuskuln 35933773 two amended
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "::@ ~ root()"]);
+    insta::assert_snapshot!(output, @"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:13 5bb1c9e4
+    │  (empty) three
+    ○  zsuskuln someone@example.org 2001-02-03 08:05:13 35933773
+    │  two amended
+    ○  tyqmnzkn someone@example.org 1970-01-01 11:00:00 d1d1a6b9
+    │  one
+    ○  quyslvkm someone@example.org 1970-01-01 11:00:00 c8ee6d80
+    │  base
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r@-", "file"]);
+    insta::assert_snapshot!(output, @"
+    two amended
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "evolog",
+        "-r@-",
+        "--no-graph",
+        "-T",
+        "commit.commit_id().short() ++ ' ' ++ commit.description()",
+    ]);
+    insta::assert_snapshot!(output, @"
+    359337738a3b two amended
+    53bc1fc46854 two
+    5f95a5ab7596 two
+    20908a02bfad two amended
+
+    Change-Id: I0757f5ec8418b4f0983d29e77f9c624a6a6a6964
+    [EOF]
+    ");
+
+    // The fetched refs are removed
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    assert_eq!(
+        git_repo
+            .references()
+            .unwrap()
+            .prefixed("refs/jj/gerrit/")
+            .unwrap()
+            .count(),
+        0
+    );
+
+    // Downloading the same patchset again is a no-op
+    if is_external_tool_installed("curl") {
+        let (url, handle) = serve_gerrit_once(r#"[{"_number": 2}]"#);
+        let output = work_dir.run_jj([
+            "gerrit",
+            "download",
+            &format!("I{change_id}6a6a6964"),
+            &format!("--config=gerrit.review-url={url}"),
+        ]);
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        git: From $TEST_ENV/remote        
+        git:  * [new ref]         refs/changes/02/2/1 -> refs/jj/gerrit/gerrit/changes/02/2/1        
+        git:  * [new ref]         refs/changes/02/2/2 -> refs/jj/gerrit/gerrit/changes/02/2/2        
+        Already up to date with patchset 2/2: zsuskuln 35933773 two amended
+        Nothing changed.
+        [EOF]
+        ");
+        insta::assert_snapshot!(handle.join().unwrap()[0].request_line, @"GET /changes/?q=change:I0757f5ec8418b4f0983d29e77f9c624a6a6a6964 HTTP/1.1");
+    } else {
+        ensure_running_outside_ci("`curl` must be in the PATH");
+        eprintln!("Skipping Change-Id lookup because curl is not installed on the system");
+    }
+
+    // A specific patchset can be selected
+    let output = work_dir.run_jj(["gerrit", "download", "2/1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    git: From $TEST_ENV/remote        
+    git:  * [new ref]         refs/changes/02/2/1 -> refs/jj/gerrit/gerrit/changes/02/2/1        
+    Updated to patchset 2/1: zsuskuln 96cafe82 two
+    Rebased 1 descendant commits
+    Working copy  (@) now at: mzvwutvl 2bb514c3 (empty) three
+    Parent commit (@-)      : zsuskuln 96cafe82 two
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r@-", "file"]);
+    insta::assert_snapshot!(output, @"
+    two
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_gerrit_download_errors() {
+    let test_env = TestEnvironment::default();
+    git::init_bare(test_env.env_root().join("remote"));
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["gerrit", "download", "1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No remote specified, and no 'gerrit' remote was found
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir
+        .run_jj(["git", "remote", "add", "gerrit", "../remote"])
+        .success();
+    let output = work_dir.run_jj(["gerrit", "download", "foo"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Invalid change 'foo'. Expected a change number or Change-Id
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["gerrit", "download", "1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Change 1 not found on remote 'gerrit'
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["gerrit", "download", "1/2"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Change 1/2 not found on remote 'gerrit'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Change-Ids are looked up on the Gerrit server
+    let change_id = "I1111111111111111111111111111111111111111";
+    let output = work_dir.run_jj(["gerrit", "download", change_id]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Cannot look up Change-Id I1111111111111111111111111111111111111111 without the Gerrit server URL
+    Hint: Set `gerrit.review-url` to the URL of the Gerrit server, or specify the change by its number
+    [EOF]
+    [exit status: 1]
+    ");
+    if cfg!(unix) {
+        let output = work_dir.run_jj([
+            "gerrit",
+            "download",
+            change_id,
+            "--config=gerrit.review-url=http://127.0.0.1:1",
+            "--config=review.http-program=nonexistent",
+        ]);
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Error: Failed to look up Change-Id I1111111111111111111111111111111111111111
+        Caused by:
+        1: Failed to run nonexistent
+        2: No such file or directory (os error 2)
+        [EOF]
+        [exit status: 1]
+        ");
+    }
+    if is_external_tool_installed("curl") {
+        let (url, handle) = serve_gerrit_once(r#"[{"_number": 1}, {"_number": 2}]"#);
+        let output = work_dir.run_jj([
+            "gerrit",
+            "download",
+            change_id,
+            &format!("--config=gerrit.review-url={url}/"),
+        ]);
+        handle.join().unwrap();
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Error: Change-Id I1111111111111111111111111111111111111111 matches multiple changes: 1, 2
+        Hint: Specify the change by its number instead
+        [EOF]
+        [exit status: 1]
+        ");
+    } else {
+        ensure_running_outside_ci("`curl` must be in the PATH");
+        eprintln!("Skipping Change-Id lookup because curl is not installed on the system");
+    }
+}
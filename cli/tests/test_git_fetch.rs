@@ -2301,3 +2301,104 @@ fn test_git_fetch_auto_track_bookmarks() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_git_fetch_abandon_landed_changes() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        git.abandon-landed-changes = true
+        git.abandon-landed-changes-by-patch-id = true
+        remotes.origin.auto-track-bookmarks = "*"
+        templates.commit_summary = "description.first_line()"
+        "#,
+    );
+    let remote_repo = git::init_bare(test_env.env_root().join("remote"));
+    let base = git::add_commit(
+        &remote_repo,
+        "refs/heads/main",
+        "base",
+        b"base\n",
+        "base",
+        &[],
+    );
+    git::add_commit(
+        &remote_repo,
+        "refs/heads/release",
+        "base",
+        b"release\n",
+        "release",
+        &[base.commit_id],
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    work_dir.run_jj(["git", "fetch"]).success();
+
+    // A feature is pushed for review, and backported to the release branch
+    work_dir
+        .run_jj(["new", "main@origin", "-m", "feature"])
+        .success();
+    work_dir.write_file("file", "feature\n");
+    work_dir
+        .run_jj(["backport", "-r@", "--onto", "release@origin"])
+        .success();
+    work_dir
+        .run_jj([
+            "git",
+            "push",
+            "--change=@",
+            "--change=children(release@origin)",
+        ])
+        .success();
+    // A copy of the feature which hasn't been pushed
+    work_dir.run_jj(["duplicate", "-r@"]).success();
+    work_dir.run_jj(["new", "main@origin"]).success();
+
+    // The feature lands on main as a different commit
+    git::add_commit(
+        &remote_repo,
+        "refs/heads/main",
+        "file",
+        b"feature\n",
+        "feature (squashed)",
+        &[base.commit_id],
+    );
+    let output = work_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: main@origin [updated] tracked
+    Abandoned 1 commits that landed upstream:
+      feature
+    [EOF]
+    ");
+
+    // The backported copy and the unpushed copy are kept
+    let template = r#"description.first_line() ++ " on " ++ parents.map(|c| c.description().first_line()) ++ "\n""#;
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        r#"mutable() & description(substring:"cherry picked")"#,
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"
+    feature on release
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        r#"mutable() & description(exact:"feature\n")"#,
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"
+    feature on base
+    [EOF]
+    ");
+}
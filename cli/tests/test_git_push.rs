@@ -434,6 +434,100 @@ fn test_git_push_forward_unexpectedly_moved() {
     ");
 }
 
+#[test]
+fn test_git_push_partial_failure_rolls_back_created_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    git::init_bare(test_env.env_root().join("remote"));
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.write_file("file", "first");
+
+    // Create push-qpvuntsmwlqt on the remote behind jj's back, so that pushing
+    // it again is rejected.
+    work_dir.run_jj(["new", "-m", "decoy"]).success();
+    work_dir.write_file("file", "decoy");
+    work_dir
+        .run_jj(["git", "push", "--named", "push-qpvuntsmwlqt=@"])
+        .success();
+    work_dir
+        .run_jj([
+            "bookmark",
+            "forget",
+            "--include-remotes",
+            "push-qpvuntsmwlqt",
+        ])
+        .success();
+    work_dir.run_jj(["edit", "@-"]).success();
+
+    // A bookmark created by this push is removed if it failed to push
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "other1"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "-c@", "-bother1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Creating bookmark push-qpvuntsmwlqt for revision qpvuntsmwlqt
+    Changes to push to origin:
+      bookmark: push-qpvuntsmwlqt [add to bde8ae465b04]
+      bookmark: other1 [add to bde8ae465b04]
+    Warning: The following references unexpectedly moved on the remote:
+      refs/heads/push-qpvuntsmwlqt (reason: stale info)
+    Hint: Try fetching from the remote, then make the bookmark point to where you want it to be, and push again.
+    Error: Failed to push some bookmarks
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    other1: qpvuntsm bde8ae46 first
+      @origin: qpvuntsm bde8ae46 first
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-n1", "-Tdescription"]);
+    insta::assert_snapshot!(output, @"
+    @  push bookmark other1 to git remote origin
+    [EOF]
+    ");
+
+    // A bookmark that already existed is kept
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "push-qpvuntsmwlqt", "other2"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "-c@", "-bother2"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: push-qpvuntsmwlqt [add to bde8ae465b04]
+      bookmark: other2 [add to bde8ae465b04]
+    Warning: The following references unexpectedly moved on the remote:
+      refs/heads/push-qpvuntsmwlqt (reason: stale info)
+    Hint: Try fetching from the remote, then make the bookmark point to where you want it to be, and push again.
+    Error: Failed to push some bookmarks
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    other1: qpvuntsm bde8ae46 first
+      @origin: qpvuntsm bde8ae46 first
+    other2: qpvuntsm bde8ae46 first
+      @origin: qpvuntsm bde8ae46 first
+    push-qpvuntsmwlqt: qpvuntsm bde8ae46 first
+      @origin (not created yet)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-n1", "-Tdescription"]);
+    insta::assert_snapshot!(output, @"
+    @  push bookmark other2 to git remote origin
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_push_sideways_unexpectedly_moved() {
     let test_env = TestEnvironment::default();
@@ -2991,6 +3085,141 @@ fn test_git_push_unmapped_refs() {
     ");
 }
 
+#[test]
+fn test_git_push_options_from_config() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "origin"])
+        .success();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "local"])
+        .success();
+    let origin_dir = test_env.work_dir("origin");
+    let local_dir = test_env.work_dir("local");
+
+    set_up_push_options_hook(&origin_dir)?;
+
+    local_dir
+        .run_jj(["git", "remote", "add", "origin", "../origin"])
+        .success();
+    local_dir.run_jj(["commit", "-mcommit1"]).success();
+    local_dir
+        .run_jj(["bookmark", "set", "-r@-", "bookmark1"])
+        .success();
+    local_dir
+        .run_jj(["bookmark", "track", "bookmark1"])
+        .success();
+
+    test_env.add_config(indoc! {r#"
+        [remotes.origin]
+        push-options = ["ci.skip", "foo"]
+        push-options-template = '"title=" ++ description.first_line() ++ "\n"'
+    "#});
+    let output = local_dir.run_jj(["git", "push", "-o=foo", "-o=bar"]);
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\s*\n", "\n");
+    settings.bind(|| {
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Changes to push to origin:
+          bookmark: bookmark1 [add to 5792bfac70a2]
+        remote: option: ci.skip
+        remote: option: foo
+        remote: option: title=commit1
+        remote: option: foo
+        remote: option: bar
+        [EOF]
+        ");
+    });
+    Ok(())
+}
+
+#[test]
+fn test_git_push_options_template_multiple_commits() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "origin"])
+        .success();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "local"])
+        .success();
+    let origin_dir = test_env.work_dir("origin");
+    let local_dir = test_env.work_dir("local");
+    set_up_push_options_hook(&origin_dir)?;
+    local_dir
+        .run_jj(["git", "remote", "add", "origin", "../origin"])
+        .success();
+    local_dir.run_jj(["commit", "-mcommit1"]).success();
+    local_dir.run_jj(["commit", "-mcommit2"]).success();
+    local_dir
+        .run_jj(["bookmark", "create", "-r@--", "bookmark1"])
+        .success();
+    local_dir
+        .run_jj(["bookmark", "create", "-r@-", "bookmark2"])
+        .success();
+    local_dir
+        .run_jj(["bookmark", "track", "bookmark1", "bookmark2"])
+        .success();
+    test_env.add_config(indoc! {r#"
+        [remotes.origin]
+        push-options-template = '"ci.skip\nmerge_request.label=" ++ description.first_line() ++ "\n"'
+    "#});
+
+    // Identical options generated for the two commits are sent once. Options
+    // with the same key but different values, such as labels for GitLab merge
+    // requests, are all sent.
+    let output = local_dir.run_jj(["git", "push", "-b=bookmark1", "-b=bookmark2"]);
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\s*\n", "\n");
+    settings.bind(|| {
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Changes to push to origin:
+          bookmark: bookmark1 [add to 5792bfac70a2]
+          bookmark: bookmark2 [add to adfa3f5929cf]
+        remote: option: ci.skip
+        remote: option: merge_request.label=commit1
+        remote: option: merge_request.label=commit2
+        [EOF]
+        ");
+    });
+    Ok(())
+}
+
+fn set_up_push_options_hook(origin_dir: &TestWorkDir) -> TestResult {
+    let mut data = origin_dir.read_file(".git/config");
+    data.extend(indoc! {b"
+        [receive]
+        \tadvertisePushOptions = true
+    "});
+    origin_dir.write_file(".git/config", data);
+    let hook_path = origin_dir
+        .root()
+        .join(".git")
+        .join("hooks")
+        .join("pre-receive");
+    std::fs::create_dir_all(hook_path.parent().unwrap())?;
+    std::fs::write(
+        &hook_path,
+        indoc! {r#"
+            #!/bin/sh
+            i=0
+            while [ "$i" -lt "${GIT_PUSH_OPTION_COUNT:-0}" ]; do
+                eval "echo \"option: \$GIT_PUSH_OPTION_$i\""
+                i=$((i + 1))
+            done
+        "#},
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    Ok(())
+}
+
 #[must_use]
 fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
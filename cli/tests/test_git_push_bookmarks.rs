@@ -0,0 +1,212 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::git;
+
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+
+fn set_up(test_env: &TestEnvironment) -> TestWorkDir<'_> {
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    git::init_bare(test_env.env_root().join("remote"));
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    work_dir
+}
+
+fn get_remote_refs(test_env: &TestEnvironment) -> Vec<String> {
+    let git_repo = git::open(test_env.env_root().join("remote"));
+    let mut names = git_repo
+        .references()
+        .unwrap()
+        .all()
+        .unwrap()
+        .map(|reference| reference.unwrap().name().as_bstr().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_git_push_bookmarks_gc() {
+    let test_env = TestEnvironment::default();
+    let work_dir = set_up(&test_env);
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.write_file("file", "first");
+    work_dir.run_jj(["new", "-m", "second"]).success();
+    work_dir.write_file("file", "second");
+    work_dir
+        .run_jj(["git", "push", "--change=@-", "--change=@"])
+        .success();
+
+    // Bookmarks created by --change are recorded
+    let output = work_dir.run_jj(["git", "push-bookmarks", "list"]);
+    insta::assert_snapshot!(output, @"
+    push-qpvuntsmwlqt: qpvuntsm bde8ae46 push-qpvuntsmwlqt | first
+    push-zsuskulnrvyr: zsuskuln 1d36c0c6 push-zsuskulnrvyr | second
+    [EOF]
+    ");
+
+    // Nothing is merged yet
+    let output = work_dir.run_jj(["git", "push-bookmarks", "gc"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No merged push bookmarks to delete.
+    [EOF]
+    ");
+
+    // The first change lands on main
+    work_dir
+        .run_jj(["bookmark", "create", "main", "-r@-"])
+        .success();
+    work_dir
+        .run_jj(["git", "push", "--bookmark=main", "--allow-new"])
+        .success();
+    let output = work_dir.run_jj(["git", "push-bookmarks", "list"]);
+    insta::assert_snapshot!(output, @"
+    push-qpvuntsmwlqt (merged): qpvuntsm bde8ae46 main push-qpvuntsmwlqt | first
+    push-zsuskulnrvyr: zsuskuln 1d36c0c6 push-zsuskulnrvyr | second
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "push-bookmarks", "gc", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Would delete merged bookmark push-qpvuntsmwlqt
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "push-bookmarks", "gc"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Deleting merged bookmark push-qpvuntsmwlqt
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    main: qpvuntsm bde8ae46 first
+      @origin: qpvuntsm bde8ae46 first
+    push-zsuskulnrvyr: zsuskuln 1d36c0c6 second
+      @origin: zsuskuln 1d36c0c6 second
+    [EOF]
+    ");
+    insta::assert_debug_snapshot!(get_remote_refs(&test_env), @r#"
+    [
+        "refs/heads/main",
+        "refs/heads/push-zsuskulnrvyr",
+    ]
+    "#);
+    let output = work_dir.run_jj(["git", "push-bookmarks", "list"]);
+    insta::assert_snapshot!(output, @"
+    push-zsuskulnrvyr: zsuskuln 1d36c0c6 push-zsuskulnrvyr | second
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_push_bookmarks_gc_rejected() {
+    let test_env = TestEnvironment::default();
+    let work_dir = set_up(&test_env);
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.run_jj(["git", "push", "--change=@"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "main", "-r@"])
+        .success();
+    work_dir
+        .run_jj(["git", "push", "--bookmark=main", "--allow-new"])
+        .success();
+
+    // The remote refuses to delete the bookmark
+    let hook_path = test_env
+        .env_root()
+        .join("remote")
+        .join("hooks")
+        .join("pre-receive");
+    std::fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh\necho 'deletion denied' >&2\nexit 1\n",
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o700)).unwrap();
+    }
+    let output = work_dir.run_jj(["git", "push-bookmarks", "gc"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Deleting merged bookmark push-qpvuntsmwlqt
+    remote: deletion denied        
+    Warning: The remote rejected the following updates:
+      refs/heads/push-qpvuntsmwlqt (reason: pre-receive hook declined)
+    Hint: Try checking if you have permission to push to all the bookmarks.
+    Error: Failed to delete some merged bookmarks from the remotes: push-qpvuntsmwlqt
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The local bookmark is kept so the deletion can be retried
+    let output = work_dir.run_jj(["git", "push-bookmarks", "list"]);
+    insta::assert_snapshot!(output, @"
+    push-qpvuntsmwlqt (merged): qpvuntsm 059d8626 main push-qpvuntsmwlqt | (empty) first
+    [EOF]
+    ");
+    std::fs::remove_file(&hook_path).unwrap();
+    let output = work_dir.run_jj(["git", "push-bookmarks", "gc"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Deleting merged bookmark push-qpvuntsmwlqt
+    [EOF]
+    ");
+    insta::assert_debug_snapshot!(get_remote_refs(&test_env), @r#"
+    [
+        "refs/heads/main",
+    ]
+    "#);
+}
+
+#[test]
+fn test_git_push_bookmarks_forgotten() {
+    let test_env = TestEnvironment::default();
+    let work_dir = set_up(&test_env);
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.run_jj(["git", "push", "--change=@"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "other", "-r@"])
+        .success();
+
+    // Only bookmarks created by --change are recorded
+    let output = work_dir.run_jj(["git", "push-bookmarks", "list"]);
+    insta::assert_snapshot!(output, @"
+    push-qpvuntsmwlqt: qpvuntsm 059d8626 other push-qpvuntsmwlqt | (empty) first
+    [EOF]
+    ");
+
+    // Push bookmarks which are forgotten explicitly are no longer recorded
+    work_dir
+        .run_jj(["bookmark", "forget", "--include-remotes", "glob:push-*"])
+        .success();
+    let output = work_dir.run_jj(["git", "push-bookmarks", "list"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["git", "push-bookmarks", "gc"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No merged push bookmarks to delete.
+    [EOF]
+    ");
+}
@@ -1291,6 +1291,8 @@ fn test_help() {
           --no-integrate-operation       Run the command as usual but don't integrate any operations
           --ignore-immutable             Allow rewriting immutable commits
           --at-operation <AT_OPERATION>  Operation to load the repo at [aliases: --at-op]
+          --output <FORMAT>              Output format of listing commands [default: text] [possible
+                                         values: text, json]
           --debug                        Enable debug logging
           --color <WHEN>                 When to colorize output [possible values: always, never, debug,
                                          auto]
@@ -0,0 +1,131 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_json_output_listing_commands() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    work_dir.run_jj(["tag", "set", "-r@-", "v1"]).success();
+
+    // The graph isn't rendered
+    let output = work_dir.run_jj(["--output=json", "log", "-r@-"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"commit":{"commit_id":"d89b4dad6db6f83b49b24e667aa2d125e139a0a8","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"first\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}}}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["log", "--output=json", "-T", "commit_id"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: `--template` cannot be used with `--output json`
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["log", "--output=json", "--stat"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Diff format options cannot be used with `--output json`
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["--output=json", "bookmark", "list"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"bookmark":{"name":"main","target":["d89b4dad6db6f83b49b24e667aa2d125e139a0a8"]}}
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["--output=json", "tag", "list"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"tag":{"name":"v1","target":["d89b4dad6db6f83b49b24e667aa2d125e139a0a8"]}}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["--output=json", "file", "list", "-r@-"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"file":{"path":"file","file_type":"file","executable":false,"conflict":false,"conflict_side_count":1}}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["--output=json", "workspace", "list"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"workspace":{"name":"default","target":{"commit_id":"3dc2485f390e68bacad675379c0675843514ff0c","parents":["d89b4dad6db6f83b49b24e667aa2d125e139a0a8"],"change_id":"rlvkpnrzqnoowoytxnquwvuryrwnrmlp","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}}}}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["--output=json", "evolog", "-r@-", "-n1"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"evolution_entry":{"commit":{"commit_id":"d89b4dad6db6f83b49b24e667aa2d125e139a0a8","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"first\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}},"operation":{"id":"aefd9f7d81774d0343c2cce4ffaa93d947db91479cf0312a0cb0f4a66b4c6b53627f3f1fb22b749887aa18976506dd1c24eaaa02d74b14fc93013500ce925f5f","parents":["2a243492aaf8e005ce85d147aaf77538553f9c4917fb4e5030130c595daedae6331cbdedded08fdfcc54bf57c91e7ab78a9353b6c66fc5cf0c248ab5c847ae30"],"time":{"start":"2001-02-03T04:05:08+07:00","end":"2001-02-03T04:05:08+07:00"},"description":"commit 6b57e33cc56babbeaa6bcd6e2a296236b52ad93c","hostname":"host.example.com","username":"test-username","is_snapshot":false,"workspace_name":"default","attributes":{"args":"jj commit -m first"}}}}
+    [EOF]
+    "#);
+
+    // Other commands don't support JSON output
+    let output = work_dir.run_jj(["--output=json", "bookmark", "set", "main"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: `--output json` cannot be used with `jj bookmark set`
+    Hint: `--output json` is supported by `jj bookmark list`, `jj diff`, `jj evolog`, `jj file list`, `jj log`, `jj operation log`, `jj show`, `jj status`, `jj tag list`, `jj workspace list`
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["--output=json", "op", "log", "-n1"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"operation":{"id":"50d6a2a0a8126e8553477e7a592ae3937ec7c1ec3d3cf96d64986d714f7fefc7b9f6c839b50023bb9265cd1dd4c6be4872eb64ed80a3ebccc3deebba28b9fb3b","parents":["a4e6037e5206acd36a13818a57ff1fac766cea3be04e8de0d9493fadca5f443f76bbb8e8e822ec9cd38b4fd9ff4a4de42f6c3988d78ee4032c4045ed6cfd5446"],"time":{"start":"2001-02-03T04:05:10+07:00","end":"2001-02-03T04:05:10+07:00"},"description":"set tag v1 to commit d89b4dad6db6f83b49b24e667aa2d125e139a0a8","hostname":"host.example.com","username":"test-username","is_snapshot":false,"workspace_name":"default","attributes":{"args":"jj tag set -r@- v1"}}}
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_json_output_status() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(r#"snapshot.auto-track = "~untracked""#);
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "a\n");
+    work_dir.write_file("file2", "b\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "a2\n");
+    work_dir.remove_file("file2");
+    work_dir.write_file("untracked", "c\n");
+
+    let output = work_dir.run_jj(["--output=json", "status"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"status":{"working_copy":{"commit_id":"838ca1de0f59d190e28100cbc7e36511fe9ef2dc","parents":["112d5fc7d2af4ce1c527b75962a4696da33f1a67"],"change_id":"rlvkpnrzqnoowoytxnquwvuryrwnrmlp","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:09+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:09+07:00"}},"parents":[{"commit_id":"112d5fc7d2af4ce1c527b75962a4696da33f1a67","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"}}],"changes":[{"path":"file1","status":"modified","source_path":null},{"path":"file2","status":"removed","source_path":null}],"untracked_paths":["untracked"],"conflicted_paths":[],"conflicted_bookmarks":[],"conflicted_remote_bookmarks":[]}}
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_json_output_ignores_aliases() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "a\n");
+    test_env.add_config(r#"template-aliases.'json(x)' = '"overridden"'"#);
+
+    // Template aliases can't change the records
+    let output = work_dir.run_jj(["--output=json", "file", "list"]);
+    insta::assert_snapshot!(output, @r#"
+    {"version":1,"file":{"path":"file","file_type":"file","executable":false,"conflict":false,"conflict_side_count":1}}
+    [EOF]
+    "#);
+}
@@ -1545,6 +1545,99 @@ fn test_log_word_wrap_with_hyperlinks() {
     ");
 }
 
+#[test]
+fn test_log_html() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir
+        .run_jj(["describe", "-m", "<first> line\nsecond & line"])
+        .success();
+    work_dir.run_jj(["new", "-m", "single line"]).success();
+    test_env.add_config(r#"colors.description = "red""#);
+
+    // Elements don't span the graph lines, and hyperlinks become links
+    let template = r#"
+        hyperlink("https://example.com/?id=" ++ commit_id.short(), change_id.short())
+        ++ " " ++ description
+    "#;
+    let output = work_dir.run_jj(["log", "--format", "html", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+    <meta charset="utf-8">
+    <style>
+    pre.jj a { color: inherit; }
+    </style>
+    </head>
+    <body>
+    <pre class="jj">
+    <span class="log commit node working_copy mutable" style="color:#00cd00;font-weight:bold">@</span>  <a href="https://example.com/?id=72e2d1ce3151"><span class="log commit change_id short" style="color:#cd00cd">kkmpptxzrspx</span></a><span class="log commit"> </span><span class="log commit description" style="color:#cd0000">single line</span>
+    <span class="log commit node mutable">○</span>  <a href="https://example.com/?id=29ed2ab83bae"><span class="log commit change_id short" style="color:#cd00cd">qpvuntsmwlqt</span></a><span class="log commit"> </span><span class="log commit description" style="color:#cd0000">&lt;first&gt; line</span>
+    │  <span class="log commit description" style="color:#cd0000">second &amp; line</span>
+    <span class="log commit node immutable" style="color:#00ffff;font-weight:bold">◆</span>  <a href="https://example.com/?id=000000000000"><span class="log commit change_id short" style="color:#cd00cd">zzzzzzzzzzzz</span></a><span class="log commit"> </span>
+    </pre>
+    </body>
+    </html>
+    [EOF]
+    "#);
+
+    // Without the graph
+    let output = work_dir.run_jj([
+        "log",
+        "--format",
+        "html",
+        "--no-graph",
+        "-r@",
+        "-T",
+        "description",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+    <meta charset="utf-8">
+    <style>
+    pre.jj a { color: inherit; }
+    </style>
+    </head>
+    <body>
+    <pre class="jj">
+    <span class="log commit description" style="color:#cd0000">single line</span>
+    </pre>
+    </body>
+    </html>
+    [EOF]
+    "#);
+
+    // JSON output is requested by --output
+    let output = work_dir.run_jj(["log", "--format", "json"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: invalid value 'json' for '--format <FORMAT>'
+      [possible values: text, html]
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // --format can't be combined with --count
+    let output = work_dir.run_jj(["log", "--format", "html", "--count"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the argument '--format <FORMAT>' cannot be used with '--count'
+
+    Usage: jj log --format <FORMAT> [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_log_diff_stat_width() {
     let test_env = TestEnvironment::default();
@@ -653,6 +653,100 @@ fn test_op_log_configurable() {
     ");
 }
 
+#[test]
+fn test_op_log_signatures() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"
+        ui.show-cryptographic-signatures = true
+        signing.backend = "test"
+        operation.sign = true
+        "#,
+    );
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "signed"]).success();
+
+    let output = work_dir.run_jj(["op", "log", "-n2"]);
+    insta::assert_snapshot!(output, @"
+    @  da5d9b3edc05 test-username@host.example.com default@ 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00 [✓︎]
+    │  describe commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    │  args: jj describe -m signed
+    ○  90267f31f904 test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
+    │  add workspace 'default'
+    [EOF]
+    ");
+    let template = r#"id.short() ++ " " ++ if(signature, signature.status(), "unsigned") ++ "\n""#;
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-n2", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    da5d9b3edc05 good
+    90267f31f904 unsigned
+    [EOF]
+    ");
+
+    // Merging an unsigned concurrent operation is flagged
+    work_dir
+        .run_jj([
+            "describe",
+            "--at-op=@-",
+            "-m",
+            "unsigned",
+            "--config=operation.sign=false",
+        ])
+        .success();
+    let output = work_dir.run_jj(["log", "-r@", "-T", "description"]);
+    insta::assert_snapshot!(output, @"
+    @  signed
+    │
+    ~
+    [EOF]
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    Warning: Operation f18acb352010 is not signed
+    [EOF]
+    ");
+
+    // Unsigned operations behind a signed head of a concurrent branch are
+    // flagged too
+    let base_op_id = work_dir.current_operation_id();
+    work_dir
+        .run_jj([
+            "describe",
+            "-m",
+            "unsigned again",
+            "--config=operation.sign=false",
+        ])
+        .success();
+    work_dir
+        .run_jj(["describe", "-m", "signed again"])
+        .success();
+    work_dir
+        .run_jj(["new", "--at-op", &base_op_id, "-m", "concurrent"])
+        .success();
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-n4", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    6c17941cf70a good
+    dde129e153e2 good
+    37ff7d5d1060 unsigned
+    bab8a722efb5 good
+    [EOF]
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    Rebased 1 descendant commits onto commits rewritten by other operation
+    Warning: Operation 37ff7d5d1060 is not signed
+    [EOF]
+    ");
+
+    // Signing operations requires a signing backend
+    let output = work_dir.run_jj(["log", "--config=signing.backend=none"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Operation signing is enabled, but no signing backend is configured
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_abandon_invalid() {
     let test_env = TestEnvironment::default();
@@ -0,0 +1,248 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::indoc;
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_patch_export() {
+    let test_env = TestEnvironment::default();
+    // The diff settings don't apply to patch files
+    test_env.add_config(
+        r#"
+        diff.git.context = 0
+        diff.git.show-path-prefix = false
+        "#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\nb\n");
+    work_dir.run_jj(["commit", "-m", "Add file"]).success();
+    work_dir.write_file("file", "a\nB\n");
+    work_dir.write_file("new", "new\n");
+    work_dir
+        .run_jj(["commit", "-m", "Change file\n\nWith a body."])
+        .success();
+
+    let output = work_dir.run_jj(["patch", "export", "-r", "::@- ~ root()", "-o", "out"]);
+    insta::assert_snapshot!(output, @"
+    out/0001-Add-file.patch
+    out/0002-Change-file.patch
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("out/0002-Change-file.patch"), @"
+    From 7141d4d7f80e74ecc6890fe40d6323d26a0fcaad Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:09 +0700
+    Subject: [PATCH 2/2] Change file
+
+    With a body.
+    ---
+    diff --git a/file b/file
+    index 422c2b7ab3..55dce135f5 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,2 @@
+     a
+    -b
+    +B
+    diff --git a/new b/new
+    new file mode 100644
+    index 0000000000..3e757656cf
+    --- /dev/null
+    +++ b/new
+    @@ -0,0 +1,1 @@
+    +new
+    ");
+
+    // A single patch isn't numbered
+    let output = work_dir.run_jj(["patch", "export", "-r", "@--", "--stdout"]);
+    insta::assert_snapshot!(output, @"
+    From 5431532543122f7c4a38eb438b2432b3e0d63a1c Mon Sep 17 00:00:00 2001
+    From: Test User <test.user@example.com>
+    Date: Sat, 3 Feb 2001 04:05:08 +0700
+    Subject: [PATCH] Add file
+
+    ---
+    diff --git a/file b/file
+    new file mode 100644
+    index 0000000000..422c2b7ab3
+    --- /dev/null
+    +++ b/file
+    @@ -0,0 +1,2 @@
+    +a
+    +b
+
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_patch_export_apply_roundtrip() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\nb\n");
+    work_dir.run_jj(["commit", "-m", "Add file"]).success();
+    work_dir.write_file("file", "a\nB\n");
+    work_dir.run_jj(["file", "chmod", "x", "file"]).success();
+    work_dir
+        .run_jj([
+            "describe",
+            "-m",
+            "Change file\n\nFrom the body.\n>From a quote.",
+            "--author",
+            "Jörg Other <other@example.com>",
+        ])
+        .success();
+    work_dir.run_jj(["new"]).success();
+
+    let output = work_dir.run_jj(["patch", "export", "-r", "@-", "--stdout"]);
+    insta::assert_snapshot!(output, @"
+    From ec952519db95085662852fe4d494d2e3d9958081 Mon Sep 17 00:00:00 2001
+    From: =?UTF-8?q?J=C3=B6rg_Other?= <other@example.com>
+    Date: Sat, 3 Feb 2001 04:05:09 +0700
+    Subject: [PATCH] Change file
+    MIME-Version: 1.0
+    Content-Type: text/plain; charset=UTF-8
+    Content-Transfer-Encoding: 8bit
+
+    >From the body.
+    >>From a quote.
+    ---
+    diff --git a/file b/file
+    old mode 100644
+    new mode 100755
+    index 422c2b7ab3..55dce135f5
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,2 @@
+     a
+    -b
+    +B
+
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["patch", "export", "-r", "::@- ~ root()", "--stdout"]);
+    let mbox = output.success().stdout.into_raw();
+    std::fs::write(test_env.env_root().join("series.mbox"), mbox).unwrap();
+
+    let output = work_dir.run_jj(["patch", "apply", "../series.mbox", "-o", "root()"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Applied 2 patches:
+      vruxwmqv 5bf893e5 Add file
+      nuwvvtmy 362342e8 Change file
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        r#"separate(" ", author, description.first_line(), diff.summary())"#,
+    ]);
+    insta::assert_snapshot!(output, @"
+    @  Test User <test.user@example.com>
+    ○  Jörg Other <other@example.com> Change file M file
+    ○  Test User <test.user@example.com> Add file A file
+    │ ○  Jörg Other <other@example.com> Change file M file
+    │ ○  Test User <test.user@example.com> Add file A file
+    ├─╯
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "@-", "-T", "description"]);
+    insta::assert_snapshot!(output, @"
+    Change file
+
+    From the body.
+    >From a quote.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git", "-r", "@-"]);
+    insta::assert_snapshot!(output, @"
+    diff --git a/file b/file
+    old mode 100644
+    new mode 100755
+    index 422c2b7ab3..55dce135f5
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,2 @@
+     a
+    -b
+    +B
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_patch_apply_mismatch() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\nb2\nc\n");
+    work_dir.run_jj(["commit", "-m", "base"]).success();
+
+    let patch = indoc! {"
+        From: Patch Author <patch@example.com>
+        Date: Tue, 1 Apr 2025 12:00:00 +0200
+        Subject: [PATCH] Change b
+
+        diff --git a/file b/file
+        --- a/file
+        +++ b/file
+        @@ -1,3 +1,3 @@
+         a
+        -b
+        +b1
+         c
+    "};
+    let output = work_dir.run_jj_with(|cmd| cmd.args(["patch", "apply"]).write_stdin(patch));
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Warning: Patch "Change b" did not apply cleanly to file
+    Applied 1 patches:
+      kkmpptxz ecb8486d (conflict) Change b
+    New conflicts appeared in 1 commits:
+      kkmpptxz ecb8486d (conflict) Change b
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new kkmpptxz
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    "#);
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "@+"]), @"
+    file    2-sided conflict
+    [EOF]
+    ");
+
+    // Invalid patch
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["patch", "apply", "-"])
+            .write_stdin("--- a/file\n+++ b/file\n@@ -1 +1 @@\n")
+    });
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Failed to parse patch standard input
+    Caused by: Hunk ends unexpectedly at line 4
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -14,6 +14,7 @@
 
 use std::path::Path;
 
+use indoc::formatdoc;
 use indoc::indoc;
 use testutils::TestResult;
 
@@ -21,6 +22,8 @@ use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
 use crate::common::create_commit_with_files;
+use crate::common::fake_editor_path;
+use crate::common::to_toml_value;
 
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
@@ -2314,3 +2317,267 @@ fn test_resolve_with_contents_of_side() -> TestResult {
     ");
     Ok(())
 }
+
+#[test]
+fn test_resolve_remembers_resolution() -> TestResult {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.add_config("merge.remember-resolutions = true");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "base\n")]);
+    create_commit_with_files(&work_dir, "a", &["base"], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &["base"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "conflict", &["a", "b"], &[]);
+
+    std::fs::write(&editor_script, "write\nresolution\n")?;
+    work_dir.run_jj(["resolve"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["util", "resolutions", "list"]), @"
+    18c636d867df file (2-sided conflict)
+    [EOF]
+    ");
+
+    // The same conflict is resolved automatically, and the file is reported
+    let output = work_dir.run_jj(["new", "a", "b"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Resolved conflicts in these files by using recorded resolutions:
+      file
+    Working copy  (@) now at: kmkuslsw 2e8d9ed1 (empty) (no description set)
+    Parent commit (@-)      : zsuskuln 45537d53 a | a
+    Parent commit (@-)      : royxmykx 89d1b299 b | b
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @"resolution");
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @"
+    ------- stderr -------
+    Error: No conflicts found at this revision
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Other merges, such as squashing, also use the recorded resolutions
+    let setup_opid = work_dir.current_operation_id();
+    let output = work_dir.run_jj(["squash", "--from=b", "--into=a", "-u"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Rebased 2 descendant commits
+    Resolved conflicts in these files by using recorded resolutions:
+      file
+    Working copy  (@) now at: kmkuslsw a1335bb1 (empty) (no description set)
+    Parent commit (@-)      : zsuskuln 360ab9e9 a | a
+    Parent commit (@-)      : rlvkpnrz 1792382a b base | base
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "show", "-r=a", "file"]), @"
+    resolution
+    [EOF]
+    ");
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+
+    // Once forgotten, the conflict is no longer resolved
+    let output = work_dir.run_jj(["util", "resolutions", "forget", "--all"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Forgot 1 resolutions.
+    [EOF]
+    ");
+    work_dir.run_jj(["new", "a", "b"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @"
+    file    2-sided conflict
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["util", "resolutions", "list"]), @"");
+
+    // Resolutions aren't recorded if disabled
+    std::fs::write(&editor_script, "write\nresolution\n")?;
+    work_dir
+        .run_jj(["resolve", "--config=merge.remember-resolutions=false"])
+        .success();
+    insta::assert_snapshot!(work_dir.run_jj(["util", "resolutions", "list"]), @"");
+    Ok(())
+}
+
+#[test]
+fn test_resolve_with_strategies() -> TestResult {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("file", "a\nbase\nc\n"), ("other", "base\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "a",
+        &["base"],
+        &[("file", "a1\nbase\nc\n"), ("other", "a\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "b",
+        &["base"],
+        &[("file", "a2\nbase\nc2\n"), ("other", "b\n")],
+    );
+    work_dir.run_jj(["rebase", "-r", "b", "-d", "a"]).success();
+    work_dir.run_jj(["new", "b"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @"
+    file     2-sided conflict
+    other    2-sided conflict
+    [EOF]
+    ");
+    let setup_opid = work_dir.current_operation_id();
+
+    // Take a side by number
+    work_dir
+        .run_jj(["resolve", "-r", "b", "--take", "1"])
+        .success();
+    insta::assert_snapshot!(work_dir.read_file("file"), @"
+    a1
+    base
+    c2
+    ");
+    insta::assert_snapshot!(work_dir.read_file("other"), @"a");
+
+    // Take a side by label
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    let output = work_dir.run_jj(["resolve", "-r", "b", "--take", "rebased revision", "file"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Rebased 1 descendant commits
+    Working copy  (@) now at: yostqsxw 48368aa5 (conflict) (empty) (no description set)
+    Parent commit (@-)      : royxmykx 02f808be b | (conflict) b
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    other    2-sided conflict
+    Warning: After this operation, some files at this revision still have conflicts:
+    other    2-sided conflict
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @"
+    a2
+    base
+    c2
+    ");
+
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    let output = work_dir.run_jj(["resolve", "--take", "unknown"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to resolve conflicts
+    Caused by: The conflict at "file" has no side labeled "unknown"
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Take all sides
+    work_dir.run_jj(["resolve", "--union", "file"]).success();
+    insta::assert_snapshot!(work_dir.read_file("file"), @"
+    a1
+    a2
+    base
+    c2
+    ");
+
+    // Run a configured resolver
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    test_env.add_config(formatdoc! {r#"
+        [conflict-resolvers.fake]
+        command = [{editor_path}, "$output"]
+        patterns = ["other"]
+        "#,
+        editor_path = to_toml_value(fake_editor_path()),
+    });
+    let output = work_dir.run_jj(["resolve", "--resolver", "unknown"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: No conflict resolver named "unknown"
+    Hint: Conflict resolvers are configured in the `conflict-resolvers` table.
+    [EOF]
+    [exit status: 1]
+    "#);
+    std::fs::write(&editor_script, "write\nresolved\n")?;
+    let output = work_dir.run_jj(["resolve", "--resolver", "fake"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Working copy  (@) now at: yostqsxw c803e937 (conflict) (no description set)
+    Parent commit (@-)      : royxmykx d9d58cc6 b | (conflict) b
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    New conflicts appeared in 1 commits:
+      yostqsxw c803e937 (conflict) (no description set)
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new yostqsxw
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("other"), @"resolved");
+
+    // Resolver commands aren't run when rebasing by default
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    work_dir
+        .run_jj(["rebase", "-r", "b", "-d", "base"])
+        .success();
+    std::fs::write(&editor_script, "fail")?;
+    work_dir.run_jj(["rebase", "-r", "b", "-d", "a"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "b"]), @"
+    file     2-sided conflict
+    other    2-sided conflict
+    [EOF]
+    ");
+
+    // Resolver commands enabled by `on-rebase` are run when rebasing
+    test_env.add_config("conflict-resolvers.fake.on-rebase = true");
+    work_dir
+        .run_jj(["rebase", "-r", "b", "-d", "base"])
+        .success();
+    std::fs::write(&editor_script, "write\nresolved by rebase\n")?;
+    work_dir.run_jj(["rebase", "-r", "b", "-d", "a"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "b"]), @"
+    file    2-sided conflict
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["file", "show", "-r", "b", "other"]), @"
+    resolved by rebase
+    [EOF]
+    ");
+
+    // Failures of configured resolvers are reported
+    work_dir
+        .run_jj(["rebase", "-r", "b", "-d", "base"])
+        .success();
+    std::fs::write(&editor_script, "fail")?;
+    let output = work_dir.run_jj(["rebase", "-r", "b", "-d", "a"]);
+    insta::with_settings!({filters => vec![("exit code", "exit status")]}, {
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Rebased 1 commits to destination
+        Warning: Failed to resolve conflicts in other by using the configured resolver
+        Caused by: Conflict resolver failed with exit status: 1
+        New conflicts appeared in 1 commits:
+          royxmykx bb986e7d b | (conflict) b
+        Hint: To resolve the conflicts, start by creating a commit on top of
+        the conflicted commit:
+          jj new royxmykx
+        Then use `jj resolve`, or edit the conflict markers in the file directly.
+        Once the conflicts are resolved, you can inspect the result with `jj diff`.
+        Then run `jj squash` to move the resolution into the conflicted commit.
+        [EOF]
+        ");
+    });
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "b"]), @"
+    file     2-sided conflict
+    other    2-sided conflict
+    [EOF]
+    ");
+    Ok(())
+}
@@ -0,0 +1,282 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::thread;
+
+use itertools::Itertools as _;
+use testutils::git;
+use testutils::http::HttpRequest;
+use testutils::http::serve_json;
+
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::create_commit;
+
+/// Serves HTTP requests with the given JSON responses in order.
+fn serve_ok(responses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<HttpRequest>>) {
+    serve_json(responses.into_iter().map(|response| ("200 OK", response)))
+}
+
+/// Formats the received request lines and bodies.
+fn format_requests(requests: &[HttpRequest]) -> String {
+    requests
+        .iter()
+        .map(|request| {
+            let body = str::from_utf8(&request.body).unwrap();
+            format!("{} {body}", request.request_line)
+        })
+        .join("\n")
+}
+
+/// Makes the remote bookmark `name@origin` point to the same commit as the
+/// local bookmark, as if it had been pushed.
+fn mark_pushed(work_dir: &TestWorkDir, name: &str) {
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", name, "-T", "commit_id"]);
+    let commit_id = gix::ObjectId::from_hex(output.stdout.raw().as_bytes()).unwrap();
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    git_repo
+        .reference(
+            format!("refs/remotes/origin/{name}"),
+            commit_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "test",
+        )
+        .unwrap();
+    work_dir.run_jj(["git", "import"]).success();
+    work_dir
+        .run_jj(["bookmark", "track", &format!("{name}@origin")])
+        .success();
+}
+
+#[test]
+fn test_review_push_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["a"]);
+
+    let output = work_dir.run_jj(["review", "push", "-r", "b"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No review provider specified via --provider, and no 'review.provider' was found
+    [EOF]
+    [exit status: 1]
+    ");
+
+    test_env.add_config(r#"review.provider = "github""#);
+    let output = work_dir.run_jj(["review", "push", "-r", "b"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Config error: Value not found for review.github.repository
+    For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    test_env.add_config(r#"review.github.repository = "octocat/hello""#);
+    let output = work_dir.run_jj(["review", "push", "-r", "b"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No base branch specified via --base, and no 'review.base-branch' was found
+    [EOF]
+    [exit status: 1]
+    ");
+
+    test_env.add_config(r#"review.base-branch = "main""#);
+    let output = work_dir.run_jj(["review", "push", "-r", "b | c"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: The revisions to publish do not form a linear stack
+    Hint: Publish each stack separately, or rebase the revisions onto each other
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["review", "push", "-r", "root()"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No revisions to publish.
+    [EOF]
+    ");
+
+    work_dir.run_jj(["new", "b", "c", "-m", "merge"]).success();
+    let output = work_dir.run_jj(["review", "push", "-r", "@"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: The revisions to publish do not form a linear stack
+    Hint: Publish each stack separately, or rebase the revisions onto each other
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir.run_jj(["new", "b"]).success();
+    work_dir.write_file("file", "content");
+    let output = work_dir.run_jj(["review", "push", "-r", "@"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Refusing to publish revision kxryzmorwvtz because it has no description
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_review_push_dry_run() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    git::init_bare(test_env.env_root().join("remote"));
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    test_env.add_config(
+        r#"
+        review.base-branch = "main"
+        review.github.repository = "octocat/hello"
+        "#,
+    );
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+
+    // Without a description, @- is published by default
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj(["review", "push", "--provider=github", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Creating bookmark push-kkmpptxzrspx for revision kkmpptxzrspx
+    Creating bookmark push-mzvwutvlkqwt for revision mzvwutvlkqwt
+    Changes to push to origin:
+      bookmark: push-kkmpptxzrspx [add to 9a0b4679cff3]
+      bookmark: push-mzvwutvlkqwt [add to c56ecbc62213]
+    Dry-run requested, not pushing.
+    Dry-run: Would create pull request for kkmpptxzrspx targeting main
+    Dry-run: Would create pull request for mzvwutvlkqwt targeting push-kkmpptxzrspx
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "review",
+        "push",
+        "--provider=gerrit",
+        "--base=stable",
+        "-r=a",
+        "--dry-run",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Found 1 heads to push to Gerrit (remote 'origin'), target branch 'stable'
+    Dry-run: Would push kkmpptxz 9a0b4679 a | a
+    Dry-run: Would create change for kkmpptxzrspx targeting stable
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_review_push_github() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("GITHUB_TOKEN", "secret");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    git::init_bare(test_env.env_root().join("remote"));
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    create_commit(&work_dir, "a", &[]);
+    work_dir
+        .run_jj(["describe", "-m", "a\n\nbody of a"])
+        .success();
+    create_commit(&work_dir, "b", &["a"]);
+
+    // Pretend that the bookmarks for the changes have been pushed so that the
+    // remote doesn't need to be contacted.
+    for revision in ["a", "b"] {
+        let template = r#""push-" ++ change_id.short()"#;
+        let output = work_dir.run_jj(["log", "--no-graph", "-r", revision, "-T", template]);
+        let name = output.stdout.raw().to_owned();
+        work_dir
+            .run_jj(["bookmark", "set", &name, "-r", revision])
+            .success();
+        mark_pushed(&work_dir, &name);
+    }
+
+    let (url, handle) = serve_ok(vec![
+        r#"{"number": 1, "html_url": "https://github.com/octocat/hello/pull/1"}"#,
+        r#"{"number": 2, "html_url": "https://github.com/octocat/hello/pull/2"}"#,
+    ]);
+    test_env.add_config(format!(
+        r#"
+        review.provider = "github"
+        review.base-branch = "main"
+        review.github.repository = "octocat/hello"
+        review.github.api-url = "{url}"
+        "#
+    ));
+    let output = work_dir.run_jj(["review", "push", "-r", "b"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Bookmark push-kkmpptxzrspx@origin already matches push-kkmpptxzrspx
+    Bookmark push-royxmykxtrkr@origin already matches push-royxmykxtrkr
+    Nothing changed.
+    Created pull request https://github.com/octocat/hello/pull/1 for kkmpptxzrspx targeting main
+    Created pull request https://github.com/octocat/hello/pull/2 for royxmykxtrkr targeting push-kkmpptxzrspx
+    [EOF]
+    ");
+    insta::assert_snapshot!(format_requests(&handle.join().unwrap()), @r#"
+    POST /repos/octocat/hello/pulls HTTP/1.1 {"base":"main","body":"body of a","head":"push-kkmpptxzrspx","title":"a"}
+    POST /repos/octocat/hello/pulls HTTP/1.1 {"base":"push-kkmpptxzrspx","body":"","head":"push-royxmykxtrkr","title":"b"}
+    "#);
+
+    // Pushing the stack again updates the existing pull requests
+    let (url, handle) = serve_ok(vec![
+        r#"{"number": 1, "html_url": "https://github.com/octocat/hello/pull/1"}"#,
+        r#"{"number": 2, "html_url": "https://github.com/octocat/hello/pull/2"}"#,
+    ]);
+    test_env.add_config(format!(r#"review.github.api-url = "{url}""#));
+    let output = work_dir.run_jj(["review", "push", "-r", "b", "--base", "develop"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Bookmark push-kkmpptxzrspx@origin already matches push-kkmpptxzrspx
+    Bookmark push-royxmykxtrkr@origin already matches push-royxmykxtrkr
+    Nothing changed.
+    Updated pull request https://github.com/octocat/hello/pull/1 for kkmpptxzrspx targeting develop
+    Updated pull request https://github.com/octocat/hello/pull/2 for royxmykxtrkr targeting push-kkmpptxzrspx
+    [EOF]
+    ");
+    insta::assert_snapshot!(format_requests(&handle.join().unwrap()), @r#"
+    PATCH /repos/octocat/hello/pulls/1 HTTP/1.1 {"base":"develop"}
+    PATCH /repos/octocat/hello/pulls/2 HTTP/1.1 {"base":"push-kkmpptxzrspx"}
+    "#);
+
+    // Pull requests in another repository are tracked separately
+    let (url, handle) = serve_ok(vec![
+        r#"{"number": 7, "html_url": "https://github.com/octocat/fork/pull/7"}"#,
+    ]);
+    test_env.add_config(format!(
+        r#"
+        review.github.repository = "octocat/fork"
+        review.github.api-url = "{url}"
+        "#
+    ));
+    let output = work_dir.run_jj(["review", "push", "-r", "a"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Bookmark push-kkmpptxzrspx@origin already matches push-kkmpptxzrspx
+    Nothing changed.
+    Created pull request https://github.com/octocat/fork/pull/7 for kkmpptxzrspx targeting main
+    [EOF]
+    ");
+    insta::assert_snapshot!(format_requests(&handle.join().unwrap()), @r#"POST /repos/octocat/fork/pulls HTTP/1.1 {"base":"main","body":"body of a","head":"push-kkmpptxzrspx","title":"a"}"#);
+}
@@ -0,0 +1,188 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_shelve_push_apply_drop() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\n");
+    work_dir.write_file("file2", "a\n");
+    work_dir.run_jj(["commit", "-m", "base"]).success();
+    work_dir.write_file("file1", "b\n");
+    work_dir.write_file("file2", "b\n");
+
+    // Shelve only the changes to file1
+    let output = work_dir.run_jj(["shelve", "push", "-m", "file1 changes", "file1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Created shelf shelf-1: kkmpptxz e8c6b232 (hidden) file1 changes
+    Working copy  (@) now at: rlvkpnrz 721c7096 (no description set)
+    Parent commit (@-)      : qpvuntsm 941e6b4a base
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["shelve", "list"]);
+    insta::assert_snapshot!(output, @"
+    shelf-1: kkmpptxz e8c6b232 (hidden) file1 changes
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @"
+    M file2
+    [EOF]
+    ");
+    // Shelved commits are hidden
+    let output = work_dir.run_jj(["log", "-r", "all()", "-T", "description"]);
+    insta::assert_snapshot!(output, @"
+    @
+    ○  base
+    ◆
+    [EOF]
+    ");
+
+    // Nothing to shelve
+    let output = work_dir.run_jj(["shelve", "push", "nonexistent"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: No matching entries for paths: nonexistent
+    No changes to shelve.
+    [EOF]
+    ");
+
+    // Apply onto a new commit, keeping the shelf
+    work_dir.run_jj(["new", "@-"]).success();
+    let output = work_dir.run_jj(["shelve", "apply", "shelf-1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Applied shelf shelf-1 to vruxwmqv e52cfa10 (no description set)
+    Working copy  (@) now at: vruxwmqv e52cfa10 (no description set)
+    Parent commit (@-)      : qpvuntsm 941e6b4a base
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @"
+    diff --git a/file1 b/file1
+    index 7898192261..6178079822 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,1 @@
+    -a
+    +b
+    [EOF]
+    ");
+
+    // Apply onto a commit without the base, which results in a conflict
+    work_dir.run_jj(["new", "root()"]).success();
+    let output = work_dir.run_jj(["shelve", "apply", "shelf-1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Applied shelf shelf-1 to kpqxywon 021b4164 (conflict) (no description set)
+    Working copy  (@) now at: kpqxywon 021b4164 (conflict) (no description set)
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 1 files, modified 0 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    file1    2-sided conflict including 1 deletion
+    New conflicts appeared in 1 commits:
+      kpqxywon 021b4164 (conflict) (no description set)
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new kpqxywon
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+
+    // Apply and drop
+    work_dir.run_jj(["new", "qpvuntsm"]).success();
+    let output = work_dir.run_jj(["shelve", "apply", "shelf-1", "--drop"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Applied shelf shelf-1 to wqnwkozp ee63943b (no description set)
+    Working copy  (@) now at: wqnwkozp ee63943b (no description set)
+    Parent commit (@-)      : qpvuntsm 941e6b4a base
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["shelve", "list"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["shelve", "apply", "shelf-1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No such shelf: shelf-1
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_shelve_name_and_drop() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\n");
+    work_dir
+        .run_jj(["shelve", "push", "--name", "foo"])
+        .success();
+    work_dir.write_file("file", "b\n");
+    work_dir.run_jj(["shelve", "push"]).success();
+    let output = work_dir.run_jj(["shelve", "list"]);
+    insta::assert_snapshot!(output, @"
+    foo: rlvkpnrz 37af6f25 (hidden) (no description set)
+    shelf-1: kkmpptxz 0ce4c2a1 (hidden) (no description set)
+    [EOF]
+    ");
+
+    // Shelf names must be unique
+    work_dir.write_file("file", "c\n");
+    let output = work_dir.run_jj(["shelve", "push", "--name", "foo"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Shelf already exists: foo
+    Hint: Use `jj shelve drop` to delete the existing shelf.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["shelve", "drop", "foo", "bar"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No such shelf: bar
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["shelve", "drop", "foo", "shelf-1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Dropped 2 shelves.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["shelve", "list"]);
+    insta::assert_snapshot!(output, @"");
+
+    // Dropping a shelf can be undone
+    work_dir.run_jj(["undo"]).success();
+    let output = work_dir.run_jj(["shelve", "list"]);
+    insta::assert_snapshot!(output, @"
+    foo: rlvkpnrz 37af6f25 (hidden) (no description set)
+    shelf-1: kkmpptxz 0ce4c2a1 (hidden) (no description set)
+    [EOF]
+    ");
+}
@@ -76,8 +76,8 @@ backend = "test"
     [EOF]
     ");
 
-    // Commits already always signed, even if they are already signed by me.
-    // https://github.com/jj-vcs/jj/issues/5786
+    // Commits are re-signed if no key is configured to compare the existing
+    // signatures with.
     let output = work_dir.run_jj(["sign", "-r", "..@"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
@@ -100,6 +100,67 @@ backend = "test"
     ");
 }
 
+#[test]
+fn test_sign_skips_commits_signed_by_key() {
+    let test_env = TestEnvironment::default();
+
+    test_env.add_config(
+        r#"
+[ui]
+show-cryptographic-signatures = true
+
+[signing]
+behavior = "keep"
+backend = "test"
+key = "some-key"
+"#,
+    );
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "one"]).success();
+    work_dir.run_jj(["commit", "-m", "two"]).success();
+    work_dir.run_jj(["sign", "-r", "@--"]).success();
+    work_dir
+        .run_jj(["sign", "-r", "@-", "--key", "another-key"])
+        .success();
+
+    // Only the commit signed by another key is re-signed
+    let output = work_dir.run_jj(["sign", "-r", "..@-"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Signed 1 commits:
+      rlvkpnrz 43b76559 (empty) two
+    Skipped 1 commits already signed by the key
+    Rebased 1 descendant commits
+    Working copy  (@) now at: kkmpptxz 3bafa99c (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz 43b76559 (empty) two
+    [EOF]
+    ");
+
+    // Nothing has to be signed again
+    let output = work_dir.run_jj(["sign", "-r", "..@-"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Skipped 2 commits already signed by the key
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Commits signed by the configured key are re-signed with the given key
+    let output = work_dir.run_jj(["sign", "-r", "..@-", "--key", "another-key"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Signed 2 commits:
+      qpvuntsm 75bf20df (empty) one
+      rlvkpnrz 6792862f (empty) two
+    Rebased 1 descendant commits
+    Working copy  (@) now at: kkmpptxz ddd19285 (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz 6792862f (empty) two
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_sign_default_revset() {
     let test_env = TestEnvironment::default();
@@ -182,6 +243,81 @@ key = "some-key"
     ");
 }
 
+#[test]
+fn test_signature_trust() {
+    let test_env = TestEnvironment::default();
+
+    test_env.add_config(
+        r#"
+[signing]
+behavior = "keep"
+backend = "test"
+key = "some-key"
+
+[signing.trust.authors]
+"test.user@example.com" = ["some-key"]
+"#,
+    );
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "one"]).success();
+    work_dir.run_jj(["commit", "-m", "two"]).success();
+    work_dir.run_jj(["commit", "-m", "three"]).success();
+
+    work_dir.run_jj(["sign", "-r", "@-"]).success();
+    work_dir
+        .run_jj(["sign", "-r", "@--", "--key", "another-key"])
+        .success();
+
+    let template = r#"description.first_line() ++ if(signature, " " ++ signature.key() ++ " " ++ signature.trusted()) ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "::@- ~ root()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    three some-key true
+    two another-key false
+    one
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "signed(trusted=true)",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"
+    three some-key true
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "signed(key='another-key')",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"
+    two another-key false
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "signed(trusted=1)"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Failed to parse revset: Expected boolean
+    Caused by:  --> 1:16
+      |
+    1 | signed(trusted=1)
+      |                ^
+      |
+      = Expected boolean
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_warn_about_signing_commits_not_authored_by_me() {
     let test_env = TestEnvironment::default();
@@ -227,6 +227,91 @@ fn test_tag_unknown() {
     ");
 }
 
+#[test]
+fn test_tag_set_annotated() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        signing.backend = "test"
+        signing.key = "tag-key"
+        "#,
+    );
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    let template = r#"
+    separate(" ",
+      name ++ ":",
+      if(tag_message, tag_message.first_line(), "(no message)"),
+      if(tagger, tagger.email()),
+      if(tag_signature, tag_signature.status() ++ " " ++ tag_signature.key()),
+    ) ++ "\n"
+    "#;
+
+    work_dir.run_jj(["tag", "set", "-r@-", "light"]).success();
+    let output = work_dir.run_jj(["tag", "set", "-r@-", "-mRelease 1", "annotated"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Created 1 tags pointing to qpvuntsm b876c5f4 (empty) commit1
+    [EOF]
+    ");
+
+    // The editor is opened if no message is specified
+    std::fs::write(&edit_script, "write\nSigned release\n").unwrap();
+    let output = work_dir.run_jj(["tag", "set", "-r@-", "--sign", "signed"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Created 1 tags pointing to qpvuntsm b876c5f4 (empty) commit1
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["tag", "list", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    annotated: Release 1 test.user@example.com
+    light: (no message)
+    signed: Signed release test.user@example.com good tag-key
+    [EOF]
+    ");
+
+    // Annotated tag objects are preserved by import/export
+    work_dir.run_jj(["git", "export"]).success();
+    work_dir.run_jj(["git", "import"]).success();
+    let output = work_dir.run_jj([
+        "log",
+        "-r@-",
+        "-T",
+        "tags.map(|t| t.tag_message().first_line())",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ◆  Release 1  Signed release
+    │
+    ~
+    [EOF]
+    ");
+
+    // Empty message from the editor
+    std::fs::write(&edit_script, "write\n").unwrap();
+    let output = work_dir.run_jj(["tag", "set", "-r@-", "--edit", "empty"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Aborting because the tag message is empty
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Signing requires a backend
+    test_env.add_config(r#"signing.backend = "none""#);
+    let output = work_dir.run_jj(["tag", "set", "-r@-", "--sign", "-mfoo", "unsigned"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No signing backend configured
+    Hint: For configuring a signing backend, see https://docs.jj-vcs.dev/latest/config/#commit-signing
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_tag_list() {
     let test_env = TestEnvironment::default();
@@ -106,6 +106,50 @@ fn test_gc_operation_log() {
     ");
 }
 
+#[test]
+fn test_gc_keeps_git_notes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m=first"]).success();
+    let commit_id = work_dir
+        .run_jj(["log", "--no-graph", "-r@", "-T=commit_id"])
+        .success()
+        .stdout
+        .into_raw();
+    let output = std::process::Command::new("git")
+        .arg("--git-dir")
+        .arg(work_dir.root().join(".jj/repo/store/git"))
+        .args([
+            "-c",
+            "user.name=Test User",
+            "-c",
+            "user.email=test.user@example.com",
+        ])
+        .args(["notes", "add", "-m", "CI: passed", &commit_id])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    work_dir.run_jj(["git", "import"]).success();
+    let import_op = work_dir.current_operation_id();
+
+    // The notes commit created by copying the notes is only referenced by the
+    // operation log
+    work_dir.run_jj(["describe", "-m=second"]).success();
+    let describe_op = work_dir.current_operation_id();
+    work_dir.run_jj(["op", "restore", &import_op]).success();
+    work_dir.run_jj(["util", "gc", "--expire=now"]).success();
+
+    work_dir.run_jj(["op", "restore", &describe_op]).success();
+    let template = r#"description.first_line() ++ ": " ++ notes"#;
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  second: CI: passed
+    ◆  :
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_shell_completions() {
     #[track_caller]
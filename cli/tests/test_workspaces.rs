@@ -1686,6 +1686,151 @@ fn test_list_workspaces_template_root_unavailable() {
     ");
 }
 
+#[test]
+fn test_workspaces_status() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    let secondary_dir = test_env.work_dir("secondary");
+
+    main_dir.write_file("file", "contents\n");
+    main_dir.run_jj(["commit", "-m", "initial"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+
+    let output = main_dir.run_jj(["workspace", "status"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    default: $TEST_ENV/main
+      Working copy: rlvkpnrz 5eb93c1d (empty) (no description set)
+      Operation: f16ca2d6c82d
+    second: $TEST_ENV/secondary
+      Working copy: pmmvwywv 192d9b6b (empty) (no description set)
+      Operation: f16ca2d6c82d
+    [EOF]
+    ");
+
+    // Make the secondary workspace stale, and modify files in it
+    main_dir
+        .run_jj(["restore", "--into", "second@", "--from", "root()"])
+        .success();
+    secondary_dir.write_file("file", "modified\n");
+    let output = main_dir.run_jj(["workspace", "status"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    default: $TEST_ENV/main
+      Working copy: rlvkpnrz 5eb93c1d (empty) (no description set)
+      Operation: 638fc2f1d917
+    second: $TEST_ENV/secondary (stale) (unsnapshotted changes)
+      Working copy: pmmvwywv 17ac9248 (no description set)
+      Operation: f16ca2d6c82d
+    [EOF]
+    ");
+
+    // Conflicts in the working-copy commit
+    main_dir.write_file("file", "main\n");
+    main_dir.run_jj(["commit", "-m", "main"]).success();
+    main_dir.run_jj(["new", "root()"]).success();
+    main_dir.write_file("file", "other\n");
+    main_dir.run_jj(["new", "@", "subject(main)"]).success();
+    let template = r#"name ++ ": " ++ conflict_count ++ "\n""#;
+    let output = main_dir.run_jj(["workspace", "status", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    default: 1
+    second: 0
+    [EOF]
+    ");
+
+    let template = r#"json(self) ++ "\n""#;
+    let output = main_dir.run_jj(["workspace", "status", "-T", template]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    {"name":"default","target":{"commit_id":"2bff87ea2df97a0f7a3e75d8b8e359621ac2d16a","parents":["3eb9a71a042fa20cc8631c413d0f5c70d5154861","09b1d034b0fda192113478378bd6d7d947519d03"],"change_id":"yostqsxwqrltovqlrlzszywzslusmuup","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:15+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:15+07:00"}},"root":"$TEST_ENV/main","operation_id":"f3c556dedcdb0176b71a07babdc12df1dac856672d53b41b669c1e012bf871db69981ba7930a428d0906af7e82c3df54375e60a00dc71b840265efdf44a99098","stale":false,"dirty":false,"conflict_count":1}
+    {"name":"second","target":{"commit_id":"17ac9248e79e77b52c01418cbd83561da7a0c1dd","parents":["4a8d8ea817a416777a551d7f41d9dfaf5dc2db5d"],"change_id":"pmmvwywvzvvnvnzntqnqknuzpwttyvkr","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:11+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:11+07:00"}},"root":"$TEST_ENV/secondary","operation_id":"f16ca2d6c82dd3ebffbb4f5471634c8c9b498ea6c2ad0e89aa8a0189e6284cd3650d0efdaf90299d9ff50b6b4fbc7aa69cec7500ea8853166ecd942696401a7a","stale":true,"dirty":true,"conflict_count":0}
+    [EOF]
+    "#);
+
+    // The secondary workspace is unavailable
+    std::fs::remove_dir_all(test_env.env_root().join("secondary")).unwrap();
+    let template = r#"separate(" ", name, stale, dirty, conflict_count) ++ "\n""#;
+    let output = main_dir
+        .run_jj(["workspace", "status", "-T", template])
+        .normalize_backslash()
+        .normalize_stdout_with(|s| {
+            s.replace(
+                "The system cannot find the file specified.",
+                "No such file or directory",
+            )
+        });
+    insta::assert_snapshot!(output, @"
+    default false false 1
+    second <Error: Failed to load workspace second: Cannot resolve absolute workspace path: $TEST_ENV/main/.jj/repo/../../../secondary: No such file or directory (os error 2)> <Error: Failed to load workspace second: Cannot resolve absolute workspace path: $TEST_ENV/main/.jj/repo/../../../secondary: No such file or directory (os error 2)> 0
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_workspaces_status_uses_workspace_config() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    let secondary_dir = test_env.work_dir("secondary");
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    secondary_dir.write_file("new-file", "contents\n");
+
+    let template = r#"separate(" ", name, dirty) ++ "\n""#;
+    let output = main_dir.run_jj(["workspace", "status", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    default false
+    second true
+    [EOF]
+    ");
+
+    // The new file isn't auto-tracked in the secondary workspace
+    secondary_dir
+        .run_jj([
+            "config",
+            "set",
+            "--workspace",
+            "--ignore-working-copy",
+            "snapshot.auto-track",
+            "none()",
+        ])
+        .success();
+    let output = main_dir.run_jj(["workspace", "status", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    default false
+    second false
+    [EOF]
+    ");
+
+    // The config of the current workspace doesn't apply to the other
+    secondary_dir
+        .run_jj([
+            "config",
+            "unset",
+            "--workspace",
+            "--ignore-working-copy",
+            "snapshot.auto-track",
+        ])
+        .success();
+    main_dir
+        .run_jj([
+            "config",
+            "set",
+            "--workspace",
+            "snapshot.auto-track",
+            "none()",
+        ])
+        .success();
+    let output = main_dir.run_jj(["workspace", "status", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    default false
+    second true
+    [EOF]
+    ");
+}
+
 /// Test getting the workspace root from primary and secondary workspaces
 #[test]
 fn test_workspaces_root() {
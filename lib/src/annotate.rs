@@ -33,6 +33,7 @@ use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
 use crate::commit::Commit;
+use crate::conflict_resolvers::ConflictResolvers;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::conflicts::ConflictMaterializeOptions;
 use crate::conflicts::MaterializedTreeValue;
@@ -54,6 +55,7 @@ use crate::revset::RevsetExpression;
 use crate::revset::RevsetFilterPredicate;
 use crate::store::Store;
 use crate::tree_merge::MergeOptions;
+use crate::word_tokenizer::WordTokenizers;
 
 /// Annotation results for a specific file
 #[derive(Clone, Debug)]
@@ -456,6 +458,8 @@ async fn get_file_contents(
                 merge: MergeOptions {
                     hunk_level: FileMergeHunkLevel::Line,
                     same_change: SameChange::Accept,
+                    word_tokenizers: WordTokenizers::default(),
+                    conflict_resolvers: ConflictResolvers::default(),
                 },
             };
             Ok(materialize_merge_result_to_bytes(
@@ -39,6 +39,7 @@ use crate::merged_tree::MergedTree;
 use crate::repo::Repo;
 use crate::rewrite::merge_commit_trees;
 use crate::rewrite::merge_commit_trees_no_resolve;
+use crate::signing::SignError;
 use crate::signing::SignResult;
 use crate::signing::Verification;
 use crate::store::Store;
@@ -214,6 +215,26 @@ impl Commit {
             .transpose()
     }
 
+    /// Returns true if the commit has a valid signature made by the given key
+    /// of the configured signing backend.
+    pub fn is_signed_by(&self, key: Option<&str>) -> SignResult<bool> {
+        let Some(sig) = &self.data.secure_sig else {
+            return Ok(false);
+        };
+        self.store.signer().is_signed_by(&sig.data, &sig.sig, key)
+    }
+
+    /// Returns true if the commit is signed by a key trusted for its author.
+    pub fn has_trusted_signature(&self) -> SignResult<bool> {
+        let verification = match self.verification() {
+            Ok(Some(verification)) => verification,
+            Ok(None) | Err(SignError::InvalidSignatureFormat) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        let trust = self.store.signer().trust_store();
+        Ok(trust.is_trusted(&verification, &self.author().email))
+    }
+
     /// A string describing the commit to be used in conflict markers. If a
     /// description is set, it will include the first line of the description.
     pub fn conflict_label(&self) -> String {
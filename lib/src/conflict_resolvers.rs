@@ -0,0 +1,652 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Non-interactive strategies to resolve file conflicts
+//!
+//! A strategy can be requested explicitly (e.g. by `jj resolve --take`), or
+//! be selected per file by the patterns configured in the `conflict-resolvers`
+//! table. Configured resolvers with built-in strategies are applied
+//! automatically when merging trees while rebasing. Resolver commands are only
+//! run after rebasing if enabled by `on-rebase`, and the caller has to run them
+//! explicitly.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use bstr::BString;
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CopyId;
+use crate::backend::TreeValue;
+use crate::config::ConfigGetError;
+use crate::conflict_labels::ConflictLabels;
+use crate::conflicts::extract_as_single_hunk;
+use crate::conflicts::resolve_file_copy_id;
+use crate::conflicts::resolve_file_executable;
+use crate::files;
+use crate::files::MergeResult;
+use crate::fileset;
+use crate::fileset::FilesetAliasesMap;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetParseContext;
+use crate::matchers::Matcher;
+use crate::merge::Merge;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree_builder::MergedTreeBuilder;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathUiConverter;
+use crate::settings::UserSettings;
+use crate::store::Store;
+
+/// Error from resolving a conflict with a [`ResolveStrategy`].
+#[derive(Debug, Error)]
+pub enum ConflictResolverError {
+    /// No side of the conflict matched.
+    #[error("The conflict at \"{path}\" has no side {side}")]
+    SideNotFound {
+        /// Path of the conflicted file.
+        path: String,
+        /// The requested side.
+        side: ConflictSide,
+    },
+    /// The resolver command only supports conflicts with 2 sides.
+    #[error(
+        "The conflict at \"{path}\" has {sides} sides. At most 2 sides are supported by resolver \
+         commands."
+    )]
+    TooManySides {
+        /// Path of the conflicted file.
+        path: String,
+        /// Number of sides of the conflict.
+        sides: usize,
+    },
+    /// The resolver command couldn't be run.
+    #[error("Failed to run conflict resolver")]
+    Io(#[from] io::Error),
+    /// The resolver command exited with an error.
+    #[error("Conflict resolver failed with {exit_status}{}", format_stderr(.stderr))]
+    Command {
+        /// Exit status of the command.
+        exit_status: ExitStatus,
+        /// Error output of the command.
+        stderr: String,
+    },
+    /// Failed to read or write file contents.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+fn format_stderr(stderr: &str) -> String {
+    if stderr.is_empty() {
+        String::new()
+    } else {
+        format!(":\n{stderr}")
+    }
+}
+
+/// Side of a conflict selected by its 1-based number or by its label.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(untagged)]
+pub enum ConflictSide {
+    /// The side at the 1-based position in the conflict.
+    Number(usize),
+    /// The side whose label is the given text, or ends with the given text in
+    /// parentheses, such as "rebase destination".
+    Label(String),
+}
+
+impl ConflictSide {
+    /// Parses a side number, or a label otherwise.
+    pub fn parse(text: &str) -> Self {
+        match text.parse() {
+            Ok(number) => Self::Number(number),
+            Err(_) => Self::Label(text.to_owned()),
+        }
+    }
+
+    /// Returns the 0-based index of the side among the adds of a conflict
+    /// with `num_sides` sides.
+    fn find_index(&self, labels: &ConflictLabels, num_sides: usize) -> Option<usize> {
+        match self {
+            Self::Number(number) => (1..=num_sides).contains(number).then(|| number - 1),
+            Self::Label(label) => (0..num_sides).find(|&index| {
+                labels.get_add(index).is_some_and(|side_label| {
+                    side_label == label
+                        || side_label
+                            .strip_suffix(')')
+                            .and_then(|rest| rest.strip_suffix(label.as_str()))
+                            .is_some_and(|rest| rest.ends_with('('))
+                })
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ConflictSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "#{number}"),
+            Self::Label(label) => write!(f, "labeled \"{label}\""),
+        }
+    }
+}
+
+/// Runs an external command to resolve a conflict with 2 sides.
+///
+/// The arguments `$base`, `$left`, `$right`, and `$output` are substituted
+/// with paths to temporary files, and `$path` with the path of the conflicted
+/// file in the repository. The command is expected to write the resolved
+/// contents to `$output`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolverCommand {
+    program: String,
+    args: Vec<String>,
+}
+
+impl ResolverCommand {
+    /// Creates a resolver which runs `program` with `args`.
+    pub fn new(program: String, args: Vec<String>) -> Self {
+        Self { program, args }
+    }
+
+    /// Runs the command and returns the resolved contents.
+    pub fn run(
+        &self,
+        path: &RepoPath,
+        contents: &Merge<BString>,
+    ) -> Result<BString, ConflictResolverError> {
+        let [left, base, right] = contents.as_slice() else {
+            return Err(ConflictResolverError::TooManySides {
+                path: path.as_internal_file_string().to_owned(),
+                sides: contents.num_sides(),
+            });
+        };
+        let temp_dir = tempfile::tempdir()?;
+        let write_file = |name: &str, content: &[u8]| -> io::Result<PathBuf> {
+            let file_path = temp_dir.path().join(name);
+            fs::write(&file_path, content)?;
+            Ok(file_path)
+        };
+        let paths = [
+            ("base", write_file("base", base)?),
+            ("left", write_file("left", left)?),
+            ("right", write_file("right", right)?),
+            ("output", write_file("output", b"")?),
+        ];
+        let args = self.args.iter().map(|arg| {
+            let arg = arg.replace("$path", path.as_internal_file_string());
+            paths.iter().fold(arg, |arg, (name, file_path)| {
+                arg.replace(&format!("${name}"), &file_path.to_string_lossy())
+            })
+        });
+        let mut command = Command::new(&self.program);
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        tracing::info!(?command, "running conflict resolver command");
+        let output = command.output()?;
+        tracing::info!(?command, ?output.status, "conflict resolver command exited");
+        if !output.status.success() {
+            return Err(ConflictResolverError::Command {
+                exit_status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
+            });
+        }
+        let (_, output_path) = &paths[3];
+        Ok(fs::read(output_path)?.into())
+    }
+}
+
+/// Strategy to resolve a file conflict non-interactively.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ResolveStrategy {
+    /// Takes the contents of a side in each conflicting hunk.
+    TakeSide(ConflictSide),
+    /// Takes the contents of all sides in each conflicting hunk, in order.
+    Union,
+    /// Runs an external command.
+    Command(ResolverCommand),
+}
+
+impl ResolveStrategy {
+    /// Returns true if this strategy runs an external command.
+    pub fn is_command(&self) -> bool {
+        matches!(self, Self::Command(_))
+    }
+}
+
+impl ResolveStrategy {
+    /// Resolves the file conflict at `path`. Returns `None` if the conflict
+    /// isn't a conflict between regular files, or the executable bits
+    /// conflict.
+    pub async fn resolve_file(
+        &self,
+        store: &Store,
+        path: &RepoPath,
+        conflict: &MergedTreeValue,
+        labels: &ConflictLabels,
+    ) -> Result<Option<MergedTreeValue>, ConflictResolverError> {
+        let (Some(file_ids), Some(executable)) = (
+            conflict.to_file_merge(),
+            conflict
+                .to_executable_merge()
+                .and_then(|merge| resolve_file_executable(&merge)),
+        ) else {
+            return Ok(None);
+        };
+        // Number the sides as in conflict markers
+        let (labels, file_ids) = labels.simplify_with(&file_ids);
+        let contents = extract_as_single_hunk(&file_ids, store, path).await?;
+        let options = store.merge_options();
+        let tokenizer = options.word_tokenizers.tokenizer_for_path(path);
+        let content = match self {
+            Self::TakeSide(side) => {
+                let index = side
+                    .find_index(&labels, file_ids.num_sides())
+                    .ok_or_else(|| ConflictResolverError::SideNotFound {
+                        path: path.as_internal_file_string().to_owned(),
+                        side: side.clone(),
+                    })?;
+                match files::merge_hunks_with_tokenizer(&contents, options, tokenizer).merged {
+                    MergeResult::Resolved(content) => content,
+                    MergeResult::Conflict(hunks) => {
+                        let mut content = BString::default();
+                        for hunk in &hunks {
+                            let hunk_content = match hunk.as_resolved() {
+                                Some(hunk_content) => hunk_content,
+                                None => hunk.get_add(index).unwrap(),
+                            };
+                            content.extend_from_slice(hunk_content);
+                        }
+                        content
+                    }
+                }
+            }
+            Self::Union => {
+                match files::merge_hunks_with_tokenizer(&contents, options, tokenizer).merged {
+                    MergeResult::Resolved(content) => content,
+                    MergeResult::Conflict(hunks) => {
+                        let mut content = BString::default();
+                        for hunk in &hunks {
+                            if let Some(hunk_content) = hunk.as_resolved() {
+                                content.extend_from_slice(hunk_content);
+                                continue;
+                            }
+                            for add in hunk.adds().unique() {
+                                content.extend_from_slice(add);
+                                // Keep the sides on separate lines
+                                if !add.is_empty() && !add.ends_with(b"\n") {
+                                    content.push(b'\n');
+                                }
+                            }
+                        }
+                        content
+                    }
+                }
+            }
+            Self::Command(command) => command.run(path, &contents)?,
+        };
+        let id = store.write_file(path, &mut content.as_slice()).await?;
+        let copy_id = conflict
+            .to_copy_id_merge()
+            .and_then(|merge| resolve_file_copy_id(&merge))
+            .unwrap_or_else(CopyId::placeholder);
+        Ok(Some(Merge::normal(TreeValue::File {
+            id,
+            executable,
+            copy_id,
+        })))
+    }
+
+    /// Resolves the file conflicts in `tree` at the given paths. Conflicts
+    /// which aren't between regular files are left unresolved.
+    pub async fn resolve_tree(
+        &self,
+        tree: &MergedTree,
+        matcher: &dyn Matcher,
+    ) -> Result<MergedTree, ConflictResolverError> {
+        let store = tree.store();
+        let mut builder = MergedTreeBuilder::new(tree.clone());
+        for (path, conflict) in tree.conflicts_matching(matcher) {
+            let conflict = conflict?;
+            if let Some(value) = self
+                .resolve_file(store, &path, &conflict, tree.labels())
+                .await?
+            {
+                builder.set_or_remove(path, value);
+            }
+        }
+        Ok(builder.write_tree().await?)
+    }
+}
+
+/// Strategy configured for the files matching the patterns.
+#[derive(Debug)]
+pub struct ConflictResolver {
+    name: String,
+    matcher: Box<dyn Matcher>,
+    strategy: ResolveStrategy,
+    on_rebase: bool,
+}
+
+impl ConflictResolver {
+    /// Matcher of the files to resolve.
+    pub fn matcher(&self) -> &dyn Matcher {
+        self.matcher.as_ref()
+    }
+
+    /// Strategy to resolve the files with.
+    pub fn strategy(&self) -> &ResolveStrategy {
+        &self.strategy
+    }
+
+    /// Whether to apply the resolver to conflicts created by rebasing.
+    pub fn on_rebase(&self) -> bool {
+        self.on_rebase
+    }
+}
+
+/// Conflict resolvers selected by file path.
+#[derive(Clone, Debug, Default)]
+pub struct ConflictResolvers {
+    rules: Arc<[ConflictResolver]>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RawConflictResolverConfig {
+    #[serde(default)]
+    take: Option<ConflictSide>,
+    #[serde(default)]
+    union: bool,
+    #[serde(default)]
+    command: Option<Vec<String>>,
+    #[serde(default)]
+    on_rebase: Option<bool>,
+    patterns: Vec<String>,
+}
+
+impl ConflictResolvers {
+    /// Loads the `conflict-resolvers` table. If more than one resolver matches
+    /// a file, the first one in alphabetical order of names is used.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::new(),
+            base: PathBuf::new(),
+        };
+        let context = FilesetParseContext {
+            aliases_map: &FilesetAliasesMap::new(),
+            path_converter: &path_converter,
+        };
+        let rules: Vec<_> = settings
+            .table_keys("conflict-resolvers")
+            .sorted()
+            .map(|name| {
+                let config_error =
+                    |error: Box<dyn std::error::Error + Send + Sync>| ConfigGetError::Type {
+                        name: format!("conflict-resolvers.{name}"),
+                        error,
+                        source_path: None,
+                    };
+                let config: RawConflictResolverConfig =
+                    settings.get(["conflict-resolvers", name])?;
+                let strategy = match (config.take, config.union, config.command) {
+                    (Some(side), false, None) => ResolveStrategy::TakeSide(side),
+                    (None, true, None) => ResolveStrategy::Union,
+                    (None, false, Some(command)) => {
+                        let Some((program, args)) = command.split_first() else {
+                            return Err(config_error("Command must not be empty".into()));
+                        };
+                        ResolveStrategy::Command(ResolverCommand::new(
+                            program.clone(),
+                            args.to_vec(),
+                        ))
+                    }
+                    _ => {
+                        return Err(config_error(
+                            "Exactly one of `take`, `union`, or `command` must be set".into(),
+                        ));
+                    }
+                };
+                let mut diagnostics = FilesetDiagnostics::new();
+                let expressions: Vec<_> = config
+                    .patterns
+                    .iter()
+                    .map(|pattern| fileset::parse(&mut diagnostics, pattern, &context))
+                    .try_collect()
+                    .map_err(|err| config_error(err.into()))?;
+                // Commands are run only if explicitly enabled
+                let on_rebase = config.on_rebase.unwrap_or(!strategy.is_command());
+                Ok(ConflictResolver {
+                    name: name.to_owned(),
+                    matcher: FilesetExpression::union_all(expressions).to_matcher(),
+                    strategy,
+                    on_rebase,
+                })
+            })
+            .try_collect()?;
+        Ok(Self {
+            rules: rules.into(),
+        })
+    }
+
+    /// Returns true if no resolvers are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the resolver named `name`.
+    pub fn get(&self, name: &str) -> Option<&ConflictResolver> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// Returns true if any resolver command is enabled on rebase.
+    pub fn has_commands_on_rebase(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.on_rebase && rule.strategy.is_command())
+    }
+
+    /// Returns the resolver for the file at `path`.
+    pub fn resolver_for_path(&self, path: &RepoPath) -> Option<&ConflictResolver> {
+        self.rules.iter().find(|rule| rule.matcher.matches(path))
+    }
+
+    /// Returns the strategy for the file at `path`.
+    pub fn strategy_for_path(&self, path: &RepoPath) -> Option<&ResolveStrategy> {
+        self.resolver_for_path(path).map(|rule| &rule.strategy)
+    }
+
+    /// Applies the built-in strategies of the resolvers enabled on rebase to
+    /// the conflicted files in `tree`. Files which can't be resolved are left
+    /// conflicted. Returns the new tree and the errors of the resolvers which
+    /// failed.
+    pub async fn resolve_tree(
+        &self,
+        tree: MergedTree,
+    ) -> Result<(MergedTree, Vec<(RepoPathBuf, ConflictResolverError)>), BackendError> {
+        self.resolve_tree_with(tree, false).await
+    }
+
+    /// Runs the commands of the resolvers enabled on rebase for the conflicted
+    /// files in `tree`. Returns the new tree and the errors of the commands
+    /// which failed.
+    pub async fn run_commands(
+        &self,
+        tree: MergedTree,
+    ) -> Result<(MergedTree, Vec<(RepoPathBuf, ConflictResolverError)>), BackendError> {
+        self.resolve_tree_with(tree, true).await
+    }
+
+    async fn resolve_tree_with(
+        &self,
+        tree: MergedTree,
+        commands: bool,
+    ) -> Result<(MergedTree, Vec<(RepoPathBuf, ConflictResolverError)>), BackendError> {
+        let mut failures = vec![];
+        if self.is_empty() || !tree.has_conflict() {
+            return Ok((tree, failures));
+        }
+        let store = tree.store();
+        let mut builder = MergedTreeBuilder::new(tree.clone());
+        let mut num_resolved = 0;
+        for (path, conflict) in tree.conflicts() {
+            let conflict = conflict?;
+            let Some(rule) = self.resolver_for_path(&path) else {
+                continue;
+            };
+            if !rule.on_rebase || rule.strategy.is_command() != commands {
+                continue;
+            }
+            match rule
+                .strategy
+                .resolve_file(store, &path, &conflict, tree.labels())
+                .await
+            {
+                Ok(Some(value)) => {
+                    builder.set_or_remove(path, value);
+                    num_resolved += 1;
+                }
+                Ok(None) => {}
+                Err(ConflictResolverError::Backend(err)) => return Err(err),
+                Err(err) => {
+                    tracing::warn!(?err, ?path, "failed to resolve conflict");
+                    failures.push((path, err));
+                }
+            }
+        }
+        if num_resolved == 0 {
+            return Ok((tree, failures));
+        }
+        Ok((builder.write_tree().await?, failures))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::ConfigLayer;
+    use crate::config::ConfigSource;
+    use crate::config::StackedConfig;
+
+    fn settings_from_text(text: &str) -> UserSettings {
+        let mut config = StackedConfig::with_defaults();
+        config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+        UserSettings::from_config(config).unwrap()
+    }
+
+    fn labels(labels: &[&str]) -> ConflictLabels {
+        ConflictLabels::from_vec(labels.iter().map(|&label| label.to_owned()).collect())
+    }
+
+    #[test]
+    fn test_conflict_side_parse() {
+        assert_eq!(ConflictSide::parse("2"), ConflictSide::Number(2));
+        assert_eq!(
+            ConflictSide::parse("rebased revision"),
+            ConflictSide::Label("rebased revision".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_conflict_side_find_index() {
+        let labels = labels(&[
+            "abc 1234 \"dest\" (rebase destination)",
+            "def 5678 \"parent\" (parents of rebased revision)",
+            "ghi 9abc \"rebased\" (rebased revision)",
+        ]);
+        let find = |side: &str| ConflictSide::parse(side).find_index(&labels, 2);
+        assert_eq!(find("1"), Some(0));
+        assert_eq!(find("2"), Some(1));
+        assert_eq!(find("0"), None);
+        assert_eq!(find("3"), None);
+        assert_eq!(find("rebase destination"), Some(0));
+        assert_eq!(find("rebased revision"), Some(1));
+        assert_eq!(find("ghi 9abc \"rebased\" (rebased revision)"), Some(1));
+        assert_eq!(find("revision"), None);
+        assert_eq!(find("parents of rebased revision"), None);
+
+        // Default labels aren't matched
+        assert_eq!(
+            ConflictSide::parse("side #1").find_index(&ConflictLabels::unlabeled(), 2),
+            None
+        );
+    }
+
+    #[test]
+    fn test_conflict_resolvers_from_settings() {
+        let settings = settings_from_text(indoc! {r#"
+            [conflict-resolvers.b-lock]
+            take = 2
+            patterns = ["glob:'**/*.lock'"]
+            [conflict-resolvers.a-changelog]
+            union = true
+            patterns = ["CHANGELOG.md", "glob:'**/*.lock'"]
+            [conflict-resolvers.json]
+            command = ["merge-json", "$left", "$base", "$right", "$output"]
+            patterns = ["glob:'**/*.json'"]
+        "#});
+        let resolvers = ConflictResolvers::from_settings(&settings).unwrap();
+        let strategy_for =
+            |path| resolvers.strategy_for_path(RepoPath::from_internal_string(path).unwrap());
+        assert_eq!(strategy_for("CHANGELOG.md"), Some(&ResolveStrategy::Union));
+        // The first resolver in alphabetical order is used
+        assert_eq!(strategy_for("Cargo.lock"), Some(&ResolveStrategy::Union));
+        assert_eq!(
+            strategy_for("a/b.json"),
+            Some(&ResolveStrategy::Command(ResolverCommand::new(
+                "merge-json".to_owned(),
+                ["$left", "$base", "$right", "$output"]
+                    .map(str::to_owned)
+                    .to_vec()
+            )))
+        );
+        assert_eq!(strategy_for("README.md"), None);
+        assert_eq!(
+            resolvers.get("b-lock").map(|resolver| resolver.strategy()),
+            Some(&ResolveStrategy::TakeSide(ConflictSide::Number(2)))
+        );
+        assert!(resolvers.get("unknown").is_none());
+        // Commands aren't run on rebase by default
+        assert!(resolvers.get("a-changelog").unwrap().on_rebase());
+        assert!(!resolvers.get("json").unwrap().on_rebase());
+        assert!(!resolvers.has_commands_on_rebase());
+
+        let settings = settings_from_text(indoc! {r#"
+            [conflict-resolvers.both]
+            take = "rebase destination"
+            union = true
+            patterns = ["all()"]
+        "#});
+        insta::assert_snapshot!(
+            ConflictResolvers::from_settings(&settings).unwrap_err(),
+            @"Invalid type or value for conflict-resolvers.both"
+        );
+    }
+}
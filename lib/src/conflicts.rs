@@ -48,6 +48,7 @@ use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::files;
 use crate::files::MergeResult;
+use crate::files::TokenizedMergeResult;
 use crate::merge::Diff;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
@@ -55,6 +56,8 @@ use crate::merge::SameChange;
 use crate::repo_path::RepoPath;
 use crate::store::Store;
 use crate::tree_merge::MergeOptions;
+use crate::word_tokenizer::DefaultWordTokenizer;
+use crate::word_tokenizer::WordTokenizer;
 
 /// Minimum length of conflict markers.
 pub const MIN_CONFLICT_MARKER_LEN: usize = 7;
@@ -299,6 +302,18 @@ pub fn resolve_file_executable(merge: &Merge<Option<bool>>) -> Option<bool> {
     }
 }
 
+/// Resolves the copy id of a file conflict. Returns `None` if the copy id can't
+/// be determined.
+pub fn resolve_file_copy_id(merge: &Merge<Option<CopyId>>) -> Option<CopyId> {
+    let resolved = merge.resolve_trivial(SameChange::Accept)?;
+    if let Some(copy_id) = resolved {
+        Some(copy_id.clone())
+    } else {
+        // Like resolve_file_executable(), pick the old copy id if unambiguous.
+        merge.removes().flatten().all_equal_value().ok().cloned()
+    }
+}
+
 /// Describes what style should be used when materializing conflicts.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -477,8 +492,28 @@ pub fn materialize_merge_result_to_bytes<T: AsRef<[u8]>>(
     labels: &ConflictLabels,
     options: &ConflictMaterializeOptions,
 ) -> BString {
-    let merge_result = files::merge_hunks(single_hunk, &options.merge);
-    match merge_result {
+    materialize_merge_result_to_bytes_with_tokenizer(
+        single_hunk,
+        labels,
+        options,
+        &DefaultWordTokenizer,
+    )
+    .merged
+}
+
+/// Like [`materialize_merge_result_to_bytes()`], but word-level hunks are split
+/// into words by the `tokenizer`.
+pub fn materialize_merge_result_to_bytes_with_tokenizer<T: AsRef<[u8]>>(
+    single_hunk: &Merge<T>,
+    labels: &ConflictLabels,
+    options: &ConflictMaterializeOptions,
+    tokenizer: &dyn WordTokenizer,
+) -> TokenizedMergeResult<BString> {
+    let TokenizedMergeResult {
+        merged,
+        tokenizer_errors,
+    } = files::merge_hunks_with_tokenizer(single_hunk, &options.merge, tokenizer);
+    let merged = match merged {
         MergeResult::Resolved(content) => content,
         MergeResult::Conflict(hunks) => {
             let marker_len = options
@@ -496,6 +531,10 @@ pub fn materialize_merge_result_to_bytes<T: AsRef<[u8]>>(
             .expect("writing to an in-memory buffer should never fail");
             output.into()
         }
+    };
+    TokenizedMergeResult {
+        merged,
+        tokenizer_errors,
     }
 }
 
@@ -1057,7 +1096,9 @@ pub async fn update_from_content(
     let simplified_file_ids = file_ids.simplify();
 
     let old_contents = extract_as_single_hunk(&simplified_file_ids, store, path).await?;
-    let old_hunks = files::merge_hunks(&old_contents, store.merge_options());
+    let options = store.merge_options();
+    let tokenizer = options.word_tokenizers.tokenizer_for_path(path);
+    let old_hunks = files::merge_hunks_with_tokenizer(&old_contents, options, tokenizer).merged;
 
     // Parse conflicts from the new content using the arity of the simplified
     // conflicts.
@@ -1133,7 +1174,9 @@ mod tests {
     use test_case::test_matrix;
 
     use super::*;
+    use crate::conflict_resolvers::ConflictResolvers;
     use crate::files::FileMergeHunkLevel;
+    use crate::word_tokenizer::WordTokenizers;
 
     #[test]
     fn test_resolve_file_executable() {
@@ -1363,6 +1406,8 @@ mod tests {
             merge: MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                word_tokenizers: WordTokenizers::default(),
+                conflict_resolvers: ConflictResolvers::default(),
             },
         };
         let actual_contents = String::from_utf8(
@@ -16,6 +16,7 @@ use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::collections::binary_heap;
+use std::fmt::Debug;
 use std::iter;
 use std::mem;
 use std::ops::Range;
@@ -35,6 +36,7 @@ use super::entry::LocalCommitPosition;
 use super::entry::SmallGlobalCommitPositionsVec;
 use super::entry::SmallLocalCommitPositionsVec;
 use super::mutable::MutableCommitIndexSegment;
+use super::patch_id_table::PatchIdTable;
 use super::readonly::ReadonlyCommitIndexSegment;
 use super::rev_walk::filter_slice_by_range;
 use super::revset_engine;
@@ -524,16 +526,19 @@ enum CompositeCommitIndexSegment {
 pub(super) struct CompositeIndex {
     commits: CompositeCommitIndexSegment,
     changed_paths: CompositeChangedPathIndex,
+    patch_ids: PatchIdTable,
 }
 
 impl CompositeIndex {
     pub(super) fn from_readonly(
         commits: Arc<ReadonlyCommitIndexSegment>,
         changed_paths: CompositeChangedPathIndex,
+        patch_ids: PatchIdTable,
     ) -> Self {
         Self {
             commits: CompositeCommitIndexSegment::Readonly(commits),
             changed_paths,
+            patch_ids,
         }
     }
 
@@ -544,9 +549,15 @@ impl CompositeIndex {
         Self {
             commits: CompositeCommitIndexSegment::Mutable(commits),
             changed_paths,
+            patch_ids: PatchIdTable::default(),
         }
     }
 
+    /// Shares the patch id table with another index.
+    pub(super) fn with_patch_ids(self, patch_ids: PatchIdTable) -> Self {
+        Self { patch_ids, ..self }
+    }
+
     pub(super) fn into_mutable(
         self,
     ) -> Option<(Box<MutableCommitIndexSegment>, CompositeChangedPathIndex)> {
@@ -585,6 +596,10 @@ impl CompositeIndex {
     pub(super) fn changed_paths_mut(&mut self) -> &mut CompositeChangedPathIndex {
         &mut self.changed_paths
     }
+
+    pub(super) fn patch_ids(&self) -> &PatchIdTable {
+        &self.patch_ids
+    }
 }
 
 impl AsCompositeIndex for CompositeIndex {
@@ -24,6 +24,7 @@ mod changed_path;
 mod composite;
 mod entry;
 mod mutable;
+mod patch_id_table;
 mod readonly;
 mod rev_walk;
 mod rev_walk_queue;
@@ -66,6 +67,7 @@ mod tests {
     use super::entry::GlobalCommitPosition;
     use super::entry::SmallGlobalCommitPositionsVec;
     use super::mutable::MutableCommitIndexSegment;
+    use super::patch_id_table::PatchIdTable;
     use super::readonly::ReadonlyCommitIndexSegment;
     use super::*;
     use crate::backend::ChangeId;
@@ -100,7 +102,8 @@ mod tests {
 
     fn get_commit_index_stats(commits: &Arc<ReadonlyCommitIndexSegment>) -> IndexStats {
         let changed_paths = CompositeChangedPathIndex::null();
-        let index = DefaultReadonlyIndex::from_segment(commits.clone(), changed_paths);
+        let patch_ids = PatchIdTable::default();
+        let index = DefaultReadonlyIndex::from_segment(commits.clone(), changed_paths, patch_ids);
         index.stats()
     }
 
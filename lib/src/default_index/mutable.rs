@@ -472,7 +472,8 @@ impl DefaultMutableIndex {
         ));
         let mut changed_paths = parent_index.changed_paths().clone();
         changed_paths.make_mutable();
-        Self(CompositeIndex::from_mutable(commits, changed_paths))
+        let patch_ids = parent_index.as_composite().patch_ids().clone();
+        Self(CompositeIndex::from_mutable(commits, changed_paths).with_patch_ids(patch_ids))
     }
 
     pub(super) fn into_segment(
@@ -0,0 +1,338 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Table of patch ids of commits, saved next to the index segments.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use blake2::Blake2b512;
+use digest::Digest as _;
+use itertools::Itertools as _;
+use tempfile::NamedTempFile;
+
+use crate::backend::CommitId;
+use crate::file_util;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::hex_util;
+use crate::object_id::ObjectId as _;
+use crate::patch_id::PatchId;
+
+/// Current format version of the patch id files.
+const FILE_FORMAT_VERSION: u32 = 1;
+
+/// Length of the file names, which are hashes of the file contents.
+const FILE_NAME_LENGTH: usize = 64 * 2;
+
+/// Number of files above which all entries are squashed into a single file.
+const MAX_NUM_FILES: usize = 16;
+
+/// Patch ids of commits, computed on demand by the revset engine.
+///
+/// Since a patch id only depends on the commit, the table isn't associated
+/// with operations. Newly computed patch ids are saved to a new file in the
+/// table directory, and all files are loaded by the next process. The table is
+/// shared by the indexes loaded from the same index store.
+#[derive(Clone, Default)]
+pub(super) struct PatchIdTable(Arc<PatchIdTableInner>);
+
+#[derive(Default)]
+struct PatchIdTableInner {
+    /// Directory of the table files, or `None` if the table is kept in memory.
+    dir: Option<PathBuf>,
+    state: Mutex<PatchIdTableState>,
+}
+
+#[derive(Default)]
+struct PatchIdTableState {
+    /// Names of the files loaded from the directory, or `None` if the files
+    /// haven't been loaded yet.
+    file_names: Option<Vec<String>>,
+    entries: HashMap<CommitId, Option<PatchId>>,
+    unsaved_ids: Vec<CommitId>,
+}
+
+impl PatchIdTable {
+    /// Creates a table which is saved in `dir`. The files are loaded lazily.
+    pub(super) fn new(dir: PathBuf) -> Self {
+        Self(Arc::new(PatchIdTableInner {
+            dir: Some(dir),
+            state: Mutex::default(),
+        }))
+    }
+
+    /// Returns the patch id of the commit, or `None` if it hasn't been
+    /// computed yet. The inner `None` means that the commit is empty.
+    pub(super) fn get(&self, commit_id: &CommitId) -> Option<Option<PatchId>> {
+        let mut state = self.0.state.lock().unwrap();
+        self.ensure_loaded(&mut state);
+        state.entries.get(commit_id).cloned()
+    }
+
+    /// Adds the patch id of the commit. It isn't written to disk until
+    /// [`Self::save()`] is called.
+    pub(super) fn insert(&self, commit_id: CommitId, patch_id: Option<PatchId>) {
+        let mut state = self.0.state.lock().unwrap();
+        if state.entries.insert(commit_id.clone(), patch_id).is_none() {
+            state.unsaved_ids.push(commit_id);
+        }
+    }
+
+    /// Writes the patch ids added since the last save to a new file. If there
+    /// are too many files, all entries are squashed into a single file.
+    pub(super) fn save(&self) -> Result<(), PathError> {
+        let Some(dir) = &self.0.dir else {
+            return Ok(());
+        };
+        let mut state = self.0.state.lock().unwrap();
+        if state.unsaved_ids.is_empty() {
+            return Ok(());
+        }
+        self.ensure_loaded(&mut state);
+        let state = &mut *state;
+        let file_names = state.file_names.get_or_insert_default();
+        let squash = file_names.len() >= MAX_NUM_FILES;
+        let ids = if squash {
+            state.entries.keys().collect_vec()
+        } else {
+            state.unsaved_ids.iter().collect_vec()
+        };
+        let entries = ids
+            .into_iter()
+            .sorted_unstable()
+            .map(|id| (id, state.entries[id].as_ref()))
+            .collect_vec();
+        file_util::create_or_reuse_dir(dir).context(dir)?;
+        let new_file_name = save_entries_in(dir, &entries)?;
+        if squash {
+            for name in file_names.drain(..) {
+                // If raced, the file may have been removed by another process.
+                let path = dir.join(name);
+                match fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err).context(&path),
+                }
+            }
+        }
+        if !file_names.contains(&new_file_name) {
+            file_names.push(new_file_name);
+        }
+        state.unsaved_ids.clear();
+        Ok(())
+    }
+
+    fn ensure_loaded(&self, state: &mut PatchIdTableState) {
+        let Some(dir) = &self.0.dir else {
+            return;
+        };
+        if state.file_names.is_some() {
+            return;
+        }
+        let mut file_names = vec![];
+        // Patch ids are a cache, so the table is reset if it can't be read.
+        match load_entries_from(dir, &mut file_names, &mut state.entries) {
+            Ok(()) => {}
+            Err(err) if err.source.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => tracing::warn!(?err, "failed to load patch ids"),
+        }
+        state.file_names = Some(file_names);
+    }
+}
+
+impl Debug for PatchIdTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatchIdTable")
+            .field("dir", &self.0.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+fn load_entries_from(
+    dir: &Path,
+    file_names: &mut Vec<String>,
+    entries: &mut HashMap<CommitId, Option<PatchId>>,
+) -> Result<(), PathError> {
+    for entry in dir.read_dir().context(dir)? {
+        let entry = entry.context(dir)?;
+        let Some(name) = entry
+            .file_name()
+            .into_string()
+            .ok()
+            .filter(|name| name.len() == FILE_NAME_LENGTH)
+        else {
+            // Skip temporary files
+            continue;
+        };
+        let path = entry.path();
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            // Removed by another process
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err).context(&path),
+        };
+        match parse_entries(&data) {
+            Some(new_entries) => entries.extend(new_entries),
+            // The file will be removed when the table is squashed
+            None => tracing::warn!(?path, "ignoring corrupt or outdated patch id file"),
+        }
+        file_names.push(name);
+    }
+    Ok(())
+}
+
+fn save_entries_in(
+    dir: &Path,
+    entries: &[(&CommitId, Option<&PatchId>)],
+) -> Result<String, PathError> {
+    let commit_id_length = entries.first().map_or(0, |(id, _)| id.as_bytes().len());
+    let mut buf = vec![];
+    buf.extend(FILE_FORMAT_VERSION.to_le_bytes());
+    buf.extend(u32::try_from(commit_id_length).unwrap().to_le_bytes());
+    buf.extend(u32::try_from(entries.len()).unwrap().to_le_bytes());
+    for (commit_id, patch_id) in entries {
+        assert_eq!(commit_id.as_bytes().len(), commit_id_length);
+        buf.extend(commit_id.as_bytes());
+        if let Some(patch_id) = patch_id {
+            buf.push(1);
+            buf.extend(patch_id.as_bytes());
+        } else {
+            buf.push(0);
+        }
+    }
+
+    let mut hasher = Blake2b512::new();
+    hasher.update(&buf);
+    let file_name = hex_util::encode_hex(&hasher.finalize());
+    let file_path = dir.join(&file_name);
+    let mut file = NamedTempFile::new_in(dir).context(dir)?;
+    file.as_file_mut().write_all(&buf).context(file.path())?;
+    persist_content_addressed_temp_file(file, &file_path).context(&file_path)?;
+    Ok(file_name)
+}
+
+/// Parses the entries of a patch id file. Returns `None` if the file is
+/// corrupt or has a different format version.
+fn parse_entries(data: &[u8]) -> Option<Vec<(CommitId, Option<PatchId>)>> {
+    let mut rest = data;
+    let mut take = |len: usize| {
+        let (head, tail) = rest.split_at_checked(len)?;
+        rest = tail;
+        Some(head)
+    };
+    let mut read_u32 = || Some(u32::from_le_bytes(take(4)?.try_into().unwrap()));
+    if read_u32()? != FILE_FORMAT_VERSION {
+        return None;
+    }
+    let commit_id_length = read_u32()? as usize;
+    let num_entries = read_u32()?;
+    let entries = (0..num_entries)
+        .map(|_| {
+            let commit_id = CommitId::from_bytes(take(commit_id_length)?);
+            let patch_id = match take(1)? {
+                [0] => None,
+                [1] => Some(PatchId::from_bytes(take(PatchId::LENGTH)?)),
+                _ => return None,
+            };
+            Some((commit_id, patch_id))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    rest.is_empty().then_some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_temp_dir;
+
+    fn commit_id(byte: u8) -> CommitId {
+        CommitId::new(vec![byte; 20])
+    }
+
+    fn patch_id(byte: u8) -> PatchId {
+        PatchId::from_bytes(&[byte; PatchId::LENGTH])
+    }
+
+    #[test]
+    fn test_patch_id_table_save_and_load() {
+        let temp_dir = new_temp_dir();
+        let dir = temp_dir.path().join("patch_ids");
+
+        let table = PatchIdTable::new(dir.clone());
+        assert_eq!(table.get(&commit_id(1)), None);
+        table.insert(commit_id(1), Some(patch_id(1)));
+        table.insert(commit_id(2), None);
+        table.save().unwrap();
+        table.insert(commit_id(3), Some(patch_id(3)));
+        table.save().unwrap();
+        // Nothing to save
+        table.save().unwrap();
+        assert_eq!(dir.read_dir().unwrap().count(), 2);
+
+        // Saved entries are loaded by a new table
+        let table = PatchIdTable::new(dir.clone());
+        assert_eq!(table.get(&commit_id(1)), Some(Some(patch_id(1))));
+        assert_eq!(table.get(&commit_id(2)), Some(None));
+        assert_eq!(table.get(&commit_id(3)), Some(Some(patch_id(3))));
+        assert_eq!(table.get(&commit_id(4)), None);
+
+        // Corrupt files are ignored
+        fs::write(dir.join("0".repeat(FILE_NAME_LENGTH)), b"corrupt").unwrap();
+        let table = PatchIdTable::new(dir.clone());
+        assert_eq!(table.get(&commit_id(1)), Some(Some(patch_id(1))));
+    }
+
+    #[test]
+    fn test_patch_id_table_squash() {
+        let temp_dir = new_temp_dir();
+        let dir = temp_dir.path().join("patch_ids");
+
+        let table = PatchIdTable::new(dir.clone());
+        for i in 0..MAX_NUM_FILES {
+            table.insert(commit_id(i as u8), Some(patch_id(i as u8)));
+            table.save().unwrap();
+        }
+        assert_eq!(dir.read_dir().unwrap().count(), MAX_NUM_FILES);
+        table.insert(commit_id(0xff), None);
+        table.save().unwrap();
+        assert_eq!(dir.read_dir().unwrap().count(), 1);
+
+        let table = PatchIdTable::new(dir.clone());
+        for i in 0..MAX_NUM_FILES {
+            assert_eq!(
+                table.get(&commit_id(i as u8)),
+                Some(Some(patch_id(i as u8)))
+            );
+        }
+        assert_eq!(table.get(&commit_id(0xff)), Some(None));
+    }
+
+    #[test]
+    fn test_patch_id_table_in_memory() {
+        let table = PatchIdTable::default();
+        table.insert(commit_id(1), None);
+        table.save().unwrap();
+        assert_eq!(table.get(&commit_id(1)), Some(None));
+    }
+}
@@ -42,6 +42,7 @@ use super::entry::LocalCommitPosition;
 use super::entry::SmallGlobalCommitPositionsVec;
 use super::entry::SmallLocalCommitPositionsVec;
 use super::mutable::DefaultMutableIndex;
+use super::patch_id_table::PatchIdTable;
 use super::revset_engine;
 use super::revset_engine::RevsetImpl;
 use crate::backend::ChangeId;
@@ -616,8 +617,13 @@ impl DefaultReadonlyIndex {
     pub(super) fn from_segment(
         commits: Arc<ReadonlyCommitIndexSegment>,
         changed_paths: CompositeChangedPathIndex,
+        patch_ids: PatchIdTable,
     ) -> Self {
-        Self(CompositeIndex::from_readonly(commits, changed_paths))
+        Self(CompositeIndex::from_readonly(
+            commits,
+            changed_paths,
+            patch_ids,
+        ))
     }
 
     pub(super) fn readonly_commits(&self) -> &Arc<ReadonlyCommitIndexSegment> {
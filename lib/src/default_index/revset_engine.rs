@@ -58,6 +58,8 @@ use crate::merge::Merge;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId as _;
 use crate::object_id::PrefixResolution;
+use crate::patch_id::PatchId;
+use crate::patch_id::commit_patch_id_without_repo;
 use crate::repo_path::RepoPath;
 use crate::revset::DiffMatchSide;
 use crate::revset::GENERATION_RANGE_FULL;
@@ -68,6 +70,8 @@ use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetFilterPredicate;
 use crate::rewrite;
+use crate::signing::SigStatus;
+use crate::signing::SignError;
 use crate::store::Store;
 use crate::str_util::StringMatcher;
 use crate::tree_merge::MergeOptions;
@@ -921,6 +925,32 @@ impl EvaluationContext<'_> {
                     .collect_vec();
                 Ok(Box::new(EagerRevset { positions }))
             }
+            ResolvedExpression::Equivalent { sources, domain } => {
+                let sources_set = self.evaluate(sources)?;
+                let patch_ids: HashSet<PatchId> = sources_set
+                    .positions()
+                    .attach(index)
+                    .map(|pos| patch_id_at(&self.store, index, pos?))
+                    .filter_map_ok(|patch_id| patch_id)
+                    .try_collect()?;
+                let mut positions = vec![];
+                if !patch_ids.is_empty() {
+                    let domain_set = self.evaluate(domain)?;
+                    for pos in domain_set.positions().attach(index) {
+                        let pos = pos?;
+                        if let Some(patch_id) = patch_id_at(&self.store, index, pos)?
+                            && patch_ids.contains(&patch_id)
+                        {
+                            positions.push(pos);
+                        }
+                    }
+                }
+                // Patch ids are a cache, so failing to save them isn't fatal.
+                if let Err(err) = index.patch_ids().save() {
+                    tracing::warn!(?err, "failed to save patch ids");
+                }
+                Ok(Box::new(EagerRevset { positions }))
+            }
             ResolvedExpression::Heads(candidates) => {
                 let candidate_set = self.evaluate(candidates)?;
                 let positions = index
@@ -1375,6 +1405,35 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.is_signed())
         }),
+        RevsetFilterPredicate::SignedBy { key, trusted } => {
+            let key_matcher = key.as_ref().map(|key| Rc::new(key.to_matcher()));
+            let trusted = *trusted;
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.commits().entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let verification = match commit.verification() {
+                    Ok(Some(verification)) => verification,
+                    Ok(None) | Err(SignError::InvalidSignatureFormat) => return Ok(false),
+                    Err(err) => return Err(RevsetEvaluationError::Other(err.into())),
+                };
+                if verification.status == SigStatus::Bad {
+                    return Ok(false);
+                }
+                if let Some(matcher) = &key_matcher
+                    && !verification
+                        .key
+                        .as_ref()
+                        .is_some_and(|key| matcher.is_match(key))
+                {
+                    return Ok(false);
+                }
+                if let Some(trusted) = trusted {
+                    let trust = store.signer().trust_store();
+                    return Ok(trust.is_trusted(&verification, &commit.author().email) == trusted);
+                }
+                Ok(true)
+            })
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1421,6 +1480,24 @@ async fn has_diff_from_parent(
     Ok(false)
 }
 
+/// Returns the patch id of the commit at `pos`, computing it if it isn't in
+/// the patch id table yet. Computed patch ids are written to the table
+/// directory when the table is saved.
+fn patch_id_at(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    pos: GlobalCommitPosition,
+) -> Result<Option<PatchId>, RevsetEvaluationError> {
+    let commit_id = index.commits().entry_by_pos(pos).commit_id();
+    if let Some(patch_id) = index.patch_ids().get(&commit_id) {
+        return Ok(patch_id);
+    }
+    let commit = store.get_commit(&commit_id)?;
+    let patch_id = commit_patch_id_without_repo(store, index, &commit).block_on()?;
+    index.patch_ids().insert(commit_id, patch_id.clone());
+    Ok(patch_id)
+}
+
 async fn matches_diff_from_parent(
     store: &Arc<Store>,
     index: &CompositeIndex,
@@ -1529,12 +1606,14 @@ mod tests {
     use indoc::indoc;
 
     use super::*;
+    use crate::conflict_resolvers::ConflictResolvers;
     use crate::default_index::DefaultMutableIndex;
     use crate::default_index::readonly::FieldLengths;
     use crate::files::FileMergeHunkLevel;
     use crate::merge::SameChange;
     use crate::str_util::StringPattern;
     use crate::tests::TestResult;
+    use crate::word_tokenizer::WordTokenizers;
 
     const TEST_FIELD_LENGTHS: FieldLengths = FieldLengths {
         commit_id: 3,
@@ -1922,6 +2001,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let diff = |needle: &str| {
             let matcher = StringPattern::substring(needle).to_matcher();
@@ -1987,6 +2068,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let diff = |needle: &str| {
             let matcher = StringPattern::substring(needle).to_matcher();
@@ -2077,6 +2160,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let diff = |needle: &str| {
             let matcher = StringPattern::substring(needle).to_matcher();
@@ -2151,6 +2236,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let diff = |needle: &str| {
             let matcher = StringPattern::substring(needle).to_matcher();
@@ -40,6 +40,7 @@ use super::composite::AsCompositeIndex as _;
 use super::composite::CommitIndexSegmentId;
 use super::entry::GlobalCommitPosition;
 use super::mutable::DefaultMutableIndex;
+use super::patch_id_table::PatchIdTable;
 use super::readonly::DefaultReadonlyIndex;
 use super::readonly::FieldLengths;
 use super::readonly::ReadonlyCommitIndexSegment;
@@ -104,6 +105,7 @@ pub enum DefaultIndexStoreError {
 #[derive(Debug)]
 pub struct DefaultIndexStore {
     dir: PathBuf,
+    patch_ids: PatchIdTable,
 }
 
 impl DefaultIndexStore {
@@ -114,6 +116,7 @@ impl DefaultIndexStore {
     pub fn init(dir: &Path) -> Result<Self, DefaultIndexStoreInitError> {
         let store = Self {
             dir: dir.to_owned(),
+            patch_ids: PatchIdTable::new(dir.join("patch_ids")),
         };
         store.ensure_base_dirs()?;
         Ok(store)
@@ -122,6 +125,7 @@ impl DefaultIndexStore {
     pub fn load(dir: &Path) -> Self {
         Self {
             dir: dir.to_owned(),
+            patch_ids: PatchIdTable::new(dir.join("patch_ids")),
         }
     }
 
@@ -218,7 +222,11 @@ impl DefaultIndexStore {
         } else {
             CompositeChangedPathIndex::null()
         };
-        Ok(DefaultReadonlyIndex::from_segment(commits, changed_paths))
+        Ok(DefaultReadonlyIndex::from_segment(
+            commits,
+            changed_paths,
+            self.patch_ids.clone(),
+        ))
     }
 
     /// Rebuilds index for the given `operation`.
@@ -470,7 +478,8 @@ impl DefaultIndexStore {
 
         // Update the operation link to point to the new segments
         let commits = index.readonly_commits().clone();
-        let index = DefaultReadonlyIndex::from_segment(commits, new_changed_paths);
+        let index =
+            DefaultReadonlyIndex::from_segment(commits, new_changed_paths, self.patch_ids.clone());
         self.associate_index_with_operation(&index, op_id)
             .map_err(|source| DefaultIndexStoreError::AssociateIndex {
                 op_id: op_id.to_owned(),
@@ -497,7 +506,8 @@ impl DefaultIndexStore {
         changed_paths
             .save_in(&self.changed_path_segments_dir())
             .map_err(DefaultIndexStoreError::SaveIndex)?;
-        let index = DefaultReadonlyIndex::from_segment(commits, changed_paths);
+        let index =
+            DefaultReadonlyIndex::from_segment(commits, changed_paths, self.patch_ids.clone());
         self.associate_index_with_operation(&index, op_id)
             .map_err(|source| DefaultIndexStoreError::AssociateIndex {
                 op_id: op_id.to_owned(),
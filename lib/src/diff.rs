@@ -29,6 +29,8 @@ use itertools::Itertools as _;
 use smallvec::SmallVec;
 use smallvec::smallvec;
 
+use crate::word_tokenizer::ContentsWordTokenizer;
+
 pub fn find_line_ranges(text: &[u8]) -> Vec<Range<usize>> {
     text.split_inclusive(|b| *b == b'\n')
         .scan(0, |total, line| {
@@ -767,6 +769,45 @@ impl<'input> ContentDiff<'input> {
         diff
     }
 
+    /// Compares the given ranges of the contents of the `tokenizer` word by
+    /// word. Each input is a pair of the index of the content and the byte
+    /// range in it.
+    ///
+    /// The inputs are usually the ranges of a changed hunk of a line-by-line
+    /// diff of the whole contents.
+    pub fn by_word_in_contents(
+        tokenizer: &ContentsWordTokenizer<'_, 'input>,
+        inputs: impl IntoIterator<Item = (usize, Range<usize>)>,
+    ) -> Self {
+        let inputs = inputs.into_iter().collect_vec();
+        let mut input_texts = inputs
+            .iter()
+            .map(|(index, range)| BStr::new(&tokenizer.content(*index)[range.clone()]));
+        let base_input = input_texts.next().expect("inputs must not be empty");
+        let other_inputs: SmallVec<[&BStr; 1]> = input_texts.collect();
+        // No need to tokenize if one of the inputs is empty. See for_tokenizer().
+        let token_ranges = if inputs.iter().any(|(_, range)| range.is_empty()) {
+            vec![vec![]; inputs.len()]
+        } else {
+            inputs
+                .into_iter()
+                .map(|(index, range)| tokenizer.find_word_ranges_in(index, range))
+                .collect()
+        };
+        let mut diff = Self::with_inputs_and_token_ranges(
+            base_input,
+            other_inputs,
+            &token_ranges[0],
+            &token_ranges[1..],
+            CompareBytesExactly,
+        );
+        diff.refine_changed_regions(
+            |text| tokenizer.find_nonword_ranges(text),
+            CompareBytesExactly,
+        );
+        diff
+    }
+
     /// Returns iterator over matching and different texts.
     pub fn hunks(&self) -> DiffHunkIterator<'_, 'input> {
         let ranges = self.hunk_ranges();
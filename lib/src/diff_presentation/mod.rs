@@ -34,6 +34,7 @@ use crate::diff::find_line_ranges;
 use crate::merge::Diff;
 use crate::repo_path::RepoPath;
 
+pub mod moved;
 pub mod unified;
 // TODO: colored_diffs utils should also be moved from `jj_cli::diff_utils` to
 // here.
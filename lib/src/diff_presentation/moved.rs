@@ -0,0 +1,325 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detection of lines moved within or between files
+//!
+//! A block of consecutive removed lines is considered moved if the same block
+//! of lines is added somewhere else in the diff. This is similar to Git's
+//! `--color-moved=plain` mode.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use bstr::BStr;
+use bstr::BString;
+
+use super::LineCompareMode;
+use super::diff_by_line;
+use crate::diff::DiffHunkKind;
+use crate::merge::Diff;
+
+/// Minimum number of alphanumeric characters in a moved block. Shorter blocks
+/// such as lone braces are likely to match by coincidence.
+pub const MIN_MOVED_BLOCK_ALNUM_COUNT: usize = 20;
+
+/// Maximum number of removed occurrences of a line for it to start a moved
+/// block. Lines repeated more often, such as blank lines, would otherwise make
+/// the detection quadratic. Such lines can still be part of a block.
+pub const MAX_MOVED_BLOCK_START_CANDIDATES: usize = 16;
+
+/// Removed and added lines of a file paired with their 0-based line indices.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChangedLines<T> {
+    pub removed: Vec<(usize, T)>,
+    pub added: Vec<(usize, T)>,
+}
+
+impl<'content> ChangedLines<&'content [u8]> {
+    /// Collects lines which differ between the `contents`.
+    pub fn from_contents(contents: Diff<&'content BStr>, options: &LineCompareMode) -> Self {
+        let mut changed = Self {
+            removed: vec![],
+            added: vec![],
+        };
+        let mut left_index = 0;
+        let mut right_index = 0;
+        let diff = diff_by_line(contents.into_array(), options);
+        for hunk in diff.hunks() {
+            let [left, right] = hunk.contents[..].try_into().unwrap();
+            let left_lines = left.split_inclusive(|b| *b == b'\n');
+            let right_lines = right.split_inclusive(|b| *b == b'\n');
+            match hunk.kind {
+                DiffHunkKind::Matching => {
+                    left_index += left_lines.count();
+                    right_index += right_lines.count();
+                }
+                DiffHunkKind::Different => {
+                    for line in left_lines {
+                        changed.removed.push((left_index, line));
+                        left_index += 1;
+                    }
+                    for line in right_lines {
+                        changed.added.push((right_index, line));
+                        right_index += 1;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Copies the lines so they can outlive the contents.
+    pub fn to_owned_lines(&self) -> ChangedLines<BString> {
+        let to_owned = |lines: &[(usize, &[u8])]| {
+            lines
+                .iter()
+                .map(|&(index, line)| (index, BString::from(line)))
+                .collect()
+        };
+        ChangedLines {
+            removed: to_owned(&self.removed),
+            added: to_owned(&self.added),
+        }
+    }
+}
+
+/// Indices of moved lines in the old and new contents of a file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MovedLines {
+    pub removed: BTreeSet<usize>,
+    pub added: BTreeSet<usize>,
+}
+
+impl MovedLines {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+}
+
+/// Finds blocks of lines which are removed from one place and added to
+/// another. `files` may include any number of files, and the blocks are
+/// detected across all of them. Returns moved lines per file.
+pub fn find_moved_lines<T: AsRef<[u8]>>(files: &[ChangedLines<T>]) -> Vec<MovedLines> {
+    let mut moved = vec![MovedLines::default(); files.len()];
+    // Removed line -> positions in the files
+    let mut removed_positions: HashMap<&[u8], Vec<(usize, usize)>> = HashMap::new();
+    for (file_index, file) in files.iter().enumerate() {
+        for (pos, (_, line)) in file.removed.iter().enumerate() {
+            removed_positions
+                .entry(line_key(line.as_ref()))
+                .or_default()
+                .push((file_index, pos));
+        }
+    }
+
+    for (file_index, file) in files.iter().enumerate() {
+        let mut added_pos = 0;
+        while added_pos < file.added.len() {
+            let (_, line) = &file.added[added_pos];
+            let candidates = removed_positions
+                .get(line_key(line.as_ref()))
+                .filter(|positions| positions.len() <= MAX_MOVED_BLOCK_START_CANDIDATES)
+                .map_or(&[][..], |positions| positions);
+            // Pick the longest block starting at the current added line.
+            let best = candidates
+                .iter()
+                .filter(|&&(removed_file, removed_pos)| {
+                    let (index, _) = files[removed_file].removed[removed_pos];
+                    !moved[removed_file].removed.contains(&index)
+                })
+                .map(|&(removed_file, removed_pos)| {
+                    let len = matching_block_len(
+                        &files[removed_file].removed[removed_pos..],
+                        &file.added[added_pos..],
+                        &moved[removed_file].removed,
+                    );
+                    (removed_file, removed_pos, len)
+                })
+                .max_by_key(|&(_, _, len)| len);
+            let Some((removed_file, removed_pos, len)) = best else {
+                added_pos += 1;
+                continue;
+            };
+            let block = &file.added[added_pos..added_pos + len];
+            let alnum_count: usize = block
+                .iter()
+                .map(|(_, line)| {
+                    line.as_ref()
+                        .iter()
+                        .filter(|b| b.is_ascii_alphanumeric())
+                        .count()
+                })
+                .sum();
+            if alnum_count < MIN_MOVED_BLOCK_ALNUM_COUNT {
+                added_pos += 1;
+                continue;
+            }
+            let removed_block = &files[removed_file].removed[removed_pos..removed_pos + len];
+            moved[removed_file]
+                .removed
+                .extend(removed_block.iter().map(|&(index, _)| index));
+            moved[file_index]
+                .added
+                .extend(block.iter().map(|&(index, _)| index));
+            added_pos += len;
+        }
+    }
+    moved
+}
+
+/// Returns the line without the trailing newline so that the last line
+/// matches regardless of whether the file ends with a newline.
+fn line_key(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+/// Returns the number of lines in the consecutive block shared by the
+/// `removed` and `added` lines.
+fn matching_block_len<T: AsRef<[u8]>>(
+    removed: &[(usize, T)],
+    added: &[(usize, T)],
+    claimed: &BTreeSet<usize>,
+) -> usize {
+    let (Some((removed_start, _)), Some((added_start, _))) = (removed.first(), added.first())
+    else {
+        return 0;
+    };
+    removed
+        .iter()
+        .zip(added)
+        .enumerate()
+        .take_while(
+            |&(offset, ((removed_index, removed_line), (added_index, added_line)))| {
+                // Blocks must be contiguous in both the old and new contents.
+                *removed_index == removed_start + offset
+                    && *added_index == added_start + offset
+                    && !claimed.contains(removed_index)
+                    && line_key(removed_line.as_ref()) == line_key(added_line.as_ref())
+            },
+        )
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn changed_lines<'a>(left: &'a str, right: &'a str) -> ChangedLines<&'a [u8]> {
+        ChangedLines::from_contents(
+            Diff::new(left.as_ref(), right.as_ref()),
+            &LineCompareMode::Exact,
+        )
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let changed = changed_lines("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(changed.removed, [(1, &b"b\n"[..])]);
+        assert_eq!(changed.added, [(1, &b"B\n"[..]), (3, &b"d\n"[..])]);
+    }
+
+    #[test]
+    fn test_moved_within_file() {
+        let left = indoc! {"
+            fn first() {
+                do_something_important();
+            }
+            fn second() {
+                do_something();
+                do_something_else();
+            }
+        "};
+        let right = indoc! {"
+            fn second() {
+                do_something();
+                do_something_else();
+            }
+            fn first() {
+                do_something_important();
+            }
+        "};
+        let moved = find_moved_lines(&[changed_lines(left, right)]);
+        assert_eq!(moved[0].removed, BTreeSet::from([0, 1, 2]));
+        assert_eq!(moved[0].added, BTreeSet::from([4, 5, 6]));
+    }
+
+    #[test]
+    fn test_moved_between_files() {
+        let block = indoc! {"
+            fn moved() {
+                let value = compute();
+            }
+        "};
+        let left_file = changed_lines(block, "");
+        let right = format!("x\n{block}y\n");
+        let right_file = changed_lines("x\n", &right);
+        let moved = find_moved_lines(&[left_file, right_file]);
+        assert_eq!(moved[0].removed, BTreeSet::from([0, 1, 2]));
+        assert!(moved[0].added.is_empty());
+        assert!(moved[1].removed.is_empty());
+        assert_eq!(moved[1].added, BTreeSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_moved_block_too_short() {
+        // Lone braces and short lines match by coincidence
+        let moved = find_moved_lines(&[changed_lines("}\nab\n", "x\n}\nab\n")]);
+        assert!(moved[0].is_empty());
+        let moved = find_moved_lines(&[changed_lines("a\n}\n", "}\na\n")]);
+        assert!(moved[0].is_empty());
+    }
+
+    #[test]
+    fn test_moved_block_claimed_once() {
+        // A removed block is matched to only one of the added copies
+        let line = "a line which is long enough\n";
+        let moved = find_moved_lines(&[changed_lines(
+            &format!("{line}x\n"),
+            &format!("x\n{line}y\n{line}"),
+        )]);
+        assert_eq!(moved[0].removed, BTreeSet::from([0]));
+        assert_eq!(moved[0].added, BTreeSet::from([1]));
+    }
+
+    #[test]
+    fn test_moved_block_repeated_lines() {
+        // Repeated lines don't start a block, but can be inside one
+        let block = "fn moved() {\n    let value = compute();\n}\n";
+        let repeated = "}\n".repeat(MAX_MOVED_BLOCK_START_CANDIDATES + 1);
+        let left = format!("{repeated}{block}");
+        let moved = find_moved_lines(&[changed_lines(&left, ""), changed_lines("", block)]);
+        let block_start = MAX_MOVED_BLOCK_START_CANDIDATES + 1;
+        assert_eq!(
+            moved[0].removed,
+            BTreeSet::from([block_start, block_start + 1, block_start + 2])
+        );
+        assert_eq!(moved[1].added, BTreeSet::from([0, 1, 2]));
+
+        // Large repetitive contents are handled in reasonable time
+        let left = "let a = 1;\nlet b = 2;\n".repeat(10000);
+        let right = format!("x\n{left}").replace("let b", "let c");
+        let moved = find_moved_lines(&[changed_lines(&left, &right)]);
+        assert!(moved[0].is_empty());
+    }
+
+    #[test]
+    fn test_edited_lines_not_moved() {
+        let left = "let value = compute_something();\n";
+        let right = "let value = compute_something_else();\n";
+        let moved = find_moved_lines(&[changed_lines(left, right)]);
+        assert!(moved[0].is_empty());
+    }
+}
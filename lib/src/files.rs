@@ -18,6 +18,7 @@ use std::borrow::Borrow;
 use std::collections::VecDeque;
 use std::iter;
 use std::mem;
+use std::ops::Range;
 
 use bstr::BStr;
 use bstr::BString;
@@ -30,6 +31,10 @@ use crate::diff::DiffHunkKind;
 use crate::merge::Merge;
 use crate::merge::SameChange;
 use crate::tree_merge::MergeOptions;
+use crate::word_tokenizer::ContentsWordTokenizer;
+use crate::word_tokenizer::DefaultWordTokenizer;
+use crate::word_tokenizer::WordTokenizer;
+use crate::word_tokenizer::WordTokenizerError;
 
 /// A diff line which may contain small hunks originating from both sides.
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -282,11 +287,31 @@ pub enum MergeResult {
     Conflict(Vec<Merge<BString>>),
 }
 
+/// Result of a merge which split words by a [`WordTokenizer`].
+#[derive(Debug)]
+pub struct TokenizedMergeResult<T> {
+    /// The merged content.
+    pub merged: T,
+    /// Errors of the tokenizer, for which words were split by the default
+    /// tokenizer instead.
+    pub tokenizer_errors: Vec<WordTokenizerError>,
+}
+
 /// Splits `inputs` into hunks, resolves trivial merge conflicts for each.
 ///
 /// Returns either fully-resolved content or list of partially-resolved hunks.
 pub fn merge_hunks<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> MergeResult {
-    merge_inner(inputs, options)
+    merge_inner(inputs, options, &DefaultWordTokenizer).merged
+}
+
+/// Like [`merge_hunks()`], but word-level hunks are split into words by the
+/// `tokenizer`.
+pub fn merge_hunks_with_tokenizer<T: AsRef<[u8]>>(
+    inputs: &Merge<T>,
+    options: &MergeOptions,
+    tokenizer: &dyn WordTokenizer,
+) -> TokenizedMergeResult<MergeResult> {
+    merge_inner(inputs, options, tokenizer)
 }
 
 /// Splits `inputs` into hunks, resolves trivial merge conflicts for each, then
@@ -295,7 +320,17 @@ pub fn merge_hunks<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) ->
 /// The returned merge object is either fully resolved or conflict having the
 /// same number of terms as the `inputs`.
 pub fn merge<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> Merge<BString> {
-    merge_inner(inputs, options)
+    merge_inner(inputs, options, &DefaultWordTokenizer).merged
+}
+
+/// Like [`merge()`], but word-level hunks are split into words by the
+/// `tokenizer`.
+pub fn merge_with_tokenizer<T: AsRef<[u8]>>(
+    inputs: &Merge<T>,
+    options: &MergeOptions,
+    tokenizer: &dyn WordTokenizer,
+) -> TokenizedMergeResult<Merge<BString>> {
+    merge_inner(inputs, options, tokenizer)
 }
 
 /// Splits `inputs` into hunks, attempts to resolve trivial merge conflicts for
@@ -303,10 +338,24 @@ pub fn merge<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> Merge
 ///
 /// If all input hunks can be merged successfully, returns the merged content.
 pub fn try_merge<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> Option<BString> {
-    merge_inner(inputs, options)
+    merge_inner(inputs, options, &DefaultWordTokenizer).merged
 }
 
-fn merge_inner<'input, T, B>(inputs: &'input Merge<T>, options: &MergeOptions) -> B
+/// Like [`try_merge()`], but word-level hunks are split into words by the
+/// `tokenizer`.
+pub fn try_merge_with_tokenizer<T: AsRef<[u8]>>(
+    inputs: &Merge<T>,
+    options: &MergeOptions,
+    tokenizer: &dyn WordTokenizer,
+) -> TokenizedMergeResult<Option<BString>> {
+    merge_inner(inputs, options, tokenizer)
+}
+
+fn merge_inner<'input, T, B>(
+    inputs: &'input Merge<T>,
+    options: &MergeOptions,
+    tokenizer: &dyn WordTokenizer,
+) -> TokenizedMergeResult<B>
 where
     T: AsRef<[u8]>,
     B: FromMergeHunks<'input>,
@@ -318,20 +367,42 @@ where
     let diff = ContentDiff::by_line(inputs.removes().chain(inputs.adds()));
     let hunks = resolve_diff_hunks(&diff, num_diffs, options.same_change);
     match options.hunk_level {
-        FileMergeHunkLevel::Line => B::from_hunks(hunks.map(MergeHunk::Borrowed)),
+        FileMergeHunkLevel::Line => TokenizedMergeResult {
+            merged: B::from_hunks(hunks.map(MergeHunk::Borrowed)),
+            tokenizer_errors: vec![],
+        },
         FileMergeHunkLevel::Word => {
-            B::from_hunks(hunks.map(|h| merge_hunk_by_word(h, options.same_change)))
+            // The contents are indexed in the same order as the diff inputs.
+            let tokenizer = ContentsWordTokenizer::new(
+                tokenizer,
+                inputs.removes().chain(inputs.adds()).map(AsRef::as_ref),
+            );
+            let hunks = iter::zip(hunks, diff.hunk_ranges());
+            let merged = B::from_hunks(
+                hunks.map(|(h, ranges)| merge_hunk_by_word(h, &ranges.ranges, &tokenizer, options)),
+            );
+            TokenizedMergeResult {
+                merged,
+                tokenizer_errors: tokenizer.into_errors(),
+            }
         }
     }
 }
 
-fn merge_hunk_by_word(inputs: Merge<&BStr>, same_change: SameChange) -> MergeHunk<'_> {
+/// Merges the `inputs` hunk word by word. The `ranges` are the positions of
+/// the hunk in the contents of the `tokenizer`.
+fn merge_hunk_by_word<'input>(
+    inputs: Merge<&'input BStr>,
+    ranges: &[Range<usize>],
+    tokenizer: &ContentsWordTokenizer<'_, 'input>,
+    options: &MergeOptions,
+) -> MergeHunk<'input> {
     if inputs.is_resolved() {
         return MergeHunk::Borrowed(inputs);
     }
     let num_diffs = inputs.removes().len();
-    let diff = ContentDiff::by_word(inputs.removes().chain(inputs.adds()));
-    let hunks = resolve_diff_hunks(&diff, num_diffs, same_change);
+    let diff = ContentDiff::by_word_in_contents(tokenizer, ranges.iter().cloned().enumerate());
+    let hunks = resolve_diff_hunks(&diff, num_diffs, options.same_change);
     // We could instead use collect_merged() to return partially-merged hunk.
     // This would be more consistent with the line-based merge function, but
     // might produce surprising results. Partially-merged conflicts would be
@@ -492,6 +563,8 @@ mod tests {
     use indoc::indoc;
 
     use super::*;
+    use crate::conflict_resolvers::ConflictResolvers;
+    use crate::word_tokenizer::WordTokenizers;
 
     fn conflict<const N: usize>(values: [&[u8]; N]) -> Merge<BString> {
         Merge::from_vec(values.map(hunk).to_vec())
@@ -855,6 +928,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let merge_hunks = |inputs: &_| merge_hunks(inputs, &options);
         // Unchanged and empty on all sides
@@ -971,6 +1046,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let merge_hunks = |inputs: &_| merge_hunks(inputs, &options);
         let merge = |inputs: &_| merge(inputs, &options);
@@ -1083,6 +1160,8 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Word,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         };
         let merge = |inputs: &_| merge(inputs, &options);
         // No context line in between, but "\n" is a context word
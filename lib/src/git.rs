@@ -16,18 +16,22 @@
 
 use std::borrow::Borrow;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::btree_map;
 use std::default::Default;
 use std::ffi::OsString;
 use std::fs::File;
 use std::iter;
 use std::num::NonZeroU32;
 use std::path::PathBuf;
+use std::pin::pin;
 use std::sync::Arc;
 
 use bstr::BStr;
 use bstr::BString;
+use bstr::ByteSlice as _;
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
 use gix::refspec::Instruction;
@@ -37,12 +41,18 @@ use thiserror::Error;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
+use crate::backend::SecureSig;
+use crate::backend::Signature;
+use crate::backend::SigningFn;
+use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::git_backend::GitBackend;
+use crate::git_backend::signature_from_git;
+use crate::git_backend::signature_to_git;
 use crate::git_subprocess::GitFetchStatus;
 pub use crate::git_subprocess::GitProgress;
 pub use crate::git_subprocess::GitSidebandLineTerminator;
@@ -55,10 +65,13 @@ use crate::merge::Diff;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::TreeDiffEntry;
 use crate::object_id::ObjectId as _;
+use crate::op_store::OpStoreError;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
 use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
+use crate::op_walk;
+use crate::operation::Operation;
 use crate::ref_name::GitRefName;
 use crate::ref_name::GitRefNameBuf;
 use crate::ref_name::RefName;
@@ -67,11 +80,14 @@ use crate::ref_name::RemoteName;
 use crate::ref_name::RemoteNameBuf;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::RemoteRefSymbolBuf;
+use crate::refs::merge_unindexed_ref_targets;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
 use crate::revset::RevsetExpression;
 use crate::settings::UserSettings;
+use crate::signing::SignError;
+use crate::signing::Signer;
 use crate::store::Store;
 use crate::str_util::StringExpression;
 use crate::str_util::StringMatcher;
@@ -170,6 +186,10 @@ fn owned_oid_from_commit_id(id: &CommitId) -> gix::ObjectId {
     gix::ObjectId::from_bytes_or_panic(id.as_bytes())
 }
 
+fn commit_id_from_oid(oid: gix::ObjectId) -> CommitId {
+    CommitId::from_bytes(oid.as_bytes())
+}
+
 /// Type of Git ref to be imported or exported.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum GitRefKind {
@@ -552,12 +572,15 @@ struct RefsToImport {
 /// Reflect changes made in the underlying Git repo in the Jujutsu repo.
 ///
 /// This function detects conflicts (if both Git and JJ modified a bookmark) and
-/// records them in JJ's view.
+/// records them in JJ's view. Changes to the Git notes are also imported.
 pub async fn import_refs(
     mut_repo: &mut MutableRepo,
     options: &GitImportOptions,
 ) -> Result<GitImportStats, GitImportError> {
-    import_some_refs(mut_repo, options, |_, _| true).await
+    let stats = import_some_refs(mut_repo, options, |_, _| true).await?;
+    let git_repo = get_git_repo(mut_repo.store())?;
+    import_git_notes(mut_repo, &git_repo)?;
+    Ok(stats)
 }
 
 /// Reflect changes made in the underlying Git repo in the Jujutsu repo.
@@ -1047,6 +1070,73 @@ pub async fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportErro
     Ok(())
 }
 
+/// Git ref where stash entries are recorded.
+const GIT_STASH_REF_NAME: &str = "refs/stash";
+
+/// Imports Git stash entries as shelves.
+///
+/// Each entry in the `refs/stash` reflog is imported as a shelf named
+/// `stash@{<n>}`. The shelf commit is created on top of the commit the stash
+/// entry was based on. Index and untracked-files parts of the stash entries
+/// are ignored. Returns the names of the imported shelves.
+pub async fn import_git_stashes(mut_repo: &mut MutableRepo) -> Result<Vec<String>, GitImportError> {
+    let store = mut_repo.store().clone();
+    let git_backend = get_git_backend(&store)?;
+    let git_repo = git_backend.git_repo();
+    let Some(git_ref) = git_repo
+        .try_find_reference(GIT_STASH_REF_NAME)
+        .map_err(GitImportError::from_git)?
+    else {
+        return Ok(vec![]);
+    };
+
+    // Reflog is sorted from the newest entry, which is "stash@{0}".
+    let mut stash_oids = Vec::new();
+    let mut log_platform = git_ref.log_iter();
+    if let Some(log_iter) = log_platform.rev().map_err(GitImportError::from_git)? {
+        for line in log_iter {
+            let line = line.map_err(GitImportError::from_git)?;
+            stash_oids.push(line.new_oid);
+        }
+    }
+    if stash_oids.is_empty()
+        && let Some(oid) = git_ref.target().try_id()
+    {
+        stash_oids.push(oid.to_owned());
+    }
+
+    let mut names = Vec::with_capacity(stash_oids.len());
+    for (i, oid) in stash_oids.iter().enumerate() {
+        let git_commit = git_repo
+            .find_commit(*oid)
+            .map_err(GitImportError::from_git)?;
+        let Some(base_oid) = git_commit.parent_ids().next() else {
+            continue;
+        };
+        let base_id = CommitId::from_bytes(base_oid.as_bytes());
+        git_backend
+            .import_head_commits([&base_id])
+            .map_err(GitImportError::Backend)?;
+        let tree_id = git_commit.tree_id().map_err(GitImportError::from_git)?;
+        let tree = MergedTree::resolved(store.clone(), TreeId::from_bytes(tree_id.as_bytes()));
+        let message = git_commit
+            .message_raw()
+            .map_err(GitImportError::from_git)?
+            .to_str_lossy()
+            .into_owned();
+        let shelf_commit = mut_repo
+            .new_commit(vec![base_id], tree)
+            .set_description(message)
+            .detach()
+            .write_hidden()
+            .await?;
+        let name = format!("stash@{{{i}}}");
+        mut_repo.set_shelf(name.clone(), shelf_commit.id().clone());
+        names.push(name);
+    }
+    Ok(names)
+}
+
 #[derive(Error, Debug)]
 pub enum GitExportError {
     #[error(transparent)]
@@ -1100,6 +1190,8 @@ pub struct GitExportStats {
     ///
     /// Since Git doesn't have remote tags, this list only contains `@git` tags.
     pub failed_tags: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    /// Reason the Git notes ref couldn't be exported, if any.
+    pub failed_notes: Option<FailedRefExportReason>,
 }
 
 #[derive(Debug)]
@@ -1128,9 +1220,13 @@ struct RefsToExport {
 /// repo compared to our last remembered view of the Git repo). These will be
 /// marked conflicted by the next `jj git import`.
 ///
-/// New/updated tags are exported as Git lightweight tags.
+/// New/updated tags are exported as Git lightweight tags. The notes recorded in
+/// the view are also exported.
 pub fn export_refs(mut_repo: &mut MutableRepo) -> Result<GitExportStats, GitExportError> {
-    export_some_refs(mut_repo, |_, _| true)
+    let mut stats = export_some_refs(mut_repo, |_, _| true)?;
+    let git_repo = get_git_repo(mut_repo.store())?;
+    stats.failed_notes = export_git_notes(mut_repo, &git_repo)?;
+    Ok(stats)
 }
 
 pub fn export_some_refs(
@@ -1144,11 +1240,30 @@ pub fn export_some_refs(
         Some(value)
     }
 
-    let AllRefsToExport { bookmarks, tags } = diff_refs_to_export(
+    let AllRefsToExport {
+        bookmarks,
+        mut tags,
+    } = diff_refs_to_export(
         mut_repo.view(),
         mut_repo.store().root_commit_id(),
         &git_ref_filter,
     );
+    // Annotated tags re-created at the same commit have to be exported even
+    // though the commit didn't change.
+    for (name, (target, _)) in mut_repo.git_tag_objects() {
+        let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+        let git_ref_name = to_git_or_remote_tag_ref_name(symbol);
+        let expected_target = RefTarget::normal(target.clone());
+        if git_ref_filter(GitRefKind::Tag, symbol)
+            && mut_repo.get_local_tag(name) == expected_target
+            && mut_repo.get_git_ref(&git_ref_name) == expected_target
+        {
+            let oid = owned_oid_from_commit_id(target);
+            tags.to_update.push((symbol.to_owned(), (Some(oid), oid)));
+        }
+    }
+    tags.to_update
+        .sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
 
     let check_and_detach_head = |git_repo: &gix::Repository| -> Result<(), GitExportError> {
         let Ok(head_ref) = git_repo.find_reference("HEAD") else {
@@ -1223,6 +1338,7 @@ pub fn export_some_refs(
     Ok(GitExportStats {
         failed_bookmarks,
         failed_tags,
+        failed_notes: None,
     })
 }
 
@@ -1252,17 +1368,23 @@ fn export_refs_to_git(
         };
         let new_ref_oid = match kind {
             GitRefKind::Bookmark => None,
-            // Copy existing tag ref, which may point to annotated tag object.
-            GitRefKind::Tag => {
-                let remote_matcher = StringMatcher::all();
-                find_git_tag_oid_to_copy(
-                    mut_repo.view(),
-                    git_repo,
-                    &symbol.name,
-                    &remote_matcher,
-                    &new_commit_oid,
-                )
-            }
+            GitRefKind::Tag => match mut_repo.git_tag_objects().get(&symbol.name) {
+                // Annotated tag object created in this transaction
+                Some((target, object_id)) if target.as_bytes() == new_commit_oid.as_bytes() => {
+                    Some(gix::ObjectId::from_bytes_or_panic(object_id))
+                }
+                // Copy existing tag ref, which may point to annotated tag object.
+                _ => {
+                    let remote_matcher = StringMatcher::all();
+                    find_git_tag_oid_to_copy(
+                        mut_repo.view(),
+                        git_repo,
+                        &symbol.name,
+                        &remote_matcher,
+                        &new_commit_oid,
+                    )
+                }
+            },
         };
         if let Err(reason) = update_git_ref(
             git_repo,
@@ -1275,6 +1397,9 @@ fn export_refs_to_git(
         } else {
             let new_target = RefTarget::normal(CommitId::from_bytes(new_commit_oid.as_bytes()));
             mut_repo.set_git_ref_target(&git_ref_name, new_target);
+            if kind == GitRefKind::Tag {
+                mut_repo.remove_git_tag_object(&symbol.name);
+            }
         }
     }
 
@@ -1552,18 +1677,20 @@ fn move_git_ref(
         // The reference was deleted in git and moved in jj
         return Err(FailedRefExportReason::ModifiedInJjDeletedInGit);
     };
-    // We still consider this a success if it was updated to our desired target
     let git_commit_oid = resolve_git_ref_to_commit_id(&git_ref, Some(&old_commit_oid));
-    if git_commit_oid == Some(new_commit_oid) {
-        Ok(())
-    } else if git_commit_oid == Some(old_commit_oid) {
-        // The reference would point to annotated tag, try again
+    if git_commit_oid == Some(old_commit_oid) {
+        // The reference would point to annotated tag, try again. This also
+        // replaces the tag object if the tag is re-created at the same commit.
         let constraint =
             gix::refs::transaction::PreviousValue::MustExistAndMatch(git_ref.inner.target);
         git_repo
             .reference(git_ref_name.as_str(), new_oid, constraint, "export from jj")
             .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?;
         Ok(())
+    } else if git_commit_oid == Some(new_commit_oid) {
+        // We still consider this a success if it was updated to our desired
+        // target
+        Ok(())
     } else {
         Err(FailedRefExportReason::FailedToSet(set_err.into()))
     }
@@ -2542,6 +2669,8 @@ pub enum GitFetchError {
     RejectedUpdates(Vec<GitRefNameBuf>),
     #[error(transparent)]
     Subprocess(#[from] GitSubprocessError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
 }
 
 #[derive(Error, Debug)]
@@ -3089,6 +3218,10 @@ pub struct GitPushOptions {
     pub extra_args: Vec<String>,
     /// `--push-option` arguments.
     pub remote_push_options: Vec<String>,
+    /// Whether to request an atomic transaction on the remote side.
+    ///
+    /// If set, either all refs are updated or none of them are.
+    pub atomic: bool,
 }
 
 /// Pushes the specified refs and updates the repo view accordingly.
@@ -3331,6 +3464,826 @@ pub enum FetchTagsOverride {
     NoTags,
 }
 
+/// Git ref where commit notes are stored.
+pub const NOTES_REF_NAME: &str = "refs/notes/commits";
+/// Git ref prefix where remote notes will be fetched.
+const REMOTE_NOTES_REF_NAMESPACE: &str = "refs/jj/remote-notes/";
+
+#[derive(Debug, Error)]
+pub enum GitNotesError {
+    #[error("No git remote named '{}'", .0.as_symbol())]
+    NoSuchRemote(RemoteNameBuf),
+    #[error("Failed to read Git notes")]
+    Read(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to write Git notes")]
+    Write(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    #[error(transparent)]
+    Push(#[from] GitPushError),
+    #[error(transparent)]
+    RemoteName(#[from] GitRemoteNameError),
+    #[error(transparent)]
+    Subprocess(#[from] GitSubprocessError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+impl GitNotesError {
+    fn read(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Read(err.into())
+    }
+
+    fn write(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Write(err.into())
+    }
+}
+
+/// Mapping from annotated object id to note blob id.
+type NotesMap = BTreeMap<gix::ObjectId, gix::ObjectId>;
+
+/// Git notes recorded in the view.
+///
+/// If the notes diverged, notes from all sides are returned.
+pub struct GitNotes {
+    git_repo: gix::Repository,
+    notes: BTreeMap<gix::ObjectId, Vec<gix::ObjectId>>,
+}
+
+impl GitNotes {
+    /// Loads all notes from the notes commit recorded in the `repo` view.
+    pub fn load(repo: &dyn Repo) -> Result<Self, GitNotesError> {
+        let git_repo = get_git_repo(repo.store())?;
+        let mut notes: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for notes_id in repo.view().git_notes().added_ids().unique() {
+            let notes_oid = owned_oid_from_commit_id(notes_id);
+            for (object_oid, blob_oid) in
+                read_notes_map(&git_repo, notes_tree(&git_repo, notes_oid)?)?
+            {
+                let blob_oids = notes.entry(object_oid).or_default();
+                if !blob_oids.contains(&blob_oid) {
+                    blob_oids.push(blob_oid);
+                }
+            }
+        }
+        Ok(Self { git_repo, notes })
+    }
+
+    /// Returns the note attached to the given commit.
+    pub fn get(&self, commit_id: &CommitId) -> Result<Option<BString>, GitNotesError> {
+        let Some(blob_oids) = self.notes.get(oid_from_commit_id(commit_id)) else {
+            return Ok(None);
+        };
+        let data = match &**blob_oids {
+            [oid] => {
+                let blob = self.git_repo.find_blob(*oid).map_err(GitNotesError::read)?;
+                blob.data.clone()
+            }
+            oids => read_concatenated_notes(&self.git_repo, oids)?,
+        };
+        Ok(Some(data.into()))
+    }
+}
+
+/// Copies notes from the predecessors to the rewritten commits.
+///
+/// `rewrites` maps new commits to their predecessors. Notes of multiple
+/// predecessors are concatenated. Commits that already have notes are left
+/// alone. The new notes commit is recorded in the view.
+pub(crate) fn copy_git_notes(
+    mut_repo: &mut MutableRepo,
+    rewrites: &[(CommitId, Vec<CommitId>)],
+) -> Result<(), GitNotesError> {
+    if rewrites.is_empty() || mut_repo.view().git_notes().is_absent() {
+        return Ok(());
+    }
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let Some(notes_oid) = resolve_git_notes(mut_repo, &git_repo)? else {
+        return Ok(());
+    };
+    let mut notes = read_notes_map(&git_repo, notes_tree(&git_repo, notes_oid)?)?;
+    let mut num_copied = 0;
+    for (new_id, old_ids) in rewrites {
+        let new_oid = owned_oid_from_commit_id(new_id);
+        if notes.contains_key(&new_oid) {
+            continue;
+        }
+        let old_notes = old_ids
+            .iter()
+            .filter_map(|id| notes.get(oid_from_commit_id(id)).copied())
+            .unique()
+            .collect_vec();
+        let blob_oid = match &*old_notes {
+            [] => continue,
+            [oid] => *oid,
+            oids => concat_note_blobs(&git_repo, oids)?,
+        };
+        notes.insert(new_oid, blob_oid);
+        num_copied += 1;
+    }
+    if num_copied > 0 {
+        let message = "Notes added by 'jj' on rewrite\n";
+        let signature = mut_repo.base_repo().settings().signature();
+        let new_oid = write_notes_commit(&git_repo, &notes, &[notes_oid], message, &signature)?;
+        mut_repo.set_git_notes_target(RefTarget::normal(commit_id_from_oid(new_oid)));
+    }
+    Ok(())
+}
+
+/// Returns the heads of the notes commits recorded in the views of `head_ops`
+/// and their ancestor operations.
+///
+/// Notes commits aren't indexed, so they aren't kept by
+/// [`Store::gc()`](crate::store::Store::gc). They should be passed to
+/// [`GitBackend::gc_with_extra_heads()`] instead so the notes remain available
+/// after restoring an old operation.
+pub async fn git_notes_heads_for_gc(
+    store: &Store,
+    head_ops: &[Operation],
+) -> Result<Vec<CommitId>, GitNotesError> {
+    let git_repo = get_git_repo(store)?;
+    let mut notes_oids = HashSet::new();
+    let mut ops = pin!(op_walk::walk_ancestors(head_ops));
+    while let Some(op) = ops.try_next().await? {
+        let view = op.view().await?;
+        notes_oids.extend(
+            itertools::chain(
+                view.git_notes().added_ids(),
+                view.git_notes_ref().added_ids(),
+            )
+            .map(owned_oid_from_commit_id),
+        );
+    }
+    // Notes commits usually form a chain, so only the heads need refs.
+    // Commits which have already been pruned are skipped.
+    let mut head_oids = HashSet::new();
+    let mut parent_oids = HashSet::new();
+    for oid in notes_oids {
+        let Ok(commit) = git_repo.find_commit(oid) else {
+            continue;
+        };
+        head_oids.insert(oid);
+        parent_oids.extend(commit.parent_ids().map(|id| id.detach()));
+    }
+    Ok(head_oids
+        .into_iter()
+        .filter(|oid| !parent_oids.contains(oid))
+        .map(commit_id_from_oid)
+        .collect())
+}
+
+/// Reflects changes made to the Git notes ref in the view.
+///
+/// If the notes were also changed in jj since the last import or export, the
+/// notes are recorded as diverged, and will be merged when exported.
+fn import_git_notes(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+) -> Result<(), GitImportError> {
+    let notes_oid = resolve_notes_ref(git_repo, GitRefName::new(NOTES_REF_NAME))
+        .map_err(GitImportError::from_git)?;
+    let new_target = RefTarget::resolved(notes_oid.map(commit_id_from_oid));
+    let old_target = mut_repo.view().git_notes_ref().clone();
+    if new_target == old_target {
+        return Ok(());
+    }
+    let merged_target =
+        merge_unindexed_ref_targets(mut_repo.view().git_notes(), &old_target, &new_target);
+    mut_repo.set_git_notes_target(merged_target);
+    mut_repo.set_git_notes_ref_target(new_target);
+    Ok(())
+}
+
+/// Exports the notes recorded in the view to the Git notes ref.
+///
+/// Diverged notes are merged first. If the Git notes ref was moved since the
+/// last import, it is left alone, and will be merged by the next import.
+/// Returns the reason if the Git notes ref couldn't be updated.
+fn export_git_notes(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+) -> Result<Option<FailedRefExportReason>, GitExportError> {
+    let new_oid = resolve_git_notes(mut_repo, git_repo).map_err(GitExportError::from_git)?;
+    let old_target = mut_repo.view().git_notes_ref().clone();
+    let new_target = mut_repo.view().git_notes().clone();
+    if new_target == old_target {
+        return Ok(None);
+    }
+    let expected = match old_target.as_resolved() {
+        Some(Some(id)) => gix::refs::transaction::PreviousValue::MustExistAndMatch(
+            owned_oid_from_commit_id(id).into(),
+        ),
+        Some(None) => gix::refs::transaction::PreviousValue::MustNotExist,
+        None => gix::refs::transaction::PreviousValue::Any,
+    };
+    let change = match new_oid {
+        Some(oid) => gix::refs::transaction::Change::Update {
+            log: gix::refs::transaction::LogChange {
+                message: "export from jj".into(),
+                ..Default::default()
+            },
+            expected,
+            new: oid.into(),
+        },
+        None => gix::refs::transaction::Change::Delete {
+            expected,
+            log: gix::refs::transaction::RefLog::AndReference,
+        },
+    };
+    let edit = gix::refs::transaction::RefEdit {
+        change,
+        name: NOTES_REF_NAME
+            .try_into()
+            .map_err(GitExportError::from_git)?,
+        deref: false,
+    };
+    if let Err(err) = git_repo.edit_reference(edit) {
+        return Ok(Some(FailedRefExportReason::FailedToSet(err.into())));
+    }
+    mut_repo.set_git_notes_ref_target(new_target);
+    Ok(None)
+}
+
+/// Merges diverged notes recorded in the view into a new notes commit.
+///
+/// Returns the resolved notes commit.
+fn resolve_git_notes(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+) -> Result<Option<gix::ObjectId>, GitNotesError> {
+    let target = mut_repo.view().git_notes();
+    if let Some(id) = target.as_resolved() {
+        return Ok(id.as_ref().map(owned_oid_from_commit_id));
+    }
+    let parents = target
+        .added_ids()
+        .map(owned_oid_from_commit_id)
+        .unique()
+        .collect_vec();
+    let new_oid = match &*parents {
+        [] => None,
+        [oid] => Some(*oid),
+        oids => {
+            let mut notes = NotesMap::new();
+            for &oid in oids {
+                let other_notes = read_notes_map(git_repo, notes_tree(git_repo, oid)?)?;
+                merge_notes_map(git_repo, &mut notes, other_notes)?;
+            }
+            let message = "Notes merged by 'jj'\n";
+            let signature = mut_repo.base_repo().settings().signature();
+            Some(write_notes_commit(
+                git_repo, &notes, oids, message, &signature,
+            )?)
+        }
+    };
+    mut_repo.set_git_notes_target(RefTarget::resolved(new_oid.map(commit_id_from_oid)));
+    Ok(new_oid)
+}
+
+/// Fetches refs from the remote without updating remote-tracking bookmarks.
+///
+/// The `source` and `destination` are fully-qualified ref names, or patterns
+/// containing a single `*`. The matching remote refs are written to the local
+/// Git repository as is, and the fetched commits aren't imported. Returns
+/// `false` if the `source` ref doesn't exist on the remote. A pattern matching
+/// no refs isn't an error.
+pub fn fetch_raw_refs(
+    store: &Store,
+    subprocess_options: GitSubprocessOptions,
+    remote_name: &RemoteName,
+    source: &str,
+    destination: &str,
+    callback: &mut dyn GitSubprocessCallback,
+) -> Result<bool, GitFetchError> {
+    validate_remote_name(remote_name)?;
+    let git_backend = get_git_backend(store)?;
+    if git_backend
+        .git_repo()
+        .try_find_remote(remote_name.as_str())
+        .is_none()
+    {
+        return Err(GitFetchError::NoSuchRemote(remote_name.to_owned()));
+    }
+    let git_ctx = GitSubprocessContext::from_git_backend(git_backend, subprocess_options);
+    let refspec = RefSpec::forced(source, destination);
+    Ok(git_ctx.spawn_fetch_refspec(remote_name, &refspec, callback)?)
+}
+
+/// Imports the commits pointed to by the Git refs starting with `prefix`, and
+/// removes the refs.
+///
+/// Unlike [`import_refs()`], the refs aren't recorded in the view, and the
+/// commits aren't added to the repo. This is useful for inspecting commits
+/// fetched by [`fetch_raw_refs()`] to a temporary ref namespace. Refs not
+/// pointing to commits are skipped, but removed as well.
+pub async fn import_raw_ref_commits(
+    store: &Arc<Store>,
+    prefix: &str,
+) -> Result<Vec<(GitRefNameBuf, Commit)>, GitImportError> {
+    let git_backend = get_git_backend(store)?;
+    let git_repo = git_backend.git_repo();
+    let mut git_refs = Vec::new();
+    let mut ref_commits = Vec::new();
+    for git_ref in git_repo
+        .references()
+        .map_err(GitImportError::from_git)?
+        .prefixed(prefix)
+        .map_err(GitImportError::from_git)?
+    {
+        let git_ref = git_ref.map_err(GitImportError::from_git)?;
+        if let Some(oid) = resolve_git_ref_to_commit_id(&git_ref, None) {
+            let name = GitRefNameBuf::from(git_ref.name().as_bstr().to_str_lossy().into_owned());
+            ref_commits.push((name, CommitId::from_bytes(oid.as_bytes())));
+        }
+        git_refs.push(git_ref);
+    }
+    // The imported commits are protected from GC by the backend, so the refs
+    // are no longer needed.
+    git_backend.import_head_commits(ref_commits.iter().map(|(_, id)| id))?;
+    for git_ref in git_refs {
+        git_ref.delete().map_err(GitImportError::from_git)?;
+    }
+    let mut commits = Vec::with_capacity(ref_commits.len());
+    for (name, id) in ref_commits {
+        let commit = store.get_commit_async(&id).await?;
+        commits.push((name, commit));
+    }
+    Ok(commits)
+}
+
+/// Fetches notes from the remote, and merges them into the notes recorded in
+/// the view.
+///
+/// If both local and remote notes exist for the same commit, the notes are
+/// concatenated. Returns `false` if the remote has no notes.
+pub fn fetch_git_notes(
+    mut_repo: &mut MutableRepo,
+    subprocess_options: GitSubprocessOptions,
+    remote_name: &RemoteName,
+    callback: &mut dyn GitSubprocessCallback,
+) -> Result<bool, GitNotesError> {
+    validate_remote_name(remote_name)?;
+    let git_backend = get_git_backend(mut_repo.store())?;
+    let git_repo = git_backend.git_repo();
+    if git_repo.try_find_remote(remote_name.as_str()).is_none() {
+        return Err(GitNotesError::NoSuchRemote(remote_name.to_owned()));
+    }
+    let git_ctx = GitSubprocessContext::from_git_backend(git_backend, subprocess_options);
+    let remote_notes_ref = remote_notes_ref_name(remote_name);
+    let refspec = RefSpec::forced(NOTES_REF_NAME, remote_notes_ref.as_str());
+    if !git_ctx.spawn_fetch_refspec(remote_name, &refspec, callback)? {
+        return Ok(false);
+    }
+    let Some(remote_oid) = resolve_notes_ref(&git_repo, &remote_notes_ref)? else {
+        return Ok(false);
+    };
+    merge_remote_git_notes(mut_repo, &git_repo, remote_name, remote_oid)?;
+    Ok(true)
+}
+
+/// Merges the remote notes commit into the notes recorded in the view.
+fn merge_remote_git_notes(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+    remote_name: &RemoteName,
+    remote_oid: gix::ObjectId,
+) -> Result<(), GitNotesError> {
+    let new_oid = match resolve_git_notes(mut_repo, git_repo)? {
+        None => remote_oid,
+        Some(local_oid) if is_notes_ancestor(git_repo, remote_oid, local_oid)? => {
+            return Ok(());
+        }
+        Some(local_oid) if is_notes_ancestor(git_repo, local_oid, remote_oid)? => remote_oid,
+        Some(local_oid) => {
+            let mut notes = read_notes_map(git_repo, notes_tree(git_repo, local_oid)?)?;
+            let remote_notes = read_notes_map(git_repo, notes_tree(git_repo, remote_oid)?)?;
+            merge_notes_map(git_repo, &mut notes, remote_notes)?;
+            let message = format!(
+                "Notes merged from remote '{remote}' by 'jj'\n",
+                remote = remote_name.as_str()
+            );
+            let signature = mut_repo.base_repo().settings().signature();
+            write_notes_commit(
+                git_repo,
+                &notes,
+                &[local_oid, remote_oid],
+                &message,
+                &signature,
+            )?
+        }
+    };
+    mut_repo.set_git_notes_target(RefTarget::normal(commit_id_from_oid(new_oid)));
+    Ok(())
+}
+
+/// Merges `other_notes` into `notes`, concatenating notes that differ.
+fn merge_notes_map(
+    git_repo: &gix::Repository,
+    notes: &mut NotesMap,
+    other_notes: NotesMap,
+) -> Result<(), GitNotesError> {
+    for (object_oid, other_blob_oid) in other_notes {
+        match notes.entry(object_oid) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(other_blob_oid);
+            }
+            btree_map::Entry::Occupied(mut entry) => {
+                if *entry.get() != other_blob_oid {
+                    let blob_oid = concat_note_blobs(git_repo, &[*entry.get(), other_blob_oid])?;
+                    entry.insert(blob_oid);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Pushes the notes recorded in the view to the remote.
+///
+/// Diverged notes are merged first. Like bookmarks, the remote notes are
+/// updated only if they haven't been moved since the last fetch.
+pub fn push_git_notes(
+    mut_repo: &mut MutableRepo,
+    subprocess_options: GitSubprocessOptions,
+    remote_name: &RemoteName,
+    callback: &mut dyn GitSubprocessCallback,
+) -> Result<GitPushStats, GitNotesError> {
+    validate_remote_name(remote_name)?;
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let notes_ref = GitRefName::new(NOTES_REF_NAME);
+    let remote_notes_ref = remote_notes_ref_name(remote_name);
+    let local_oid = resolve_git_notes(mut_repo, &git_repo)?;
+    let remote_oid = resolve_notes_ref(&git_repo, &remote_notes_ref)?;
+    if local_oid.is_none() || local_oid == remote_oid {
+        return Ok(GitPushStats::default());
+    }
+    let update = GitRefUpdate {
+        qualified_name: notes_ref.to_owned(),
+        targets: Diff::new(remote_oid, local_oid),
+    };
+    let push_stats = push_updates(
+        mut_repo,
+        subprocess_options,
+        remote_name,
+        &[update],
+        callback,
+        &GitPushOptions::default(),
+    )?;
+    if push_stats.all_ok() {
+        let new_oid = local_oid.expect("local notes should exist");
+        update_notes_ref(&git_repo, &remote_notes_ref, remote_oid, new_oid)?;
+    }
+    Ok(push_stats)
+}
+
+fn remote_notes_ref_name(remote_name: &RemoteName) -> GitRefNameBuf {
+    format!(
+        "{REMOTE_NOTES_REF_NAMESPACE}{remote}",
+        remote = remote_name.as_str()
+    )
+    .into()
+}
+
+fn resolve_notes_ref(
+    git_repo: &gix::Repository,
+    notes_ref: &GitRefName,
+) -> Result<Option<gix::ObjectId>, GitNotesError> {
+    let Some(mut git_ref) = git_repo
+        .try_find_reference(notes_ref.as_str())
+        .map_err(GitNotesError::read)?
+    else {
+        return Ok(None);
+    };
+    let oid = git_ref.peel_to_id().map_err(GitNotesError::read)?.detach();
+    Ok(Some(oid))
+}
+
+fn notes_tree(
+    git_repo: &gix::Repository,
+    notes_oid: gix::ObjectId,
+) -> Result<gix::Tree<'_>, GitNotesError> {
+    let commit = git_repo
+        .find_commit(notes_oid)
+        .map_err(GitNotesError::read)?;
+    commit.tree().map_err(GitNotesError::read)
+}
+
+/// Reads all notes from the notes tree, flattening the fan-out directories.
+fn read_notes_map(
+    git_repo: &gix::Repository,
+    tree: gix::Tree<'_>,
+) -> Result<NotesMap, GitNotesError> {
+    fn collect(
+        git_repo: &gix::Repository,
+        tree: gix::Tree<'_>,
+        prefix: &str,
+        notes: &mut NotesMap,
+    ) -> Result<(), GitNotesError> {
+        for entry in tree.iter() {
+            let entry = entry.map_err(GitNotesError::read)?;
+            let Ok(name) = str::from_utf8(entry.filename()) else {
+                continue;
+            };
+            let hex = format!("{prefix}{name}");
+            if entry.mode().is_tree() {
+                let subtree = git_repo
+                    .find_tree(entry.oid())
+                    .map_err(GitNotesError::read)?;
+                collect(git_repo, subtree, &hex, notes)?;
+            } else if entry.mode().is_blob()
+                && let Ok(object_oid) = gix::ObjectId::from_hex(hex.as_bytes())
+            {
+                notes.insert(object_oid, entry.oid().to_owned());
+            }
+        }
+        Ok(())
+    }
+    let mut notes = NotesMap::new();
+    collect(git_repo, tree, "", &mut notes)?;
+    Ok(notes)
+}
+
+/// Writes new notes blob which concatenates the given notes.
+fn concat_note_blobs(
+    git_repo: &gix::Repository,
+    blob_oids: &[gix::ObjectId],
+) -> Result<gix::ObjectId, GitNotesError> {
+    let data = read_concatenated_notes(git_repo, blob_oids)?;
+    let oid = git_repo.write_blob(data).map_err(GitNotesError::write)?;
+    Ok(oid.detach())
+}
+
+/// Reads the given notes, and concatenates them separated by blank lines.
+fn read_concatenated_notes(
+    git_repo: &gix::Repository,
+    blob_oids: &[gix::ObjectId],
+) -> Result<Vec<u8>, GitNotesError> {
+    let mut data = Vec::new();
+    for &oid in blob_oids {
+        let blob = git_repo.find_blob(oid).map_err(GitNotesError::read)?;
+        let text = blob.data.trim_ascii_end();
+        if !data.is_empty() {
+            data.push(b'\n');
+        }
+        data.extend_from_slice(text);
+        data.push(b'\n');
+    }
+    Ok(data)
+}
+
+fn write_notes_commit(
+    git_repo: &gix::Repository,
+    notes: &NotesMap,
+    parents: &[gix::ObjectId],
+    message: &str,
+    signature: &Signature,
+) -> Result<gix::ObjectId, GitNotesError> {
+    let entries = notes
+        .iter()
+        .map(|(object_oid, blob_oid)| gix::objs::tree::Entry {
+            mode: gix::object::tree::EntryKind::Blob.into(),
+            filename: object_oid.to_hex().to_string().into(),
+            oid: *blob_oid,
+        })
+        .collect();
+    let tree_oid = git_repo
+        .write_object(gix::objs::Tree { entries })
+        .map_err(GitNotesError::write)?;
+    let signature = signature_to_git(signature);
+    let commit = gix::objs::Commit {
+        message: message.into(),
+        tree: tree_oid.detach(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        parents: parents.iter().copied().collect(),
+        extra_headers: vec![],
+    };
+    let oid = git_repo
+        .write_object(&commit)
+        .map_err(GitNotesError::write)?;
+    Ok(oid.detach())
+}
+
+/// Checks if `ancestor_oid` is reachable from `oid` in the notes history.
+fn is_notes_ancestor(
+    git_repo: &gix::Repository,
+    ancestor_oid: gix::ObjectId,
+    oid: gix::ObjectId,
+) -> Result<bool, GitNotesError> {
+    let mut visited = HashSet::new();
+    let mut queue = vec![oid];
+    while let Some(oid) = queue.pop() {
+        if oid == ancestor_oid {
+            return Ok(true);
+        }
+        if !visited.insert(oid) {
+            continue;
+        }
+        let commit = git_repo.find_commit(oid).map_err(GitNotesError::read)?;
+        queue.extend(commit.parent_ids().map(|id| id.detach()));
+    }
+    Ok(false)
+}
+
+fn update_notes_ref(
+    git_repo: &gix::Repository,
+    notes_ref: &GitRefName,
+    old_oid: Option<gix::ObjectId>,
+    new_oid: gix::ObjectId,
+) -> Result<(), GitNotesError> {
+    let constraint = match old_oid {
+        Some(oid) => gix::refs::transaction::PreviousValue::MustExistAndMatch(oid.into()),
+        None => gix::refs::transaction::PreviousValue::MustNotExist,
+    };
+    git_repo
+        .reference(notes_ref.as_str(), new_oid, constraint, "notes from jj")
+        .map_err(GitNotesError::write)?;
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum GitTagError {
+    #[error("Failed to read Git tag")]
+    Read(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to write Git tag")]
+    Write(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to sign Git tag")]
+    Sign(#[source] SignError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+impl GitTagError {
+    fn read(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Read(err.into())
+    }
+
+    fn write(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Write(err.into())
+    }
+}
+
+/// Annotated Git tag object.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitTag {
+    /// Commit the tag points to.
+    pub target: CommitId,
+    /// Tag message without the signature.
+    pub message: String,
+    /// Who created the tag.
+    pub tagger: Option<Signature>,
+    /// Signature over the tag object, if signed.
+    pub secure_sig: Option<SecureSig>,
+}
+
+/// Line prefixes which start a signature in a Git tag message.
+const TAG_SIGNATURE_PREFIXES: &[&[u8]] = &[
+    b"-----BEGIN PGP SIGNATURE-----",
+    b"-----BEGIN PGP MESSAGE-----",
+    b"-----BEGIN SIGNED MESSAGE-----",
+    b"-----BEGIN SSH SIGNATURE-----",
+];
+
+/// Returns the annotated tag object of the local tag or the remote tag if
+/// `remote` is specified. Returns `None` if the tag is a lightweight tag or
+/// doesn't point to a commit.
+///
+/// Besides the signature formats known to Git, signatures which can be read
+/// by the backends of `signer` are split from the message.
+pub fn get_git_tag(
+    git_repo: &gix::Repository,
+    name: &RefName,
+    remote: Option<&RemoteName>,
+    signer: &Signer,
+) -> Result<Option<GitTag>, GitTagError> {
+    let git_ref_name = to_git_or_remote_tag_ref_name(
+        name.to_remote_symbol(remote.unwrap_or(REMOTE_NAME_FOR_LOCAL_GIT_REPO)),
+    );
+    let Some(git_ref) = git_repo
+        .try_find_reference(git_ref_name.as_str())
+        .map_err(GitTagError::read)?
+    else {
+        return Ok(None);
+    };
+    let Ok(oid) = git_ref.inner.target.try_into_id() else {
+        return Ok(None);
+    };
+    let object = git_repo.find_object(oid).map_err(GitTagError::read)?;
+    if object.kind != gix::object::Kind::Tag {
+        return Ok(None);
+    }
+    let tag = gix::objs::TagRef::from_bytes(&object.data).map_err(GitTagError::read)?;
+    if tag.target_kind != gix::object::Kind::Commit {
+        return Ok(None);
+    }
+    let target = CommitId::from_bytes(tag.target().as_bytes());
+    let tagger = tag
+        .tagger()
+        .map_err(GitTagError::read)?
+        .map(signature_from_git);
+    let (payload, message, sig) = split_git_tag_signature(&object.data, signer);
+    Ok(Some(GitTag {
+        target,
+        message: message.to_str_lossy().into_owned(),
+        tagger,
+        secure_sig: sig.map(|sig| SecureSig {
+            data: payload.to_vec(),
+            sig: sig.to_vec(),
+        }),
+    }))
+}
+
+/// Splits the encoded tag object into the signed payload, the message, and
+/// the signature. Like Git, the last signature block in the message is used.
+fn split_git_tag_signature<'a>(
+    data: &'a [u8],
+    signer: &Signer,
+) -> (&'a [u8], &'a [u8], Option<&'a [u8]>) {
+    let body_start = data.find(b"\n\n").map_or(data.len(), |pos| pos + 2);
+    let sig_start = data[body_start..]
+        .lines_with_terminator()
+        .scan(body_start, |pos, line| {
+            let start = *pos;
+            *pos += line.len();
+            Some((start, line))
+        })
+        .map(|(start, _)| start)
+        .filter(|&start| is_tag_signature_start(&data[start..], signer))
+        .last();
+    match sig_start {
+        Some(pos) => (&data[..pos], &data[body_start..pos], Some(&data[pos..])),
+        None => (data, &data[body_start..], None),
+    }
+}
+
+fn is_tag_signature_start(data: &[u8], signer: &Signer) -> bool {
+    TAG_SIGNATURE_PREFIXES
+        .iter()
+        .any(|prefix| data.starts_with(prefix))
+        || signer.can_read(data)
+}
+
+/// Encoded Git object which is written as is.
+struct RawGitTag(Vec<u8>);
+
+impl gix::objs::WriteTo for RawGitTag {
+    fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        out.write_all(&self.0)
+    }
+
+    fn kind(&self) -> gix::object::Kind {
+        gix::object::Kind::Tag
+    }
+
+    fn size(&self) -> u64 {
+        self.0.len() as u64
+    }
+}
+
+/// Creates an annotated tag object pointing to the `target` commit, and sets
+/// the local tag to it.
+///
+/// The message is signed by `sign_with` if specified. Since the view only
+/// records the target commit, the tag object is remembered by `mut_repo` and
+/// written to the Git ref by [`export_refs()`].
+pub fn set_annotated_tag(
+    mut_repo: &mut MutableRepo,
+    name: &RefName,
+    target: &CommitId,
+    message: &str,
+    tagger: &Signature,
+    sign_with: Option<&mut SigningFn>,
+) -> Result<(), GitTagError> {
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let mut message = message.to_owned();
+    if !message.ends_with('\n') {
+        message.push('\n');
+    }
+    let tag = gix::objs::Tag {
+        target: owned_oid_from_commit_id(target),
+        target_kind: gix::object::Kind::Commit,
+        name: name.as_str().into(),
+        tagger: Some(signature_to_git(tagger)),
+        message: message.into(),
+        pgp_signature: None,
+    };
+    let mut data = Vec::with_capacity(512);
+    gix::objs::WriteTo::write_to(&tag, &mut data).map_err(GitTagError::write)?;
+    if let Some(sign) = sign_with {
+        // The signature is appended to the message
+        let sig = sign(&data).map_err(GitTagError::Sign)?;
+        data.extend_from_slice(&sig);
+    }
+    let tag_oid = git_repo
+        .write_object(RawGitTag(data))
+        .map_err(GitTagError::write)?
+        .detach();
+    mut_repo.set_git_tag_object(name, target.clone(), tag_oid.as_bytes().to_vec());
+    mut_repo.set_local_tag_target(name, RefTarget::normal(target.clone()));
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -452,6 +452,45 @@ impl GitBackend {
         self.save_extra_metadata_table(mut_table, &table_lock)
     }
 
+    /// Like [`Backend::gc()`], but also keeps the `extra_heads` and their
+    /// ancestors reachable. They are Git commits which aren't indexed, such as
+    /// Git notes commits.
+    #[tracing::instrument(skip(self, index, extra_heads))]
+    pub fn gc_with_extra_heads(
+        &self,
+        index: &dyn Index,
+        extra_heads: &[CommitId],
+        keep_newer: SystemTime,
+    ) -> BackendResult<()> {
+        let git_repo = self.lock_git_repo();
+        let new_heads = index
+            .all_heads_for_gc()
+            .map_err(|err| BackendError::Other(err.into()))?
+            .filter(|id| *id != self.root_commit_id)
+            .chain(extra_heads.iter().cloned());
+        recreate_no_gc_refs(&git_repo, new_heads, keep_newer)?;
+
+        // No locking is needed since we aren't going to add new "commits".
+        let table = self.cached_extra_metadata_table()?;
+        // TODO: remove unreachable entries from extras table if segment file
+        // mtime <= keep_newer? (it won't be consistent with no-gc refs
+        // preserved by the keep_newer timestamp though)
+        self.extra_metadata_store
+            .gc(&table, keep_newer)
+            .map_err(|err| BackendError::Other(err.into()))?;
+
+        run_git_gc(
+            self.git_executable.as_ref(),
+            self.git_repo_path(),
+            keep_newer,
+        )
+        .map_err(|err| BackendError::Other(err.into()))?;
+        // Since "git gc" will move loose refs into packed refs, in-memory
+        // packed-refs cache should be invalidated without relying on mtime.
+        git_repo.refs.force_refresh_packed_buffer().ok();
+        Ok(())
+    }
+
     fn read_file_sync(&self, id: &FileId) -> BackendResult<Vec<u8>> {
         let git_blob_id = validate_git_object_id(id)?;
         let locked_repo = self.lock_git_repo();
@@ -719,7 +758,7 @@ pub fn synthetic_change_id_from_git_commit_id(id: &CommitId) -> ChangeId {
 
 const EMPTY_STRING_PLACEHOLDER: &str = "JJ_EMPTY_STRING";
 
-fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
+pub(crate) fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     let name = signature.name;
     let name = if name != EMPTY_STRING_PLACEHOLDER {
         String::from_utf8_lossy(name).into_owned()
@@ -745,7 +784,7 @@ fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     }
 }
 
-fn signature_to_git(signature: &Signature) -> gix::actor::Signature {
+pub(crate) fn signature_to_git(signature: &Signature) -> gix::actor::Signature {
     // git does not support empty names or emails
     let name = if !signature.name.is_empty() {
         &signature.name
@@ -1485,34 +1524,8 @@ impl Backend for GitBackend {
         Ok(futures::stream::iter(records).boxed())
     }
 
-    #[tracing::instrument(skip(self, index))]
     fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
-        let git_repo = self.lock_git_repo();
-        let new_heads = index
-            .all_heads_for_gc()
-            .map_err(|err| BackendError::Other(err.into()))?
-            .filter(|id| *id != self.root_commit_id);
-        recreate_no_gc_refs(&git_repo, new_heads, keep_newer)?;
-
-        // No locking is needed since we aren't going to add new "commits".
-        let table = self.cached_extra_metadata_table()?;
-        // TODO: remove unreachable entries from extras table if segment file
-        // mtime <= keep_newer? (it won't be consistent with no-gc refs
-        // preserved by the keep_newer timestamp though)
-        self.extra_metadata_store
-            .gc(&table, keep_newer)
-            .map_err(|err| BackendError::Other(err.into()))?;
-
-        run_git_gc(
-            self.git_executable.as_ref(),
-            self.git_repo_path(),
-            keep_newer,
-        )
-        .map_err(|err| BackendError::Other(err.into()))?;
-        // Since "git gc" will move loose refs into packed refs, in-memory
-        // packed-refs cache should be invalidated without relying on mtime.
-        git_repo.refs.force_refresh_packed_buffer().ok();
-        Ok(())
+        self.gc_with_extra_heads(index, &[], keep_newer)
     }
 }
 
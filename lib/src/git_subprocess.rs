@@ -211,6 +211,41 @@ impl GitSubprocessContext {
         parse_git_fetch_output(&output)
     }
 
+    /// Fetch refs matching a single refspec without pruning
+    ///
+    /// Returns `false` if the source ref doesn't exist on the remote.
+    pub(crate) fn spawn_fetch_refspec(
+        &self,
+        remote_name: &RemoteName,
+        refspec: &RefSpec,
+        callback: &mut dyn GitSubprocessCallback,
+    ) -> Result<bool, GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::piped());
+        command.args(["fetch", "--no-write-fetch-head"]);
+        if callback.needs_progress() {
+            command.arg("--progress");
+        }
+        command.args(["--", remote_name.as_str()]);
+        command.arg(refspec.to_git_format());
+
+        let output = wait_with_progress(self.spawn_cmd(command)?, callback)?;
+
+        if output.status.success() {
+            return Ok(true);
+        }
+        if let Some(option) = parse_unknown_option(&output.stderr) {
+            return Err(GitSubprocessError::UnsupportedGitOption(option));
+        }
+        if let Some(remote) = parse_no_such_remote(&output.stderr) {
+            return Err(GitSubprocessError::NoSuchRepository(remote));
+        }
+        if parse_no_remote_ref(&output.stderr).is_some() {
+            return Ok(false);
+        }
+        Err(external_git_error(&output.stderr))
+    }
+
     /// Prune particular branches
     pub(crate) fn spawn_branch_prune(
         &self,
@@ -281,6 +316,9 @@ impl GitSubprocessContext {
         if callback.needs_progress() {
             command.arg("--progress");
         }
+        if options.atomic {
+            command.arg("--atomic");
+        }
         command.args(
             options
                 .remote_push_options
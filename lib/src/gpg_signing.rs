@@ -42,6 +42,11 @@ use crate::signing::Verification;
 /// Assume signature is invalid if none of the above was found, and if there are
 /// at least one line other than general program failures `[GNUPG:] FAILURE`.
 ///
+/// The key is the fingerprint of the signing key, which is looked up in
+/// `[GNUPG:] VALIDSIG <fingerprint> ...`, `[GNUPG:] ERRSIG ... <fingerprint>`,
+/// and `[GNUPG:] KEY_CONSIDERED <fingerprint> ...` by the long key ID. If GnuPG
+/// didn't report the fingerprint, the key is the long key ID.
+///
 /// https://github.com/gpg/gnupg/blob/gnupg-2.5.18/doc/DETAILS#format-of-the-status-fd-output
 fn parse_gpg_verify_output(
     output: &[u8],
@@ -75,6 +80,7 @@ fn parse_gpg_verify_output(
         let mut args = args.fuse();
         let key = args
             .next()
+            .map(|key_id| find_gpg_fingerprint(output, key_id.trim_ascii()).unwrap_or(key_id))
             .and_then(|bs| str::from_utf8(bs).ok())
             .map(|value| value.trim().to_owned());
         let display = args
@@ -92,6 +98,49 @@ fn parse_gpg_verify_output(
     }
 }
 
+/// Finds the fingerprint ending with the long key ID in the status lines.
+fn find_gpg_fingerprint<'a>(output: &'a [u8], key_id: &[u8]) -> Option<&'a [u8]> {
+    output
+        .split(|&b| b == b'\n')
+        .filter_map(|line| line.strip_prefix(b"[GNUPG:] "))
+        .filter_map(|line| {
+            let mut fields = line.split(|&b| b == b' ');
+            match fields.next()? {
+                b"VALIDSIG" | b"KEY_CONSIDERED" => fields.next(),
+                b"ERRSIG" => fields.nth(6),
+                _ => None,
+            }
+        })
+        .map(|fingerprint| fingerprint.trim_ascii())
+        .find(|fingerprint| {
+            !key_id.is_empty()
+                && fingerprint.len() >= key_id.len()
+                && fingerprint[fingerprint.len() - key_id.len()..].eq_ignore_ascii_case(key_id)
+        })
+}
+
+/// Collects the fingerprints from the `fpr` records of the `--with-colons`
+/// output. Returns no fingerprints if more than one secret key (`sec` or `crs`
+/// record) matched, since the key used for signing is ambiguous.
+///
+/// https://github.com/gpg/gnupg/blob/gnupg-2.5.18/doc/DETAILS#fpr---fingerprint
+fn parse_gpg_fingerprints(output: &[u8]) -> Vec<String> {
+    let lines = || output.split(|&b| b == b'\n');
+    let num_keys = lines()
+        .filter(|line| line.starts_with(b"sec:") || line.starts_with(b"crs:"))
+        .count();
+    if num_keys > 1 {
+        return vec![];
+    }
+    lines()
+        .filter_map(|line| line.strip_prefix(b"fpr:"))
+        .filter_map(|fields| fields.split(|&b| b == b':').nth(8))
+        .filter(|fingerprint| !fingerprint.is_empty())
+        .filter_map(|fingerprint| str::from_utf8(fingerprint).ok())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
 fn make_command_error(output: &process::Output) -> GpgError {
     GpgError::Command {
         exit_status: output.status,
@@ -128,6 +177,17 @@ fn run_verify_command(command: &mut Command, input: &[u8]) -> Result<process::Ou
     }
 }
 
+fn run_list_keys_command(command: &mut Command) -> Result<Vec<u8>, GpgError> {
+    tracing::info!(?command, "running GPG key listing command");
+    let output = command.stderr(Stdio::piped()).output()?;
+    tracing::info!(?command, ?output.status, "GPG key listing command exited");
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(make_command_error(&output))
+    }
+}
+
 fn write_temp_file(prefix: &str, content: &[u8]) -> io::Result<tempfile::TempPath> {
     let mut file = tempfile::Builder::new().prefix(prefix).tempfile()?;
     file.write_all(content)?;
@@ -232,6 +292,21 @@ impl SigningBackend for GpgBackend {
         parse_gpg_verify_output(&output.stdout, self.allow_expired_keys)
             .unwrap_or_else(|| Err(make_command_error(&output).into()))
     }
+
+    fn key_fingerprints(&self, key: Option<&str>) -> Result<Vec<String>, SignError> {
+        // The default key is looked up by the user email, which may not
+        // identify the key gpg signs with.
+        let Some(key) = key else {
+            return Ok(vec![]);
+        };
+        let output = run_list_keys_command(self.create_command().args([
+            "--with-colons",
+            "--with-subkey-fingerprint",
+            "--list-secret-keys",
+            key,
+        ]))?;
+        Ok(parse_gpg_fingerprints(&output))
+    }
 }
 
 #[derive(Debug)]
@@ -316,6 +391,20 @@ impl SigningBackend for GpgsmBackend {
         parse_gpg_verify_output(&output.stdout, self.allow_expired_keys)
             .unwrap_or_else(|| Err(make_command_error(&output).into()))
     }
+
+    fn key_fingerprints(&self, key: Option<&str>) -> Result<Vec<String>, SignError> {
+        // The default key is looked up by the user email, which may not
+        // identify the key gpg signs with.
+        let Some(key) = key else {
+            return Ok(vec![]);
+        };
+        let output = run_list_keys_command(self.create_command().args([
+            "--with-colons",
+            "--list-secret-keys",
+            key,
+        ]))?;
+        Ok(parse_gpg_fingerprints(&output))
+    }
 }
 
 #[cfg(test)]
@@ -345,6 +434,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gpg_verify_bad_signature_fingerprint() {
+        let output = b"\
+[GNUPG:] KEY_CONSIDERED F4A3D0A1B5C6D7E8F9A04ED556E9729E000F 0
+[GNUPG:] BADSIG 4ED556E9729E000F Someone
+";
+        assert_eq!(
+            parse_gpg_verify_output(output, true).unwrap().unwrap(),
+            Verification::new(
+                SigStatus::Bad,
+                Some("F4A3D0A1B5C6D7E8F9A04ED556E9729E000F".into()),
+                Some("Someone".into())
+            )
+        );
+
+        // The primary key didn't make the signature, so the fingerprint of the
+        // signing key is unknown
+        let output = b"\
+[GNUPG:] KEY_CONSIDERED F4A3D0A1B5C6D7E8F9A04ED556E9729E000F 0
+[GNUPG:] BADSIG B00A1DC5C2C3F3E0 Someone
+";
+        assert_eq!(
+            parse_gpg_verify_output(output, true).unwrap().unwrap(),
+            Verification::new(
+                SigStatus::Bad,
+                Some("B00A1DC5C2C3F3E0".into()),
+                Some("Someone".into())
+            )
+        );
+    }
+
     #[test]
     fn gpg_verify_unknown_signature() {
         assert_eq!(
@@ -353,6 +473,19 @@ mod tests {
                 .unwrap(),
             Verification::new(SigStatus::Unknown, Some("123".into()), None)
         );
+
+        let output = b"\
+[GNUPG:] ERRSIG 4ED556E9729E000F 22 10 00 1700000000 9 F4A3D0A1B5C6D7E8F9A04ED556E9729E000F
+[GNUPG:] NO_PUBKEY 4ED556E9729E000F
+";
+        assert_eq!(
+            parse_gpg_verify_output(output, true).unwrap().unwrap(),
+            Verification::new(
+                SigStatus::Unknown,
+                Some("F4A3D0A1B5C6D7E8F9A04ED556E9729E000F".into()),
+                None
+            )
+        );
     }
 
     #[test]
@@ -365,6 +498,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gpg_verify_good_signature_fingerprint() {
+        let output = b"\
+[GNUPG:] GOODSIG 4ED556E9729E000F Someone
+[GNUPG:] VALIDSIG F4A3D0A1B5C6D7E8F9A04ED556E9729E000F 2023-11-14 1700000000 0 4 0 22 10 00 \
+F4A3D0A1B5C6D7E8F9A04ED556E9729E000F
+";
+        assert_eq!(
+            parse_gpg_verify_output(output, true).unwrap().unwrap(),
+            Verification::new(
+                SigStatus::Good,
+                Some("F4A3D0A1B5C6D7E8F9A04ED556E9729E000F".into()),
+                Some("Someone".into())
+            )
+        );
+    }
+
     #[test]
     fn gpg_verify_expired_signature() {
         assert_eq!(
@@ -412,4 +562,32 @@ mod tests {
             Some(Err(SignError::InvalidSignatureFormat))
         );
     }
+
+    #[test]
+    fn gpg_list_keys_fingerprints() {
+        let output = b"\
+sec:u:255:22:4ED556E9729E000F:1700000000:::u:::scESC:::+:::ed25519:::0:
+fpr:::::::::F4A3D0A1B5C6D7E8F9A04ED556E9729E000F:
+grp:::::::::0123456789ABCDEF0123456789ABCDEF01234567:
+uid:u::::1700000000::ABCDEF::Test User <test.user@example.com>::::::::::0:
+ssb:u:255:18:B00A1DC5C2C3F3E0:1700000000::::::e:::+:::cv25519::
+fpr:::::::::0A1B2C3D4E5F60718293B00A1DC5C2C3F3E0:
+";
+        assert_eq!(
+            parse_gpg_fingerprints(output),
+            [
+                "F4A3D0A1B5C6D7E8F9A04ED556E9729E000F",
+                "0A1B2C3D4E5F60718293B00A1DC5C2C3F3E0",
+            ]
+        );
+        assert!(parse_gpg_fingerprints(b"").is_empty());
+
+        let output = b"\
+sec:u:255:22:4ED556E9729E000F:1700000000:::u:::scESC:::+:::ed25519:::0:
+fpr:::::::::F4A3D0A1B5C6D7E8F9A04ED556E9729E000F:
+sec:u:255:22:638785CB16FEA061:1700000000:::u:::scESC:::+:::ed25519:::0:
+fpr:::::::::A58E5EE9067153C8F5E5E9E9638785CB16FEA061:
+";
+        assert!(parse_gpg_fingerprints(output).is_empty());
+    }
 }
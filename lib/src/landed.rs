@@ -0,0 +1,190 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detection of local changes which have landed upstream.
+//!
+//! Forges often land changes with different commit ids than the ones pushed
+//! for review, for example when squashing or rebasing them on merge. The local
+//! commits are then matched with the landed commits so that they can be
+//! abandoned in favor of them.
+
+use std::collections::HashMap;
+
+use crate::backend::BackendResult;
+use crate::backend::ChangeId;
+use crate::backend::CommitId;
+use crate::commit::Commit;
+use crate::patch_id::commit_patch_id;
+use crate::repo::MutableRepo;
+use crate::repo::Repo;
+use crate::trailer::gerrit_change_id;
+use crate::trailer::parse_description_trailers;
+
+/// Line added to the description of commits copied by `jj backport` or
+/// `git cherry-pick -x`.
+const CHERRY_PICK_LINE_PREFIX: &str = "(cherry picked from commit ";
+
+/// How a local commit was matched with a landed commit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LandedMatch {
+    /// The commits have the same change id.
+    ChangeId,
+    /// The upstream commit has the `Change-Id` trailer generated for the
+    /// change id of the local commit, or a `Reviewed-on` trailer of the local
+    /// commit.
+    Trailer,
+    /// The commits make the same changes.
+    PatchId,
+}
+
+/// Local commit whose changes have landed upstream.
+#[derive(Clone, Debug)]
+pub struct LandedCommit {
+    /// The local commit.
+    pub local: Commit,
+    /// The upstream commit containing the changes of the local commit.
+    pub upstream: Commit,
+    /// How the commits were matched.
+    pub matched_by: LandedMatch,
+}
+
+/// Finds the `local` commits whose changes are in the `upstream` commits.
+///
+/// Commits are matched by change id first, then by the `Change-Id` trailer
+/// `jj gerrit upload` generates from the change id, and then by the
+/// `Reviewed-on` trailers Gerrit adds to the submitted commits. Explicit
+/// `Change-Id` trailers of the local commits aren't used since Gerrit keeps
+/// them when a change is cherry-picked to another branch. If `match_patch_ids`
+/// is true, the remaining commits are matched by patch id. Empty commits are
+/// never matched by patch id.
+///
+/// Local commits which were cherry-picked from another commit, as recorded by
+/// `jj backport`, are only matched by change id, since the upstream commit
+/// may well be the one they were copied from.
+pub async fn find_landed_commits(
+    repo: &dyn Repo,
+    local: &[Commit],
+    upstream: &[Commit],
+    match_patch_ids: bool,
+) -> BackendResult<Vec<LandedCommit>> {
+    let mut by_change_id: HashMap<&ChangeId, &Commit> = HashMap::new();
+    let mut by_gerrit_change_id: HashMap<String, &Commit> = HashMap::new();
+    let mut by_review_url: HashMap<String, &Commit> = HashMap::new();
+    for commit in upstream.iter().rev() {
+        by_change_id.insert(commit.change_id(), commit);
+        for change_id in trailer_values(commit, "Change-Id") {
+            by_gerrit_change_id.insert(change_id, commit);
+        }
+        for url in trailer_values(commit, "Reviewed-on") {
+            by_review_url.insert(url, commit);
+        }
+    }
+
+    let mut landed = vec![];
+    let mut unmatched = vec![];
+    for commit in local {
+        let found = if let Some(&upstream) = by_change_id.get(commit.change_id()) {
+            Some((upstream, LandedMatch::ChangeId))
+        } else if is_cherry_pick(commit) {
+            continue;
+        } else {
+            by_gerrit_change_id
+                .get(&gerrit_change_id(commit.change_id()))
+                .or_else(|| {
+                    trailer_values(commit, "Reviewed-on")
+                        .iter()
+                        .find_map(|url| by_review_url.get(url))
+                })
+                .map(|&upstream| (upstream, LandedMatch::Trailer))
+        };
+        match found {
+            Some((upstream, matched_by)) => landed.push(LandedCommit {
+                local: commit.clone(),
+                upstream: upstream.clone(),
+                matched_by,
+            }),
+            None => unmatched.push(commit),
+        }
+    }
+
+    let mut local_patch_ids = vec![];
+    for commit in unmatched {
+        if !match_patch_ids {
+            break;
+        }
+        if let Some(patch_id) = commit_patch_id(repo, commit).await? {
+            local_patch_ids.push((commit, patch_id));
+        }
+    }
+    if !local_patch_ids.is_empty() {
+        let mut by_patch_id = HashMap::new();
+        for commit in upstream.iter().rev() {
+            if let Some(patch_id) = commit_patch_id(repo, commit).await? {
+                by_patch_id.insert(patch_id, commit);
+            }
+        }
+        for (commit, patch_id) in local_patch_ids {
+            if let Some(&upstream) = by_patch_id.get(&patch_id) {
+                landed.push(LandedCommit {
+                    local: commit.clone(),
+                    upstream: upstream.clone(),
+                    matched_by: LandedMatch::PatchId,
+                });
+            }
+        }
+    }
+    // Keep the order of the local commits
+    let local_positions: HashMap<&CommitId, usize> = local
+        .iter()
+        .enumerate()
+        .map(|(position, commit)| (commit.id(), position))
+        .collect();
+    landed.sort_by_key(|landed| local_positions[landed.local.id()]);
+    Ok(landed)
+}
+
+/// Abandons the landed local commits in favor of the upstream commits.
+///
+/// Descendants of the local commits are rebased onto the upstream commits.
+/// Returns the number of rebased descendants.
+pub async fn abandon_landed_commits(
+    mut_repo: &mut MutableRepo,
+    landed: &[LandedCommit],
+) -> BackendResult<usize> {
+    for commit in landed {
+        mut_repo.record_abandoned_commit_with_parents(
+            commit.local.id().clone(),
+            [commit.upstream.id().clone()],
+        );
+    }
+    mut_repo.rebase_descendants().await
+}
+
+/// Returns the values of the trailers of the commit with the given key.
+fn trailer_values(commit: &Commit, key: &str) -> Vec<String> {
+    parse_description_trailers(commit.description())
+        .into_iter()
+        .filter(|trailer| trailer.key.eq_ignore_ascii_case(key))
+        .map(|trailer| trailer.value)
+        .collect()
+}
+
+/// Returns true if the description records that the commit was copied from
+/// another commit.
+fn is_cherry_pick(commit: &Commit) -> bool {
+    commit
+        .description()
+        .lines()
+        .any(|line| line.trim_start().starts_with(CHERRY_PICK_LINE_PREFIX))
+}
@@ -37,6 +37,7 @@ pub mod commit_builder;
 pub mod config;
 mod config_resolver;
 pub mod conflict_labels;
+pub mod conflict_resolvers;
 pub mod conflicts;
 pub mod copies;
 pub mod dag_walk;
@@ -69,6 +70,7 @@ pub mod hex_util;
 pub mod id_prefix;
 pub mod index;
 pub mod iter_util;
+pub mod landed;
 pub mod local_working_copy;
 pub mod lock;
 pub mod matchers;
@@ -80,12 +82,15 @@ pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod patch;
+pub mod patch_id;
 #[expect(missing_docs)]
 pub mod protos;
 pub mod ref_name;
 pub mod refs;
 pub mod repo;
 pub mod repo_path;
+pub mod resolution_cache;
 pub mod revset;
 mod revset_parser;
 pub mod rewrite;
@@ -93,6 +98,7 @@ pub mod rewrite;
 pub mod secret_backend;
 pub mod secure_config;
 pub mod settings;
+pub mod shelf;
 pub mod signing;
 pub mod tree_merge;
 // TODO: This file is mostly used for testing, whenever we no longer require it
@@ -115,6 +121,7 @@ pub mod tree;
 pub mod tree_builder;
 pub mod union_find;
 pub mod view;
+pub mod word_tokenizer;
 pub mod working_copy;
 pub mod workspace;
 pub mod workspace_store;
@@ -75,7 +75,7 @@ use crate::conflicts::ConflictMaterializeOptions;
 use crate::conflicts::MIN_CONFLICT_MARKER_LEN;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::choose_materialized_conflict_marker_len;
-use crate::conflicts::materialize_merge_result_to_bytes;
+use crate::conflicts::materialize_merge_result_to_bytes_with_tokenizer;
 use crate::conflicts::materialize_tree_value;
 pub use crate::eol::EolConversionMode;
 use crate::eol::TargetEolStrategy;
@@ -2372,13 +2372,26 @@ impl TreeState {
                         marker_len: Some(conflict_marker_len),
                         merge: self.store.merge_options().clone(),
                     };
+                    let tokenizer = options.merge.word_tokenizers.tokenizer_for_path(&path);
                     let exec_bit = ExecBit::new_from_repo(
                         file.executable.unwrap_or(false),
                         self.exec_policy,
                         get_prev_exec,
                     );
-                    let contents =
-                        materialize_merge_result_to_bytes(&file.contents, &file.labels, &options);
+                    let materialized = materialize_merge_result_to_bytes_with_tokenizer(
+                        &file.contents,
+                        &file.labels,
+                        &options,
+                        tokenizer,
+                    );
+                    for err in &materialized.tokenizer_errors {
+                        tracing::warn!(
+                            ?err,
+                            ?path,
+                            "word tokenizer failed while materializing conflict"
+                        );
+                    }
+                    let contents = materialized.merged;
                     let mut file_state =
                         self.write_conflict(&disk_path, &contents, exec_bit).await?;
                     file_state.materialized_conflict_data = Some(MaterializedConflictData {
@@ -16,8 +16,10 @@
 
 use std::any::Any;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::fmt::Write as _;
 use std::iter;
 use std::sync::LazyLock;
 use std::time::SystemTime;
@@ -30,6 +32,8 @@ use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
+use crate::content_hash::blake2b_hash;
 use crate::merge::Merge;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId as _;
@@ -245,7 +249,7 @@ pub struct LocalRemoteRefTarget<'a> {
 
 /// Represents the way the repo looks at a given time, just like how a Tree
 /// object represents how the file system looks at a given time.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct View {
     /// All head commits. There should be at least one head commit.
     pub head_ids: HashSet<CommitId>,
@@ -261,6 +265,66 @@ pub struct View {
     // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
     // precise: the commit to which we most recently completed an update to).
     pub wc_commit_ids: BTreeMap<WorkspaceNameBuf, CommitId>,
+    /// Shelved commits by name. These commits are hidden, so they aren't
+    /// included in `head_ids`.
+    pub shelves: BTreeMap<String, CommitId>,
+    /// Names of the bookmarks created automatically for pushing changes, which
+    /// can be deleted once the changes are merged.
+    pub push_bookmarks: BTreeSet<RefNameBuf>,
+    /// The Git notes commit `refs/notes/commits` should point to.
+    ///
+    /// Notes commits are stored as `CommitId`s, but they aren't jj commits.
+    /// They aren't indexed, and aren't included in
+    /// [`all_referenced_commit_ids()`](crate::view::View::all_referenced_commit_ids).
+    /// The notes commits of the retained operations are kept reachable in the
+    /// Git repository by [`git_notes_heads_for_gc()`](crate::git::git_notes_heads_for_gc).
+    pub git_notes: RefTarget,
+    /// The Git notes commit `refs/notes/commits` pointed to as of the last
+    /// import or export. Like `git_notes`, this isn't a jj commit.
+    pub git_notes_ref: RefTarget,
+}
+
+/// Version of the encoding of the view fields added after the view ids were
+/// first computed.
+const VIEW_EXTENSIONS_VERSION: u32 = 1;
+
+// Implemented manually so that the view ids don't change if there are no
+// shelves, push bookmarks, nor Git notes. If any of them is set, all of them
+// are hashed after the version number. New fields should bump the version.
+impl ContentHash for View {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let Self {
+            head_ids,
+            local_bookmarks,
+            local_tags,
+            remote_views,
+            git_refs,
+            git_head,
+            wc_commit_ids,
+            shelves,
+            push_bookmarks,
+            git_notes,
+            git_notes_ref,
+        } = self;
+        head_ids.hash(state);
+        local_bookmarks.hash(state);
+        local_tags.hash(state);
+        remote_views.hash(state);
+        git_refs.hash(state);
+        git_head.hash(state);
+        wc_commit_ids.hash(state);
+        let has_extensions = !shelves.is_empty()
+            || !push_bookmarks.is_empty()
+            || git_notes.is_present()
+            || git_notes_ref.is_present();
+        if has_extensions {
+            VIEW_EXTENSIONS_VERSION.hash(state);
+            shelves.hash(state);
+            push_bookmarks.hash(state);
+            git_notes.hash(state);
+            git_notes_ref.hash(state);
+        }
+    }
 }
 
 impl View {
@@ -274,6 +338,10 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: BTreeMap::new(),
+            shelves: BTreeMap::new(),
+            push_bookmarks: BTreeSet::new(),
+            git_notes: RefTarget::absent(),
+            git_notes_ref: RefTarget::absent(),
         }
     }
 }
@@ -356,7 +424,7 @@ pub struct TimestampRange {
 /// taken and it will be checked that the current head of the operation
 /// graph is unchanged. If the current head has changed, there has been
 /// concurrent operation.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug, serde::Serialize)]
+#[derive(PartialEq, Eq, Clone, Debug, serde::Serialize)]
 pub struct Operation {
     #[serde(skip)] // TODO: should be exposed?
     pub view_id: ViewId,
@@ -380,6 +448,31 @@ pub struct Operation {
     // cost matters, maybe this can be changed to sorted Vec.
     #[serde(skip)] // TODO: should be exposed?
     pub commit_predecessors: Option<BTreeMap<CommitId, Vec<CommitId>>>,
+    /// Signature of [`Self::signed_data()`] made by the configured signing
+    /// backend, or `None` if the operation isn't signed.
+    #[serde(skip)]
+    pub signature: Option<Vec<u8>>,
+}
+
+impl ContentHash for Operation {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let Self {
+            view_id,
+            parents,
+            metadata,
+            commit_predecessors,
+            signature,
+        } = self;
+        view_id.hash(state);
+        parents.hash(state);
+        metadata.hash(state);
+        commit_predecessors.hash(state);
+        // Hashed only if present so the ids of unsigned operations are the
+        // same as before operations could be signed.
+        if let Some(signature) = signature {
+            signature.hash(state);
+        }
+    }
 }
 
 impl Operation {
@@ -409,7 +502,27 @@ impl Operation {
             // may be other commits created within the abandoned operations.
             // They don't have any predecessors records as well.
             commit_predecessors: Some(BTreeMap::new()),
+            signature: None,
+        }
+    }
+
+    /// Returns the data to be signed by the operation signature.
+    ///
+    /// The data consists of the view id, the parent operation ids, and the id
+    /// the operation would have without the signature, which covers the
+    /// remaining fields.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        let mut data = format!("view {}\n", self.view_id.hex());
+        for parent_id in &self.parents {
+            writeln!(data, "parent {}", parent_id.hex()).unwrap();
         }
+        let unsigned_id = OperationId::new(blake2b_hash(&unsigned).to_vec());
+        writeln!(data, "operation {}", unsigned_id.hex()).unwrap();
+        data.into_bytes()
     }
 }
 
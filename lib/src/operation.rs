@@ -16,6 +16,7 @@
 
 use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::fmt::Display;
 use std::fmt::Error;
 use std::fmt::Formatter;
 use std::hash::Hash;
@@ -32,6 +33,11 @@ use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
 use crate::op_store::ViewId;
+use crate::signing::SigStatus;
+use crate::signing::SignError;
+use crate::signing::SignResult;
+use crate::signing::Signer;
+use crate::signing::Verification;
 use crate::view::View;
 
 /// A wrapper around [`op_store::Operation`] that defines additional methods and
@@ -151,7 +157,66 @@ impl Operation {
         })
     }
 
+    /// Returns the signature of the operation if it's signed.
+    pub fn signature(&self) -> Option<&[u8]> {
+        self.data.signature.as_deref()
+    }
+
+    /// Verifies the signature of the operation. Returns `None` if the
+    /// operation isn't signed.
+    pub fn verify_signature(&self, signer: &Signer) -> SignResult<Option<Verification>> {
+        self.data
+            .signature
+            .as_ref()
+            .map(|signature| signer.verify_data(&self.data.signed_data(), signature))
+            .transpose()
+    }
+
+    /// Verifies the signature of the operation. Returns the problem if the
+    /// operation isn't signed or its signature isn't good.
+    pub fn verification_problem(
+        &self,
+        signer: &Signer,
+    ) -> SignResult<Option<OpVerificationProblem>> {
+        match self.verify_signature(signer) {
+            Ok(None) => Ok(Some(OpVerificationProblem::Unsigned)),
+            Ok(Some(verification)) => match verification.status {
+                SigStatus::Good => Ok(None),
+                SigStatus::Unknown => Ok(Some(OpVerificationProblem::UnknownSignature)),
+                SigStatus::Bad => Ok(Some(OpVerificationProblem::BadSignature)),
+            },
+            Err(SignError::InvalidSignatureFormat) => {
+                Ok(Some(OpVerificationProblem::InvalidSignature))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn store_operation(&self) -> &op_store::Operation {
         &self.data
     }
 }
+
+/// Reason why an operation isn't verified by its signature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OpVerificationProblem {
+    /// The operation isn't signed.
+    Unsigned,
+    /// The signature doesn't match the operation.
+    BadSignature,
+    /// The signature can't be verified, e.g. because the key is unknown.
+    UnknownSignature,
+    /// The signature can't be parsed.
+    InvalidSignature,
+}
+
+impl Display for OpVerificationProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Unsigned => "is not signed",
+            Self::BadSignature => "has a bad signature",
+            Self::UnknownSignature => "has a signature that can't be verified",
+            Self::InvalidSignature => "has an invalid signature",
+        })
+    }
+}
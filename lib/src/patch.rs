@@ -0,0 +1,1135 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and applying patches in the unified diff format.
+//!
+//! Patches can be plain unified diffs, Git-style diffs, or email messages as
+//! produced by `git format-patch`. A series of messages can be read from an
+//! mbox file.
+//!
+//! Hunks which don't apply to the destination file are not rejected. Instead,
+//! the file is merged with the content of the patch, which may result in a
+//! conflict.
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+
+use bstr::BString;
+use bstr::ByteSlice as _;
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CopyId;
+use crate::backend::Timestamp;
+use crate::backend::TreeValue;
+use crate::conflict_labels::ConflictLabels;
+use crate::conflicts::MaterializedTreeValue;
+use crate::conflicts::materialize_tree_value;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree_builder::MergedTreeBuilder;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::store::Store;
+
+/// Error that may occur while parsing a patch.
+#[derive(Debug, Error)]
+pub enum PatchParseError {
+    /// The hunk header couldn't be parsed.
+    #[error("Invalid hunk header at line {line}: {header}")]
+    InvalidHunkHeader {
+        /// 1-based line number.
+        line: usize,
+        /// The hunk header line.
+        header: String,
+    },
+    /// The hunk has fewer lines than specified by its header.
+    #[error("Hunk ends unexpectedly at line {line}")]
+    TruncatedHunk {
+        /// 1-based line number.
+        line: usize,
+    },
+    /// The file mode isn't supported.
+    #[error("Unsupported file mode at line {line}: {mode}")]
+    InvalidMode {
+        /// 1-based line number.
+        line: usize,
+        /// The file mode.
+        mode: String,
+    },
+    /// The file path isn't a valid repository path.
+    #[error("Invalid path at line {line}: {path}")]
+    InvalidPath {
+        /// 1-based line number.
+        line: usize,
+        /// The file path.
+        path: String,
+    },
+    /// The patch contains binary changes.
+    #[error("Binary patch for {path} is not supported")]
+    BinaryPatch {
+        /// The file path.
+        path: String,
+    },
+}
+
+/// Error that may occur while applying a patch.
+#[derive(Debug, Error)]
+pub enum PatchApplyError {
+    /// Failed to read or write file contents.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// The destination file is conflicted.
+    #[error("Cannot apply patch to conflicted file {}", path.as_internal_file_string())]
+    ConflictedFile {
+        /// The file path.
+        path: RepoPathBuf,
+    },
+    /// The destination file is neither a regular file nor a symlink.
+    #[error("Cannot apply patch to {}: not a file", path.as_internal_file_string())]
+    UnsupportedFile {
+        /// The file path.
+        path: RepoPathBuf,
+    },
+    /// The patch would create a symlink with a non-UTF-8 target.
+    #[error("Invalid symlink target for {}", path.as_internal_file_string())]
+    InvalidSymlink {
+        /// The file path.
+        path: RepoPathBuf,
+    },
+}
+
+/// A patch to one or more files, with optional commit metadata.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Patch {
+    /// Author name parsed from the `From:` header.
+    pub author_name: Option<String>,
+    /// Author email parsed from the `From:` header.
+    pub author_email: Option<String>,
+    /// Author timestamp parsed from the `Date:` header.
+    pub author_timestamp: Option<Timestamp>,
+    /// Description built from the `Subject:` header and the message body.
+    /// Empty if the patch has no message.
+    pub description: String,
+    /// Changes to files.
+    pub files: Vec<FilePatch>,
+}
+
+/// Mode of a file in a patch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PatchFileMode {
+    /// Regular file (`100644`).
+    Normal,
+    /// Executable file (`100755`).
+    Executable,
+    /// Symbolic link (`120000`).
+    Symlink,
+}
+
+/// Changes to a single file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FilePatch {
+    /// Path of the original file. `None` if the file is added.
+    pub old_path: Option<RepoPathBuf>,
+    /// Path of the new file. `None` if the file is deleted.
+    pub new_path: Option<RepoPathBuf>,
+    /// Mode of the original file, if specified.
+    pub old_mode: Option<PatchFileMode>,
+    /// Mode of the new file, if specified.
+    pub new_mode: Option<PatchFileMode>,
+    /// Whether the original file is kept if the paths differ.
+    pub is_copy: bool,
+    /// Content changes.
+    pub hunks: Vec<PatchHunk>,
+}
+
+/// Lines replaced by a patch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PatchHunk {
+    /// 1-based line number of the first original line. If there are no
+    /// original lines, this is the line after which the new lines are
+    /// inserted.
+    pub old_start: usize,
+    /// Original lines including context.
+    pub old_lines: Vec<BString>,
+    /// New lines including context.
+    pub new_lines: Vec<BString>,
+}
+
+/// Result of [`apply_patch()`].
+#[derive(Clone, Debug)]
+pub struct AppliedPatch {
+    /// The patched tree, which may contain conflicts.
+    pub tree: MergedTree,
+    /// Paths of the files whose hunks didn't match the destination contents.
+    /// These files were merged with the contents of the patch.
+    pub mismatched_paths: Vec<RepoPathBuf>,
+}
+
+/// Parses patches from `input`, which may be an mbox file containing a patch
+/// series, a single email message, or a bare diff.
+pub fn parse_patches(input: &[u8]) -> Result<Vec<Patch>, PatchParseError> {
+    let lines = input.split_inclusive(|&b| b == b'\n').collect_vec();
+    let message_starts = (0..lines.len())
+        .filter(|&i| {
+            lines[i].starts_with(b"From ") && (i == 0 || trim_eol(lines[i - 1]).is_empty())
+        })
+        .collect_vec();
+    if message_starts.is_empty() {
+        if lines.iter().all(|line| line.trim().is_empty()) {
+            return Ok(vec![]);
+        }
+        return Ok(vec![parse_message(&lines, 0)?]);
+    }
+    message_starts
+        .iter()
+        .zip(message_starts.iter().skip(1).chain([&lines.len()]))
+        .map(|(&start, &end)| parse_message(&lines[start + 1..end], start + 1))
+        .try_collect()
+}
+
+/// Parses a single message. `offset` is the number of lines before `lines` in
+/// the input.
+fn parse_message(lines: &[&[u8]], offset: usize) -> Result<Patch, PatchParseError> {
+    let mut patch = Patch::default();
+    let mut pos = 0;
+    let mut subject = None;
+    if lines.first().is_some_and(|line| is_header_line(line)) {
+        let mut headers: Vec<(String, String)> = vec![];
+        while pos < lines.len() {
+            let line = trim_eol(lines[pos]);
+            if line.is_empty() {
+                pos += 1;
+                break;
+            }
+            if line.starts_with(b" ") || line.starts_with(b"\t") {
+                // Continuation of a folded header
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.to_str_lossy().trim());
+                }
+            } else if let Some((name, value)) = line.split_once_str(":") {
+                headers.push((
+                    name.to_str_lossy().trim().to_ascii_lowercase(),
+                    value.to_str_lossy().trim().to_owned(),
+                ));
+            }
+            pos += 1;
+        }
+        for (name, value) in headers {
+            match name.as_str() {
+                "from" => {
+                    let (name, email) = parse_address(&decode_header_value(&value));
+                    patch.author_name = Some(name);
+                    patch.author_email = Some(email);
+                }
+                "date" => {
+                    patch.author_timestamp = chrono::DateTime::parse_from_rfc2822(&value)
+                        .ok()
+                        .map(Timestamp::from_datetime);
+                }
+                "subject" => {
+                    subject = Some(strip_subject_prefix(&decode_header_value(&value)).to_owned());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let body_start = pos;
+    while pos < lines.len() && !is_diff_start(lines, pos) && trim_eol(lines[pos]) != b"---" {
+        pos += 1;
+    }
+    let body = lines[body_start..pos]
+        .iter()
+        .map(|line| unescape_from_line(line))
+        .collect_vec()
+        .concat()
+        .to_str_lossy()
+        .trim()
+        .to_owned();
+    patch.description = match (subject, body.is_empty()) {
+        (Some(subject), true) => format!("{subject}\n"),
+        (Some(subject), false) => format!("{subject}\n\n{body}\n"),
+        (None, true) => String::new(),
+        (None, false) => format!("{body}\n"),
+    };
+
+    while pos < lines.len() {
+        if trim_eol(lines[pos]) == b"-- " {
+            // Signature
+            break;
+        } else if is_diff_start(lines, pos) {
+            let (file, next_pos) = parse_file_patch(lines, pos, offset)?;
+            patch.files.push(file);
+            pos = next_pos;
+        } else {
+            pos += 1;
+        }
+    }
+    Ok(patch)
+}
+
+fn is_header_line(line: &[u8]) -> bool {
+    let Some((name, _)) = trim_eol(line).split_once_str(":") else {
+        return false;
+    };
+    !name.is_empty() && name.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+fn is_diff_start(lines: &[&[u8]], pos: usize) -> bool {
+    lines[pos].starts_with(b"diff --git ")
+        || (lines[pos].starts_with(b"--- ")
+            && lines
+                .get(pos + 1)
+                .is_some_and(|line| line.starts_with(b"+++ ")))
+}
+
+/// Parses the changes to a single file starting at `pos`. Returns the index of
+/// the line following the file patch.
+fn parse_file_patch(
+    lines: &[&[u8]],
+    mut pos: usize,
+    offset: usize,
+) -> Result<(FilePatch, usize), PatchParseError> {
+    let line_number = |pos: usize| offset + pos + 1;
+    let parse_path = |path: &str, pos: usize| -> Result<RepoPathBuf, PatchParseError> {
+        RepoPathBuf::from_internal_string(path).map_err(|_| PatchParseError::InvalidPath {
+            line: line_number(pos),
+            path: path.to_owned(),
+        })
+    };
+    let parse_mode = |mode: &str, pos: usize| match mode.trim() {
+        "100644" | "100664" => Ok(PatchFileMode::Normal),
+        "100755" => Ok(PatchFileMode::Executable),
+        "120000" => Ok(PatchFileMode::Symlink),
+        mode => Err(PatchParseError::InvalidMode {
+            line: line_number(pos),
+            mode: mode.to_owned(),
+        }),
+    };
+
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut old_mode = None;
+    let mut new_mode = None;
+    let mut is_copy = false;
+    let mut is_new_file = false;
+    let mut is_deleted_file = false;
+    if let Some(paths) = lines[pos].strip_prefix(b"diff --git ") {
+        let paths = trim_eol(paths).to_str_lossy();
+        if let Some((old, new)) = split_git_diff_paths(&paths) {
+            old_path = Some(parse_path(old, pos)?);
+            new_path = Some(parse_path(new, pos)?);
+        }
+        pos += 1;
+        while pos < lines.len() {
+            let line = trim_eol(lines[pos]).to_str_lossy();
+            if let Some(mode) = line.strip_prefix("old mode ") {
+                old_mode = Some(parse_mode(mode, pos)?);
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                new_mode = Some(parse_mode(mode, pos)?);
+            } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+                old_mode = Some(parse_mode(mode, pos)?);
+                is_deleted_file = true;
+            } else if let Some(mode) = line.strip_prefix("new file mode ") {
+                new_mode = Some(parse_mode(mode, pos)?);
+                is_new_file = true;
+            } else if let Some(path) = line
+                .strip_prefix("rename from ")
+                .or_else(|| line.strip_prefix("copy from "))
+            {
+                old_path = Some(parse_path(path, pos)?);
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                new_path = Some(parse_path(path, pos)?);
+            } else if let Some(path) = line.strip_prefix("copy to ") {
+                new_path = Some(parse_path(path, pos)?);
+                is_copy = true;
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                let path = new_path.as_ref().or(old_path.as_ref());
+                return Err(PatchParseError::BinaryPatch {
+                    path: path
+                        .map_or("", |path| path.as_internal_file_string())
+                        .to_owned(),
+                });
+            } else if !(line.starts_with("index ")
+                || line.starts_with("similarity index ")
+                || line.starts_with("dissimilarity index "))
+            {
+                break;
+            }
+            pos += 1;
+        }
+    }
+
+    if pos + 1 < lines.len()
+        && lines[pos].starts_with(b"--- ")
+        && lines[pos + 1].starts_with(b"+++ ")
+    {
+        let old = parse_diff_path(&lines[pos][4..]);
+        let new = parse_diff_path(&lines[pos + 1][4..]);
+        let has_prefix = (old.is_some() || new.is_some())
+            && old.as_ref().is_none_or(|path| path.starts_with("a/"))
+            && new.as_ref().is_none_or(|path| path.starts_with("b/"));
+        let strip = |path: String| {
+            if has_prefix {
+                path[2..].to_owned()
+            } else {
+                path
+            }
+        };
+        old_path = old
+            .map(strip)
+            .map(|path| parse_path(&path, pos))
+            .transpose()?;
+        new_path = new
+            .map(strip)
+            .map(|path| parse_path(&path, pos + 1))
+            .transpose()?;
+        pos += 2;
+    } else if is_new_file {
+        old_path = None;
+    } else if is_deleted_file {
+        new_path = None;
+    }
+    if old_path.is_none() && new_path.is_none() {
+        return Err(PatchParseError::InvalidPath {
+            line: line_number(pos),
+            path: "/dev/null".to_owned(),
+        });
+    }
+
+    let mut hunks = vec![];
+    while pos < lines.len() && lines[pos].starts_with(b"@@ ") {
+        let (hunk, next_pos) = parse_hunk(lines, pos, offset)?;
+        hunks.push(hunk);
+        pos = next_pos;
+    }
+    let file = FilePatch {
+        old_path,
+        new_path,
+        old_mode,
+        new_mode,
+        is_copy,
+        hunks,
+    };
+    Ok((file, pos))
+}
+
+/// Splits the paths in a `diff --git a/<old> b/<new>` header. This is
+/// ambiguous if the paths contain spaces, so the paths are expected to be the
+/// same in that case.
+fn split_git_diff_paths(paths: &str) -> Option<(&str, &str)> {
+    let paths = paths.strip_prefix("a/")?;
+    let candidates = paths.match_indices(" b/").map(|(i, _)| i).collect_vec();
+    let index = candidates
+        .iter()
+        .copied()
+        .find(|&i| paths[..i] == paths[i + 3..])
+        .or_else(|| candidates.first().copied())?;
+    Some((&paths[..index], &paths[index + 3..]))
+}
+
+/// Parses the path in a `---` or `+++` line. Returns `None` for `/dev/null`.
+fn parse_diff_path(line: &[u8]) -> Option<String> {
+    let line = trim_eol(line).to_str_lossy();
+    // Plain diffs may have a timestamp after a tab.
+    let path = line.split('\t').next().unwrap();
+    (path != "/dev/null").then(|| path.to_owned())
+}
+
+fn parse_hunk(
+    lines: &[&[u8]],
+    mut pos: usize,
+    offset: usize,
+) -> Result<(PatchHunk, usize), PatchParseError> {
+    let header = trim_eol(lines[pos]).to_str_lossy();
+    let invalid_header = || PatchParseError::InvalidHunkHeader {
+        line: offset + pos + 1,
+        header: header.clone().into_owned(),
+    };
+    let ranges = header
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split_once(" @@"))
+        .map(|(ranges, _)| ranges)
+        .ok_or_else(invalid_header)?;
+    let (old_range, new_range) = ranges.split_once(' ').ok_or_else(invalid_header)?;
+    let parse_range = |range: Option<&str>| -> Option<(usize, usize)> {
+        let range = range?;
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, mut old_count) =
+        parse_range(old_range.strip_prefix('-')).ok_or_else(invalid_header)?;
+    let (_, mut new_count) = parse_range(new_range.strip_prefix('+')).ok_or_else(invalid_header)?;
+    pos += 1;
+
+    let mut old_lines: Vec<BString> = vec![];
+    let mut new_lines: Vec<BString> = vec![];
+    // Whether the last line was added to old_lines and new_lines
+    let mut last_line_sides = (false, false);
+    while old_count > 0 || new_count > 0 || lines.get(pos).is_some_and(|l| l.starts_with(b"\\")) {
+        let Some(line) = lines.get(pos) else {
+            return Err(PatchParseError::TruncatedHunk {
+                line: offset + pos + 1,
+            });
+        };
+        match line.first() {
+            Some(b' ') if old_count > 0 && new_count > 0 => {
+                old_lines.push(line[1..].into());
+                new_lines.push(line[1..].into());
+                old_count -= 1;
+                new_count -= 1;
+                last_line_sides = (true, true);
+            }
+            // Some email clients strip the trailing space of empty context
+            // lines.
+            Some(b'\n' | b'\r') if old_count > 0 && new_count > 0 => {
+                old_lines.push("\n".into());
+                new_lines.push("\n".into());
+                old_count -= 1;
+                new_count -= 1;
+                last_line_sides = (true, true);
+            }
+            Some(b'-') if old_count > 0 => {
+                old_lines.push(line[1..].into());
+                old_count -= 1;
+                last_line_sides = (true, false);
+            }
+            Some(b'+') if new_count > 0 => {
+                new_lines.push(line[1..].into());
+                new_count -= 1;
+                last_line_sides = (false, true);
+            }
+            Some(b'\\') => {
+                // "\ No newline at end of file" applies to the previous line.
+                let (old, new) = last_line_sides;
+                for (side, lines) in [(old, &mut old_lines), (new, &mut new_lines)] {
+                    if side && let Some(last) = lines.last_mut() {
+                        let trimmed_len = trim_eol(last).len();
+                        last.truncate(trimmed_len);
+                    }
+                }
+            }
+            _ => {
+                return Err(PatchParseError::TruncatedHunk {
+                    line: offset + pos + 1,
+                });
+            }
+        }
+        pos += 1;
+    }
+    let hunk = PatchHunk {
+        old_start,
+        old_lines,
+        new_lines,
+    };
+    Ok((hunk, pos))
+}
+
+fn trim_eol(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Parses `Name <email>`.
+fn parse_address(value: &str) -> (String, String) {
+    match value.rsplit_once('<') {
+        Some((name, email)) => {
+            let name = name.trim().trim_matches('"');
+            let email = email.trim_end_matches('>').trim();
+            (name.to_owned(), email.to_owned())
+        }
+        None => (String::new(), value.trim().to_owned()),
+    }
+}
+
+/// Removes `[PATCH ...]` prefixes from the subject.
+fn strip_subject_prefix(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while let Some(rest) = subject.strip_prefix('[')
+        && let Some((_, rest)) = rest.split_once(']')
+    {
+        subject = rest.trim_start();
+    }
+    subject
+}
+
+/// Removes the `>` added in front of `From ` lines in an mbox body.
+fn unescape_from_line(line: &[u8]) -> &[u8] {
+    match line.strip_prefix(b">") {
+        Some(rest) if rest.trim_start_with(|c| c == '>').starts_with(b"From ") => rest,
+        _ => line,
+    }
+}
+
+/// Encodes the header value as RFC 2047 encoded words using the "Q" encoding
+/// if it contains non-ASCII characters.
+pub fn encode_header_value(value: &str) -> Cow<'_, str> {
+    // Encoded words can't be longer than 75 characters.
+    const MAX_TEXT_LEN: usize = 75 - "=?UTF-8?q??=".len();
+    if value.is_ascii() {
+        return Cow::Borrowed(value);
+    }
+    let mut words = vec![];
+    let mut text = String::new();
+    for c in value.chars() {
+        let mut encoded = String::new();
+        if c == ' ' {
+            encoded.push('_');
+        } else if c.is_ascii_alphanumeric() || "!*+-/".contains(c) {
+            encoded.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                write!(encoded, "={b:02X}").unwrap();
+            }
+        }
+        if text.len() + encoded.len() > MAX_TEXT_LEN {
+            words.push(format!("=?UTF-8?q?{text}?="));
+            text.clear();
+        }
+        text.push_str(&encoded);
+    }
+    words.push(format!("=?UTF-8?q?{text}?="));
+    // Fold the header between the encoded words
+    Cow::Owned(words.join("\n "))
+}
+
+/// Decodes RFC 2047 encoded words using the "Q" encoding, as emitted by `git
+/// format-patch` for non-ASCII headers. Other encodings are left as is.
+fn decode_header_value(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut last_was_encoded = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        let Some((charset, encoding, text, after)) = split_encoded_word(word) else {
+            decoded.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            last_was_encoded = false;
+            continue;
+        };
+        if !charset.eq_ignore_ascii_case("utf-8") || !encoding.eq_ignore_ascii_case("q") {
+            decoded.push_str(&rest[..rest.len() - after.len()]);
+            rest = after;
+            last_was_encoded = false;
+            continue;
+        }
+        // Whitespace between adjacent encoded words is ignored.
+        if !(last_was_encoded && before.trim().is_empty()) {
+            decoded.push_str(before);
+        }
+        decoded.push_str(&decode_q(text));
+        rest = after;
+        last_was_encoded = true;
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Splits `=?charset?encoding?text?=rest`.
+fn split_encoded_word(word: &str) -> Option<(&str, &str, &str, &str)> {
+    let word = word.strip_prefix("=?")?;
+    let (charset, word) = word.split_once('?')?;
+    let (encoding, word) = word.split_once('?')?;
+    let (text, rest) = word.split_once("?=")?;
+    Some((charset, encoding, text, rest))
+}
+
+fn decode_q(text: &str) -> String {
+    let mut bytes = vec![];
+    let mut iter = text.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hex = [iter.next().unwrap_or(b'0'), iter.next().unwrap_or(b'0')];
+                let value = std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                bytes.extend(value);
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Applies `hunks` to `content`. Returns `None` if a hunk doesn't match the
+/// content.
+///
+/// Hunks are looked up near the line numbers in their headers, so the
+/// content may have lines added or removed before the hunks.
+pub fn apply_hunks(content: &[u8], hunks: &[PatchHunk]) -> Option<BString> {
+    let lines = content.split_inclusive(|&b| b == b'\n').collect_vec();
+    let mut result = BString::default();
+    let mut pos = 0;
+    let mut offset: isize = 0;
+    for hunk in hunks {
+        let old_len = hunk.old_lines.len();
+        let nominal = if old_len == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = nominal.saturating_add_signed(offset);
+        let start = find_hunk_start(&lines, pos, expected, &hunk.old_lines)?;
+        result.extend(lines[pos..start].concat());
+        result.extend(hunk.new_lines.concat());
+        pos = start + old_len;
+        offset = start as isize - nominal as isize;
+    }
+    result.extend(lines[pos..].concat());
+    Some(result)
+}
+
+/// Finds the line where `old_lines` appear, starting from `expected` and
+/// moving away from it. Lines before `min_start` are not considered.
+fn find_hunk_start(
+    lines: &[&[u8]],
+    min_start: usize,
+    expected: usize,
+    old_lines: &[BString],
+) -> Option<usize> {
+    let max_start = lines.len().checked_sub(old_lines.len())?;
+    if min_start > max_start {
+        return None;
+    }
+    let expected = expected.clamp(min_start, max_start);
+    if old_lines.is_empty() {
+        return Some(expected);
+    }
+    let matches_at = |start: usize| {
+        lines[start..start + old_lines.len()]
+            .iter()
+            .zip(old_lines)
+            .all(|(line, old_line)| *line == old_line.as_slice())
+    };
+    let max_distance = (expected - min_start).max(max_start - expected);
+    (0..=max_distance)
+        .flat_map(|distance| {
+            let before = expected.checked_sub(distance).filter(|&i| i >= min_start);
+            let after = (distance > 0)
+                .then_some(expected + distance)
+                .filter(|&i| i <= max_start);
+            before.into_iter().chain(after)
+        })
+        .find(|&start| matches_at(start))
+}
+
+/// Applies the file changes in `patch` to `tree`.
+///
+/// Files whose hunks don't match are 3-way merged: the destination file is
+/// merged with the new lines of the patch, using the original lines of the
+/// patch as the base. The base and patched contents are built from the
+/// destination file by replacing the lines at the position stated in each
+/// hunk header. The result may contain conflicts, which are labeled
+/// with `tree_label` and `patch_label`.
+pub async fn apply_patch(
+    tree: &MergedTree,
+    tree_label: String,
+    patch: &Patch,
+    patch_label: String,
+) -> Result<AppliedPatch, PatchApplyError> {
+    let store = tree.store();
+    let mut base_builder = MergedTreeBuilder::new(tree.clone());
+    let mut patched_builder = MergedTreeBuilder::new(tree.clone());
+    let mut mismatched_paths = vec![];
+    for file in &patch.files {
+        let source_path = file
+            .old_path
+            .as_deref()
+            .or(file.new_path.as_deref())
+            .unwrap();
+        let current = read_file(tree, source_path).await?;
+        let current_mode = current.as_ref().map(|(_, mode)| *mode);
+        let old_mode = file
+            .old_mode
+            .or(current_mode)
+            .unwrap_or(PatchFileMode::Normal);
+        let new_mode = file.new_mode.or(file.old_mode).or(current_mode);
+        let new_mode = new_mode.unwrap_or(PatchFileMode::Normal);
+        let applied = match (&current, &file.old_path) {
+            (None, None) => apply_hunks(b"", &file.hunks),
+            (Some((content, _)), Some(_)) => apply_hunks(content, &file.hunks),
+            // The file to add already exists, or the file to modify doesn't
+            (Some(_), None) | (None, Some(_)) => None,
+        };
+        let applied = applied.filter(|content| file.new_path.is_some() || content.is_empty());
+        let removes_old_path = file.old_path.is_some() && file.old_path != file.new_path;
+        if let Some(content) = applied {
+            if let Some(new_path) = &file.new_path {
+                let value = write_file(store, new_path, &content, new_mode).await?;
+                patched_builder.set_or_remove(new_path.clone(), Merge::normal(value));
+            }
+            if removes_old_path && !file.is_copy {
+                let old_path = file.old_path.clone().unwrap();
+                patched_builder.set_or_remove(old_path, Merge::absent());
+            }
+        } else {
+            mismatched_paths.push(source_path.to_owned());
+            let content = match (&current, &file.old_path) {
+                (Some((content, _)), Some(_)) => content.as_slice(),
+                _ => b"",
+            };
+            let (old_content, new_content) = splice_hunks(content, &file.hunks);
+            match &file.old_path {
+                Some(old_path) => {
+                    let value = write_file(store, old_path, &old_content, old_mode).await?;
+                    base_builder.set_or_remove(old_path.clone(), Merge::normal(value));
+                }
+                None => {
+                    let new_path = file.new_path.clone().unwrap();
+                    base_builder.set_or_remove(new_path, Merge::absent());
+                }
+            }
+            if let Some(new_path) = &file.new_path {
+                let value = write_file(store, new_path, &new_content, new_mode).await?;
+                patched_builder.set_or_remove(new_path.clone(), Merge::normal(value));
+            }
+            if removes_old_path && !file.is_copy {
+                let old_path = file.old_path.clone().unwrap();
+                patched_builder.set_or_remove(old_path, Merge::absent());
+            }
+        }
+    }
+    let base_tree = base_builder.write_tree().await?;
+    let patched_tree = patched_builder.write_tree().await?;
+    let tree = MergedTree::merge(Merge::from_vec(vec![
+        (tree.clone(), format!("{tree_label} (patch destination)")),
+        (base_tree, format!("{patch_label} (patch context)")),
+        (patched_tree, format!("{patch_label} (patch)")),
+    ]))
+    .await?;
+    Ok(AppliedPatch {
+        tree,
+        mismatched_paths,
+    })
+}
+
+/// Replaces the lines at the position stated in each hunk header with the
+/// original and the new lines of the hunk respectively. Lines outside the
+/// hunks are taken from `content`, so the resulting base and patched contents
+/// only differ from `content` where the hunks are expected to apply.
+fn splice_hunks(content: &[u8], hunks: &[PatchHunk]) -> (BString, BString) {
+    let lines = content.split_inclusive(|&b| b == b'\n').collect_vec();
+    let mut old_content = BString::default();
+    let mut new_content = BString::default();
+    let mut pos = 0;
+    for hunk in hunks {
+        let old_len = hunk.old_lines.len();
+        let nominal = if old_len == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let start = nominal.clamp(pos, lines.len());
+        let end = (start + old_len).min(lines.len());
+        let unchanged = lines[pos..start].concat();
+        old_content.extend_from_slice(&unchanged);
+        new_content.extend_from_slice(&unchanged);
+        old_content.extend(hunk.old_lines.concat());
+        new_content.extend(hunk.new_lines.concat());
+        pos = end;
+    }
+    let unchanged = lines[pos..].concat();
+    old_content.extend_from_slice(&unchanged);
+    new_content.extend_from_slice(&unchanged);
+    (old_content, new_content)
+}
+
+async fn read_file(
+    tree: &MergedTree,
+    path: &RepoPath,
+) -> Result<Option<(Vec<u8>, PatchFileMode)>, PatchApplyError> {
+    let value = tree.path_value(path).await?;
+    if !value.is_resolved() {
+        return Err(PatchApplyError::ConflictedFile {
+            path: path.to_owned(),
+        });
+    }
+    let materialized =
+        materialize_tree_value(tree.store(), path, value, &ConflictLabels::unlabeled()).await?;
+    match materialized {
+        MaterializedTreeValue::Absent => Ok(None),
+        MaterializedTreeValue::File(mut file) => {
+            let mode = if file.executable {
+                PatchFileMode::Executable
+            } else {
+                PatchFileMode::Normal
+            };
+            Ok(Some((file.read_all(path).await?, mode)))
+        }
+        MaterializedTreeValue::Symlink { id: _, target } => {
+            Ok(Some((target.into_bytes(), PatchFileMode::Symlink)))
+        }
+        _ => Err(PatchApplyError::UnsupportedFile {
+            path: path.to_owned(),
+        }),
+    }
+}
+
+async fn write_file(
+    store: &Store,
+    path: &RepoPath,
+    content: &[u8],
+    mode: PatchFileMode,
+) -> Result<TreeValue, PatchApplyError> {
+    match mode {
+        PatchFileMode::Normal | PatchFileMode::Executable => {
+            let id = store.write_file(path, &mut &*content).await?;
+            Ok(TreeValue::File {
+                id,
+                executable: mode == PatchFileMode::Executable,
+                copy_id: CopyId::placeholder(),
+            })
+        }
+        PatchFileMode::Symlink => {
+            let target =
+                std::str::from_utf8(content).map_err(|_| PatchApplyError::InvalidSymlink {
+                    path: path.to_owned(),
+                })?;
+            let id = store.write_symlink(path, target).await?;
+            Ok(TreeValue::Symlink(id))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn hunk(old_start: usize, old_lines: &[&str], new_lines: &[&str]) -> PatchHunk {
+        PatchHunk {
+            old_start,
+            old_lines: old_lines.iter().map(|&line| line.into()).collect(),
+            new_lines: new_lines.iter().map(|&line| line.into()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_format_patch_series() {
+        let input = indoc! {"
+            From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+            From: =?UTF-8?q?J=C3=B6rg=20User?= <jorg@example.com>
+            Date: Tue, 1 Apr 2025 12:00:00 +0200
+            Subject: [PATCH 1/2] Add a
+             file
+
+            The body.
+            ---
+             a | 1 +
+             1 file changed, 1 insertion(+)
+
+            diff --git a/a b/a
+            new file mode 100644
+            index 0000000000..7898192261
+            --- /dev/null
+            +++ b/a
+            @@ -0,0 +1 @@
+            +a
+            --\x20
+            2.40.0
+
+            From 1123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+            From: Test User <test@example.com>
+            Subject: [PATCH 2/2] Rename a
+
+            ---
+            diff --git a/a b/dir/b
+            old mode 100644
+            new mode 100755
+            similarity index 50%
+            rename from a
+            rename to dir/b
+            index 7898192261..e61ef7b965
+            --- a/a
+            +++ b/dir/b
+            @@ -1 +1,2 @@
+            -a
+            +b
+            +c
+            \\ No newline at end of file
+        "};
+        let patches = parse_patches(input.as_bytes()).unwrap();
+        assert_eq!(patches.len(), 2);
+
+        assert_eq!(patches[0].author_name.as_deref(), Some("Jörg User"));
+        assert_eq!(patches[0].author_email.as_deref(), Some("jorg@example.com"));
+        let timestamp = patches[0].author_timestamp.unwrap();
+        assert_eq!(timestamp.timestamp.0, 1743501600000);
+        assert_eq!(timestamp.tz_offset, 120);
+        assert_eq!(patches[0].description, "Add a file\n\nThe body.\n");
+        assert_eq!(
+            patches[0].files,
+            vec![FilePatch {
+                old_path: None,
+                new_path: Some(RepoPathBuf::from_internal_string("a").unwrap()),
+                old_mode: None,
+                new_mode: Some(PatchFileMode::Normal),
+                is_copy: false,
+                hunks: vec![hunk(0, &[], &["a\n"])],
+            }]
+        );
+
+        assert_eq!(patches[1].author_timestamp, None);
+        assert_eq!(patches[1].description, "Rename a\n");
+        assert_eq!(
+            patches[1].files,
+            vec![FilePatch {
+                old_path: Some(RepoPathBuf::from_internal_string("a").unwrap()),
+                new_path: Some(RepoPathBuf::from_internal_string("dir/b").unwrap()),
+                old_mode: Some(PatchFileMode::Normal),
+                new_mode: Some(PatchFileMode::Executable),
+                is_copy: false,
+                hunks: vec![hunk(1, &["a\n"], &["b\n", "c"])],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_diff() {
+        let input = indoc! {"
+            --- file\t2025-01-01 00:00:00
+            +++ file\t2025-01-02 00:00:00
+            @@ -1,3 +1,3 @@
+             a
+            -b
+            +B
+
+            --- a/deleted
+            +++ /dev/null
+            @@ -1 +0,0 @@
+            -x
+        "};
+        let patches = parse_patches(input.as_bytes()).unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].description, "");
+        let files = &patches[0].files;
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].old_path, files[0].new_path);
+        assert_eq!(
+            files[0].hunks,
+            vec![hunk(1, &["a\n", "b\n", "\n"], &["a\n", "B\n", "\n"])]
+        );
+        assert_eq!(
+            files[1].old_path,
+            Some(RepoPathBuf::from_internal_string("deleted").unwrap())
+        );
+        assert_eq!(files[1].new_path, None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = indoc! {"
+            --- a/file
+            +++ b/file
+            @@ -1,2 +1,2 @@
+             a
+        "};
+        assert_matches::assert_matches!(
+            parse_patches(input.as_bytes()),
+            Err(PatchParseError::TruncatedHunk { line: 5 })
+        );
+
+        let input = indoc! {"
+            diff --git a/file b/file
+            index 0123456789..abcdef0123 100644
+            Binary files a/file and b/file differ
+        "};
+        assert_matches::assert_matches!(
+            parse_patches(input.as_bytes()),
+            Err(PatchParseError::BinaryPatch { path }) if path == "file"
+        );
+    }
+
+    #[test]
+    fn test_encode_header_value() {
+        assert_eq!(encode_header_value("ascii text"), "ascii text");
+        assert_eq!(
+            encode_header_value("[PATCH] Jörg's fix"),
+            "=?UTF-8?q?=5BPATCH=5D_J=C3=B6rg=27s_fix?="
+        );
+        // Long values are split into multiple encoded words
+        let value = "ä".repeat(20);
+        let encoded = encode_header_value(&value);
+        assert!(
+            encoded.lines().all(|line| line.trim().len() <= 75),
+            "{encoded}"
+        );
+        assert_eq!(decode_header_value(&encoded.replace('\n', "")), value);
+    }
+
+    #[test]
+    fn test_parse_escaped_from_line() {
+        let input = indoc! {"
+            From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+            From: Test User <test.user@example.com>
+            Subject: [PATCH] Subject
+
+            >From the body.
+            >>From a quote.
+            ---
+        "};
+        let patches = parse_patches(input.as_bytes()).unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(
+            patches[0].description,
+            "Subject\n\nFrom the body.\n>From a quote.\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_subject_prefix() {
+        assert_eq!(strip_subject_prefix("[PATCH] foo"), "foo");
+        assert_eq!(strip_subject_prefix("[PATCH v2 1/3] [RFC] foo"), "foo");
+        assert_eq!(strip_subject_prefix("foo [bar]"), "foo [bar]");
+    }
+
+    #[test]
+    fn test_apply_hunks() {
+        let content = b"1\n2\n3\n4\n5\n";
+        // Exact position
+        assert_eq!(
+            apply_hunks(content, &[hunk(2, &["2\n", "3\n"], &["2\n", "x\n"])]).unwrap(),
+            "1\n2\nx\n4\n5\n"
+        );
+        // Shifted position
+        assert_eq!(
+            apply_hunks(content, &[hunk(1, &["3\n", "4\n"], &["3\n"])]).unwrap(),
+            "1\n2\n3\n5\n"
+        );
+        // Insertion after a line
+        assert_eq!(
+            apply_hunks(content, &[hunk(5, &[], &["6\n"])]).unwrap(),
+            "1\n2\n3\n4\n5\n6\n"
+        );
+        // Mismatch
+        assert_eq!(apply_hunks(content, &[hunk(2, &["x\n"], &["y\n"])]), None);
+        // Hunks must not overlap
+        assert_eq!(
+            apply_hunks(
+                content,
+                &[hunk(3, &["3\n"], &["x\n"]), hunk(1, &["1\n"], &["y\n"]),]
+            ),
+            None
+        );
+    }
+}
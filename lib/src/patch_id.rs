@@ -0,0 +1,222 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Content-based identification of the changes made by commits.
+//!
+//! A patch id is computed from the lines added and removed by a commit, not
+//! from the contents of its parents. Commits which make the same changes on
+//! top of different parents, such as rebased or cherry-picked commits, usually
+//! have the same patch id, similar to `git patch-id`. Whitespace is ignored
+//! when comparing and hashing lines, unless a file only has whitespace
+//! changes.
+
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use blake2::Blake2b512;
+use digest::Digest as _;
+use futures::StreamExt as _;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::TreeValue;
+use crate::commit::Commit;
+use crate::content_hash::ContentHash as _;
+use crate::diff::CompareBytes;
+use crate::diff::CompareBytesExactly;
+use crate::diff::CompareBytesIgnoreAllWhitespace;
+use crate::diff::ContentDiff;
+use crate::diff::DiffHunkKind;
+use crate::diff::find_line_ranges;
+use crate::hex_util;
+use crate::index::Index;
+use crate::matchers::EverythingMatcher;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::TreeDiffEntry;
+use crate::repo::Repo;
+use crate::repo_path::RepoPath;
+use crate::rewrite::merge_commit_trees_no_resolve_without_repo;
+use crate::store::Store;
+
+/// Identifier of the changes made by a commit.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PatchId(Vec<u8>);
+
+impl PatchId {
+    /// Length of patch ids in bytes.
+    pub(crate) const LENGTH: usize = 64;
+
+    /// Creates a patch id from the bytes of a previously computed id.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+
+    /// Returns the patch id as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the patch id in hexadecimal form.
+    pub fn hex(&self) -> String {
+        hex_util::encode_hex(&self.0)
+    }
+}
+
+/// Computes the patch id of the changes made by `commit` to its parents.
+///
+/// Returns `None` if the commit is empty.
+pub async fn commit_patch_id(repo: &dyn Repo, commit: &Commit) -> BackendResult<Option<PatchId>> {
+    let from_tree = commit.parent_tree(repo).await?;
+    compute_patch_id(&from_tree, &commit.tree()).await
+}
+
+/// Like [`commit_patch_id()`], but looks up the parents in the given `index`.
+pub async fn commit_patch_id_without_repo(
+    store: &Arc<Store>,
+    index: &dyn Index,
+    commit: &Commit,
+) -> BackendResult<Option<PatchId>> {
+    let parents = commit.parents().await?;
+    let from_tree = if let [parent] = parents.as_slice() {
+        parent.tree()
+    } else {
+        merge_commit_trees_no_resolve_without_repo(store, index, &parents)
+            .await?
+            .resolve()
+            .await?
+    };
+    compute_patch_id(&from_tree, &commit.tree()).await
+}
+
+/// Computes the patch id of the changes from `from_tree` to `to_tree`.
+///
+/// Files are compared line by line, ignoring the positions of the changed
+/// lines and whitespace. Files with only whitespace changes are compared
+/// including whitespace, so that different whitespace changes have different
+/// patch ids. Symlinks, submodules, and conflicts are compared by their ids.
+/// Returns `None` if the trees have no differences.
+pub async fn compute_patch_id(
+    from_tree: &MergedTree,
+    to_tree: &MergedTree,
+) -> BackendResult<Option<PatchId>> {
+    let store = from_tree.store();
+    let mut hasher = Blake2b512::new();
+    let mut changed = false;
+    let mut diff_stream = from_tree.diff_stream(to_tree, &EverythingMatcher);
+    while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+        let values = values?;
+        changed = true;
+        hasher.update(path.as_internal_file_string().as_bytes());
+        hasher.update([0]);
+        hasher.update(file_mode(&values.before));
+        hasher.update(file_mode(&values.after));
+        let before = read_file(store, &path, &values.before).await?;
+        let after = read_file(store, &path, &values.after).await?;
+        if let (Some(before), Some(after)) = (before, after) {
+            let found = hash_line_changes(
+                &mut hasher,
+                &before,
+                &after,
+                CompareBytesIgnoreAllWhitespace,
+            );
+            if !found {
+                hasher.update(b"whitespace\0");
+                hash_line_changes(&mut hasher, &before, &after, CompareBytesExactly);
+            }
+        } else {
+            values.before.hash(&mut hasher);
+            values.after.hash(&mut hasher);
+        }
+    }
+    Ok(changed.then(|| PatchId(hasher.finalize().to_vec())))
+}
+
+/// Hashes the lines removed and added by each hunk of the line diff from
+/// `before` to `after`. Returns false if there are no changes.
+fn hash_line_changes(
+    hasher: &mut Blake2b512,
+    before: &[u8],
+    after: &[u8],
+    compare: impl CompareBytes,
+) -> bool {
+    let diff = ContentDiff::for_tokenizer([before, after], find_line_ranges, &compare);
+    let mut found = false;
+    for hunk in diff.hunks() {
+        if hunk.kind == DiffHunkKind::Different {
+            found = true;
+            hasher.update(b"-");
+            compare.hash(hunk.contents[0], &mut DigestHasher(hasher));
+            hasher.update(b"+");
+            compare.hash(hunk.contents[1], &mut DigestHasher(hasher));
+        }
+    }
+    found
+}
+
+/// Feeds the bytes written by [`CompareBytes::hash()`] to the digest.
+///
+/// [`CompareBytes::hash()`]: crate::diff::CompareBytes::hash
+struct DigestHasher<'a>(&'a mut Blake2b512);
+
+impl Hasher for DigestHasher<'_> {
+    fn finish(&self) -> u64 {
+        unreachable!("the digest is finalized separately")
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+fn file_mode(value: &MergedTreeValue) -> &'static [u8] {
+    match value.as_resolved() {
+        Some(None) => b"absent\0",
+        Some(Some(TreeValue::File {
+            executable: false, ..
+        })) => b"file\0",
+        Some(Some(TreeValue::File {
+            executable: true, ..
+        })) => b"executable\0",
+        _ => b"other\0",
+    }
+}
+
+/// Reads the content of a resolved file. An absent file is read as empty.
+/// Returns `None` for other values.
+async fn read_file(
+    store: &Store,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+) -> BackendResult<Option<Vec<u8>>> {
+    match value.as_resolved() {
+        Some(None) => Ok(Some(vec![])),
+        Some(Some(TreeValue::File { id, .. })) => {
+            let mut content = vec![];
+            store
+                .read_file(path, id)
+                .await?
+                .read_to_end(&mut content)
+                .await
+                .map_err(|err| BackendError::ReadFile {
+                    path: path.to_owned(),
+                    id: id.clone(),
+                    source: err.into(),
+                })?;
+            Ok(Some(content))
+        }
+        _ => Ok(None),
+    }
+}
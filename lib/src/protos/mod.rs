@@ -8,6 +8,9 @@ pub mod git_store {
 pub mod local_working_copy {
     include!("local_working_copy.rs");
 }
+pub mod resolution_cache {
+    include!("resolution_cache.rs");
+}
 pub mod secure_config {
     include!("secure_config.rs");
 }
@@ -0,0 +1,13 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct Resolution {
+    /// Terms of the normalized conflict hunk, alternating between adds and
+    /// removes, starting with an add.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub conflict: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub resolution: ::prost::alloc::vec::Vec<u8>,
+    /// Path of the file where the resolution was recorded. Only for display.
+    #[prost(string, tag = "3")]
+    pub path: ::prost::alloc::string::String,
+}
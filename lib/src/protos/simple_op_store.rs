@@ -139,6 +139,22 @@ pub struct View {
     /// Whether "@git" tags have been migrated to remote_views.
     #[prost(bool, tag = "12")]
     pub has_git_refs_migrated_to_remote_tags: bool,
+    /// Shelved commits, which are hidden from the head_ids.
+    #[prost(map = "string, bytes", tag = "13")]
+    pub shelves: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::vec::Vec<u8>,
+    >,
+    /// Bookmarks created automatically for pushing changes.
+    #[prost(string, repeated, tag = "14")]
+    pub push_bookmarks: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The Git notes commit refs/notes/commits should point to.
+    #[prost(message, optional, tag = "15")]
+    pub git_notes: ::core::option::Option<RefTarget>,
+    /// The Git notes commit refs/notes/commits pointed to as of the last import
+    /// or export.
+    #[prost(message, optional, tag = "16")]
+    pub git_notes_ref: ::core::option::Option<RefTarget>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RemoteView {
@@ -163,6 +179,9 @@ pub struct Operation {
     /// Whether or not `commit_predecessors` is recorded.
     #[prost(bool, tag = "5")]
     pub stores_commit_predecessors: bool,
+    /// Signature of the view id, parent ids, and unsigned operation id.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub signature: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
 /// TODO: Share with store.proto? Do we even need the timezone here?
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
@@ -134,6 +134,28 @@ pub fn merge_ref_targets(
     }
 }
 
+/// Merges ref targets without looking up the commit ancestry.
+///
+/// This is for targets that aren't indexed, such as Git notes commits. If both
+/// sides changed, the result is left conflicted.
+pub fn merge_unindexed_ref_targets(
+    left: &RefTarget,
+    base: &RefTarget,
+    right: &RefTarget,
+) -> RefTarget {
+    if let Some(&resolved) = trivial_merge(&[left, base, right], SameChange::Accept) {
+        return resolved.clone();
+    }
+    let merge = Merge::from_vec(vec![
+        left.as_merge().clone(),
+        base.as_merge().clone(),
+        right.as_merge().clone(),
+    ])
+    .flatten()
+    .simplify();
+    RefTarget::from_merge(merge)
+}
+
 pub fn merge_remote_refs(
     index: &dyn Index,
     left: &RemoteRef,
@@ -46,6 +46,7 @@ use crate::commit::Commit;
 use crate::commit::CommitByCommitterTimestamp;
 use crate::commit_builder::CommitBuilder;
 use crate::commit_builder::DetachedCommitBuilder;
+use crate::conflict_resolvers::ConflictResolverError;
 use crate::dag_walk;
 use crate::dag_walk_async;
 use crate::default_index::DefaultIndexStore;
@@ -67,6 +68,7 @@ use crate::merge::SameChange;
 use crate::merge::trivial_merge;
 use crate::merged_tree::MergedTree;
 use crate::object_id::HexPrefix;
+use crate::object_id::ObjectId as _;
 use crate::object_id::PrefixResolution;
 use crate::op_heads_store;
 use crate::op_heads_store::OpHeadResolutionError;
@@ -84,6 +86,7 @@ use crate::op_store::RootOperationData;
 use crate::operation::Operation;
 use crate::ref_name::GitRefName;
 use crate::ref_name::RefName;
+use crate::ref_name::RefNameBuf;
 use crate::ref_name::RemoteName;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::WorkspaceName;
@@ -93,6 +96,9 @@ use crate::refs::diff_named_ref_targets;
 use crate::refs::diff_named_remote_refs;
 use crate::refs::merge_ref_targets;
 use crate::refs::merge_remote_refs;
+use crate::refs::merge_unindexed_ref_targets;
+use crate::repo_path::RepoPathBuf;
+use crate::resolution_cache::ResolutionCache;
 use crate::revset;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
@@ -104,6 +110,7 @@ use crate::rewrite::RewriteRefsOptions;
 use crate::rewrite::merge_commit_trees;
 use crate::rewrite::rebase_commit_with_options;
 use crate::settings::UserSettings;
+use crate::signing::SignError;
 use crate::signing::SignInitError;
 use crate::signing::Signer;
 use crate::simple_backend::SimpleBackend;
@@ -222,7 +229,9 @@ impl ReadonlyRepo {
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
         let merge_options =
             MergeOptions::from_settings(settings).map_err(|err| BackendInitError(err.into()))?;
-        let store = Store::new(backend, signer, merge_options);
+        let resolution_cache = ResolutionCache::from_settings(settings, &repo_path)
+            .map_err(|err| BackendInitError(err.into()))?;
+        let store = Store::new(backend, signer, merge_options, resolution_cache);
 
         let op_store_path = repo_path.join("op_store");
         fs::create_dir(&op_store_path).context(&op_store_path)?;
@@ -664,6 +673,8 @@ pub enum RepoLoaderError {
     OpStore(#[from] OpStoreError),
     #[error(transparent)]
     TransactionCommit(#[from] TransactionCommitError),
+    #[error("Failed to verify operation signature")]
+    Sign(#[from] SignError),
 }
 
 /// Helps create `ReadonlyRepo` instances of a repo at the head operation or at
@@ -707,10 +718,13 @@ impl RepoLoader {
     ) -> Result<Self, StoreLoadError> {
         let merge_options =
             MergeOptions::from_settings(settings).map_err(|err| BackendLoadError(err.into()))?;
+        let resolution_cache = ResolutionCache::from_settings(settings, repo_path)
+            .map_err(|err| BackendLoadError(err.into()))?;
         let store = Store::new(
             store_factories.load_backend(settings, &repo_path.join("store"))?,
             Signer::from_settings(settings)?,
             merge_options,
+            resolution_cache,
         );
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
@@ -829,6 +843,9 @@ impl RepoLoader {
                 tx.merge_operation(other_op).await?;
                 tx.repo_mut().rebase_descendants().await?;
             }
+            for (op, problem) in tx.unverified_operations() {
+                tracing::warn!(op_id = %op.id().hex(), "Merged operation {problem}");
+            }
             let tx_description = tx_description.map_or_else(
                 || format!("merge {num_operations} operations"),
                 |tx_description| tx_description.to_string(),
@@ -912,6 +929,15 @@ pub struct MutableRepo {
     //   commits. However, if the type is `Abandoned`, a new working-copy commit should be created
     //   on top of all of the new commits instead.
     parent_mapping: HashMap<CommitId, Rewrite>,
+    /// Errors of the configured conflict resolvers which failed, by path.
+    conflict_resolver_failures: BTreeMap<RepoPathBuf, ConflictResolverError>,
+    /// Annotated Git tag objects created in this transaction, by tag name.
+    /// The values are the tagged commit and the id of the tag object.
+    git_tag_objects: HashMap<RefNameBuf, (CommitId, Vec<u8>)>,
+    /// The first error which occurred while copying Git notes to the rewritten
+    /// commits.
+    #[cfg(feature = "git")]
+    git_notes_copy_error: Option<crate::git::GitNotesError>,
 }
 
 impl MutableRepo {
@@ -924,6 +950,10 @@ impl MutableRepo {
             view: DirtyCell::with_clean(mut_view),
             commit_predecessors: Default::default(),
             parent_mapping: Default::default(),
+            conflict_resolver_failures: Default::default(),
+            git_tag_objects: Default::default(),
+            #[cfg(feature = "git")]
+            git_notes_copy_error: None,
         }
     }
 
@@ -1151,6 +1181,8 @@ impl MutableRepo {
     }
 
     async fn update_all_references(&mut self, options: &RewriteRefsOptions) -> BackendResult<()> {
+        #[cfg(feature = "git")]
+        self.copy_git_notes_to_rewritten_commits();
         let rewrite_mapping = self.resolve_rewrite_mapping_with(|_| true)?;
         self.update_local_bookmarks(&rewrite_mapping, options)
             // TODO: indexing error shouldn't be a "BackendError"
@@ -1159,6 +1191,40 @@ impl MutableRepo {
         Ok(())
     }
 
+    /// Copies Git notes of the rewritten commits to their new versions.
+    /// Abandoned commits have no new versions to copy the notes to.
+    ///
+    /// Since notes aren't essential, errors are recorded instead of failing
+    /// the rewrite.
+    #[cfg(feature = "git")]
+    fn copy_git_notes_to_rewritten_commits(&mut self) {
+        if self.view().git_notes().is_absent() {
+            return;
+        }
+        let rewrites = self
+            .parent_mapping
+            .iter()
+            .flat_map(|(old_id, rewrite)| {
+                let new_ids = match rewrite {
+                    Rewrite::Rewritten(new_id) => slice::from_ref(new_id),
+                    Rewrite::Divergent(new_ids) => new_ids.as_slice(),
+                    Rewrite::Abandoned(_) => &[],
+                };
+                // Squashed commits are recorded only as predecessors
+                new_ids.iter().map(|new_id| {
+                    let old_ids = self
+                        .commit_predecessors
+                        .get(new_id)
+                        .map_or_else(|| vec![old_id.clone()], |ids| ids.clone());
+                    (new_id.clone(), old_ids)
+                })
+            })
+            .collect_vec();
+        if let Err(err) = crate::git::copy_git_notes(self, &rewrites) {
+            self.git_notes_copy_error.get_or_insert(err);
+        }
+    }
+
     fn update_local_bookmarks(
         &mut self,
         rewrite_mapping: &HashMap<CommitId, Vec<CommitId>>,
@@ -1538,6 +1604,46 @@ impl MutableRepo {
         }
     }
 
+    /// Records the commit as a shelf named `name`, replacing the existing
+    /// shelf of the same name. The commit isn't made visible.
+    pub fn set_shelf(&mut self, name: String, commit_id: CommitId) {
+        self.view_mut().set_shelf(name, commit_id);
+    }
+
+    /// Removes the shelf named `name`. The shelved commit is left as is.
+    pub fn remove_shelf(&mut self, name: &str) {
+        self.view_mut().remove_shelf(name);
+    }
+
+    /// Records the bookmark as created automatically for pushing a change.
+    pub fn add_push_bookmark(&mut self, name: RefNameBuf) {
+        self.view_mut().add_push_bookmark(name);
+    }
+
+    /// Forgets that the bookmark was created for pushing a change. The
+    /// bookmark itself is left as is.
+    pub fn remove_push_bookmark(&mut self, name: &RefName) {
+        self.view_mut().remove_push_bookmark(name);
+    }
+
+    fn merge_shelf(&mut self, name: &str, base_id: Option<&CommitId>, other_id: Option<&CommitId>) {
+        let view = self.view.get_mut();
+        let self_id = view.get_shelf(name);
+        // Like working-copy commits, shelves aren't related by ancestry, so
+        // the self side wins if both sides changed.
+        let new_id = if let Some(resolved) =
+            trivial_merge(&[self_id, base_id, other_id], SameChange::Accept)
+        {
+            resolved.cloned()
+        } else {
+            self_id.cloned()
+        };
+        match new_id {
+            Some(id) => view.set_shelf(name.to_owned(), id),
+            None => view.remove_shelf(name),
+        }
+    }
+
     pub fn rename_workspace(
         &mut self,
         old_name: &WorkspaceName,
@@ -1720,7 +1826,20 @@ impl MutableRepo {
         self.view.with_ref(|v| v.get_local_bookmark(name).clone())
     }
 
+    /// Sets the local bookmark. If the target changes, the bookmark is no
+    /// longer managed as a push bookmark. Call [`Self::add_push_bookmark()`]
+    /// afterwards to set a push bookmark.
     pub fn set_local_bookmark_target(&mut self, name: &RefName, target: RefTarget) {
+        let view = self.view_mut();
+        if *view.get_local_bookmark(name) != target {
+            view.remove_push_bookmark(name);
+        }
+        self.update_local_bookmark_target(name, target);
+    }
+
+    /// Sets the local bookmark without touching the push bookmarks. Used when
+    /// the bookmark is moved automatically, e.g. by rebasing or importing.
+    fn update_local_bookmark_target(&mut self, name: &RefName, target: RefTarget) {
         let view = self.view_mut();
         for id in target.added_ids() {
             view.add_head(id);
@@ -1739,7 +1858,7 @@ impl MutableRepo {
         let index = self.index.as_index();
         let self_target = view.get_local_bookmark(name);
         let new_target = merge_ref_targets(index, self_target, base_target, other_target)?;
-        self.set_local_bookmark_target(name, new_target);
+        self.update_local_bookmark_target(name, new_target);
         Ok(())
     }
 
@@ -1848,6 +1967,25 @@ impl MutableRepo {
         self.view_mut().set_git_ref_target(name, target);
     }
 
+    /// Annotated Git tag objects to be exported for the local tags, by tag
+    /// name. The values are the tagged commit and the id of the tag object.
+    pub fn git_tag_objects(&self) -> &HashMap<RefNameBuf, (CommitId, Vec<u8>)> {
+        &self.git_tag_objects
+    }
+
+    /// Records the annotated Git tag object to be exported for the local tag
+    /// `name`. The object is used only while the local tag points to
+    /// `target`.
+    pub fn set_git_tag_object(&mut self, name: &RefName, target: CommitId, object_id: Vec<u8>) {
+        self.git_tag_objects
+            .insert(name.to_owned(), (target, object_id));
+    }
+
+    /// Forgets the annotated Git tag object of the local tag `name`.
+    pub fn remove_git_tag_object(&mut self, name: &RefName) {
+        self.git_tag_objects.remove(name);
+    }
+
     fn merge_git_ref(
         &mut self,
         name: &GitRefName,
@@ -1870,6 +2008,43 @@ impl MutableRepo {
         self.view_mut().set_git_head_target(target);
     }
 
+    pub fn set_git_notes_target(&mut self, target: RefTarget) {
+        self.view_mut().set_git_notes_target(target);
+    }
+
+    pub fn set_git_notes_ref_target(&mut self, target: RefTarget) {
+        self.view_mut().set_git_notes_ref_target(target);
+    }
+
+    /// The error which occurred while copying Git notes to the commits
+    /// rewritten in this transaction.
+    #[cfg(feature = "git")]
+    pub fn git_notes_copy_error(&self) -> Option<&crate::git::GitNotesError> {
+        self.git_notes_copy_error.as_ref()
+    }
+
+    /// Errors of the configured conflict resolvers which failed in this
+    /// transaction. Only the first error is kept for each path.
+    pub fn conflict_resolver_failures(&self) -> &BTreeMap<RepoPathBuf, ConflictResolverError> {
+        &self.conflict_resolver_failures
+    }
+
+    /// Records that the configured conflict resolvers failed.
+    pub fn record_conflict_resolver_failures(
+        &mut self,
+        failures: impl IntoIterator<Item = (RepoPathBuf, ConflictResolverError)>,
+    ) {
+        for (path, err) in failures {
+            self.conflict_resolver_failures.entry(path).or_insert(err);
+        }
+    }
+
+    /// Returns the mapping from new commit to its predecessors recorded in
+    /// this transaction.
+    pub fn commit_predecessors(&self) -> &BTreeMap<CommitId, Vec<CommitId>> {
+        &self.commit_predecessors
+    }
+
     pub fn set_view(&mut self, data: op_store::View) {
         self.view_mut().set_view(data);
         self.view.mark_dirty();
@@ -1903,6 +2078,21 @@ impl MutableRepo {
             self.merge_wc_commit(name, base_id, other_id);
         }
 
+        let changed_shelves = diff_named_commit_ids(base.shelves(), other.shelves());
+        for (name, (base_id, other_id)) in changed_shelves {
+            self.merge_shelf(name, base_id, other_id);
+        }
+
+        // Apply the additions and removals made on the other side.
+        let base_push_bookmarks = base.push_bookmarks();
+        let other_push_bookmarks = other.push_bookmarks();
+        for name in other_push_bookmarks.difference(base_push_bookmarks) {
+            self.add_push_bookmark(name.clone());
+        }
+        for name in base_push_bookmarks.difference(other_push_bookmarks) {
+            self.remove_push_bookmark(name);
+        }
+
         let base_heads = base.heads().iter().cloned().collect_vec();
         let own_heads = self.view().heads().iter().cloned().collect_vec();
         let other_heads = other.heads().iter().cloned().collect_vec();
@@ -1963,6 +2153,21 @@ impl MutableRepo {
         )?;
         self.set_git_head_target(new_git_head_target);
 
+        // Notes commits aren't indexed. Diverged notes are left conflicted, and
+        // will be merged when exported.
+        let new_git_notes = merge_unindexed_ref_targets(
+            self.view().git_notes(),
+            base.git_notes(),
+            other.git_notes(),
+        );
+        self.set_git_notes_target(new_git_notes);
+        let new_git_notes_ref = merge_unindexed_ref_targets(
+            self.view().git_notes_ref(),
+            base.git_notes_ref(),
+            other.git_notes_ref(),
+        );
+        self.set_git_notes_ref_target(new_git_notes_ref);
+
         Ok(())
     }
 
@@ -0,0 +1,403 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of conflict resolutions recorded by the user.
+//!
+//! Resolutions are recorded per conflict hunk, and are keyed by the
+//! normalized content of the hunk. When trees are merged and a file can't be
+//! merged cleanly, the remembered resolutions are applied if all the
+//! conflicting hunks of the file have been resolved before. This is similar to
+//! Git's "rerere".
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use bstr::BString;
+use itertools::Itertools as _;
+use prost::Message as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::FileId;
+use crate::config::ConfigGetError;
+use crate::conflicts::extract_as_single_hunk;
+use crate::content_hash::blake2b_hash;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::files;
+use crate::files::MergeResult;
+use crate::merge::Merge;
+use crate::object_id::ObjectId as _;
+use crate::object_id::id_type;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::settings::UserSettings;
+use crate::store::Store;
+
+id_type!(
+    /// Identifier of a recorded resolution. This is the hash of the normalized
+    /// conflict hunk.
+    pub ResolutionId { hex() }
+);
+
+/// Error that may occur while reading or writing the resolution cache.
+#[derive(Debug, Error)]
+pub enum ResolutionCacheError {
+    /// Failed to access the resolution file.
+    #[error(transparent)]
+    Io(#[from] PathError),
+    /// The resolution file is corrupt.
+    #[error("Failed to decode resolution {id}")]
+    Decode {
+        /// Hex-encoded id of the resolution.
+        id: String,
+        /// Underlying error.
+        source: prost::DecodeError,
+    },
+    /// Failed to read the file contents to record.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// Conflict resolution recorded by the user.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordedResolution {
+    /// Identifier of this resolution.
+    pub id: ResolutionId,
+    /// Normalized conflict hunk.
+    pub conflict: Merge<BString>,
+    /// Content the conflict hunk was resolved to.
+    pub resolution: BString,
+    /// Path of the file where the resolution was recorded.
+    pub path: String,
+}
+
+/// Repo-level store of conflict resolutions.
+#[derive(Debug)]
+pub struct ResolutionCache {
+    dir: PathBuf,
+    /// Paths of the files resolved by using the recorded resolutions since
+    /// they were last taken.
+    resolved_paths: Mutex<BTreeSet<RepoPathBuf>>,
+}
+
+impl ResolutionCache {
+    /// Creates the cache of the repo at `repo_path`. The directory is created
+    /// when the first resolution is recorded.
+    pub fn new(repo_path: &Path) -> Self {
+        let dir = repo_path.join("resolution_cache");
+        Self {
+            dir,
+            resolved_paths: Mutex::default(),
+        }
+    }
+
+    /// Creates the cache of the repo at `repo_path` if remembering resolutions
+    /// is enabled in `settings`.
+    pub fn from_settings(
+        settings: &UserSettings,
+        repo_path: &Path,
+    ) -> Result<Option<Self>, ConfigGetError> {
+        let enabled = settings.get_bool("merge.remember-resolutions")?;
+        Ok(enabled.then(|| Self::new(repo_path)))
+    }
+
+    /// Directory where the resolutions are stored.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Looks up the resolution of the given conflict hunk.
+    pub fn get(
+        &self,
+        conflict: &Merge<BString>,
+    ) -> Result<Option<RecordedResolution>, ResolutionCacheError> {
+        self.read(&hunk_id(conflict))
+    }
+
+    /// Resolves the conflicting hunks by using the recorded resolutions.
+    /// Returns the resolved content if all the conflicting hunks have been
+    /// resolved before.
+    pub fn resolve_hunks(
+        &self,
+        hunks: &[Merge<BString>],
+    ) -> Result<Option<BString>, ResolutionCacheError> {
+        let mut content = BString::default();
+        for hunk in hunks {
+            if let Some(resolved) = hunk.as_resolved() {
+                content.extend_from_slice(resolved);
+            } else if let Some(recorded) = self.get(hunk)? {
+                content.extend_from_slice(&recorded.resolution);
+            } else {
+                return Ok(None);
+            }
+        }
+        Ok(Some(content))
+    }
+
+    /// Resolves the file conflict at `path` by using the recorded
+    /// resolutions. Returns the resolved content if all the conflicting hunks
+    /// of the file have been resolved before. The path is remembered until
+    /// [`Self::take_resolved_paths()`] is called.
+    ///
+    /// This is called by the tree merge when the file contents can't be merged
+    /// cleanly, so every merged tree gets the recorded resolutions.
+    pub fn resolve_file<T: AsRef<[u8]>>(
+        &self,
+        store: &Store,
+        path: &RepoPath,
+        contents: &Merge<T>,
+    ) -> Result<Option<BString>, ResolutionCacheError> {
+        let options = store.merge_options();
+        let tokenizer = options.word_tokenizers.tokenizer_for_path(path);
+        let MergeResult::Conflict(hunks) =
+            files::merge_hunks_with_tokenizer(contents, options, tokenizer).merged
+        else {
+            return Ok(None);
+        };
+        let Some(content) = self.resolve_hunks(&hunks)? else {
+            return Ok(None);
+        };
+        self.resolved_paths.lock().unwrap().insert(path.to_owned());
+        Ok(Some(content))
+    }
+
+    /// Returns the paths of the files resolved by using the recorded
+    /// resolutions since the last call, and forgets them.
+    pub fn take_resolved_paths(&self) -> BTreeSet<RepoPathBuf> {
+        mem::take(&mut *self.resolved_paths.lock().unwrap())
+    }
+
+    /// Records the resolution of the given conflict hunk.
+    pub fn record(
+        &self,
+        conflict: &Merge<BString>,
+        resolution: &[u8],
+        path: &RepoPath,
+    ) -> Result<ResolutionId, ResolutionCacheError> {
+        let conflict = normalize_hunk(conflict);
+        let id = hunk_id(&conflict);
+        fs::create_dir_all(&self.dir).context(&self.dir)?;
+        let proto = crate::protos::resolution_cache::Resolution {
+            conflict: conflict.iter().map(|term| term.to_vec()).collect(),
+            resolution: resolution.to_vec(),
+            path: path.as_internal_file_string().to_owned(),
+        };
+        let temp_file = NamedTempFile::new_in(&self.dir).context(&self.dir)?;
+        temp_file
+            .as_file()
+            .write_all(&proto.encode_to_vec())
+            .context(temp_file.path())?;
+        let new_path = self.dir.join(id.hex());
+        persist_content_addressed_temp_file(temp_file, &new_path).context(&new_path)?;
+        Ok(id)
+    }
+
+    /// Records resolutions of the conflicting hunks in the file at `path`.
+    ///
+    /// The `resolved` content is split into resolutions of the conflicting
+    /// hunks by matching the non-conflicting hunks in order. Returns the number
+    /// of recorded resolutions, which is 0 if the content couldn't be matched
+    /// with the conflict.
+    pub async fn record_file_resolution(
+        &self,
+        store: &Store,
+        path: &RepoPath,
+        conflict: &Merge<Option<FileId>>,
+        resolved: &[u8],
+    ) -> Result<usize, ResolutionCacheError> {
+        let contents = extract_as_single_hunk(conflict, store, path).await?;
+        let options = store.merge_options();
+        let tokenizer = options.word_tokenizers.tokenizer_for_path(path);
+        let MergeResult::Conflict(hunks) =
+            files::merge_hunks_with_tokenizer(&contents, options, tokenizer).merged
+        else {
+            return Ok(0);
+        };
+        let Some(resolutions) = split_hunk_resolutions(&hunks, resolved) else {
+            return Ok(0);
+        };
+        for (conflict, resolution) in &resolutions {
+            self.record(conflict, resolution, path)?;
+        }
+        Ok(resolutions.len())
+    }
+
+    /// Returns all recorded resolutions.
+    pub fn list(&self) -> Result<Vec<RecordedResolution>, ResolutionCacheError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err).context(&self.dir)?,
+        };
+        let mut resolutions = vec![];
+        for entry in entries {
+            let entry = entry.context(&self.dir)?;
+            let file_name = entry.file_name();
+            // Skip temporary files
+            let Some(id) = file_name.to_str().and_then(ResolutionId::try_from_hex) else {
+                continue;
+            };
+            if let Some(resolution) = self.read(&id)? {
+                resolutions.push(resolution);
+            }
+        }
+        resolutions.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(resolutions)
+    }
+
+    /// Removes the recorded resolution. Returns false if it didn't exist.
+    pub fn remove(&self, id: &ResolutionId) -> Result<bool, ResolutionCacheError> {
+        let path = self.dir.join(id.hex());
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err).context(&path)?,
+        }
+    }
+
+    fn read(&self, id: &ResolutionId) -> Result<Option<RecordedResolution>, ResolutionCacheError> {
+        let path = self.dir.join(id.hex());
+        let buf = match fs::read(&path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context(&path)?,
+        };
+        let proto =
+            crate::protos::resolution_cache::Resolution::decode(&*buf).map_err(|source| {
+                ResolutionCacheError::Decode {
+                    id: id.hex(),
+                    source,
+                }
+            })?;
+        Ok(Some(RecordedResolution {
+            id: id.clone(),
+            conflict: Merge::from_vec(proto.conflict.into_iter().map(BString::from).collect_vec()),
+            resolution: proto.resolution.into(),
+            path: proto.path,
+        }))
+    }
+}
+
+/// Normalizes the conflict hunk so the order of the sides doesn't matter.
+fn normalize_hunk(conflict: &Merge<BString>) -> Merge<BString> {
+    let conflict = conflict.simplify();
+    let removes = conflict.removes().sorted().cloned().collect_vec();
+    let adds = conflict.adds().sorted().cloned().collect_vec();
+    Merge::from_removes_adds(removes, adds)
+}
+
+fn hunk_id(conflict: &Merge<BString>) -> ResolutionId {
+    let conflict = normalize_hunk(conflict);
+    ResolutionId::new(blake2b_hash(&conflict.map(|term| term.to_vec())).to_vec())
+}
+
+/// Splits `resolved` content into resolutions of the conflicting `hunks`.
+///
+/// The resolved hunks must appear in `resolved` in order. The content between
+/// them is the resolution of the conflicting hunk. Returns `None` if the
+/// content doesn't match the hunks, or if it can be split in more than one way.
+pub fn split_hunk_resolutions<'a>(
+    hunks: &'a [Merge<BString>],
+    resolved: &'a [u8],
+) -> Option<Vec<(&'a Merge<BString>, &'a [u8])>> {
+    let mut splitter = HunkSplitter {
+        hunks,
+        resolved,
+        num_splits: HashMap::new(),
+    };
+    if splitter.count_splits(0, 0) != 1 {
+        return None;
+    }
+    let mut resolutions = vec![];
+    let mut pos = 0;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let end = splitter
+            .hunk_ends(index, pos)
+            .into_iter()
+            .find(|&end| splitter.count_splits(index + 1, end) > 0)?;
+        if !hunk.is_resolved() {
+            resolutions.push((hunk, &resolved[pos..end]));
+        }
+        pos = end;
+    }
+    Some(resolutions)
+}
+
+/// Finds the ways to split resolved content into hunks.
+struct HunkSplitter<'a> {
+    hunks: &'a [Merge<BString>],
+    resolved: &'a [u8],
+    /// Number of splits (up to 2) of the hunks from the index, keyed by the
+    /// hunk index and the content position.
+    num_splits: HashMap<(usize, usize), usize>,
+}
+
+impl HunkSplitter<'_> {
+    /// Returns the possible end positions of the hunk at `index` starting at
+    /// `pos`.
+    fn hunk_ends(&self, index: usize, pos: usize) -> Vec<usize> {
+        let rest = &self.resolved[pos..];
+        if let Some(content) = self.hunks[index].as_resolved() {
+            return if rest.starts_with(content) {
+                vec![pos + content.len()]
+            } else {
+                vec![]
+            };
+        }
+        match self.hunks.get(index + 1) {
+            // The context may also appear in the resolution, so every
+            // occurrence of the next resolved hunk is a candidate.
+            Some(next_hunk) => match next_hunk.as_resolved() {
+                Some(content) if !content.is_empty() => rest
+                    .windows(content.len())
+                    .positions(|window| window == content.as_slice())
+                    .map(|len| pos + len)
+                    .collect(),
+                _ => vec![],
+            },
+            None => vec![self.resolved.len()],
+        }
+    }
+
+    /// Counts the ways to split the content from `pos` into the hunks from
+    /// `index`. The count saturates at 2.
+    fn count_splits(&mut self, index: usize, pos: usize) -> usize {
+        if index == self.hunks.len() {
+            return usize::from(pos == self.resolved.len());
+        }
+        if let Some(&count) = self.num_splits.get(&(index, pos)) {
+            return count;
+        }
+        let mut count = 0;
+        for end in self.hunk_ends(index, pos) {
+            count += self.count_splits(index + 1, end);
+            if count >= 2 {
+                count = 2;
+                break;
+            }
+        }
+        self.num_splits.insert((index, pos), count);
+        count
+    }
+}
@@ -224,6 +224,12 @@ pub enum RevsetFilterPredicate {
     HasConflict,
     /// Commits that are cryptographically signed.
     Signed,
+    /// Commits with a signature which was made by a key matching the pattern,
+    /// and/or is (not) trusted for the author.
+    SignedBy {
+        key: Option<StringExpression>,
+        trusted: Option<bool>,
+    },
     /// Custom predicates provided by extensions
     Extension(Arc<dyn RevsetFilterExtension>),
 }
@@ -304,6 +310,11 @@ pub enum RevsetExpression<St: ExpressionState> {
         sources: Arc<Self>,
         domain: Arc<Self>,
     },
+    // Commits within "domain" making the same changes as any of "sources"
+    Equivalent {
+        sources: Arc<Self>,
+        domain: Arc<Self>,
+    },
     Heads(Arc<Self>),
     /// Heads of the set of commits which are ancestors of `heads` but are not
     /// ancestors of `roots`, and which also are contained in `filter`.
@@ -602,6 +613,15 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// All commits within `domain` making the same changes as any commit in
+    /// this set, as determined by their patch ids.
+    pub fn equivalent(self: &Arc<Self>, domain: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self::Equivalent {
+            sources: self.clone(),
+            domain: domain.clone(),
+        })
+    }
+
     /// Commits reachable from `heads` but not from `self`.
     pub fn range(self: &Arc<Self>, heads: &Arc<Self>) -> Arc<Self> {
         Arc::new(Self::Range {
@@ -758,6 +778,11 @@ pub enum ResolvedExpression {
         sources: Box<Self>,
         domain: Box<Self>,
     },
+    /// Commits within `domain` making the same changes as any of `sources`.
+    Equivalent {
+        sources: Box<Self>,
+        domain: Box<Self>,
+    },
     Heads(Box<Self>),
     /// Heads of the set of commits which are ancestors of `heads` but are not
     /// ancestors of `roots`, and which also are contained in `filter`.
@@ -874,6 +899,18 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
         let domain = lower_expression(diagnostics, domain_arg, context)?;
         Ok(sources.reachable(&domain))
     });
+    map.insert("equivalent", |diagnostics, function, context| {
+        let [source_arg, domain_arg] = function.expect_exact_arguments()?;
+        let sources = lower_expression(diagnostics, source_arg, context)?;
+        let domain = lower_expression(diagnostics, domain_arg, context)?;
+        Ok(sources.equivalent(&domain))
+    });
+    map.insert("duplicates_of", |diagnostics, function, context| {
+        let [source_arg, domain_arg] = function.expect_exact_arguments()?;
+        let sources = lower_expression(diagnostics, source_arg, context)?;
+        let domain = lower_expression(diagnostics, domain_arg, context)?;
+        Ok(sources.equivalent(&domain).minus(&sources))
+    });
     map.insert("none", |_diagnostics, function, _context| {
         function.expect_no_arguments()?;
         Ok(RevsetExpression::none())
@@ -1067,9 +1104,20 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
             pattern,
         )))
     });
-    map.insert("signed", |_diagnostics, function, _context| {
-        function.expect_no_arguments()?;
-        let predicate = RevsetFilterPredicate::Signed;
+    map.insert("signed", |diagnostics, function, context| {
+        let ([], [key_opt_arg, trusted_opt_arg]) =
+            function.expect_named_arguments(&["key", "trusted"])?;
+        let predicate = if key_opt_arg.is_none() && trusted_opt_arg.is_none() {
+            RevsetFilterPredicate::Signed
+        } else {
+            let key = key_opt_arg
+                .map(|arg| expect_string_expression(diagnostics, arg, context))
+                .transpose()?;
+            let trusted = trusted_opt_arg
+                .map(|arg| expect_literal("boolean", arg))
+                .transpose()?;
+            RevsetFilterPredicate::SignedBy { key, trusted }
+        };
         Ok(RevsetExpression::filter(predicate))
     });
     map.insert("mine", |_diagnostics, function, context| {
@@ -1584,6 +1632,10 @@ fn try_transform_expression<St: ExpressionState, E>(
                 transform_rec_pair((sources, domain), pre, post)?
                     .map(|(sources, domain)| RevsetExpression::Reachable { sources, domain })
             }
+            RevsetExpression::Equivalent { sources, domain } => {
+                transform_rec_pair((sources, domain), pre, post)?
+                    .map(|(sources, domain)| RevsetExpression::Equivalent { sources, domain })
+            }
             RevsetExpression::Heads(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::Heads)
             }
@@ -1829,6 +1881,11 @@ where
             let domain = folder.fold_expression(domain)?;
             RevsetExpression::Reachable { sources, domain }.into()
         }
+        RevsetExpression::Equivalent { sources, domain } => {
+            let sources = folder.fold_expression(sources)?;
+            let domain = folder.fold_expression(domain)?;
+            RevsetExpression::Equivalent { sources, domain }.into()
+        }
         RevsetExpression::Heads(heads) => {
             let heads = folder.fold_expression(heads)?;
             RevsetExpression::Heads(heads).into()
@@ -2634,7 +2691,8 @@ fn reload_repo_at_operation(
             | RepoLoaderError::OpHeadResolution(_)
             | RepoLoaderError::OpHeadsStoreError(_)
             | RepoLoaderError::OpStore(_)
-            | RepoLoaderError::TransactionCommit(_) => RevsetResolutionError::Other(err.into()),
+            | RepoLoaderError::TransactionCommit(_)
+            | RepoLoaderError::Sign(_) => RevsetResolutionError::Other(err.into()),
         })
 }
 
@@ -3218,6 +3276,10 @@ impl VisibilityResolutionContext<'_> {
                 sources: self.resolve(sources).into(),
                 domain: self.resolve(domain).into(),
             },
+            RevsetExpression::Equivalent { sources, domain } => ResolvedExpression::Equivalent {
+                sources: self.resolve(sources).into(),
+                domain: self.resolve(domain).into(),
+            },
             RevsetExpression::Heads(candidates) => {
                 ResolvedExpression::Heads(self.resolve(candidates).into())
             }
@@ -3372,6 +3434,7 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Range { .. }
             | RevsetExpression::DagRange { .. }
             | RevsetExpression::Reachable { .. }
+            | RevsetExpression::Equivalent { .. }
             | RevsetExpression::Heads(_)
             | RevsetExpression::HeadsRange { .. }
             | RevsetExpression::Roots(_)
@@ -36,6 +36,7 @@ use crate::commit::CommitIteratorExt as _;
 use crate::commit::conflict_label_for_commits;
 use crate::commit_builder::CommitBuilder;
 use crate::conflict_labels::ConflictLabels;
+use crate::conflict_resolvers::ConflictResolverError;
 use crate::index::Index;
 use crate::index::IndexResult;
 use crate::index::ResolvedChangeTargets;
@@ -50,6 +51,7 @@ use crate::merged_tree_builder::MergedTreeBuilder;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::revset::RevsetExpression;
 use crate::revset::RevsetStreamExt as _;
 use crate::store::Store;
@@ -67,6 +69,106 @@ pub async fn merge_commit_trees(repo: &dyn Repo, commits: &[Commit]) -> BackendR
     }
 }
 
+/// Applies the built-in strategies of the resolvers configured in the
+/// `conflict-resolvers` table to the matching conflicted files in `tree`. The
+/// errors of the resolvers which failed are recorded in `mut_repo`.
+pub async fn apply_conflict_resolvers(
+    mut_repo: &mut MutableRepo,
+    tree: MergedTree,
+) -> BackendResult<MergedTree> {
+    let store = mut_repo.store().clone();
+    let (tree, failures) = store
+        .merge_options()
+        .conflict_resolvers
+        .resolve_tree(tree)
+        .await?;
+    mut_repo.record_conflict_resolver_failures(failures);
+    Ok(tree)
+}
+
+/// Runs the resolver commands enabled by `on-rebase` in the
+/// `conflict-resolvers` table for the conflicted commits created in this
+/// transaction, and rebases the descendants of the resolved commits. Returns
+/// the errors of the commands which failed.
+///
+/// Unlike the built-in strategies, resolver commands aren't run by
+/// [`CommitRewriter`]. This should be called after
+/// [`MutableRepo::rebase_descendants()`].
+pub async fn run_conflict_resolver_commands(
+    mut_repo: &mut MutableRepo,
+) -> BackendResult<Vec<(RepoPathBuf, ConflictResolverError)>> {
+    let store = mut_repo.store().clone();
+    let resolvers = &store.merge_options().conflict_resolvers;
+    let mut failures = vec![];
+    if !resolvers.has_commands_on_rebase() {
+        return Ok(failures);
+    }
+    let new_ids = mut_repo.commit_predecessors().keys().cloned().collect_vec();
+    let new_commits: Vec<Commit> = RevsetExpression::commits(new_ids)
+        .intersection(&RevsetExpression::visible_heads().ancestors())
+        .evaluate(mut_repo)
+        .map_err(|err| err.into_backend_error())?
+        .stream()
+        .commits(&store)
+        .try_collect()
+        .await
+        .map_err(|err| err.into_backend_error())?;
+    let mut to_resolve = vec![];
+    for commit in new_commits {
+        if !commit.has_conflict() {
+            continue;
+        }
+        // Skip new commits and rewrites which kept the tree, such as
+        // description changes.
+        let predecessor_ids = &mut_repo.commit_predecessors()[commit.id()];
+        if predecessor_ids.is_empty() {
+            continue;
+        }
+        let mut tree_changed = true;
+        for id in predecessor_ids {
+            let predecessor = store.get_commit_async(id).await?;
+            if predecessor.tree_ids() == commit.tree_ids() {
+                tree_changed = false;
+                break;
+            }
+        }
+        if tree_changed {
+            to_resolve.push(commit.id().clone());
+        }
+    }
+    if to_resolve.is_empty() {
+        return Ok(failures);
+    }
+    let to_resolve_set: HashSet<CommitId> = to_resolve.iter().cloned().collect();
+    // Parents are visited first, so the descendants are rebased onto the
+    // resolved commits before the commands run on them.
+    mut_repo
+        .transform_descendants(to_resolve, async |rewriter| {
+            let parents_changed = rewriter.parents_changed();
+            if !parents_changed && !to_resolve_set.contains(rewriter.old_commit().id()) {
+                return Ok(());
+            }
+            let mut builder = rewriter.rebase().await?;
+            let tree = builder.tree();
+            let mut tree_changed = false;
+            if tree.has_conflict() {
+                let (new_tree, new_failures) = resolvers.run_commands(tree.clone()).await?;
+                failures.extend(new_failures);
+                if new_tree.tree_ids() != tree.tree_ids() {
+                    builder = builder.set_tree(new_tree);
+                    tree_changed = true;
+                }
+            }
+            if parents_changed || tree_changed {
+                builder.write().await?;
+            }
+            Ok(())
+        })
+        .await?;
+    mut_repo.rebase_descendants().await?;
+    Ok(failures)
+}
+
 /// Merges `commits` without attempting to resolve file conflicts.
 pub async fn merge_commit_trees_no_resolve(
     repo: &dyn Repo,
@@ -345,30 +447,30 @@ impl<'repo> CommitRewriter<'repo> {
             let new_base_tree_fut = merge_commit_trees(self.mut_repo, &new_parents);
             let old_tree = self.old_commit.tree();
             let (old_base_tree, new_base_tree) = try_join!(old_base_tree_fut, new_base_tree_fut)?;
-            (
-                old_base_tree.tree_ids() == self.old_commit.tree_ids(),
-                MergedTree::merge(Merge::from_vec(vec![
-                    (
-                        new_base_tree,
-                        format!(
-                            "{} (rebase destination)",
-                            conflict_label_for_commits(&new_parents)
-                        ),
-                    ),
-                    (
-                        old_base_tree,
-                        format!(
-                            "{} (parents of rebased revision)",
-                            conflict_label_for_commits(&old_parents)
-                        ),
+            let was_empty = old_base_tree.tree_ids() == self.old_commit.tree_ids();
+            let new_tree = MergedTree::merge(Merge::from_vec(vec![
+                (
+                    new_base_tree,
+                    format!(
+                        "{} (rebase destination)",
+                        conflict_label_for_commits(&new_parents)
                     ),
-                    (
-                        old_tree,
-                        format!("{} (rebased revision)", self.old_commit.conflict_label()),
+                ),
+                (
+                    old_base_tree,
+                    format!(
+                        "{} (parents of rebased revision)",
+                        conflict_label_for_commits(&old_parents)
                     ),
-                ]))
-                .await?,
-            )
+                ),
+                (
+                    old_tree,
+                    format!("{} (rebased revision)", self.old_commit.conflict_label()),
+                ),
+            ]))
+            .await?;
+            let new_tree = apply_conflict_resolvers(self.mut_repo, new_tree).await?;
+            (was_empty, new_tree)
         };
         // Ensure we don't abandon commits with multiple parents (merge commits), even
         // if they're empty.
@@ -56,6 +56,7 @@ struct UserSettingsData {
     operation_username: String,
     signing_behavior: SignBehavior,
     signing_key: Option<String>,
+    sign_operations: bool,
 }
 
 pub type RemoteSettingsMap = HashMap<RemoteNameBuf, RemoteSettings>;
@@ -70,6 +71,13 @@ pub struct RemoteSettings {
     /// automatically.
     #[serde(default)]
     pub auto_track_created_bookmarks: Option<String>,
+    /// Push options to send by default when pushing to this remote.
+    #[serde(default)]
+    pub push_options: Vec<String>,
+    /// Commit template to generate additional push options from the pushed
+    /// commits. Each non-empty line of the output is a push option.
+    #[serde(default)]
+    pub push_options_template: Option<String>,
 }
 
 impl RemoteSettings {
@@ -144,6 +152,7 @@ impl UserSettings {
         let operation_username = config.get("operation.username")?;
         let signing_behavior = config.get("signing.behavior")?;
         let signing_key = config.get("signing.key").optional()?;
+        let sign_operations = config.get("operation.sign")?;
         let data = UserSettingsData {
             user_name,
             user_email,
@@ -153,6 +162,7 @@ impl UserSettings {
             operation_username,
             signing_behavior,
             signing_key,
+            sign_operations,
         };
         Ok(Self {
             config: Arc::new(config),
@@ -197,6 +207,11 @@ impl UserSettings {
         &self.data.operation_username
     }
 
+    /// Whether operations should be signed by the signing backend.
+    pub fn sign_operations(&self) -> bool {
+        self.data.sign_operations
+    }
+
     pub fn signature(&self) -> Signature {
         let timestamp = self.data.commit_timestamp.unwrap_or_else(Timestamp::now);
         Signature {
@@ -0,0 +1,99 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shelves of changes that can be applied later.
+//!
+//! A shelf is a hidden commit referenced by name from the view. Since the
+//! commit isn't a head, it doesn't show up in the log, but it is kept alive
+//! as long as the operation referencing it is reachable.
+
+use crate::backend::BackendResult;
+use crate::commit::Commit;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::repo::MutableRepo;
+use crate::rewrite::CommitWithSelection;
+
+/// Moves the selected changes to a new shelf named `name`.
+///
+/// The shelf commit is created on top of the parents of the source commit,
+/// and the selected changes are removed from the source commit. Returns the
+/// shelf commit, which isn't visible in the repo.
+pub async fn shelve_changes(
+    mut_repo: &mut MutableRepo,
+    selection: &CommitWithSelection,
+    name: String,
+    description: String,
+) -> BackendResult<Commit> {
+    let source = &selection.commit;
+    let shelf_commit = mut_repo
+        .new_commit(
+            source.parent_ids().to_vec(),
+            selection.selected_tree.clone(),
+        )
+        .set_description(description)
+        .detach()
+        .write_hidden()
+        .await?;
+
+    // Apply the reverse of the selected changes onto the source
+    let diff = selection
+        .diff_with_labels("parents of shelved revision", "shelved changes", "shelf")
+        .await?;
+    let new_source_tree = MergedTree::merge(Merge::from_diffs(
+        (source.tree(), source.conflict_label()),
+        [diff.invert()],
+    ))
+    .await?;
+    mut_repo
+        .rewrite_commit(source)
+        .set_tree(new_source_tree)
+        .write()
+        .await?;
+
+    mut_repo.set_shelf(name, shelf_commit.id().clone());
+    Ok(shelf_commit)
+}
+
+/// Applies the changes in the shelf commit to the `destination` commit.
+///
+/// Conflicts are recorded in the rewritten destination commit, which is
+/// returned.
+pub async fn apply_shelf(
+    mut_repo: &mut MutableRepo,
+    shelf_commit: &Commit,
+    destination: &Commit,
+) -> BackendResult<Commit> {
+    let selection = CommitWithSelection {
+        commit: shelf_commit.clone(),
+        selected_tree: shelf_commit.tree(),
+        parent_tree: shelf_commit.parent_tree(mut_repo).await?,
+    };
+    let diff = selection
+        .diff_with_labels("parents of shelf", "shelved changes", "shelf")
+        .await?;
+    let new_tree = MergedTree::merge(Merge::from_diffs(
+        (
+            destination.tree(),
+            format!("{} (shelf destination)", destination.conflict_label()),
+        ),
+        [diff],
+    ))
+    .await?;
+    mut_repo
+        .rewrite_commit(destination)
+        .set_tree(new_tree)
+        .write()
+        .await
+}
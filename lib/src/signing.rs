@@ -15,6 +15,7 @@
 //! Generic APIs to work with cryptographic signatures created and verified by
 //! various backends.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::sync::Mutex;
@@ -114,6 +115,18 @@ pub trait SigningBackend: Debug + Send + Sync {
     /// verify(data, sign(data)?)?.status == SigStatus::Good
     /// ```
     fn verify(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification>;
+
+    /// Returns the fingerprints of the signing key in the format reported by
+    /// [`Verification::key`]. A key may have several fingerprints, e.g. one
+    /// for each of its subkeys.
+    ///
+    /// The `key` parameter is the same as the one passed to `sign`. Returns no
+    /// fingerprints if the backend can't tell which signatures were made by
+    /// the key, in which case data is always re-signed.
+    fn key_fingerprints(&self, key: Option<&str>) -> SignResult<Vec<String>> {
+        let _ = key;
+        Ok(vec![])
+    }
 }
 
 /// An error type for the signing/verifying operations
@@ -139,6 +152,103 @@ pub enum SignInitError {
     /// Failed to load backend configuration.
     #[error("Failed to configure signing backend")]
     BackendConfig(#[source] ConfigGetError),
+    /// Failed to load trusted keys.
+    #[error("Failed to configure trusted signing keys")]
+    TrustConfig(#[source] ConfigGetError),
+    /// Operations should be signed, but no backend is configured.
+    #[error("Operation signing is enabled, but no signing backend is configured")]
+    NoBackendForOperations,
+}
+
+/// Keys which are trusted to sign commits, configured by `signing.trust`.
+///
+/// Only signatures with [`SigStatus::Good`] can be trusted.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TrustStore {
+    /// Trust SSH signatures verified against the allowed signers file if the
+    /// principal matches the author email.
+    allowed_signers: bool,
+    /// Keys trusted for any author.
+    keys: Vec<String>,
+    /// Keys trusted for the given author emails.
+    authors: HashMap<String, Vec<String>>,
+}
+
+impl TrustStore {
+    /// Loads the trusted keys from `signing.trust`.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        settings.get("signing.trust")
+    }
+
+    /// Creates a trust store of the given keys.
+    pub fn new(keys: Vec<String>, authors: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            allowed_signers: false,
+            keys,
+            authors,
+        }
+    }
+
+    /// Returns true if no key is trusted.
+    pub fn is_empty(&self) -> bool {
+        !self.allowed_signers && self.keys.is_empty() && self.authors.is_empty()
+    }
+
+    /// Returns true if the verified signature was made by a key trusted for
+    /// the author.
+    pub fn is_trusted(&self, verification: &Verification, author_email: &str) -> bool {
+        if verification.status != SigStatus::Good {
+            return false;
+        }
+        let Some(key) = &verification.key else {
+            return false;
+        };
+        // ssh-keygen reports "SHA256:" fingerprints, and the principal which
+        // was looked up in the allowed signers file.
+        if self.allowed_signers
+            && key.starts_with("SHA256:")
+            && verification
+                .display
+                .as_ref()
+                .is_some_and(|principal| principal.eq_ignore_ascii_case(author_email))
+        {
+            return true;
+        }
+        let author_keys = self
+            .authors
+            .iter()
+            .filter(|(email, _)| email.eq_ignore_ascii_case(author_email))
+            .flat_map(|(_, keys)| keys);
+        self.keys
+            .iter()
+            .chain(author_keys)
+            .any(|trusted| key_matches(trusted, key))
+    }
+}
+
+/// Compares key identifiers. Whitespace in the trusted key is ignored, and
+/// hexadecimal fingerprints are compared case-insensitively.
+fn key_matches(trusted: &str, key: &str) -> bool {
+    let trusted: String = trusted.split_whitespace().collect();
+    let is_hex = |s: &str| s.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_hex(&trusted) && is_hex(key) {
+        trusted.eq_ignore_ascii_case(key)
+    } else {
+        trusted == key
+    }
+}
+
+/// Checks if the key of a signature identifies the key with the given
+/// `fingerprint`. The signature key may be a key ID, which is a suffix of the
+/// fingerprint.
+fn fingerprint_matches(fingerprint: &str, signature_key: &str) -> bool {
+    !signature_key.is_empty()
+        && fingerprint
+            .len()
+            .checked_sub(signature_key.len())
+            .and_then(|start| fingerprint.get(start..))
+            .is_some_and(|suffix| key_matches(suffix, signature_key))
 }
 
 /// A enum that describes if a created/rewritten commit should be signed or not.
@@ -171,7 +281,9 @@ pub struct Signer {
     /// Main backend is also used for verification, but it's not in this list
     /// for ownership reasons.
     backends: Vec<Box<dyn SigningBackend>>,
+    trust: TrustStore,
     cache: Mutex<CLruCache<CommitId, Verification>>,
+    key_fingerprints: Mutex<HashMap<Option<String>, Vec<String>>>,
 }
 
 impl Signer {
@@ -197,8 +309,12 @@ impl Signer {
                     .ok_or(SignInitError::UnknownBackend(backend))
             })
             .transpose()?;
+        if settings.sign_operations() && main_backend.is_none() {
+            return Err(SignInitError::NoBackendForOperations);
+        }
+        let trust = TrustStore::from_settings(settings).map_err(SignInitError::TrustConfig)?;
 
-        Ok(Self::new(main_backend, backends))
+        Ok(Self::new(main_backend, backends).with_trust_store(trust))
     }
 
     /// Creates a signer with the given backends.
@@ -209,10 +325,31 @@ impl Signer {
         Self {
             main_backend,
             backends: other_backends,
+            trust: TrustStore::default(),
             cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
+            key_fingerprints: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Sets the keys trusted to sign commits.
+    pub fn with_trust_store(mut self, trust: TrustStore) -> Self {
+        self.trust = trust;
+        self
+    }
+
+    /// Keys trusted to sign commits.
+    pub fn trust_store(&self) -> &TrustStore {
+        &self.trust
+    }
+
+    /// Checks if any of the backends can read the signature.
+    pub fn can_read(&self, signature: &[u8]) -> bool {
+        self.main_backend
+            .iter()
+            .chain(self.backends.iter())
+            .any(|backend| backend.can_read(signature))
+    }
+
     /// Checks if the signer can sign, i.e. if a main backend is configured.
     pub fn can_sign(&self) -> bool {
         self.main_backend.is_some()
@@ -227,6 +364,60 @@ impl Signer {
             .sign(data, key)
     }
 
+    /// Checks if the signature of `data` is valid and was made by the given
+    /// key of the main backend, so the data doesn't have to be signed again.
+    ///
+    /// Returns false if the main backend can't tell the key of the signature.
+    pub fn is_signed_by(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+        key: Option<&str>,
+    ) -> SignResult<bool> {
+        let Some(backend) = &self.main_backend else {
+            return Ok(false);
+        };
+        if !backend.can_read(signature) {
+            return Ok(false);
+        }
+        let fingerprints = self.key_fingerprints(backend.as_ref(), key)?;
+        if fingerprints.is_empty() {
+            return Ok(false);
+        }
+        // The cached verification can't be used because it doesn't include
+        // the key of signatures by unknown principals.
+        let verification = match backend.verify(data, signature) {
+            Ok(verification) => verification,
+            Err(SignError::InvalidSignatureFormat) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        if verification.status == SigStatus::Bad {
+            return Ok(false);
+        }
+        Ok(verification.key.is_some_and(|signature_key| {
+            fingerprints
+                .iter()
+                .any(|fingerprint| fingerprint_matches(fingerprint, &signature_key))
+        }))
+    }
+
+    fn key_fingerprints(
+        &self,
+        backend: &dyn SigningBackend,
+        key: Option<&str>,
+    ) -> SignResult<Vec<String>> {
+        let key = key.map(ToOwned::to_owned);
+        if let Some(fingerprints) = self.key_fingerprints.lock().unwrap().get(&key) {
+            return Ok(fingerprints.clone());
+        }
+        let fingerprints = backend.key_fingerprints(key.as_deref())?;
+        self.key_fingerprints
+            .lock()
+            .unwrap()
+            .insert(key, fingerprints.clone());
+        Ok(fingerprints)
+    }
+
     /// Looks for backend that can verify the signature and returns the result
     /// of its verification.
     pub fn verify(
@@ -240,21 +431,7 @@ impl Signer {
             return Ok(check);
         }
 
-        let verification = self
-            .main_backend
-            .iter()
-            .chain(self.backends.iter())
-            .filter(|b| b.can_read(signature))
-            // skip unknown and invalid sigs to allow other backends that can read to try
-            // for example, we might have gpg and sq, both of which could read a PGP signature
-            .find_map(|backend| match backend.verify(data, signature) {
-                Ok(check) if check.status == SigStatus::Unknown => None,
-                Err(SignError::InvalidSignatureFormat) => None,
-                e => Some(e),
-            })
-            .transpose()?;
-
-        if let Some(verification) = verification {
+        if let Some(verification) = self.verify_with_backends(data, signature)? {
             // a key might get imported before next call?.
             // realistically this is unlikely, but technically
             // it's correct to not cache unknowns here
@@ -277,4 +454,30 @@ impl Signer {
             Ok(Verification::unknown())
         }
     }
+
+    /// Verifies the signature of arbitrary data such as a tag object. Unlike
+    /// [`Self::verify()`], the result isn't cached.
+    pub fn verify_data(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        let verification = self.verify_with_backends(data, signature)?;
+        Ok(verification.unwrap_or_else(Verification::unknown))
+    }
+
+    fn verify_with_backends(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+    ) -> SignResult<Option<Verification>> {
+        self.main_backend
+            .iter()
+            .chain(self.backends.iter())
+            .filter(|b| b.can_read(signature))
+            // skip unknown and invalid sigs to allow other backends that can read to try
+            // for example, we might have gpg and sq, both of which could read a PGP signature
+            .find_map(|backend| match backend.verify(data, signature) {
+                Ok(check) if check.status == SigStatus::Unknown => None,
+                Err(SignError::InvalidSignatureFormat) => None,
+                e => Some(e),
+            })
+            .transpose()
+    }
 }
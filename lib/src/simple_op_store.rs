@@ -517,6 +517,7 @@ fn operation_to_proto(operation: &Operation) -> crate::protos::simple_op_store::
         metadata: Some(operation_metadata_to_proto(&operation.metadata)),
         commit_predecessors,
         stores_commit_predecessors,
+        signature: operation.signature.clone(),
     }
 }
 
@@ -538,6 +539,7 @@ fn operation_from_proto(
         parents,
         metadata,
         commit_predecessors,
+        signature: proto.signature,
     })
 }
 
@@ -577,6 +579,21 @@ fn view_to_proto(view: &View) -> crate::protos::simple_op_store::View {
 
     let git_head = ref_target_to_proto(&view.git_head);
 
+    let shelves = view
+        .shelves
+        .iter()
+        .map(|(name, id)| (name.clone(), id.to_bytes()))
+        .collect();
+
+    let push_bookmarks = view
+        .push_bookmarks
+        .iter()
+        .map(|name| name.as_str().to_owned())
+        .collect();
+
+    let git_notes = ref_target_to_proto(&view.git_notes);
+    let git_notes_ref = ref_target_to_proto(&view.git_notes_ref);
+
     #[expect(deprecated)]
     crate::protos::simple_op_store::View {
         head_ids,
@@ -590,6 +607,10 @@ fn view_to_proto(view: &View) -> crate::protos::simple_op_store::View {
         git_head,
         // New/loaded view should have been migrated to the latest format
         has_git_refs_migrated_to_remote_tags: true,
+        shelves,
+        push_bookmarks,
+        git_notes,
+        git_notes_ref,
     }
 }
 
@@ -676,6 +697,17 @@ fn view_from_proto(proto: crate::protos::simple_op_store::View) -> Result<View,
         RefTarget::absent()
     };
 
+    let shelves = proto
+        .shelves
+        .into_iter()
+        .map(|(name, commit_id)| (name, CommitId::new(commit_id)))
+        .collect();
+    let push_bookmarks = proto
+        .push_bookmarks
+        .into_iter()
+        .map(RefNameBuf::from)
+        .collect();
+
     Ok(View {
         head_ids,
         local_bookmarks,
@@ -684,6 +716,10 @@ fn view_from_proto(proto: crate::protos::simple_op_store::View) -> Result<View,
         git_refs,
         git_head,
         wc_commit_ids,
+        shelves,
+        push_bookmarks,
+        git_notes: ref_target_from_proto(proto.git_notes),
+        git_notes_ref: ref_target_from_proto(proto.git_notes_ref),
     })
 }
 
@@ -947,6 +983,7 @@ mod tests {
     use insta::assert_snapshot;
     use itertools::Itertools as _;
     use maplit::btreemap;
+    use maplit::btreeset;
     use maplit::hashset;
 
     use super::*;
@@ -1007,6 +1044,10 @@ mod tests {
                 WorkspaceName::DEFAULT.to_owned() => default_wc_commit_id,
                 "test".into() => test_wc_commit_id,
             },
+            shelves: btreemap! {},
+            push_bookmarks: btreeset! {},
+            git_notes: RefTarget::absent(),
+            git_notes_ref: RefTarget::absent(),
         }
     }
 
@@ -1050,6 +1091,7 @@ mod tests {
                     CommitId::from_hex("444444"),
                 ],
             }),
+            signature: None,
         }
     }
 
@@ -1062,6 +1104,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_view_extensions() {
+        // The id of the view without the fields added later is tested by
+        // test_hash_view(), and must stay the same.
+        let hash_view = |view: &View| ViewId::new(blake2b_hash(view).to_vec()).hex();
+        let legacy_view = create_view();
+        let mut view = legacy_view.clone();
+        view.push_bookmarks.insert("push-abc".into());
+        assert_snapshot!(
+            hash_view(&view),
+            @"3930730b78321eb06fcc35b52e29584a947810844e37299b84a69cbbc4b30a54bfcb2d146b0a89acec3ae5d58e9e14ff6fda46a1edd5aa58903b0c956a4bb772"
+        );
+
+        // Each field is encoded at its own position
+        let mut view1 = legacy_view.clone();
+        view1.git_notes = RefTarget::normal(CommitId::from_hex("eee111"));
+        let mut view2 = legacy_view;
+        view2.git_notes_ref = RefTarget::normal(CommitId::from_hex("eee111"));
+        assert_ne!(hash_view(&view1), hash_view(&view2));
+    }
+
     #[test]
     fn test_hash_operation() {
         // Test exact output so we detect regressions in compatibility
@@ -1078,7 +1141,12 @@ mod tests {
             root_commit_id: CommitId::from_hex("000000"),
         };
         let store = SimpleOpStore::init(temp_dir.path(), root_data)?;
-        let view = create_view();
+        let mut view = create_view();
+        view.shelves
+            .insert("shelf-1".into(), CommitId::from_hex("abc333"));
+        view.push_bookmarks.insert("push-abc".into());
+        view.git_notes = RefTarget::normal(CommitId::from_hex("eee111"));
+        view.git_notes_ref = RefTarget::normal(CommitId::from_hex("eee111"));
         let view_id = store.write_view(&view).block_on()?;
         let read_view = store.read_view(&view_id).block_on()?;
         assert_eq!(read_view, view);
@@ -1096,6 +1164,16 @@ mod tests {
         let op_id = store.write_operation(&operation).block_on()?;
         let read_operation = store.read_operation(&op_id).block_on()?;
         assert_eq!(read_operation, operation);
+
+        // The signature is part of the operation id
+        let signed_operation = Operation {
+            signature: Some(b"signature".to_vec()),
+            ..operation
+        };
+        let signed_op_id = store.write_operation(&signed_operation).block_on()?;
+        assert_ne!(signed_op_id, op_id);
+        let read_operation = store.read_operation(&signed_op_id).block_on()?;
+        assert_eq!(read_operation, signed_operation);
         Ok(())
     }
 
@@ -111,6 +111,15 @@ fn ensure_key_as_file(key: &str) -> SshResult<Either<PathBuf, tempfile::TempPath
     Ok(either::Right(pub_key_path))
 }
 
+/// Parses the `ssh-keygen -l` output: `<bits> <fingerprint> <comment> (<type>)`
+fn parse_key_fingerprint(output: &[u8]) -> SshResult<String> {
+    Ok(parse_utf8_string(output)?
+        .split_whitespace()
+        .nth(1)
+        .ok_or(SshError::BadResult)?
+        .into())
+}
+
 fn parse_fingerprint(output: &[u8]) -> SshResult<String> {
     Ok(parse_utf8_string(output)?
         .rsplit_once(' ')
@@ -309,6 +318,30 @@ impl SigningBackend for SshBackend {
             }
         }
     }
+
+    fn key_fingerprints(&self, key: Option<&str>) -> Result<Vec<String>, SignError> {
+        let Some(key) = key else {
+            return Ok(vec![]);
+        };
+
+        let pub_key_path = ensure_key_as_file(key)?;
+        let mut command = self.create_command();
+
+        let path = match &pub_key_path {
+            either::Left(path) => path.as_os_str(),
+            either::Right(path) => path.as_os_str(),
+        };
+
+        command
+            .arg("-l")
+            .arg("-E")
+            .arg("sha256")
+            .arg("-f")
+            .arg(path);
+
+        let output = run_command(&mut command, b"")?;
+        Ok(vec![parse_key_fingerprint(&output)?])
+    }
 }
 
 #[cfg(test)]
@@ -42,6 +42,7 @@ use crate::merge::Merge;
 use crate::merged_tree::MergedTree;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::resolution_cache::ResolutionCache;
 use crate::signing::Signer;
 use crate::tree::Tree;
 use crate::tree_merge::MergeOptions;
@@ -59,6 +60,7 @@ pub struct Store {
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
     merge_options: MergeOptions,
+    resolution_cache: Option<ResolutionCache>,
 }
 
 impl Debug for Store {
@@ -74,6 +76,7 @@ impl Store {
         backend: Box<dyn Backend>,
         signer: Signer,
         merge_options: MergeOptions,
+        resolution_cache: Option<ResolutionCache>,
     ) -> Arc<Self> {
         Arc::new(Self {
             backend,
@@ -81,6 +84,7 @@ impl Store {
             commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
             merge_options,
+            resolution_cache,
         })
     }
 
@@ -102,6 +106,12 @@ impl Store {
         &self.merge_options
     }
 
+    /// Cache of conflict resolutions recorded by the user, or `None` if
+    /// remembering resolutions is disabled.
+    pub fn resolution_cache(&self) -> Option<&ResolutionCache> {
+        self.resolution_cache.as_ref()
+    }
+
     pub fn get_copy_records(
         &self,
         paths: Option<&[RepoPathBuf]>,
@@ -73,4 +73,8 @@ impl SigningBackend for TestSigningBackend {
             })
         }
     }
+
+    fn key_fingerprints(&self, key: Option<&str>) -> SignResult<Vec<String>> {
+        Ok(key.into_iter().map(ToOwned::to_owned).collect())
+    }
 }
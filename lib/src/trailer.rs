@@ -17,6 +17,14 @@
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::backend::ChangeId;
+use crate::object_id::ObjectId as _;
+
+/// Suffix padding the 32 hex digits of jj change ids to the 40 hex digits of
+/// Gerrit `Change-Id`s. This is the hexadecimal of `jjid` in ASCII, just to not
+/// pad with zeros.
+const GERRIT_CHANGE_ID_SUFFIX: &str = "6a6a6964";
+
 /// A key-value pair representing a trailer in a commit message, of the
 /// form `Key: Value`.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -87,6 +95,54 @@ pub fn parse_trailers(body: &str) -> Result<Vec<Trailer>, TrailerParseError> {
     Ok(trailers)
 }
 
+/// Appends a raw `line` to the trailer paragraph of a commit message, such as
+/// a `(cherry picked from commit ...)` line.
+///
+/// A new trailer paragraph is created if the description doesn't already end
+/// with one. The description is returned unchanged if `line` is blank or is
+/// already present in the trailer paragraph.
+pub fn append_trailer_line(description: &str, line: &str) -> String {
+    let line = line.trim();
+    if line.is_empty() {
+        return description.to_owned();
+    }
+    let (trailers, found_blank, found_git_trailer, non_trailer_line) =
+        parse_trailers_impl(description);
+    let has_trailer_paragraph =
+        found_blank && (found_git_trailer || (non_trailer_line.is_none() && !trailers.is_empty()));
+    let last_paragraph = description
+        .trim_ascii_end()
+        .rsplit_once("\n\n")
+        .map_or("", |(_, paragraph)| paragraph);
+    if has_trailer_paragraph && last_paragraph.lines().any(|l| l.trim() == line) {
+        return description.to_owned();
+    }
+    let mut new_description = description.trim_ascii_end().to_owned();
+    if !has_trailer_paragraph {
+        // create a new paragraph for the trailer; an empty description gets
+        // a first empty line where the user will edit the commit summary
+        new_description.push('\n');
+    }
+    new_description.push('\n');
+    new_description.push_str(line);
+    new_description.push('\n');
+    new_description
+}
+
+/// Returns the Gerrit `Change-Id` trailer value derived from the jj change id.
+pub fn gerrit_change_id(change_id: &ChangeId) -> String {
+    format!("I{}{GERRIT_CHANGE_ID_SUFFIX}", change_id.hex())
+}
+
+/// Returns the jj change id the Gerrit `Change-Id` was derived from by
+/// [`gerrit_change_id()`], or `None` if it wasn't derived from a change id.
+pub fn change_id_from_gerrit_change_id(gerrit_change_id: &str) -> Option<ChangeId> {
+    let hex = gerrit_change_id
+        .strip_prefix('I')?
+        .strip_suffix(GERRIT_CHANGE_ID_SUFFIX)?;
+    (hex.len() == 32).then(|| ChangeId::try_from_hex(hex))?
+}
+
 fn parse_trailers_impl(body: &str) -> (Vec<Trailer>, bool, bool, Option<String>) {
     // a trailer always comes at the end of a message; we can split the message
     // by newline, but we need to immediately reverse the order of the lines
@@ -452,4 +508,49 @@ mod tests {
         assert_eq!(trailers[0].key, "foo");
         assert_eq!(trailers[0].value, "bar");
     }
+
+    #[test]
+    fn test_append_trailer_line() {
+        let line = "(cherry picked from commit 72bb9f9cf4bbb6bbb11da9cda4499c55c44e87b9)";
+        assert_eq!(
+            append_trailer_line("subject\n\nbody\n", line),
+            format!("subject\n\nbody\n\n{line}\n")
+        );
+        assert_eq!(
+            append_trailer_line("subject\n\nChange-Id: I1234\n", line),
+            format!("subject\n\nChange-Id: I1234\n{line}\n")
+        );
+        assert_eq!(append_trailer_line("", line), format!("\n\n{line}\n"));
+        assert_eq!(append_trailer_line("subject\n", "  "), "subject\n");
+
+        // already present
+        let description = format!("subject\n\nChange-Id: I1234\n{line}\n");
+        assert_eq!(append_trailer_line(&description, line), description);
+
+        // appending another cherry-pick line to an existing one
+        let other = "(cherry picked from commit 0000000000000000000000000000000000000000)";
+        assert_eq!(
+            append_trailer_line(&format!("subject\n\n{line}\n"), other),
+            format!("subject\n\n{line}\n{other}\n")
+        );
+    }
+
+    #[test]
+    fn test_gerrit_change_id() {
+        let change_id = ChangeId::from_hex("0123456789abcdef0123456789abcdef");
+        let gerrit_change_id = gerrit_change_id(&change_id);
+        assert_eq!(
+            gerrit_change_id,
+            "I0123456789abcdef0123456789abcdef6a6a6964"
+        );
+        assert_eq!(
+            change_id_from_gerrit_change_id(&gerrit_change_id),
+            Some(change_id)
+        );
+        assert_eq!(
+            change_id_from_gerrit_change_id("I0123456789abcdef0123456789abcdef01234567"),
+            None
+        );
+        assert_eq!(change_id_from_gerrit_change_id("I6a6a6964"), None);
+    }
 }
@@ -14,8 +14,10 @@
 
 #![expect(missing_docs)]
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use futures::TryStreamExt as _;
 use thiserror::Error;
 
 use crate::backend::Timestamp;
@@ -26,8 +28,11 @@ use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
 use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
 use crate::op_store::TimestampRange;
+use crate::op_walk;
+use crate::operation::OpVerificationProblem;
 use crate::operation::Operation;
 use crate::ref_name::WorkspaceName;
 use crate::repo::MutableRepo;
@@ -36,6 +41,7 @@ use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
 use crate::repo::RepoLoaderError;
 use crate::settings::UserSettings;
+use crate::signing::SignError;
 use crate::view::View;
 
 /// Error from attempts to write and publish transaction.
@@ -45,6 +51,27 @@ pub enum TransactionCommitError {
     IndexStore(#[from] IndexStoreError),
     OpHeadsStore(#[from] OpHeadsStoreError),
     OpStore(#[from] OpStoreError),
+    Sign(#[from] SignError),
+}
+
+/// Whether and how to sign the operation written by a transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OpSigning {
+    /// Operations aren't signed, and merged operations aren't verified.
+    Disabled,
+    /// Operations are signed with the given key, or the default key of the
+    /// signing backend if `None`. Merged operations are verified.
+    Key(Option<String>),
+}
+
+impl OpSigning {
+    pub fn from_settings(user_settings: &UserSettings) -> Self {
+        if user_settings.sign_operations() {
+            Self::Key(user_settings.sign_settings().key)
+        } else {
+            Self::Disabled
+        }
+    }
 }
 
 /// An in-memory representation of a repo and any changes being made to it.
@@ -63,6 +90,10 @@ pub struct Transaction {
     parent_ops: Vec<Operation>,
     op_metadata: OperationMetadata,
     end_time: Option<Timestamp>,
+    signing: OpSigning,
+    /// Merged operations which aren't verified by their signatures.
+    unverified_ops: Vec<(Operation, OpVerificationProblem)>,
+    verified_op_ids: HashSet<OperationId>,
 }
 
 impl Transaction {
@@ -70,11 +101,15 @@ impl Transaction {
         let parent_ops = vec![mut_repo.base_repo().operation().clone()];
         let op_metadata = create_op_metadata(user_settings, "".to_string(), false);
         let end_time = user_settings.operation_timestamp();
+        let signing = OpSigning::from_settings(user_settings);
         Self {
             mut_repo,
             parent_ops,
             op_metadata,
             end_time,
+            signing,
+            unverified_ops: vec![],
+            verified_op_ids: HashSet::new(),
         }
     }
 
@@ -103,6 +138,10 @@ impl Transaction {
         )
         .await?
         .unwrap();
+        if self.signing != OpSigning::Disabled {
+            self.verify_operations_since(&other_op, &ancestor_op)
+                .await?;
+        }
         let repo_loader = self.base_repo().loader();
         let base_repo = repo_loader.load_at(&ancestor_op).await?;
         let other_repo = repo_loader.load_at(&other_op).await?;
@@ -112,6 +151,40 @@ impl Transaction {
         Ok(())
     }
 
+    /// Verifies the signatures of the operations between `ancestor_op` and
+    /// the heads to be merged, which are the parent operations and `other_op`.
+    async fn verify_operations_since(
+        &mut self,
+        other_op: &Operation,
+        ancestor_op: &Operation,
+    ) -> Result<(), RepoLoaderError> {
+        let store = self.base_repo().store().clone();
+        let signer = store.signer();
+        let head_ops = itertools::chain(&self.parent_ops, [other_op])
+            .cloned()
+            .collect::<Vec<_>>();
+        let ops: Vec<Operation> =
+            op_walk::walk_ancestors_range(&head_ops, std::slice::from_ref(ancestor_op))
+                .try_collect()
+                .await?;
+        for op in ops {
+            if !self.verified_op_ids.insert(op.id().clone()) {
+                continue;
+            }
+            if let Some(problem) = op.verification_problem(signer)? {
+                self.unverified_ops.push((op, problem));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the merged operations which aren't signed or whose signatures
+    /// can't be verified, in the order they were found. Operations are only
+    /// verified if operations are signed.
+    pub fn unverified_operations(&self) -> &[(Operation, OpVerificationProblem)] {
+        &self.unverified_ops
+    }
+
     pub fn set_is_snapshot(&mut self, is_snapshot: bool) {
         self.op_metadata.is_snapshot = is_snapshot;
     }
@@ -149,12 +222,24 @@ impl Transaction {
             self.op_metadata.description = description.into();
             self.op_metadata.time.end = self.end_time.unwrap_or_else(Timestamp::now);
             let parents = self.parent_ops.iter().map(|op| op.id().clone()).collect();
-            let store_operation = op_store::Operation {
+            let mut store_operation = op_store::Operation {
                 view_id,
                 parents,
                 metadata: self.op_metadata,
                 commit_predecessors: Some(predecessors),
+                signature: None,
             };
+            let signer = base_repo.store().signer();
+            if let OpSigning::Key(key) = &self.signing {
+                // Signer::from_settings() fails if operations should be signed
+                // but no backend is configured. Don't write unsigned
+                // operations if the signer was created otherwise.
+                if !signer.can_sign() {
+                    return Err(SignError::Backend("No signing backend configured".into()).into());
+                }
+                let signature = signer.sign(&store_operation.signed_data(), key.as_deref())?;
+                store_operation.signature = Some(signature);
+            }
             let new_op_id = base_repo
                 .op_store()
                 .write_operation(&store_operation)
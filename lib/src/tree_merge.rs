@@ -35,6 +35,7 @@ use crate::backend::BackendResult;
 use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::config::ConfigGetError;
+use crate::conflict_resolvers::ConflictResolvers;
 use crate::files;
 use crate::files::FileMergeHunkLevel;
 use crate::merge::Merge;
@@ -49,6 +50,7 @@ use crate::repo_path::RepoPathComponentBuf;
 use crate::settings::UserSettings;
 use crate::store::Store;
 use crate::tree::Tree;
+use crate::word_tokenizer::WordTokenizers;
 
 /// Options for tree/file conflict resolution.
 #[derive(Clone, Debug)]
@@ -57,6 +59,10 @@ pub struct MergeOptions {
     pub hunk_level: FileMergeHunkLevel,
     /// Whether to resolve conflict that makes the same change at all sides.
     pub same_change: SameChange,
+    /// Tokenizers used by word-level merges.
+    pub word_tokenizers: WordTokenizers,
+    /// Resolvers applied to file conflicts left after merging while rebasing.
+    pub conflict_resolvers: ConflictResolvers,
 }
 
 impl MergeOptions {
@@ -67,6 +73,8 @@ impl MergeOptions {
             // needed. It wouldn't be translated to FileMergeHunkLevel.
             hunk_level: settings.get("merge.hunk-level")?,
             same_change: settings.get("merge.same-change")?,
+            word_tokenizers: WordTokenizers::from_settings(settings)?,
+            conflict_resolvers: ConflictResolvers::from_settings(settings)?,
         })
     }
 }
@@ -489,7 +497,20 @@ async fn try_resolve_file_conflict(
             BackendResult::Ok(content)
         })
         .await?;
-    if let Some(merged_content) = files::try_merge(&contents, options) {
+    let tokenizer = options.word_tokenizers.tokenizer_for_path(filename);
+    let merge_result = files::try_merge_with_tokenizer(&contents, options, tokenizer);
+    for err in &merge_result.tokenizer_errors {
+        tracing::warn!(?err, ?filename, "word tokenizer failed while merging file");
+    }
+    let mut merged_content = merge_result.merged;
+    if merged_content.is_none()
+        && let Some(cache) = store.resolution_cache()
+    {
+        merged_content = cache
+            .resolve_file(store, filename, &contents)
+            .map_err(|err| BackendError::Other(err.into()))?;
+    }
+    if let Some(merged_content) = merged_content {
         let id = store
             .write_file(filename, &mut merged_content.as_slice())
             .await?;
@@ -15,6 +15,7 @@
 #![expect(missing_docs)]
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashSet;
 
 use itertools::Itertools as _;
@@ -30,6 +31,7 @@ use crate::op_store::RemoteView;
 use crate::ref_name::GitRefName;
 use crate::ref_name::GitRefNameBuf;
 use crate::ref_name::RefName;
+use crate::ref_name::RefNameBuf;
 use crate::ref_name::RemoteName;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::WorkspaceName;
@@ -101,6 +103,32 @@ impl View {
         &self.data.git_head
     }
 
+    /// Returns the Git notes commit `refs/notes/commits` should point to.
+    pub fn git_notes(&self) -> &RefTarget {
+        &self.data.git_notes
+    }
+
+    /// Returns the Git notes commit `refs/notes/commits` pointed to as of the
+    /// last import or export.
+    pub fn git_notes_ref(&self) -> &RefTarget {
+        &self.data.git_notes_ref
+    }
+
+    /// Returns shelved commits by name.
+    pub fn shelves(&self) -> &BTreeMap<String, CommitId> {
+        &self.data.shelves
+    }
+
+    pub fn get_shelf(&self, name: &str) -> Option<&CommitId> {
+        self.data.shelves.get(name)
+    }
+
+    /// Returns names of the bookmarks created automatically for pushing
+    /// changes.
+    pub fn push_bookmarks(&self) -> &BTreeSet<RefNameBuf> {
+        &self.data.push_bookmarks
+    }
+
     pub fn set_wc_commit(&mut self, name: WorkspaceNameBuf, commit_id: CommitId) {
         self.data.wc_commit_ids.insert(name, commit_id);
     }
@@ -109,6 +137,24 @@ impl View {
         self.data.wc_commit_ids.remove(name);
     }
 
+    pub fn set_shelf(&mut self, name: String, commit_id: CommitId) {
+        self.data.shelves.insert(name, commit_id);
+    }
+
+    pub fn remove_shelf(&mut self, name: &str) {
+        self.data.shelves.remove(name);
+    }
+
+    pub fn add_push_bookmark(&mut self, name: RefNameBuf) {
+        self.data.push_bookmarks.insert(name);
+    }
+
+    /// Removes the bookmark from the set of push bookmarks without touching
+    /// the local or remote bookmark of the same name.
+    pub fn remove_push_bookmark(&mut self, name: &RefName) {
+        self.data.push_bookmarks.remove(name);
+    }
+
     pub fn rename_workspace(
         &mut self,
         old_name: &WorkspaceName,
@@ -545,6 +591,16 @@ impl View {
         self.data.git_head = target;
     }
 
+    /// Sets the Git notes commit `refs/notes/commits` should point to.
+    pub fn set_git_notes_target(&mut self, target: RefTarget) {
+        self.data.git_notes = target;
+    }
+
+    /// Sets the last imported or exported Git notes commit.
+    pub fn set_git_notes_ref_target(&mut self, target: RefTarget) {
+        self.data.git_notes_ref = target;
+    }
+
     /// Iterates all commit ids referenced by this view.
     ///
     /// This can include hidden commits referenced by remote bookmarks, previous
@@ -570,6 +626,10 @@ impl View {
             git_refs,
             git_head,
             wc_commit_ids,
+            shelves,
+            push_bookmarks: _,
+            git_notes: _,
+            git_notes_ref: _,
         } = &self.data;
         itertools::chain!(
             head_ids,
@@ -582,7 +642,8 @@ impl View {
             }),
             git_refs.values().flat_map(ref_target_ids),
             ref_target_ids(git_head),
-            wc_commit_ids.values()
+            wc_commit_ids.values(),
+            shelves.values()
         )
     }
 
@@ -0,0 +1,777 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tokenizers which split contents into words for word-level diffs and merges
+//!
+//! The tokenizer is chosen per file by the patterns configured in the
+//! `word-tokenizers` table.
+
+use std::cell::OnceCell;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::io;
+use std::io::Write as _;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::thread;
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::config::ConfigGetError;
+use crate::diff;
+use crate::fileset;
+use crate::fileset::FilesetAliasesMap;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetParseContext;
+use crate::matchers::Matcher;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathUiConverter;
+use crate::settings::UserSettings;
+
+/// Splits text into tokens for word-level diffs and merges.
+pub trait WordTokenizer: Debug + Send + Sync {
+    /// Returns the ranges of words in `text`. The ranges must be non-empty,
+    /// sorted, and non-overlapping.
+    fn find_word_ranges(&self, text: &[u8]) -> Result<Vec<Range<usize>>, WordTokenizerError>;
+
+    /// Returns the ranges of tokens used to refine the changed regions left
+    /// after matching words. Defaults to single non-word bytes.
+    fn find_nonword_ranges(&self, text: &[u8]) -> Vec<Range<usize>> {
+        diff::find_nonword_ranges(text)
+    }
+
+    /// Returns true if running the tokenizer is expensive, so the whole
+    /// contents of a file should be tokenized at once instead of each hunk.
+    /// See [`ContentsWordTokenizer`].
+    fn prefers_whole_contents(&self) -> bool {
+        false
+    }
+}
+
+/// Splits text into runs of alphanumeric characters and underscores.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultWordTokenizer;
+
+impl WordTokenizer for DefaultWordTokenizer {
+    fn find_word_ranges(&self, text: &[u8]) -> Result<Vec<Range<usize>>, WordTokenizerError> {
+        Ok(diff::find_word_ranges(text))
+    }
+}
+
+/// Splits identifiers further at `camelCase` and `snake_case` boundaries, so
+/// that a change to one part of an identifier doesn't replace the whole
+/// identifier.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SubwordTokenizer;
+
+impl WordTokenizer for SubwordTokenizer {
+    fn find_word_ranges(&self, text: &[u8]) -> Result<Vec<Range<usize>>, WordTokenizerError> {
+        let ranges = diff::find_word_ranges(text)
+            .into_iter()
+            .flat_map(|word| {
+                split_subwords(&text[word.clone()])
+                    .map(move |range| (range.start + word.start)..(range.end + word.start))
+            })
+            .collect();
+        Ok(ranges)
+    }
+}
+
+fn split_subwords(word: &[u8]) -> impl Iterator<Item = Range<usize>> {
+    let boundaries = (1..word.len()).filter(|&i| {
+        let (prev, cur) = (word[i - 1], word[i]);
+        let next = word.get(i + 1);
+        // Runs of underscores are separate tokens.
+        (prev == b'_') != (cur == b'_')
+            // "fooBar" -> "foo", "Bar"
+            || (prev.is_ascii_lowercase() || prev.is_ascii_digit()) && cur.is_ascii_uppercase()
+            // "HTTPServer" -> "HTTP", "Server"
+            || prev.is_ascii_uppercase()
+                && cur.is_ascii_uppercase()
+                && next.is_some_and(u8::is_ascii_lowercase)
+    });
+    itertools::chain!([0], boundaries, [word.len()])
+        .tuple_windows()
+        .map(|(start, end)| start..end)
+}
+
+/// Keeps string literals and comments as single tokens, and splits the other
+/// text as [`DefaultWordTokenizer`] does.
+///
+/// This recognizes the syntax common to many programming languages:
+///
+/// * `"..."`, `'...'` and `` `...` `` strings, which must end on the same line
+///   and mustn't be directly followed by a word character (so that `don't` and
+///   Rust lifetimes aren't strings). A backslash escapes the next character.
+/// * `// ...` comments and `# ...` comments preceded and followed by
+///   whitespace, up to the end of the line.
+/// * `/* ... */` comments, which may span lines.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CodeWordTokenizer;
+
+impl WordTokenizer for CodeWordTokenizer {
+    fn find_word_ranges(&self, text: &[u8]) -> Result<Vec<Range<usize>>, WordTokenizerError> {
+        Ok(split_around_literals(text, diff::find_word_ranges))
+    }
+
+    fn find_nonword_ranges(&self, text: &[u8]) -> Vec<Range<usize>> {
+        // Changed literals must not be refined into smaller tokens either
+        split_around_literals(text, diff::find_nonword_ranges)
+    }
+}
+
+/// Returns the ranges of string literals and comments in `text`, and the
+/// ranges found by `split` in the text between them.
+fn split_around_literals(
+    text: &[u8],
+    split: impl Fn(&[u8]) -> Vec<Range<usize>>,
+) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let push_split = |ranges: &mut Vec<Range<usize>>, start: usize, end: usize| {
+        let split_ranges = split(&text[start..end]);
+        ranges.extend(
+            split_ranges
+                .into_iter()
+                .map(|range| (range.start + start)..(range.end + start)),
+        );
+    };
+    let mut split_start = 0;
+    let mut pos = 0;
+    while pos < text.len() {
+        if let Some(end) = find_literal_end(text, pos) {
+            push_split(&mut ranges, split_start, pos);
+            ranges.push(pos..end);
+            split_start = end;
+            pos = end;
+        } else {
+            pos += 1;
+        }
+    }
+    push_split(&mut ranges, split_start, text.len());
+    ranges
+}
+
+/// Returns the end of the string literal or comment starting at `pos`, if any.
+fn find_literal_end(text: &[u8], pos: usize) -> Option<usize> {
+    let line_end = || {
+        text[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(text.len(), |len| pos + len)
+    };
+    match &text[pos..] {
+        [b'/', b'/', ..] => Some(line_end()),
+        [b'/', b'*', rest @ ..] => {
+            let len = rest
+                .windows(2)
+                .position(|window| window == b"*/")
+                .map_or(rest.len(), |len| len + 2);
+            Some(pos + 2 + len)
+        }
+        [b'#', next, ..]
+            if next.is_ascii_whitespace() && (pos == 0 || text[pos - 1].is_ascii_whitespace()) =>
+        {
+            Some(line_end())
+        }
+        [quote @ (b'"' | b'\'' | b'`'), rest @ ..] => {
+            let mut len = 0;
+            loop {
+                match *rest.get(len)? {
+                    b'\\' => len += 2,
+                    b'\n' => return None,
+                    b if b == *quote => break,
+                    _ => len += 1,
+                }
+            }
+            let end = pos + len + 2;
+            let is_word_next = text
+                .get(end)
+                .is_some_and(|&b| b.is_ascii_alphanumeric() || b == b'_');
+            (!is_word_next).then_some(end)
+        }
+        _ => None,
+    }
+}
+
+/// Error from running a word tokenizer.
+#[derive(Debug, Error)]
+pub enum WordTokenizerError {
+    /// The command couldn't be run.
+    #[error("Failed to run word tokenizer")]
+    Io(#[from] io::Error),
+    /// The command exited with an error.
+    #[error("Word tokenizer failed with {exit_status}:\n{stderr}")]
+    Command {
+        /// Exit status of the command.
+        exit_status: ExitStatus,
+        /// Error output of the command.
+        stderr: String,
+    },
+    /// The command printed something other than valid word ranges.
+    #[error("Invalid word range from word tokenizer: {0}")]
+    InvalidOutput(String),
+}
+
+/// Runs an external command to find words.
+///
+/// The command reads the text from stdin and prints the byte range of each
+/// word as `<start> <end>` on its own line. The end offset is exclusive.
+#[derive(Debug)]
+pub struct ExternalWordTokenizer {
+    program: String,
+    args: Vec<String>,
+}
+
+impl ExternalWordTokenizer {
+    /// Creates a tokenizer which runs `program` with `args`.
+    pub fn new(program: String, args: Vec<String>) -> Self {
+        Self { program, args }
+    }
+}
+
+impl WordTokenizer for ExternalWordTokenizer {
+    fn find_word_ranges(&self, text: &[u8]) -> Result<Vec<Range<usize>>, WordTokenizerError> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        tracing::info!(?command, "running word tokenizer command");
+        let mut process = command.spawn()?;
+        let mut stdin = process.stdin.take().unwrap();
+        // Write from another thread so a tokenizer that streams its output
+        // can't block on a full stdout pipe.
+        let output = thread::scope(|scope| {
+            let writer = scope.spawn(move || stdin.write_all(text));
+            let output = process.wait_with_output();
+            (writer.join().unwrap(), output)
+        });
+        let (write_result, output) = output;
+        let output = output?;
+        tracing::info!(?command, ?output.status, "word tokenizer command exited");
+        if !output.status.success() {
+            return Err(WordTokenizerError::Command {
+                exit_status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
+            });
+        }
+        write_result?;
+        parse_word_ranges(&output.stdout, text.len())
+    }
+
+    fn prefers_whole_contents(&self) -> bool {
+        true
+    }
+}
+
+/// Tokenizer which splits ranges of file contents by the words found in the
+/// whole contents.
+///
+/// If the underlying tokenizer [prefers the whole
+/// contents](WordTokenizer::prefers_whole_contents), each content is
+/// tokenized once on first use. Otherwise, each range is tokenized separately.
+///
+/// If the underlying tokenizer fails, words are split by the default tokenizer
+/// instead. The errors can be obtained by [`Self::into_errors()`].
+#[derive(Debug)]
+pub struct ContentsWordTokenizer<'a, 'input> {
+    tokenizer: &'a dyn WordTokenizer,
+    contents: Vec<LazyWordRanges<'input>>,
+    errors: RefCell<Vec<WordTokenizerError>>,
+}
+
+#[derive(Debug)]
+struct LazyWordRanges<'a> {
+    content: &'a [u8],
+    ranges: OnceCell<Vec<Range<usize>>>,
+}
+
+impl<'a, 'input> ContentsWordTokenizer<'a, 'input> {
+    /// Creates a tokenizer for ranges of the `contents`.
+    pub fn new(
+        tokenizer: &'a dyn WordTokenizer,
+        contents: impl IntoIterator<Item = &'input [u8]>,
+    ) -> Self {
+        let contents = contents
+            .into_iter()
+            .map(|content| LazyWordRanges {
+                content,
+                ranges: OnceCell::new(),
+            })
+            .collect();
+        Self {
+            tokenizer,
+            contents,
+            errors: RefCell::new(vec![]),
+        }
+    }
+
+    /// Returns the content at `index`.
+    pub fn content(&self, index: usize) -> &'input [u8] {
+        self.contents[index].content
+    }
+
+    /// Returns the ranges of words in the `range` of the content at `index`.
+    /// The returned ranges are relative to the start of the `range`. Words
+    /// crossing the boundaries of the `range` are truncated.
+    pub fn find_word_ranges_in(&self, index: usize, range: Range<usize>) -> Vec<Range<usize>> {
+        let lazy = &self.contents[index];
+        if !self.tokenizer.prefers_whole_contents() {
+            return self.find_word_ranges(&lazy.content[range]);
+        }
+        let ranges = lazy
+            .ranges
+            .get_or_init(|| self.find_word_ranges(lazy.content));
+        let Range { start, end } = range;
+        let first = ranges.partition_point(|range| range.end <= start);
+        ranges[first..]
+            .iter()
+            .take_while(|range| range.start < end)
+            .map(|range| (range.start.max(start) - start)..(range.end.min(end) - start))
+            .collect()
+    }
+
+    /// Returns the ranges of tokens used to refine the changed regions left
+    /// after matching words.
+    pub fn find_nonword_ranges(&self, text: &[u8]) -> Vec<Range<usize>> {
+        self.tokenizer.find_nonword_ranges(text)
+    }
+
+    /// Returns the errors of the underlying tokenizer, for which words were
+    /// split by the default tokenizer instead.
+    pub fn into_errors(self) -> Vec<WordTokenizerError> {
+        self.errors.into_inner()
+    }
+
+    fn find_word_ranges(&self, text: &[u8]) -> Vec<Range<usize>> {
+        self.tokenizer.find_word_ranges(text).unwrap_or_else(|err| {
+            tracing::warn!(?err, "falling back to default word tokenizer");
+            self.errors.borrow_mut().push(err);
+            diff::find_word_ranges(text)
+        })
+    }
+}
+
+fn parse_word_ranges(
+    output: &[u8],
+    text_len: usize,
+) -> Result<Vec<Range<usize>>, WordTokenizerError> {
+    let invalid =
+        |line: &[u8]| WordTokenizerError::InvalidOutput(String::from_utf8_lossy(line).into_owned());
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for line in output.split(|b| *b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.is_empty() {
+            continue;
+        }
+        let range = str::from_utf8(line)
+            .ok()
+            .and_then(|line| line.split_once(' '))
+            .and_then(|(start, end)| Some(start.trim().parse().ok()?..end.trim().parse().ok()?))
+            .ok_or_else(|| invalid(line))?;
+        let prev_end = ranges.last().map_or(0, |prev| prev.end);
+        if range.is_empty() || range.start < prev_end || range.end > text_len {
+            return Err(invalid(line));
+        }
+        ranges.push(range);
+    }
+    Ok(ranges)
+}
+
+#[derive(Debug)]
+struct WordTokenizerRule {
+    matcher: Box<dyn Matcher>,
+    tokenizer: Arc<dyn WordTokenizer>,
+}
+
+/// Word tokenizers selected by file path.
+#[derive(Clone, Debug, Default)]
+pub struct WordTokenizers {
+    rules: Arc<[WordTokenizerRule]>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum BuiltinWordTokenizer {
+    Default,
+    Subword,
+    Code,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RawWordTokenizerConfig {
+    #[serde(default)]
+    builtin: Option<BuiltinWordTokenizer>,
+    #[serde(default)]
+    command: Option<Vec<String>>,
+    patterns: Vec<String>,
+}
+
+impl WordTokenizers {
+    /// Loads the `word-tokenizers` table. If more than one tokenizer matches
+    /// a file, the first one in alphabetical order of names is used.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::new(),
+            base: PathBuf::new(),
+        };
+        let context = FilesetParseContext {
+            aliases_map: &FilesetAliasesMap::new(),
+            path_converter: &path_converter,
+        };
+        let rules: Vec<_> = settings
+            .table_keys("word-tokenizers")
+            .sorted()
+            .map(|name| {
+                let config_error =
+                    |error: Box<dyn std::error::Error + Send + Sync>| ConfigGetError::Type {
+                        name: format!("word-tokenizers.{name}"),
+                        error,
+                        source_path: None,
+                    };
+                let config: RawWordTokenizerConfig = settings.get(["word-tokenizers", name])?;
+                let tokenizer: Arc<dyn WordTokenizer> = match (config.builtin, config.command) {
+                    (Some(BuiltinWordTokenizer::Default), None) => Arc::new(DefaultWordTokenizer),
+                    (Some(BuiltinWordTokenizer::Subword), None) => Arc::new(SubwordTokenizer),
+                    (Some(BuiltinWordTokenizer::Code), None) => Arc::new(CodeWordTokenizer),
+                    (None, Some(command)) => {
+                        let Some((program, args)) = command.split_first() else {
+                            return Err(config_error("Command must not be empty".into()));
+                        };
+                        Arc::new(ExternalWordTokenizer::new(program.clone(), args.to_vec()))
+                    }
+                    _ => {
+                        return Err(config_error(
+                            "Exactly one of `builtin` or `command` must be set".into(),
+                        ));
+                    }
+                };
+                let mut diagnostics = FilesetDiagnostics::new();
+                let expressions: Vec<_> = config
+                    .patterns
+                    .iter()
+                    .map(|pattern| fileset::parse(&mut diagnostics, pattern, &context))
+                    .try_collect()
+                    .map_err(|err| config_error(err.into()))?;
+                Ok(WordTokenizerRule {
+                    matcher: FilesetExpression::union_all(expressions).to_matcher(),
+                    tokenizer,
+                })
+            })
+            .try_collect()?;
+        Ok(Self {
+            rules: rules.into(),
+        })
+    }
+
+    /// Returns the tokenizer for the file at `path`.
+    pub fn tokenizer_for_path(&self, path: &RepoPath) -> &dyn WordTokenizer {
+        self.rules
+            .iter()
+            .find(|rule| rule.matcher.matches(path))
+            .map_or(&DefaultWordTokenizer, |rule| rule.tokenizer.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::ConfigLayer;
+    use crate::config::ConfigSource;
+    use crate::config::StackedConfig;
+    use crate::files;
+    use crate::files::FileMergeHunkLevel;
+    use crate::merge::Merge;
+    use crate::merge::SameChange;
+    use crate::tree_merge::MergeOptions;
+
+    fn repo_path(value: &str) -> &RepoPath {
+        RepoPath::from_internal_string(value).unwrap()
+    }
+
+    fn settings_from_text(text: &str) -> UserSettings {
+        let mut config = StackedConfig::with_defaults();
+        config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+        UserSettings::from_config(config).unwrap()
+    }
+
+    fn words<'a>(tokenizer: &dyn WordTokenizer, text: &'a str) -> Vec<&'a str> {
+        tokenizer
+            .find_word_ranges(text.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_subword_tokenizer() {
+        let tokenizer = SubwordTokenizer;
+        assert_eq!(
+            words(&tokenizer, "fooBar foo_bar"),
+            ["foo", "Bar", "foo", "_", "bar"]
+        );
+        assert_eq!(
+            words(&tokenizer, "HTTPServer parseHTTP2Request"),
+            ["HTTP", "Server", "parse", "HTTP2", "Request"]
+        );
+        assert_eq!(
+            words(&tokenizer, "__init__ x1y"),
+            ["__", "init", "__", "x1y"]
+        );
+        assert_eq!(words(&tokenizer, "ünicodeWörd"), ["ünicode", "Wörd"]);
+    }
+
+    #[test]
+    fn test_code_word_tokenizer() {
+        let tokenizer = CodeWordTokenizer;
+        assert_eq!(
+            words(&tokenizer, r#"let s = "foo bar"; // a comment"#),
+            ["let", "s", r#""foo bar""#, "// a comment"]
+        );
+        assert_eq!(
+            words(&tokenizer, "x = 'a \\' b' # comment\ny"),
+            ["x", "'a \\' b'", "# comment", "y"]
+        );
+        assert_eq!(
+            words(&tokenizer, "a /* multi\nline */ b /* unterminated"),
+            ["a", "/* multi\nline */", "b", "/* unterminated"]
+        );
+        // Quotes which don't form strings, and '#' which isn't a comment
+        assert_eq!(
+            words(&tokenizer, "don't fn f<'a>(x: &'a str) \"open\n"),
+            ["don", "t", "fn", "f", "a", "x", "a", "str", "open"]
+        );
+        assert_eq!(
+            words(&tokenizer, "#[derive(Debug)] a#b"),
+            ["derive", "Debug", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_contents_word_tokenizer() {
+        #[derive(Debug)]
+        struct RecordingTokenizer {
+            texts: Mutex<Vec<String>>,
+            whole_contents: bool,
+        }
+
+        impl Default for RecordingTokenizer {
+            fn default() -> Self {
+                Self {
+                    texts: Mutex::default(),
+                    whole_contents: true,
+                }
+            }
+        }
+
+        impl WordTokenizer for RecordingTokenizer {
+            fn find_word_ranges(
+                &self,
+                text: &[u8],
+            ) -> Result<Vec<Range<usize>>, WordTokenizerError> {
+                let text_str = str::from_utf8(text).unwrap().to_owned();
+                self.texts.lock().unwrap().push(text_str);
+                Ok(diff::find_word_ranges(text))
+            }
+
+            fn prefers_whole_contents(&self) -> bool {
+                self.whole_contents
+            }
+        }
+
+        let underlying = RecordingTokenizer::default();
+        let contents = ["foo bar\nbaz qux\n", "other text"];
+        let tokenizer = ContentsWordTokenizer::new(&underlying, contents.map(str::as_bytes));
+        let words_in = |index: usize, range: Range<usize>| {
+            let text = &contents[index][range.clone()];
+            tokenizer
+                .find_word_ranges_in(index, range)
+                .into_iter()
+                .map(|range| &text[range])
+                .collect_vec()
+        };
+        assert_eq!(words_in(0, 8..16), ["baz", "qux"]);
+        // Words crossing the boundaries are truncated
+        assert_eq!(words_in(0, 5..10), ["ar", "ba"]);
+        assert_eq!(words_in(1, 0..10), ["other", "text"]);
+        // Each content is tokenized only once
+        assert_eq!(words_in(0, 0..7), ["foo", "bar"]);
+        assert_eq!(*underlying.texts.lock().unwrap(), contents);
+
+        // Cheap tokenizers are run on each range
+        let underlying = RecordingTokenizer {
+            whole_contents: false,
+            ..Default::default()
+        };
+        let tokenizer = ContentsWordTokenizer::new(&underlying, contents.map(str::as_bytes));
+        assert_eq!(tokenizer.find_word_ranges_in(0, 5..10), [0..2, 3..5]);
+        assert_eq!(*underlying.texts.lock().unwrap(), ["ar\nba"]);
+    }
+
+    #[test]
+    fn test_external_word_tokenizer_errors() {
+        let underlying =
+            ExternalWordTokenizer::new("jj-test-missing-word-tokenizer".to_owned(), vec![]);
+        let text = b"fooBar baz";
+        assert!(matches!(
+            underlying.find_word_ranges(text),
+            Err(WordTokenizerError::Io(_))
+        ));
+        // Falls back to the default tokenizer
+        let tokenizer = ContentsWordTokenizer::new(&underlying, [text.as_slice()]);
+        assert_eq!(tokenizer.find_word_ranges_in(0, 0..10), [0..6, 7..10]);
+        let errors = tokenizer.into_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], WordTokenizerError::Io(_)));
+    }
+
+    #[test]
+    fn test_parse_word_ranges() {
+        assert_eq!(
+            parse_word_ranges(b"0 3\n4 7\r\n\n", 7).unwrap(),
+            [0..3, 4..7]
+        );
+        assert!(parse_word_ranges(b"", 0).unwrap().is_empty());
+        // Overlapping, empty, or out-of-bounds ranges are rejected
+        assert!(parse_word_ranges(b"0 3\n2 4\n", 7).is_err());
+        assert!(parse_word_ranges(b"1 1\n", 7).is_err());
+        assert!(parse_word_ranges(b"0 8\n", 7).is_err());
+        assert!(parse_word_ranges(b"0\n", 7).is_err());
+    }
+
+    #[test]
+    fn test_word_tokenizers_from_settings() {
+        let settings = settings_from_text(indoc! {r#"
+            [word-tokenizers.java]
+            builtin = "subword"
+            patterns = ["glob:'**/*.java'"]
+        "#});
+        let tokenizers = WordTokenizers::from_settings(&settings).unwrap();
+        let text = "getUserName";
+        assert_eq!(
+            words(
+                tokenizers.tokenizer_for_path(repo_path("src/Foo.java")),
+                text
+            ),
+            ["get", "User", "Name"]
+        );
+        assert_eq!(
+            words(tokenizers.tokenizer_for_path(repo_path("foo.rs")), text),
+            ["getUserName"]
+        );
+
+        let settings = settings_from_text(indoc! {r#"
+            [word-tokenizers.invalid]
+            builtin = "subword"
+            command = ["tokenize"]
+            patterns = ["all()"]
+        "#});
+        assert!(WordTokenizers::from_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn test_merge_by_code() {
+        let settings = settings_from_text(indoc! {r#"
+            merge.hunk-level = "word"
+            [word-tokenizers.code]
+            builtin = "code"
+            patterns = ["glob:'*.rs'"]
+        "#});
+        let options = MergeOptions::from_settings(&settings).unwrap();
+        let inputs = Merge::from_vec(vec![
+            "print(\"Hello, world!\");\n",
+            "print(\"Hello, World!\");\n",
+            "print(\"Hi, World!\");\n",
+        ]);
+        // Words changed by each side in the same string can be merged
+        let tokenizer = options
+            .word_tokenizers
+            .tokenizer_for_path(repo_path("foo.txt"));
+        let result = files::try_merge_with_tokenizer(&inputs, &options, tokenizer);
+        assert_eq!(result.merged.unwrap(), "print(\"Hi, world!\");\n");
+        // The string is a single token, so the changes conflict
+        let tokenizer = options
+            .word_tokenizers
+            .tokenizer_for_path(repo_path("foo.rs"));
+        let result = files::try_merge_with_tokenizer(&inputs, &options, tokenizer);
+        assert!(result.merged.is_none());
+    }
+
+    #[test]
+    fn test_merge_by_subword() {
+        let settings = settings_from_text(indoc! {r#"
+            merge.hunk-level = "word"
+            [word-tokenizers.java]
+            builtin = "subword"
+            patterns = ["glob:'*.java'"]
+        "#});
+        let options = MergeOptions::from_settings(&settings).unwrap();
+        assert_eq!(options.hunk_level, FileMergeHunkLevel::Word);
+        assert_eq!(options.same_change, SameChange::Accept);
+        let inputs = Merge::from_vec(vec![
+            "fetchUserName();\n",
+            "getUserName();\n",
+            "getUserId();\n",
+        ]);
+        // Both sides changed the same identifier
+        let tokenizer = options
+            .word_tokenizers
+            .tokenizer_for_path(repo_path("foo.rs"));
+        let result = files::try_merge_with_tokenizer(&inputs, &options, tokenizer);
+        assert!(result.merged.is_none());
+        // Subwords changed by each side can be merged
+        let tokenizer = options
+            .word_tokenizers
+            .tokenizer_for_path(repo_path("Foo.java"));
+        let result = files::try_merge_with_tokenizer(&inputs, &options, tokenizer);
+        assert_eq!(result.merged.unwrap(), "fetchUserId();\n");
+        assert!(result.tokenizer_errors.is_empty());
+    }
+
+    #[test]
+    fn test_merge_tokenizer_errors() {
+        let settings = settings_from_text(indoc! {r#"
+            merge.hunk-level = "word"
+            [word-tokenizers.missing]
+            command = ["jj-test-missing-word-tokenizer"]
+            patterns = ["all()"]
+        "#});
+        let options = MergeOptions::from_settings(&settings).unwrap();
+        let inputs = Merge::from_vec(vec!["foo bar\n", "foo baz\n", "qux baz\n"]);
+        // Words are split by the default tokenizer, and the error is returned
+        let tokenizer = options
+            .word_tokenizers
+            .tokenizer_for_path(repo_path("file"));
+        let result = files::try_merge_with_tokenizer(&inputs, &options, tokenizer);
+        assert_eq!(result.merged.unwrap(), "qux bar\n");
+        assert_eq!(result.tokenizer_errors.len(), 3);
+        assert!(matches!(
+            result.tokenizer_errors[0],
+            WordTokenizerError::Io(_)
+        ));
+    }
+}
@@ -11,6 +11,7 @@ mod test_bad_locking;
 mod test_bisect;
 mod test_commit_builder;
 mod test_commit_concurrent;
+mod test_conflict_resolvers;
 mod test_conflicts;
 mod test_default_revset_graph_iterator;
 mod test_eol;
@@ -22,6 +23,7 @@ mod test_gpg;
 mod test_id_prefix;
 mod test_index;
 mod test_init;
+mod test_landed;
 mod test_load_repo;
 mod test_local_working_copy;
 mod test_local_working_copy_concurrent;
@@ -31,12 +33,16 @@ mod test_merge_trees;
 mod test_merged_tree;
 mod test_mut_repo;
 mod test_operations;
+mod test_patch;
+mod test_patch_id;
 mod test_refs;
+mod test_resolution_cache;
 mod test_revset;
 mod test_revset_optimized;
 mod test_rewrite;
 mod test_rewrite_duplicate;
 mod test_rewrite_transform;
+mod test_shelf;
 mod test_signing;
 mod test_ssh_signing;
 mod test_view;
@@ -0,0 +1,236 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use indoc::indoc;
+use itertools::Itertools as _;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::conflict_resolvers::ConflictResolverError;
+use jj_lib::conflict_resolvers::ConflictSide;
+use jj_lib::conflict_resolvers::ResolveStrategy;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::rebase_commit;
+use jj_lib::rewrite::run_conflict_resolver_commands;
+use jj_lib::settings::UserSettings;
+use pollster::FutureExt as _;
+use testutils::CommitBuilderExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::assert_tree_eq;
+use testutils::create_tree;
+use testutils::repo_path;
+
+fn merge_trees(base: &MergedTree, left: &MergedTree, right: &MergedTree) -> MergedTree {
+    MergedTree::merge(Merge::from_vec(vec![
+        (left.clone(), "left (rebase destination)".into()),
+        (base.clone(), "base".into()),
+        (right.clone(), "right (rebased revision)".into()),
+    ]))
+    .block_on()
+    .unwrap()
+}
+
+#[test]
+fn test_resolve_take_side() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path = repo_path("file");
+    let other_path = repo_path("other");
+    let base = create_tree(repo, &[(path, "a\nb\nc\nd\ne\n"), (other_path, "x\n")]);
+    let left = create_tree(repo, &[(path, "a\nb1\nc\nd\ne\n"), (other_path, "x1\n")]);
+    let right = create_tree(repo, &[(path, "a\nb2\nc\nd2\ne\n"), (other_path, "x2\n")]);
+    let merged = merge_trees(&base, &left, &right);
+
+    // Only the conflicting hunks are taken from the side
+    let strategy = ResolveStrategy::TakeSide(ConflictSide::Number(1));
+    let expected = create_tree(repo, &[(path, "a\nb1\nc\nd2\ne\n"), (other_path, "x1\n")]);
+    assert_tree_eq!(
+        strategy
+            .resolve_tree(&merged, &EverythingMatcher)
+            .block_on()?,
+        expected
+    );
+
+    // The side can be selected by label
+    let strategy = ResolveStrategy::TakeSide(ConflictSide::parse("rebased revision"));
+    let expected = create_tree(repo, &[(path, "a\nb2\nc\nd2\ne\n"), (other_path, "x2\n")]);
+    assert_tree_eq!(
+        strategy
+            .resolve_tree(&merged, &EverythingMatcher)
+            .block_on()?,
+        expected
+    );
+
+    let strategy = ResolveStrategy::TakeSide(ConflictSide::parse("base"));
+    assert_matches!(
+        strategy
+            .resolve_tree(&merged, &EverythingMatcher)
+            .block_on(),
+        Err(ConflictResolverError::SideNotFound { .. })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_resolve_union() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path = repo_path("file");
+    let base = create_tree(repo, &[(path, "a\nc\n")]);
+    let left = create_tree(repo, &[(path, "a\nb1\nc\nd\n")]);
+    let right = create_tree(repo, &[(path, "a\nb2\nc\ne")]);
+    let merged = merge_trees(&base, &left, &right);
+
+    // The last line of the right side has no newline, but the lines of the
+    // sides are kept separate
+    let expected = create_tree(repo, &[(path, "a\nb1\nb2\nc\nd\ne\n")]);
+    assert_tree_eq!(
+        ResolveStrategy::Union
+            .resolve_tree(&merged, &EverythingMatcher)
+            .block_on()?,
+        expected
+    );
+    Ok(())
+}
+
+#[test]
+fn test_conflict_resolvers_rebase() -> TestResult {
+    let mut config = testutils::base_user_config();
+    config.add_layer(ConfigLayer::parse(
+        ConfigSource::User,
+        indoc! {r#"
+            [conflict-resolvers.lockfiles]
+            patterns = ["glob:'*.lock'"]
+            take = "rebase destination"
+        "#},
+    )?);
+    let settings = UserSettings::from_config(config)?;
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    // Rebase B onto C:
+    // C foo=c          B' foo=conflict, a.lock=c
+    // | B foo=b   =>   |
+    // |/               C  foo=c
+    // A   foo=a        |
+    //                  A  foo=a
+    let lock_path = repo_path("a.lock");
+    let path = repo_path("foo");
+    let mut tx = repo.start_transaction();
+    let tree_a = create_tree(repo, &[(lock_path, "a\n"), (path, "a\n")]);
+    let tree_b = create_tree(repo, &[(lock_path, "b\n"), (path, "b\n")]);
+    let tree_c = create_tree(repo, &[(lock_path, "c\n"), (path, "c\n")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![store.root_commit_id().clone()], tree_a)
+        .write_unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b)
+        .write_unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c)
+        .write_unwrap();
+
+    let commit_b2 =
+        rebase_commit(tx.repo_mut(), commit_b, vec![commit_c.id().clone()]).block_on()?;
+    let tree = commit_b2.tree();
+    assert!(tree.path_value(lock_path).block_on()?.is_resolved());
+    assert!(!tree.path_value(path).block_on()?.is_resolved());
+    let expected = create_tree(repo, &[(lock_path, "c\n")]);
+    assert_eq!(
+        tree.path_value(lock_path).block_on()?,
+        expected.path_value(lock_path).block_on()?
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_conflict_resolver_commands_on_rebase() -> TestResult {
+    let mut config = testutils::base_user_config();
+    config.add_layer(ConfigLayer::parse(
+        ConfigSource::User,
+        indoc! {r#"
+            [conflict-resolvers.take-right]
+            patterns = ["glob:'*.lock'"]
+            command = ["cp", "$right", "$output"]
+            on-rebase = true
+        "#},
+    )?);
+    let settings = UserSettings::from_config(config)?;
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    // Rebase B onto C, and D is rebased onto B':
+    // D                D' a.lock=d
+    // B a.lock=b       B' a.lock=b
+    // | C a.lock=c =>  C  a.lock=c
+    // |/               |
+    // A   a.lock=a     A  a.lock=a
+    let lock_path = repo_path("a.lock");
+    let mut tx = repo.start_transaction();
+    let tree_a = create_tree(repo, &[(lock_path, "a\n")]);
+    let tree_b = create_tree(repo, &[(lock_path, "b\n")]);
+    let tree_c = create_tree(repo, &[(lock_path, "c\n")]);
+    let tree_d = create_tree(repo, &[(lock_path, "d\n")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![store.root_commit_id().clone()], tree_a)
+        .write_unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b.clone())
+        .write_unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c)
+        .write_unwrap();
+    let commit_d = tx
+        .repo_mut()
+        .new_commit(vec![commit_b.id().clone()], tree_d.clone())
+        .write_unwrap();
+    let repo = tx.commit("test").block_on()?;
+
+    // The command isn't run while rebasing
+    let mut tx = repo.start_transaction();
+    let commit_b2 =
+        rebase_commit(tx.repo_mut(), commit_b, vec![commit_c.id().clone()]).block_on()?;
+    assert!(commit_b2.has_conflict());
+    tx.repo_mut().rebase_descendants().block_on()?;
+
+    // The command is run for the conflicted commits, parents first
+    let failures = run_conflict_resolver_commands(tx.repo_mut()).block_on()?;
+    assert!(failures.is_empty());
+    let new_heads = tx.repo().view().heads().clone();
+    let [head_id] = new_heads.iter().collect_vec()[..] else {
+        panic!("expected a single head: {new_heads:?}");
+    };
+    let commit_d2 = tx.repo().store().get_commit(head_id)?;
+    assert_ne!(commit_d2.id(), commit_d.id());
+    assert_tree_eq!(commit_d2.tree(), tree_d);
+    let commit_b3 = commit_d2.parents().block_on()?.remove(0);
+    assert_eq!(commit_b3.change_id(), commit_b2.change_id());
+    assert_tree_eq!(commit_b3.tree(), tree_b);
+    Ok(())
+}
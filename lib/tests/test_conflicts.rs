@@ -16,6 +16,7 @@ use indoc::indoc;
 use itertools::Itertools as _;
 use jj_lib::backend::FileId;
 use jj_lib::conflict_labels::ConflictLabels;
+use jj_lib::conflict_resolvers::ConflictResolvers;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::ConflictMaterializeOptions;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
@@ -31,6 +32,7 @@ use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::store::Store;
 use jj_lib::tree_merge::MergeOptions;
+use jj_lib::word_tokenizer::WordTokenizers;
 use pollster::FutureExt as _;
 use test_case::test_case;
 use testutils::TestRepo;
@@ -2547,6 +2549,8 @@ fn materialize_conflict_string_with_labels(
         merge: MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            word_tokenizers: WordTokenizers::default(),
+            conflict_resolvers: ConflictResolvers::default(),
         },
     };
     String::from_utf8(
@@ -79,12 +79,14 @@ use jj_lib::ref_name::RemoteName;
 use jj_lib::ref_name::RemoteRefSymbol;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
-use jj_lib::repo::Repo as _;
+use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::SigStatus;
 use jj_lib::signing::Signer;
 use jj_lib::str_util::StringExpression;
 use jj_lib::str_util::StringMatcher;
 use jj_lib::str_util::StringPattern;
+use jj_lib::test_signing_backend::TestSigningBackend;
 use jj_lib::workspace::Workspace;
 use maplit::btreemap;
 use maplit::hashset;
@@ -95,9 +97,11 @@ use testutils::CommitBuilderExt as _;
 use testutils::TestRepo;
 use testutils::TestRepoBackend;
 use testutils::TestResult;
+use testutils::assert_tree_eq;
 use testutils::base_user_config;
 use testutils::commit_transactions;
 use testutils::create_random_commit;
+use testutils::create_tree;
 use testutils::repo_path;
 use testutils::write_random_commit;
 use testutils::write_random_commit_with_parents;
@@ -4766,6 +4770,86 @@ fn test_push_bookmarks_partial_success() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_push_bookmarks_atomic_failure() -> TestResult {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let mut tx = setup.jj_repo.start_transaction();
+    let subprocess_options = GitSubprocessOptions::from_settings(&settings)?;
+
+    let targets = GitPushRefTargets {
+        bookmarks: vec![
+            (
+                "main".into(),
+                Diff::new(
+                    Some(setup.main_commit.id().clone()),
+                    Some(setup.child_of_main_commit.id().clone()),
+                ),
+            ),
+            (
+                "other".into(),
+                Diff::new(
+                    Some(setup.main_commit.id().clone()), // bad old state
+                    Some(setup.child_of_main_commit.id().clone()),
+                ),
+            ),
+        ],
+        tags: vec![],
+    };
+    let stats = git::push_refs(
+        tx.repo_mut(),
+        subprocess_options,
+        "origin".as_ref(),
+        &targets,
+        &mut NullCallback,
+        &GitPushOptions {
+            atomic: true,
+            ..Default::default()
+        },
+    )?;
+    insta::assert_debug_snapshot!(stats, @r#"
+    GitPushStats {
+        pushed: [],
+        rejected: [
+            (
+                GitRefNameBuf(
+                    "refs/heads/main",
+                ),
+                Some(
+                    "atomic push failed",
+                ),
+            ),
+            (
+                GitRefNameBuf(
+                    "refs/heads/other",
+                ),
+                Some(
+                    "stale info",
+                ),
+            ),
+        ],
+        remote_rejected: [],
+        unexported_bookmarks: [],
+    }
+    "#);
+
+    // Nothing should be pushed
+    let view = tx.repo().view();
+    assert_eq!(
+        *view.get_remote_bookmark(remote_symbol("main", "origin")),
+        RemoteRef {
+            target: RefTarget::normal(setup.main_commit.id().clone()),
+            state: RemoteRefState::Tracked,
+        }
+    );
+    assert_eq!(
+        view.get_remote_bookmark(remote_symbol("other", "origin")),
+        RemoteRef::absent_ref()
+    );
+    Ok(())
+}
+
 #[test]
 fn test_push_bookmarks_unmapped_refs() -> TestResult {
     let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
@@ -6255,6 +6339,7 @@ fn test_push_updates_with_options() -> TestResult {
                 "merge_request.create".to_owned(),
                 "merge_request.draft".to_owned(),
             ],
+            atomic: false,
         },
     )?;
 
@@ -6274,6 +6359,497 @@ fn test_push_updates_with_options() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_import_git_stashes() -> TestResult {
+    let test_data = GitRepoData::create();
+    let git_workdir = test_data.git_repo.workdir().unwrap().to_owned();
+    let run_git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args([
+                "-c",
+                "user.name=Test User",
+                "-c",
+                "user.email=test.user@example.com",
+            ])
+            .args(args)
+            .current_dir(&git_workdir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+    fs::write(git_workdir.join("file"), "base\n")?;
+    run_git(&["add", "file"]);
+    run_git(&["commit", "-m", "base"]);
+    fs::write(git_workdir.join("file"), "stash 1\n")?;
+    run_git(&["stash", "push", "-m", "first"]);
+    fs::write(git_workdir.join("file"), "stash 2\n")?;
+    run_git(&["stash", "push", "-m", "second"]);
+    let base_oid = test_data.git_repo.head_id()?.detach();
+
+    let mut tx = test_data.repo.start_transaction();
+    let names = git::import_git_stashes(tx.repo_mut()).block_on()?;
+    assert_eq!(names, ["stash@{0}", "stash@{1}"]);
+    let repo = tx.commit("test").block_on()?;
+    let shelves = repo.view().shelves();
+    let shelf_commit = repo.store().get_commit(&shelves["stash@{0}"])?;
+    assert_eq!(shelf_commit.parent_ids(), [jj_id(base_oid)]);
+    assert!(shelf_commit.description().ends_with(": second"));
+    assert_tree_eq!(
+        shelf_commit.tree(),
+        create_tree(&repo, &[(repo_path("file"), "stash 2\n")])
+    );
+    let shelf_commit = repo.store().get_commit(&shelves["stash@{1}"])?;
+    assert_tree_eq!(
+        shelf_commit.tree(),
+        create_tree(&repo, &[(repo_path("file"), "stash 1\n")])
+    );
+    // Shelves aren't visible
+    assert!(!repo.view().heads().contains(shelf_commit.id()));
+    Ok(())
+}
+
+#[test]
+fn test_set_annotated_tag() -> TestResult {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let tagger = Signature {
+        name: "Tagger".to_owned(),
+        email: "tagger@example.com".to_owned(),
+        timestamp: Timestamp {
+            timestamp: MillisSinceEpoch(1_000_000),
+            tz_offset: 60,
+        },
+    };
+
+    let mut tx = repo.start_transaction();
+    let commit = write_random_commit(tx.repo_mut());
+    git::set_annotated_tag(
+        tx.repo_mut(),
+        "v1".as_ref(),
+        commit.id(),
+        "Release 1",
+        &tagger,
+        None,
+    )?;
+    let mut sign = |data: &[u8]| signer.sign(data, Some("key"));
+    git::set_annotated_tag(
+        tx.repo_mut(),
+        "v2".as_ref(),
+        commit.id(),
+        "Release 2\n\nDetails\n",
+        &tagger,
+        Some(&mut sign),
+    )?;
+    // The Git refs aren't updated until exported
+    let git_repo = get_git_repo(repo);
+    assert!(git_repo.try_find_reference("refs/tags/v1")?.is_none());
+    // The tag objects are exported as is
+    let stats = git::export_refs(tx.repo_mut())?;
+    assert!(stats.failed_tags.is_empty());
+    let repo = tx.commit("test").block_on()?;
+    let target = RefTarget::normal(commit.id().clone());
+    assert_eq!(*repo.view().get_local_tag("v1".as_ref()), target);
+    assert_eq!(
+        repo.view()
+            .get_remote_tag(remote_symbol("v2", "git"))
+            .target,
+        target
+    );
+
+    let tag = git::get_git_tag(&git_repo, "v1".as_ref(), None, &signer)?.unwrap();
+    assert_eq!(tag.target, *commit.id());
+    assert_eq!(tag.message, "Release 1\n");
+    assert_eq!(tag.tagger.as_ref(), Some(&tagger));
+    assert_eq!(tag.secure_sig, None);
+
+    let tag = git::get_git_tag(&git_repo, "v2".as_ref(), None, &signer)?.unwrap();
+    assert_eq!(tag.message, "Release 2\n\nDetails\n");
+    let sig = tag.secure_sig.unwrap();
+    let verification = signer.verify_data(&sig.data, &sig.sig)?;
+    assert_eq!(verification.status, SigStatus::Good);
+    assert_eq!(verification.key.as_deref(), Some("key"));
+
+    // Tampered tag doesn't verify
+    let data = [&sig.data[..], b"x"].concat();
+    let verification = signer.verify_data(&data, &sig.sig)?;
+    assert_eq!(verification.status, SigStatus::Bad);
+
+    // The signature isn't recognized without a backend which can read it
+    let tag = git::get_git_tag(&git_repo, "v2".as_ref(), None, &Signer::new(None, vec![]))?;
+    let tag = tag.unwrap();
+    assert!(tag.message.starts_with("Release 2\n\nDetails\n"));
+    assert_eq!(tag.secure_sig, None);
+
+    // Re-creating the tag at the same commit replaces the tag object
+    let mut tx = repo.start_transaction();
+    git::set_annotated_tag(
+        tx.repo_mut(),
+        "v1".as_ref(),
+        commit.id(),
+        "Release 1 again",
+        &tagger,
+        None,
+    )?;
+    let stats = git::export_refs(tx.repo_mut())?;
+    assert!(stats.failed_tags.is_empty());
+    tx.commit("test").block_on()?;
+    let tag = git::get_git_tag(&git_repo, "v1".as_ref(), None, &signer)?.unwrap();
+    assert_eq!(tag.message, "Release 1 again\n");
+
+    // Lightweight or missing tag
+    let mut tx = repo.start_transaction();
+    tx.repo_mut()
+        .set_local_tag_target("light".as_ref(), target.clone());
+    git::export_refs(tx.repo_mut())?;
+    assert_eq!(
+        git::get_git_tag(&git_repo, "light".as_ref(), None, &signer)?,
+        None
+    );
+    assert_eq!(
+        git::get_git_tag(&git_repo, "missing".as_ref(), None, &signer)?,
+        None
+    );
+    Ok(())
+}
+
+fn add_git_note(git_dir: &Path, commit_id: &CommitId, message: &str) {
+    let output = std::process::Command::new("git")
+        .arg("--git-dir")
+        .arg(git_dir)
+        .args([
+            "-c",
+            "user.name=Test User",
+            "-c",
+            "user.email=test.user@example.com",
+        ])
+        .args(["notes", "add", "-f", "-m", message, &commit_id.hex()])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "Failed to add note: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn get_git_note(repo: &dyn Repo, commit_id: &CommitId) -> Option<String> {
+    let note = git::GitNotes::load(repo).unwrap().get(commit_id).unwrap()?;
+    Some(String::from_utf8(note.into()).unwrap())
+}
+
+fn show_git_note(git_dir: &Path, commit_id: &CommitId) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("--git-dir")
+        .arg(git_dir)
+        .args(["notes", "show", &commit_id.hex()])
+        .output()
+        .unwrap();
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn test_copy_git_notes() -> TestResult {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let git_repo = get_git_repo(&setup.jj_repo);
+    add_git_note(git_repo.path(), setup.main_commit.id(), "note 1");
+    add_git_note(git_repo.path(), setup.parent_of_main_commit.id(), "note 2");
+
+    let mut tx = setup.jj_repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &default_import_options()).block_on()?;
+    let old_notes_target = tx.repo().view().git_notes().clone();
+    assert!(old_notes_target.is_present());
+    assert_eq!(tx.repo().view().git_notes_ref(), &old_notes_target);
+
+    // Notes of multiple predecessors are concatenated
+    let commit1 = tx
+        .repo_mut()
+        .rewrite_commit(&setup.child_of_main_commit)
+        .set_description("rewritten 1")
+        .set_predecessors(vec![setup.main_commit.id().clone()])
+        .write()
+        .block_on()?;
+    let commit2 = tx
+        .repo_mut()
+        .rewrite_commit(&setup.sideways_commit)
+        .set_description("rewritten 2")
+        .set_predecessors(vec![
+            setup.main_commit.id().clone(),
+            setup.parent_of_main_commit.id().clone(),
+        ])
+        .write()
+        .block_on()?;
+    // Notes are copied when the rewrites are processed
+    assert_eq!(get_git_note(tx.repo(), commit1.id()), None);
+    tx.repo_mut().rebase_descendants().block_on()?;
+    assert!(tx.repo().git_notes_copy_error().is_none());
+    assert_eq!(
+        get_git_note(tx.repo(), commit1.id()).as_deref(),
+        Some("note 1\n")
+    );
+    assert_eq!(
+        get_git_note(tx.repo(), commit2.id()).as_deref(),
+        Some("note 1\n\nnote 2\n")
+    );
+    assert_ne!(tx.repo().view().git_notes(), &old_notes_target);
+
+    // Notes aren't copied from abandoned commits
+    let notes_target = tx.repo().view().git_notes().clone();
+    tx.repo_mut().record_abandoned_commit(&commit2);
+    tx.repo_mut().rebase_descendants().block_on()?;
+    assert_eq!(tx.repo().view().git_notes(), &notes_target);
+
+    // The Git notes ref isn't updated until exported
+    assert_eq!(tx.repo().view().git_notes_ref(), &old_notes_target);
+    assert_eq!(show_git_note(git_repo.path(), commit1.id()), None);
+    git::export_refs(tx.repo_mut())?;
+    assert_eq!(
+        tx.repo().view().git_notes_ref(),
+        tx.repo().view().git_notes()
+    );
+    assert_eq!(
+        show_git_note(git_repo.path(), commit1.id()).as_deref(),
+        Some("note 1\n")
+    );
+    let repo = tx.commit("test").block_on()?;
+
+    // The previous operation doesn't see the copied notes
+    assert_eq!(get_git_note(setup.jj_repo.as_ref(), commit1.id()), None);
+    assert_eq!(
+        get_git_note(repo.as_ref(), commit1.id()).as_deref(),
+        Some("note 1\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_export_git_notes_moved_in_git() -> TestResult {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let git_repo = get_git_repo(&setup.jj_repo);
+    add_git_note(git_repo.path(), setup.main_commit.id(), "note 1");
+
+    let mut tx = setup.jj_repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &default_import_options()).block_on()?;
+    let old_notes_target = tx.repo().view().git_notes().clone();
+    tx.repo_mut()
+        .rewrite_commit(&setup.child_of_main_commit)
+        .set_description("rewritten")
+        .set_predecessors(vec![setup.main_commit.id().clone()])
+        .write()
+        .block_on()?;
+    tx.repo_mut().rebase_descendants().block_on()?;
+
+    // The Git notes ref was moved since the last import
+    add_git_note(git_repo.path(), setup.parent_of_main_commit.id(), "note 2");
+    let stats = git::export_refs(tx.repo_mut())?;
+    assert_matches!(
+        stats.failed_notes,
+        Some(FailedRefExportReason::FailedToSet(_))
+    );
+    assert_eq!(tx.repo().view().git_notes_ref(), &old_notes_target);
+    assert_ne!(tx.repo().view().git_notes(), &old_notes_target);
+    Ok(())
+}
+
+#[test]
+fn test_import_diverged_git_notes() -> TestResult {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let git_repo = get_git_repo(&setup.jj_repo);
+    add_git_note(git_repo.path(), setup.main_commit.id(), "note 1");
+    let mut tx = setup.jj_repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &default_import_options()).block_on()?;
+    let repo = tx.commit("import").block_on()?;
+
+    // Notes changed in both Git and jj
+    let mut tx = repo.start_transaction();
+    let commit1 = tx
+        .repo_mut()
+        .rewrite_commit(&setup.child_of_main_commit)
+        .set_description("rewritten")
+        .set_predecessors(vec![setup.main_commit.id().clone()])
+        .write()
+        .block_on()?;
+    tx.repo_mut().rebase_descendants().block_on()?;
+    add_git_note(git_repo.path(), setup.main_commit.id(), "note 2");
+    git::import_refs(tx.repo_mut(), &default_import_options()).block_on()?;
+    assert!(tx.repo().view().git_notes().has_conflict());
+    assert_eq!(
+        get_git_note(tx.repo(), commit1.id()).as_deref(),
+        Some("note 1\n")
+    );
+    assert_eq!(
+        get_git_note(tx.repo(), setup.main_commit.id()).as_deref(),
+        Some("note 1\n\nnote 2\n")
+    );
+
+    // Diverged notes are merged on export
+    git::export_refs(tx.repo_mut())?;
+    assert!(tx.repo().view().git_notes().is_present());
+    assert_eq!(
+        tx.repo().view().git_notes_ref(),
+        tx.repo().view().git_notes()
+    );
+    assert_eq!(
+        show_git_note(git_repo.path(), commit1.id()).as_deref(),
+        Some("note 1\n")
+    );
+    assert_eq!(
+        show_git_note(git_repo.path(), setup.main_commit.id()).as_deref(),
+        Some("note 1\n\nnote 2\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_fetch_raw_refs() -> TestResult {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let subprocess_options = GitSubprocessOptions::from_settings(&settings)?;
+    let source_repo = testutils::git::open(&setup.source_repo_dir);
+    let change_commit = empty_git_commit(&source_repo, "refs/changes/01/1/1", &[]);
+    let git_repo = get_git_repo(&setup.jj_repo);
+    let remote = RemoteName::new("origin");
+
+    let fetched = git::fetch_raw_refs(
+        setup.jj_repo.store(),
+        subprocess_options.clone(),
+        remote,
+        "refs/changes/*",
+        "refs/fetched/*",
+        &mut NullCallback,
+    )?;
+    assert!(fetched);
+    let fetched_ref = git_repo.find_reference("refs/fetched/01/1/1")?;
+    assert_eq!(fetched_ref.id().detach(), change_commit);
+    // The fetched commits aren't imported
+    assert!(!setup.jj_repo.index().has_id(&jj_id(change_commit))?);
+
+    let ref_commits =
+        git::import_raw_ref_commits(setup.jj_repo.store(), "refs/fetched/").block_on()?;
+    assert_eq!(ref_commits.len(), 1);
+    assert_eq!(ref_commits[0].0, "refs/fetched/01/1/1");
+    assert_eq!(ref_commits[0].1.id(), &jj_id(change_commit));
+    // The commits aren't added to the repo
+    assert!(!setup.jj_repo.index().has_id(&jj_id(change_commit))?);
+    // The refs are removed
+    assert!(
+        git_repo
+            .try_find_reference("refs/fetched/01/1/1")?
+            .is_none()
+    );
+
+    // Nonexistent ref
+    let fetched = git::fetch_raw_refs(
+        setup.jj_repo.store(),
+        subprocess_options.clone(),
+        remote,
+        "refs/changes/02/2/1",
+        "refs/fetched/02/2/1",
+        &mut NullCallback,
+    )?;
+    assert!(!fetched);
+
+    // Nonexistent remote
+    let result = git::fetch_raw_refs(
+        setup.jj_repo.store(),
+        subprocess_options,
+        RemoteName::new("unknown"),
+        "refs/changes/*",
+        "refs/fetched/*",
+        &mut NullCallback,
+    );
+    assert_matches!(result, Err(GitFetchError::NoSuchRemote(_)));
+    Ok(())
+}
+
+#[test]
+fn test_fetch_and_push_git_notes() -> TestResult {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let subprocess_options = GitSubprocessOptions::from_settings(&settings)?;
+    let git_repo = get_git_repo(&setup.jj_repo);
+    let remote = RemoteName::new("origin");
+
+    // Nothing to fetch or push
+    let mut tx = setup.jj_repo.start_transaction();
+    let fetched = git::fetch_git_notes(
+        tx.repo_mut(),
+        subprocess_options.clone(),
+        remote,
+        &mut NullCallback,
+    )?;
+    assert!(!fetched);
+    let stats = git::push_git_notes(
+        tx.repo_mut(),
+        subprocess_options.clone(),
+        remote,
+        &mut NullCallback,
+    )?;
+    assert!(stats.pushed.is_empty());
+
+    // Diverged notes are merged
+    add_git_note(&setup.source_repo_dir, setup.main_commit.id(), "remote 1");
+    add_git_note(
+        &setup.source_repo_dir,
+        setup.parent_of_main_commit.id(),
+        "remote 2",
+    );
+    add_git_note(git_repo.path(), setup.main_commit.id(), "local 1");
+    git::import_refs(tx.repo_mut(), &default_import_options()).block_on()?;
+    let fetched = git::fetch_git_notes(
+        tx.repo_mut(),
+        subprocess_options.clone(),
+        remote,
+        &mut NullCallback,
+    )?;
+    assert!(fetched);
+    assert_eq!(
+        get_git_note(tx.repo(), setup.main_commit.id()).as_deref(),
+        Some("local 1\n\nremote 1\n")
+    );
+    assert_eq!(
+        get_git_note(tx.repo(), setup.parent_of_main_commit.id()).as_deref(),
+        Some("remote 2\n")
+    );
+
+    // Merged notes can be pushed
+    let stats = git::push_git_notes(
+        tx.repo_mut(),
+        subprocess_options.clone(),
+        remote,
+        &mut NullCallback,
+    )?;
+    assert!(stats.all_ok());
+    assert_eq!(
+        stats.pushed,
+        vec![GitRefNameBuf::from("refs/notes/commits")]
+    );
+    assert_eq!(
+        show_git_note(&setup.source_repo_dir, setup.main_commit.id()).as_deref(),
+        Some("local 1\n\nremote 1\n")
+    );
+
+    // Pushing again is no-op
+    let stats = git::push_git_notes(tx.repo_mut(), subprocess_options, remote, &mut NullCallback)?;
+    assert!(stats.pushed.is_empty());
+    Ok(())
+}
+
 #[test]
 fn test_remote_add_with_refspecs() -> TestResult {
     let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
@@ -240,7 +240,10 @@ fn gpg_signing_roundtrip() -> TestResult {
 
     let check = backend.verify(data, &signature)?;
     assert_eq!(check.status, SigStatus::Good);
-    assert_eq!(check.key.unwrap(), "638785CB16FEA061");
+    assert_eq!(
+        check.key.unwrap(),
+        "A58E5EE9067153C8F5E5E9E9638785CB16FEA061"
+    );
     assert_eq!(
         check.display.unwrap(),
         "Someone (jj test signing key) <someone@example.com>"
@@ -248,7 +251,10 @@ fn gpg_signing_roundtrip() -> TestResult {
 
     let check = backend.verify(b"so so bad", &signature)?;
     assert_eq!(check.status, SigStatus::Bad);
-    assert_eq!(check.key.unwrap(), "638785CB16FEA061");
+    assert_eq!(
+        check.key.unwrap(),
+        "A58E5EE9067153C8F5E5E9E9638785CB16FEA061"
+    );
     assert_eq!(
         check.display.unwrap(),
         "Someone (jj test signing key) <someone@example.com>"
@@ -270,7 +276,7 @@ fn gpg_signing_roundtrip_explicit_key() -> TestResult {
     Verification {
         status: Good,
         key: Some(
-            "4ED556E9729E000F",
+            "475040684A54DD3294894BC54ED556E9729E000F",
         ),
         display: Some(
             "Someone Else (jj test signing key) <someone-else@example.com>",
@@ -281,7 +287,7 @@ fn gpg_signing_roundtrip_explicit_key() -> TestResult {
     Verification {
         status: Bad,
         key: Some(
-            "4ED556E9729E000F",
+            "475040684A54DD3294894BC54ED556E9729E000F",
         ),
         display: Some(
             "Someone Else (jj test signing key) <someone-else@example.com>",
@@ -291,6 +297,27 @@ fn gpg_signing_roundtrip_explicit_key() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(windows, ignore = "stuck randomly on Windows CI #3140")] // FIXME
+fn gpg_key_fingerprints() -> TestResult {
+    gpg_guard!();
+
+    let env = GpgEnvironment::new().unwrap();
+    let backend = gpg_backend(&env);
+
+    // The default key isn't looked up by the user email
+    assert!(backend.key_fingerprints(None)?.is_empty());
+    // The fingerprints are in the format reported by verification
+    let fingerprints = backend.key_fingerprints(Some("someone@example.com"))?;
+    assert!(fingerprints.contains(&"A58E5EE9067153C8F5E5E9E9638785CB16FEA061".to_owned()));
+    let fingerprints = backend.key_fingerprints(Some("Someone Else"))?;
+    assert!(fingerprints.contains(&"475040684A54DD3294894BC54ED556E9729E000F".to_owned()));
+    assert!(!fingerprints.contains(&"A58E5EE9067153C8F5E5E9E9638785CB16FEA061".to_owned()));
+    // Ambiguous keys have no fingerprints
+    assert!(backend.key_fingerprints(Some("Someone"))?.is_empty());
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(windows, ignore = "stuck randomly on Windows CI #3140")] // FIXME
 fn gpg_unknown_key() -> TestResult {
@@ -309,7 +336,7 @@ fn gpg_unknown_key() -> TestResult {
     Verification {
         status: Unknown,
         key: Some(
-            "071FE3E324DD7333",
+            "2CDB7F2953B782FD13A83C9D071FE3E324DD7333",
         ),
         display: None,
     }
@@ -318,7 +345,7 @@ fn gpg_unknown_key() -> TestResult {
     Verification {
         status: Unknown,
         key: Some(
-            "071FE3E324DD7333",
+            "2CDB7F2953B782FD13A83C9D071FE3E324DD7333",
         ),
         display: None,
     }
@@ -1138,6 +1138,68 @@ fn test_commit_is_empty(indexed: bool) -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_patch_ids_saved_in_index_dir() -> TestResult {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+    let root_commit_id = repo.store().root_commit_id();
+
+    let tree1 = create_tree(repo, &[(repo_path("a"), "a\n")]);
+    let tree2 = create_tree(repo, &[(repo_path("b"), "b\n")]);
+
+    let mut tx = repo.start_transaction();
+    let commit1 = tx
+        .repo_mut()
+        .new_commit(vec![root_commit_id.clone()], tree1.clone())
+        .write_unwrap();
+    let commit2 = tx
+        .repo_mut()
+        .new_commit(vec![root_commit_id.clone()], tree2)
+        .write_unwrap();
+    let commit3 = tx
+        .repo_mut()
+        .new_commit(vec![root_commit_id.clone()], tree1)
+        .write_unwrap();
+    let repo = tx.commit("test").block_on()?;
+
+    let evaluate_equivalent = |repo: &ReadonlyRepo| {
+        // Constructs ResolvedExpression directly to bypass symbol resolution.
+        let expression = ResolvedExpression::Equivalent {
+            sources: ResolvedExpression::Commits(vec![commit1.id().clone()]).into(),
+            domain: ResolvedExpression::Commits(vec![
+                commit1.id().clone(),
+                commit2.id().clone(),
+                commit3.id().clone(),
+            ])
+            .into(),
+        };
+        let revset = repo
+            .index()
+            .evaluate_revset(&expression, repo.store())
+            .unwrap();
+        revset.iter().try_collect::<_, Vec<_>, _>().unwrap()
+    };
+
+    let patch_ids_dir = test_repo.repo_path().join("index").join("patch_ids");
+    assert!(!patch_ids_dir.exists());
+    assert_eq!(
+        evaluate_equivalent(&repo),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(patch_ids_dir.read_dir()?.count(), 1);
+
+    // Patch ids are loaded from the saved file, so no new file is written.
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(
+        evaluate_equivalent(&repo),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(patch_ids_dir.read_dir()?.count(), 1);
+    Ok(())
+}
+
 #[test]
 fn test_change_id_index() {
     let test_repo = TestRepo::init();
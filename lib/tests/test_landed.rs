@@ -0,0 +1,350 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::slice;
+
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::commit::Commit;
+use jj_lib::landed::LandedCommit;
+use jj_lib::landed::LandedMatch;
+use jj_lib::landed::abandon_landed_commits;
+use jj_lib::landed::find_landed_commits;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use pollster::FutureExt as _;
+use testutils::CommitBuilderExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::create_tree;
+use testutils::repo_path;
+
+fn summarize(landed: &[LandedCommit]) -> Vec<(&Commit, &Commit, LandedMatch)> {
+    landed
+        .iter()
+        .map(|landed| (&landed.local, &landed.upstream, landed.matched_by))
+        .collect()
+}
+
+#[test]
+fn test_find_landed_commits() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path_1 = repo_path("file1");
+    let path_2 = repo_path("file2");
+    let path_3 = repo_path("file3");
+
+    // Upstream landed L1 by patch, L2 by Change-Id trailer, and L4 with the
+    // same change id. L3 hasn't landed.
+    //
+    // U3
+    // U2
+    // U1  L3
+    // X   L2
+    // |   L1  L4
+    // |  /   /
+    // A -----
+    let tree_a = create_tree(repo, &[(path_1, "a\n")]);
+    let tree_l1 = create_tree(repo, &[(path_1, "b\n")]);
+    let tree_l2 = create_tree(repo, &[(path_1, "b\n"), (path_2, "l2\n")]);
+    let tree_l3 = create_tree(repo, &[(path_1, "b\n"), (path_2, "l2\n"), (path_3, "l3\n")]);
+    let tree_l4 = create_tree(repo, &[(path_1, "a\n"), (path_3, "l4\n")]);
+    let tree_x = create_tree(repo, &[(path_1, "a\n"), (path_3, "x\n")]);
+    let tree_u1 = create_tree(repo, &[(path_1, "b\n"), (path_3, "x\n")]);
+    let tree_u2 = create_tree(repo, &[(path_1, "b\n"), (path_2, "u2\n"), (path_3, "x\n")]);
+    let tree_u3 = create_tree(repo, &[(path_1, "b\n"), (path_2, "u2\n"), (path_3, "u3\n")]);
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit_a = mut_repo
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a)
+        .write_unwrap();
+    let commit_l1 = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_l1)
+        .set_description("l1")
+        .write_unwrap();
+    let commit_l2 = mut_repo
+        .new_commit(vec![commit_l1.id().clone()], tree_l2)
+        .set_description("l2")
+        .write_unwrap();
+    let commit_l3 = mut_repo
+        .new_commit(vec![commit_l2.id().clone()], tree_l3)
+        .set_description("l3")
+        .write_unwrap();
+    let commit_l4 = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_l4)
+        .set_description("l4")
+        .write_unwrap();
+    let commit_x = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_x)
+        .set_description("x")
+        .write_unwrap();
+    let commit_u1 = mut_repo
+        .new_commit(vec![commit_x.id().clone()], tree_u1)
+        .set_description("l1 (squashed)")
+        .write_unwrap();
+    let commit_u2 = mut_repo
+        .new_commit(vec![commit_u1.id().clone()], tree_u2)
+        .set_description(format!(
+            "l2 (amended)\n\nChange-Id: I{}6a6a6964\n",
+            commit_l2.change_id().hex()
+        ))
+        .write_unwrap();
+    let commit_u3 = mut_repo
+        .new_commit(vec![commit_u2.id().clone()], tree_u3)
+        .set_change_id(commit_l4.change_id().clone())
+        .set_description("l4 (amended)")
+        .write_unwrap();
+
+    let local = [
+        commit_l1.clone(),
+        commit_l2.clone(),
+        commit_l3.clone(),
+        commit_l4.clone(),
+    ];
+    let upstream = [
+        commit_x.clone(),
+        commit_u1.clone(),
+        commit_u2.clone(),
+        commit_u3.clone(),
+    ];
+    let landed = find_landed_commits(tx.repo(), &local, &upstream, true).block_on()?;
+    assert_eq!(
+        summarize(&landed),
+        [
+            (&commit_l1, &commit_u1, LandedMatch::PatchId),
+            (&commit_l2, &commit_u2, LandedMatch::Trailer),
+            (&commit_l4, &commit_u3, LandedMatch::ChangeId),
+        ]
+    );
+
+    // Patch ids aren't compared unless requested
+    let landed = find_landed_commits(tx.repo(), &local, &upstream, false).block_on()?;
+    assert_eq!(
+        summarize(&landed),
+        [
+            (&commit_l2, &commit_u2, LandedMatch::Trailer),
+            (&commit_l4, &commit_u3, LandedMatch::ChangeId),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_find_landed_commits_by_trailer() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path = repo_path("file");
+    let tree_1 = create_tree(repo, &[(path, "1\n")]);
+    let tree_2 = create_tree(repo, &[(path, "2\n")]);
+    let tree_3 = create_tree(repo, &[(path, "3\n")]);
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let root_id = repo.store().root_commit_id().clone();
+    let commit_local_1 = mut_repo
+        .new_commit(vec![root_id.clone()], tree_1.clone())
+        .set_description("local\n\nChange-Id: I1111\n")
+        .write_unwrap();
+    let commit_local_2 = mut_repo
+        .new_commit(vec![root_id.clone()], tree_2.clone())
+        .set_description("local\n\nReviewed-on: https://example.com/c/2\n")
+        .write_unwrap();
+    // Explicit Change-Id trailers of the local commits aren't used since they
+    // are kept by cherry-picks to other branches
+    let commit_upstream_1 = mut_repo
+        .new_commit(vec![root_id.clone()], tree_3.clone())
+        .set_description("upstream\n\nchange-id: I1111\n")
+        .write_unwrap();
+    // The generated Change-Id takes precedence over the Reviewed-on trailer
+    let commit_upstream_2 = mut_repo
+        .new_commit(vec![root_id.clone()], tree_3.clone())
+        .set_description("upstream\n\nReviewed-on: https://example.com/c/2\n")
+        .write_unwrap();
+    // The Change-Id generated from the change id is matched
+    let commit_upstream_3 = mut_repo
+        .new_commit(vec![root_id.clone()], tree_3)
+        .set_description(format!(
+            "upstream\n\nchange-id: I{}6a6a6964\n",
+            commit_local_2.change_id().hex()
+        ))
+        .write_unwrap();
+
+    let local = [commit_local_1.clone(), commit_local_2.clone()];
+    let upstream = [
+        commit_upstream_3.clone(),
+        commit_upstream_2.clone(),
+        commit_upstream_1.clone(),
+    ];
+    let landed = find_landed_commits(tx.repo(), &local, &upstream, false).block_on()?;
+    assert_eq!(
+        summarize(&landed),
+        [(&commit_local_2, &commit_upstream_3, LandedMatch::Trailer)]
+    );
+
+    // The Reviewed-on trailer is matched
+    let commit_local_3 = tx
+        .repo_mut()
+        .new_commit(vec![root_id.clone()], tree_2.clone())
+        .set_description("local\n\nreviewed-on: https://example.com/c/2\n")
+        .write_unwrap();
+    let landed = find_landed_commits(
+        tx.repo(),
+        slice::from_ref(&commit_local_3),
+        &upstream,
+        false,
+    )
+    .block_on()?;
+    assert_eq!(
+        summarize(&landed),
+        [(&commit_local_3, &commit_upstream_2, LandedMatch::Trailer)]
+    );
+
+    // Nothing matches without the trailers
+    let commit_local_4 = tx
+        .repo_mut()
+        .new_commit(vec![root_id], tree_2)
+        .set_change_id(ChangeId::from_hex("00000000000000000000000000000000"))
+        .set_description("local")
+        .write_unwrap();
+    let landed = find_landed_commits(tx.repo(), &[commit_local_4], &upstream, false).block_on()?;
+    assert!(landed.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_find_landed_commits_empty() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Empty commits aren't matched by patch id
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let root_id = repo.store().root_commit_id().clone();
+    let empty_tree = repo.store().empty_merged_tree();
+    let commit_local = mut_repo
+        .new_commit(vec![root_id.clone()], empty_tree.clone())
+        .write_unwrap();
+    let commit_upstream = mut_repo
+        .new_commit(vec![root_id], empty_tree)
+        .write_unwrap();
+    let landed =
+        find_landed_commits(tx.repo(), &[commit_local], &[commit_upstream], true).block_on()?;
+    assert!(landed.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_find_landed_commits_cherry_picked() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path = repo_path("file");
+    let tree = create_tree(repo, &[(path, "1\n")]);
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let root_id = repo.store().root_commit_id().clone();
+    let commit_upstream = mut_repo
+        .new_commit(vec![root_id.clone()], tree.clone())
+        .set_description("upstream\n")
+        .write_unwrap();
+    let commit_local = mut_repo
+        .new_commit(vec![root_id.clone()], tree.clone())
+        .set_description("local\n")
+        .write_unwrap();
+    // A backported copy makes the same changes as the upstream commit, but
+    // doesn't land with it
+    let commit_copy = mut_repo
+        .new_commit(vec![root_id], tree)
+        .set_description(format!(
+            "upstream\n\n(cherry picked from commit {})\n",
+            commit_upstream.id().hex()
+        ))
+        .write_unwrap();
+
+    let landed = find_landed_commits(
+        tx.repo(),
+        &[commit_local.clone(), commit_copy],
+        slice::from_ref(&commit_upstream),
+        true,
+    )
+    .block_on()?;
+    assert_eq!(
+        summarize(&landed),
+        [(&commit_local, &commit_upstream, LandedMatch::PatchId)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_abandon_landed_commits() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path_1 = repo_path("file1");
+    let path_2 = repo_path("file2");
+    let tree_a = create_tree(repo, &[(path_1, "a\n")]);
+    let tree_b = create_tree(repo, &[(path_1, "b\n")]);
+    let tree_c = create_tree(repo, &[(path_1, "b\n"), (path_2, "c\n")]);
+    let tree_x = create_tree(repo, &[(path_1, "a\n"), (path_2, "x\n")]);
+    let tree_b2 = create_tree(repo, &[(path_1, "b\n"), (path_2, "x\n")]);
+
+    // C
+    // B  B'
+    // |  X
+    // | /
+    // A
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit_a = mut_repo
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a)
+        .write_unwrap();
+    let commit_b = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_b)
+        .write_unwrap();
+    let commit_c = mut_repo
+        .new_commit(vec![commit_b.id().clone()], tree_c)
+        .write_unwrap();
+    let commit_x = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_x)
+        .write_unwrap();
+    let commit_b2 = mut_repo
+        .new_commit(vec![commit_x.id().clone()], tree_b2.clone())
+        .write_unwrap();
+    let repo = tx.commit("test").block_on()?;
+
+    let mut tx = repo.start_transaction();
+    let landed = find_landed_commits(
+        tx.repo(),
+        &[commit_b.clone(), commit_c.clone()],
+        &[commit_x, commit_b2.clone()],
+        true,
+    )
+    .block_on()?;
+    assert_eq!(
+        summarize(&landed),
+        [(&commit_b, &commit_b2, LandedMatch::PatchId)]
+    );
+    let num_rebased = abandon_landed_commits(tx.repo_mut(), &landed).block_on()?;
+    assert_eq!(num_rebased, 1);
+    let repo = tx.commit("test").block_on()?;
+
+    // C is rebased onto B', and B is abandoned
+    assert!(!repo.view().heads().contains(commit_b.id()));
+    let new_commit_c = repo
+        .store()
+        .get_commit(repo.view().heads().iter().exactly_one().unwrap())?;
+    assert_eq!(new_commit_c.change_id(), commit_c.change_id());
+    assert_eq!(new_commit_c.parent_ids(), [commit_b2.id().clone()]);
+    Ok(())
+}
@@ -33,6 +33,7 @@ use jj_lib::backend::CopyId;
 use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::conflict_labels::ConflictLabels;
+use jj_lib::conflict_resolvers::ConflictResolvers;
 use jj_lib::conflicts::ConflictMaterializeOptions;
 use jj_lib::file_util;
 use jj_lib::file_util::check_symlink_support;
@@ -60,6 +61,7 @@ use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::secret_backend::SecretBackend;
 use jj_lib::tree_builder::TreeBuilder;
 use jj_lib::tree_merge::MergeOptions;
+use jj_lib::word_tokenizer::WordTokenizers;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::SnapshotOptions;
@@ -1363,6 +1365,8 @@ fn test_snapshot_modified_materialized_conflict(
                 merge: MergeOptions {
                     hunk_level: FileMergeHunkLevel::Line,
                     same_change: SameChange::Accept,
+                    word_tokenizers: WordTokenizers::default(),
+                    conflict_resolvers: ConflictResolvers::default(),
                 },
             },
         )?;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::index::Index;
 use jj_lib::merge::Merge;
@@ -801,3 +802,63 @@ fn test_bookmark_hidden_commit() -> TestResult {
     assert_eq!(*repo.view().heads(), hashset! {commit.id().clone()});
     Ok(())
 }
+
+#[test]
+fn test_push_bookmark_moved_explicitly() -> TestResult {
+    // Test that a push bookmark is forgotten once the bookmark is moved by
+    // MutableRepo::set_local_bookmark_target(), but not when it's moved by
+    // rebasing.
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit(tx.repo_mut());
+    for name in ["push-a", "push-b", "push-c"] {
+        tx.repo_mut()
+            .set_local_bookmark_target(name.as_ref(), RefTarget::normal(commit_a.id().clone()));
+        tx.repo_mut().add_push_bookmark(name.into());
+    }
+    let repo = tx.commit("test").block_on()?;
+
+    let mut tx = repo.start_transaction();
+    // Setting the same target keeps the push bookmark
+    tx.repo_mut()
+        .set_local_bookmark_target("push-a".as_ref(), RefTarget::normal(commit_a.id().clone()));
+    tx.repo_mut()
+        .set_local_bookmark_target("push-b".as_ref(), RefTarget::normal(commit_b.id().clone()));
+    tx.repo_mut()
+        .set_local_bookmark_target("push-c".as_ref(), RefTarget::absent());
+    assert_eq!(
+        tx.repo()
+            .view()
+            .push_bookmarks()
+            .iter()
+            .map(|name| name.as_str())
+            .collect_vec(),
+        ["push-a"]
+    );
+
+    // Rewriting the target commit moves the bookmark but keeps it a push
+    // bookmark
+    let commit_a2 = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_description("rewritten")
+        .write_unwrap();
+    tx.repo_mut().rebase_descendants().block_on()?;
+    assert_eq!(
+        *tx.repo().view().get_local_bookmark("push-a".as_ref()),
+        RefTarget::normal(commit_a2.id().clone())
+    );
+    assert_eq!(
+        tx.repo()
+            .view()
+            .push_bookmarks()
+            .iter()
+            .map(|name| name.as_str())
+            .collect_vec(),
+        ["push-a"]
+    );
+    Ok(())
+}
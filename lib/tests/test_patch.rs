@@ -0,0 +1,161 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::indoc;
+use jj_lib::patch::apply_patch;
+use jj_lib::patch::parse_patches;
+use pollster::FutureExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::assert_tree_eq;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::repo_path_buf;
+
+#[test]
+fn test_apply_patch_clean() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let modified_path = repo_path("modified");
+    let renamed_path = repo_path("renamed");
+    let deleted_path = repo_path("deleted");
+    let added_path = repo_path("dir/added");
+    let tree = create_tree(
+        repo,
+        &[
+            (modified_path, "a\nb\nc\nd\n"),
+            (repo_path("old"), "x\n"),
+            (deleted_path, "gone\n"),
+        ],
+    );
+    let patches = parse_patches(
+        indoc! {"
+            diff --git a/modified b/modified
+            --- a/modified
+            +++ b/modified
+            @@ -2,2 +2,2 @@
+             b
+            -c
+            +C
+            diff --git a/old b/renamed
+            similarity index 100%
+            rename from old
+            rename to renamed
+            diff --git a/deleted b/deleted
+            deleted file mode 100644
+            --- a/deleted
+            +++ /dev/null
+            @@ -1 +0,0 @@
+            -gone
+            diff --git a/dir/added b/dir/added
+            new file mode 100644
+            --- /dev/null
+            +++ b/dir/added
+            @@ -0,0 +1 @@
+            +new
+        "}
+        .as_bytes(),
+    )?;
+    assert_eq!(patches.len(), 1);
+    let applied = apply_patch(&tree, "dest".into(), &patches[0], "patch".into()).block_on()?;
+    assert!(applied.mismatched_paths.is_empty());
+    let expected = create_tree(
+        repo,
+        &[
+            (modified_path, "a\nb\nC\nd\n"),
+            (renamed_path, "x\n"),
+            (added_path, "new\n"),
+        ],
+    );
+    assert_tree_eq!(applied.tree, expected);
+    Ok(())
+}
+
+#[test]
+fn test_apply_patch_mismatch() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path = repo_path("file");
+    let patches = parse_patches(
+        indoc! {"
+            --- a/file
+            +++ b/file
+            @@ -1,5 +1,5 @@
+             a
+             b
+            -c
+            +C
+             d
+             e
+            @@ -8,3 +8,3 @@
+             h
+            -i
+            +I
+             j
+        "}
+        .as_bytes(),
+    )?;
+
+    // The context of the first hunk was changed, but it can be merged
+    let tree = create_tree(repo, &[(path, "a2\nb\nc\nd\ne\nf\ng\nh\ni\nj\n")]);
+    let applied = apply_patch(&tree, "dest".into(), &patches[0], "patch".into()).block_on()?;
+    assert_eq!(applied.mismatched_paths, [repo_path_buf("file")]);
+    let expected = create_tree(repo, &[(path, "a2\nb\nC\nd\ne\nf\ng\nh\nI\nj\n")]);
+    assert_tree_eq!(applied.tree, expected);
+
+    // The changed line was also changed in the destination
+    let tree = create_tree(repo, &[(path, "a\nb\nc2\nd\ne\nf\ng\nh\ni\nj\n")]);
+    let applied = apply_patch(&tree, "dest".into(), &patches[0], "patch".into()).block_on()?;
+    assert_eq!(applied.mismatched_paths, [repo_path_buf("file")]);
+    assert!(applied.tree.has_conflict());
+    let value = applied.tree.path_value(path).block_on()?;
+    assert_eq!(value.num_sides(), 2);
+
+    // The file doesn't exist
+    let tree = create_tree(repo, &[]);
+    let applied = apply_patch(&tree, "dest".into(), &patches[0], "patch".into()).block_on()?;
+    assert!(applied.tree.has_conflict());
+    Ok(())
+}
+
+#[test]
+fn test_apply_patch_mismatch_wrong_offset() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path = repo_path("file");
+    let patches = parse_patches(
+        indoc! {"
+            --- a/file
+            +++ b/file
+            @@ -5,4 +5,4 @@
+             a
+             x
+            -b
+            +B
+             y
+        "}
+        .as_bytes(),
+    )?;
+    // The context doesn't match, and the changed lines also appear at another
+    // offset. The change is merged at the offset stated in the hunk header.
+    let tree = create_tree(repo, &[(path, "x\nb\ny\nq\na2\nx\nb\ny\nz\n")]);
+    let applied = apply_patch(&tree, "dest".into(), &patches[0], "patch".into()).block_on()?;
+    assert_eq!(applied.mismatched_paths, [repo_path_buf("file")]);
+    let expected = create_tree(repo, &[(path, "x\nb\ny\nq\na2\nx\nB\ny\nz\n")]);
+    assert_tree_eq!(applied.tree, expected);
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::patch_id::commit_patch_id;
+use jj_lib::patch_id::compute_patch_id;
+use jj_lib::repo::Repo as _;
+use pollster::FutureExt as _;
+use testutils::CommitBuilderExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::create_tree;
+use testutils::create_tree_with;
+use testutils::repo_path;
+
+#[test]
+fn test_patch_id_rebased_changes() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path_1 = repo_path("file1");
+    let path_2 = repo_path("file2");
+
+    // The same line changed on top of different contents
+    let tree_a = create_tree(repo, &[(path_1, "a\nb\nc\n"), (path_2, "x\n")]);
+    let tree_b = create_tree(repo, &[(path_1, "a\nB\nc\n"), (path_2, "x\n")]);
+    let tree_c = create_tree(repo, &[(path_1, "0\na\nb\nc\n"), (path_2, "y\n")]);
+    let tree_d = create_tree(repo, &[(path_1, "0\na\nB\nc\n"), (path_2, "y\n")]);
+    let patch_id_ab = compute_patch_id(&tree_a, &tree_b).block_on()?;
+    let patch_id_cd = compute_patch_id(&tree_c, &tree_d).block_on()?;
+    assert!(patch_id_ab.is_some());
+    assert_eq!(patch_id_ab, patch_id_cd);
+
+    // Different changes
+    let tree_e = create_tree(repo, &[(path_1, "a\nE\nc\n"), (path_2, "x\n")]);
+    let patch_id_ae = compute_patch_id(&tree_a, &tree_e).block_on()?;
+    assert_ne!(patch_id_ab, patch_id_ae);
+
+    // The same change to a different file
+    let tree_f = create_tree(repo, &[(path_1, "a\nb\nc\n"), (path_2, "x\n")]);
+    let tree_g = create_tree(repo, &[(path_1, "a\nb\nc\n"), (path_2, "a\nB\nc\n")]);
+    let patch_id_fg = compute_patch_id(&tree_f, &tree_g).block_on()?;
+    assert_ne!(patch_id_ab, patch_id_fg);
+
+    // No changes
+    assert_eq!(compute_patch_id(&tree_a, &tree_a).block_on()?, None);
+    Ok(())
+}
+
+#[test]
+fn test_patch_id_ignores_whitespace() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path = repo_path("file");
+
+    // The same line changed with different indentation
+    let tree_a = create_tree(repo, &[(path, "fn f() {\n    a();\n}\n")]);
+    let tree_b = create_tree(repo, &[(path, "fn f() {\n    b();\n}\n")]);
+    let tree_c = create_tree(repo, &[(path, "fn f() {\n\ta();\n}\n")]);
+    let tree_d = create_tree(repo, &[(path, "fn f() {\n\tb( );\n}\n")]);
+    let patch_id_ab = compute_patch_id(&tree_a, &tree_b).block_on()?;
+    let patch_id_cd = compute_patch_id(&tree_c, &tree_d).block_on()?;
+    assert!(patch_id_ab.is_some());
+    assert_eq!(patch_id_ab, patch_id_cd);
+
+    // Whitespace-only changes are still changes
+    let patch_id_ac = compute_patch_id(&tree_a, &tree_c).block_on()?;
+    assert!(patch_id_ac.is_some());
+    assert_ne!(patch_id_ab, patch_id_ac);
+    Ok(())
+}
+
+#[test]
+fn test_patch_id_whitespace_only_changes() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path = repo_path("file");
+
+    // Two different whitespace-only changes to the same file
+    let tree_a = create_tree(repo, &[(path, "fn f() {\n    a();\n}\n")]);
+    let tree_b = create_tree(repo, &[(path, "fn f() {\n\ta();\n}\n")]);
+    let tree_c = create_tree(repo, &[(path, "fn f() {\n    a( );\n}\n")]);
+    let patch_id_ab = compute_patch_id(&tree_a, &tree_b).block_on()?;
+    let patch_id_ac = compute_patch_id(&tree_a, &tree_c).block_on()?;
+    assert!(patch_id_ab.is_some());
+    assert!(patch_id_ac.is_some());
+    assert_ne!(patch_id_ab, patch_id_ac);
+
+    // The same whitespace-only change on top of different contents
+    let tree_d = create_tree(repo, &[(path, "use x;\nfn f() {\n    a();\n}\n")]);
+    let tree_e = create_tree(repo, &[(path, "use x;\nfn f() {\n\ta();\n}\n")]);
+    let patch_id_de = compute_patch_id(&tree_d, &tree_e).block_on()?;
+    assert_eq!(patch_id_ab, patch_id_de);
+    Ok(())
+}
+
+#[test]
+fn test_patch_id_executable_bit() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let path = repo_path("file");
+    let tree = create_tree(repo, &[(path, "a\n")]);
+    let tree_executable = create_tree_with(repo, |builder| {
+        builder.file(path, "a\n").executable(true);
+    });
+
+    // Adding an executable file differs from adding a regular file
+    let mut tx = repo.start_transaction();
+    let commit = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree)
+        .write_unwrap();
+    let commit_executable = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_executable)
+        .write_unwrap();
+    let patch_id = commit_patch_id(tx.repo(), &commit).block_on()?;
+    let patch_id_executable = commit_patch_id(tx.repo(), &commit_executable).block_on()?;
+    assert!(patch_id.is_some());
+    assert_ne!(patch_id, patch_id_executable);
+    Ok(())
+}
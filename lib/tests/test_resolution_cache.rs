@@ -0,0 +1,223 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bstr::BString;
+use itertools::Itertools as _;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo as _;
+use jj_lib::resolution_cache::split_hunk_resolutions;
+use jj_lib::rewrite::rebase_commit;
+use jj_lib::settings::UserSettings;
+use pollster::FutureExt as _;
+use testutils::CommitBuilderExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::assert_tree_eq;
+use testutils::create_tree;
+use testutils::repo_path;
+
+fn remember_resolutions_settings() -> UserSettings {
+    let mut config = testutils::base_user_config();
+    let mut layer = ConfigLayer::empty(ConfigSource::User);
+    layer.set_value("merge.remember-resolutions", true).unwrap();
+    config.add_layer(layer);
+    UserSettings::from_config(config).unwrap()
+}
+
+fn merge_trees(base: &MergedTree, left: &MergedTree, right: &MergedTree) -> MergedTree {
+    MergedTree::merge(Merge::from_vec(vec![
+        (left.clone(), "left".into()),
+        (base.clone(), "base".into()),
+        (right.clone(), "right".into()),
+    ]))
+    .block_on()
+    .unwrap()
+}
+
+#[test]
+fn test_resolution_cache_reuse() -> TestResult {
+    let test_repo = TestRepo::init_with_settings(&remember_resolutions_settings());
+    let repo = &test_repo.repo;
+    let store = repo.store();
+    let cache = store.resolution_cache().unwrap();
+
+    let path = repo_path("file");
+    let base = create_tree(repo, &[(path, "a\nb\nc\nd\ne\n")]);
+    let left = create_tree(repo, &[(path, "a\nb1\nc\nd1\ne\n")]);
+    let right = create_tree(repo, &[(path, "a\nb2\nc\nd2\ne\n")]);
+
+    // Nothing has been recorded yet, so the merge is conflicted
+    let merged = merge_trees(&base, &left, &right);
+    assert!(merged.has_conflict());
+    let conflict = merged.path_value(path).block_on()?;
+    let file_conflict = conflict.to_file_merge().unwrap();
+
+    // A resolution which doesn't match the resolved hunks isn't recorded
+    let num_recorded = cache
+        .record_file_resolution(store, path, &file_conflict, b"a\nb12\nd12\ne\n")
+        .block_on()?;
+    assert_eq!(num_recorded, 0);
+    assert!(cache.list()?.is_empty());
+
+    let num_recorded = cache
+        .record_file_resolution(store, path, &file_conflict, b"a\nb12\nc\nd12\ne\n")
+        .block_on()?;
+    assert_eq!(num_recorded, 2);
+    let resolutions = cache.list()?;
+    assert_eq!(resolutions.len(), 2);
+    assert!(
+        resolutions
+            .iter()
+            .all(|resolution| resolution.path == "file")
+    );
+
+    // The same conflict is now resolved when merging, regardless of the order
+    // of the sides
+    let expected = create_tree(repo, &[(path, "a\nb12\nc\nd12\ne\n")]);
+    assert!(cache.take_resolved_paths().is_empty());
+    assert_tree_eq!(merge_trees(&base, &left, &right), expected);
+    assert_eq!(
+        cache.take_resolved_paths().into_iter().collect_vec(),
+        vec![path.to_owned()]
+    );
+    assert_tree_eq!(merge_trees(&base, &right, &left), expected);
+
+    // The same hunks are resolved in a different context
+    let base2 = create_tree(repo, &[(path, "x\nb\ny\nd\nz\n")]);
+    let left2 = create_tree(repo, &[(path, "x\nb1\ny\nd1\nz\n")]);
+    let right2 = create_tree(repo, &[(path, "x\nb2\ny\nd2\nz\n")]);
+    let expected2 = create_tree(repo, &[(path, "x\nb12\ny\nd12\nz\n")]);
+    assert_tree_eq!(merge_trees(&base2, &left2, &right2), expected2);
+
+    // If one of the hunks is forgotten, the file is left conflicted
+    assert!(cache.remove(&resolutions[0].id)?);
+    assert!(!cache.remove(&resolutions[0].id)?);
+    cache.take_resolved_paths();
+    let merged = merge_trees(&base, &left, &right);
+    assert!(merged.has_conflict());
+    assert!(cache.take_resolved_paths().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_resolution_cache_rebase() -> TestResult {
+    let test_repo = TestRepo::init_with_settings(&remember_resolutions_settings());
+    let repo = &test_repo.repo;
+    let store = repo.store();
+    let cache = store.resolution_cache().unwrap();
+
+    // Rebase B onto C:
+    // C foo=c          B' foo=bc
+    // | B foo=b   =>   |
+    // |/               C  foo=c
+    // A   foo=a        |
+    //                  A  foo=a
+    let path = repo_path("foo");
+    let mut tx = repo.start_transaction();
+    let tree_a = create_tree(repo, &[(path, "a\n")]);
+    let tree_b = create_tree(repo, &[(path, "b\n")]);
+    let tree_c = create_tree(repo, &[(path, "c\n")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![store.root_commit_id().clone()], tree_a.clone())
+        .write_unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b.clone())
+        .write_unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c.clone())
+        .write_unwrap();
+
+    let conflict = merge_trees(&tree_a, &tree_c, &tree_b)
+        .path_value(path)
+        .block_on()?
+        .to_file_merge()
+        .unwrap();
+    cache
+        .record_file_resolution(store, path, &conflict, b"bc\n")
+        .block_on()?;
+
+    let commit_b2 =
+        rebase_commit(tx.repo_mut(), commit_b, vec![commit_c.id().clone()]).block_on()?;
+    let expected = create_tree(repo, &[(path, "bc\n")]);
+    assert_tree_eq!(commit_b2.tree(), expected);
+    assert_eq!(
+        cache.take_resolved_paths().into_iter().collect_vec(),
+        vec![path.to_owned()]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_resolution_cache_disabled_by_default() {
+    let test_repo = TestRepo::init();
+    assert!(test_repo.repo.store().resolution_cache().is_none());
+}
+
+#[test]
+fn test_split_hunk_resolutions() {
+    let hunks = [
+        Merge::resolved(BString::from("a\n")),
+        Merge::from_vec(vec![
+            BString::from("b1\n"),
+            BString::from("b\n"),
+            BString::from("b2\n"),
+        ]),
+        Merge::resolved(BString::from("c\n")),
+    ];
+    let resolutions = split_hunk_resolutions(&hunks, b"a\nb1\nb2\nc\n").unwrap();
+    assert_eq!(resolutions.len(), 1);
+    assert_eq!(resolutions[0].0, &hunks[1]);
+    assert_eq!(resolutions[0].1, b"b1\nb2\n");
+
+    // The conflicting hunk can be resolved to nothing
+    let resolutions = split_hunk_resolutions(&hunks, b"a\nc\n").unwrap();
+    assert_eq!(resolutions[0].1, b"");
+
+    // The resolved hunks must be present
+    assert!(split_hunk_resolutions(&hunks, b"b1\nb2\nc\n").is_none());
+    assert!(split_hunk_resolutions(&hunks, b"a\nb1\nb2\n").is_none());
+    assert!(split_hunk_resolutions(&hunks, b"a\nb1\nc\nextra\n").is_none());
+
+    // The context line can also appear in the resolution
+    let resolutions = split_hunk_resolutions(&hunks, b"a\nc\nb1\nc\n").unwrap();
+    assert_eq!(resolutions[0].1, b"c\nb1\n");
+
+    let hunks = [
+        Merge::from_vec(vec![
+            BString::from("a1\n"),
+            BString::from("a\n"),
+            BString::from("a2\n"),
+        ]),
+        Merge::resolved(BString::from("c\n")),
+        Merge::from_vec(vec![
+            BString::from("b1\n"),
+            BString::from("b\n"),
+            BString::from("b2\n"),
+        ]),
+        Merge::resolved(BString::from("d\n")),
+    ];
+    let resolutions = split_hunk_resolutions(&hunks, b"x\nc\ny\nd\n").unwrap();
+    assert_eq!(resolutions[0].1, b"x\n");
+    assert_eq!(resolutions[1].1, b"y\n");
+
+    // The content can't be split if it's ambiguous
+    let resolutions = split_hunk_resolutions(&hunks, b"a\nc\nc\nb\nd\n");
+    assert!(resolutions.is_none(), "ambiguous split: {resolutions:?}");
+}
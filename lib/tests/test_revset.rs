@@ -64,6 +64,7 @@ use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::revset::parse;
 use jj_lib::signing::SignBehavior;
 use jj_lib::signing::Signer;
+use jj_lib::signing::TrustStore;
 use jj_lib::test_signing_backend::TestSigningBackend;
 use jj_lib::workspace::Workspace;
 use pollster::FutureExt as _;
@@ -2170,6 +2171,103 @@ fn test_evaluate_expression_connected() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_equivalent() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    // B and B' make the same change on top of different parents
+    //
+    // B  B'
+    // |  X
+    // A  |  E1  E2
+    // |  |  |  /
+    // root -----
+    let path_1 = repo_path("file1");
+    let path_2 = repo_path("file2");
+    let tree_a = create_tree(repo, &[(path_1, "a\n")]);
+    let tree_b = create_tree(repo, &[(path_1, "b\n")]);
+    let tree_x = create_tree(repo, &[(path_1, "a\n"), (path_2, "x\n")]);
+    let tree_b2 = create_tree(repo, &[(path_1, "b\n"), (path_2, "x\n")]);
+    let root_id = repo.store().root_commit_id().clone();
+    let commit_a = mut_repo
+        .new_commit(vec![root_id.clone()], tree_a)
+        .write_unwrap();
+    let commit_b = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_b)
+        .write_unwrap();
+    let commit_x = mut_repo
+        .new_commit(vec![root_id.clone()], tree_x)
+        .write_unwrap();
+    let commit_b2 = mut_repo
+        .new_commit(vec![commit_x.id().clone()], tree_b2)
+        .write_unwrap();
+    let empty_tree = repo.store().empty_merged_tree();
+    let commit_e1 = mut_repo
+        .new_commit(vec![root_id.clone()], empty_tree.clone())
+        .write_unwrap();
+    let commit_e2 = mut_repo
+        .new_commit(vec![root_id], empty_tree)
+        .write_unwrap();
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("equivalent({}, all())", commit_b.id())),
+        vec![commit_b2.id().clone(), commit_b.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("equivalent({}, all())", commit_b2.id())),
+        vec![commit_b2.id().clone(), commit_b.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("equivalent({}, all())", commit_a.id())),
+        vec![commit_a.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("equivalent({}, {}::)", commit_b.id(), commit_a.id())
+        ),
+        vec![commit_b.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("duplicates_of({}, all())", commit_b.id())
+        ),
+        vec![commit_b2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("duplicates_of({}, {}::)", commit_b.id(), commit_x.id())
+        ),
+        vec![commit_b2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("duplicates_of({}, {}::)", commit_b.id(), commit_a.id())
+        ),
+        vec![]
+    );
+
+    // Empty commits aren't equivalent to each other
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("equivalent({}, all())", commit_e1.id())),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("duplicates_of({}, all())", commit_e2.id())
+        ),
+        vec![]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_reachable() {
     let test_repo = TestRepo::init();
@@ -3994,6 +4092,52 @@ fn test_evaluate_expression_signed() {
     assert!(unsigned_commits.contains(commit2.id()));
 }
 
+#[test]
+fn test_evaluate_expression_signed_by() {
+    let trust = TrustStore::new(vec!["trusted".to_owned()], HashMap::new());
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]).with_trust_store(trust);
+    let settings = testutils::user_settings();
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Test, signer, &settings);
+    let repo = &test_workspace.repo;
+    let repo = repo.clone();
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let trusted_commit = create_random_commit(mut_repo)
+        .set_sign_behavior(SignBehavior::Own)
+        .set_sign_key("trusted".to_owned())
+        .write_unwrap();
+    let untrusted_commit = create_random_commit(mut_repo)
+        .set_sign_behavior(SignBehavior::Own)
+        .set_sign_key("untrusted".to_owned())
+        .write_unwrap();
+    let unsigned_commit = create_random_commit(mut_repo)
+        .set_sign_behavior(SignBehavior::Drop)
+        .write_unwrap();
+
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(key='trusted')"),
+        vec![trusted_commit.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(key=glob:'*trusted')").len(),
+        2
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(trusted=true)"),
+        vec![trusted_commit.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "signed(trusted=false)"),
+        vec![untrusted_commit.id().clone()]
+    );
+    let unsigned_commits = resolve_commit_ids(mut_repo, "~signed()");
+    assert!(!unsigned_commits.contains(untrusted_commit.id()));
+    assert!(unsigned_commits.contains(unsigned_commit.id()));
+}
+
 #[test]
 fn test_evaluate_expression_committer() {
     let test_repo = TestRepo::init();
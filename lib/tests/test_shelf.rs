@@ -0,0 +1,136 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::CommitWithSelection;
+use jj_lib::shelf::apply_shelf;
+use jj_lib::shelf::shelve_changes;
+use pollster::FutureExt as _;
+use testutils::CommitBuilderExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::assert_tree_eq;
+use testutils::create_tree;
+use testutils::repo_path;
+
+#[test]
+fn test_shelve_and_apply() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path_1 = repo_path("file1");
+    let path_2 = repo_path("file2");
+    let tree_a = create_tree(repo, &[(path_1, "a\n"), (path_2, "a\n")]);
+    let tree_b = create_tree(repo, &[(path_1, "b\n"), (path_2, "b\n")]);
+    let tree_selected = create_tree(repo, &[(path_1, "b\n"), (path_2, "a\n")]);
+    let tree_c = create_tree(repo, &[(path_1, "a\n"), (path_2, "c\n")]);
+
+    // B C
+    // |/
+    // A
+    let mut tx = repo.start_transaction();
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a.clone())
+        .write_unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b.clone())
+        .set_description("b")
+        .write_unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c)
+        .write_unwrap();
+    let repo = tx.commit("test").block_on()?;
+
+    // Shelve changes to file1
+    let mut tx = repo.start_transaction();
+    let selection = CommitWithSelection {
+        commit: commit_b.clone(),
+        selected_tree: tree_selected.clone(),
+        parent_tree: tree_a.clone(),
+    };
+    let shelf_commit =
+        shelve_changes(tx.repo_mut(), &selection, "foo".into(), "shelf".into()).block_on()?;
+    tx.repo_mut().rebase_descendants().block_on()?;
+    let repo = tx.commit("test").block_on()?;
+    assert_eq!(repo.view().get_shelf("foo"), Some(shelf_commit.id()));
+    assert_eq!(shelf_commit.parent_ids(), [commit_a.id().clone()]);
+    assert_eq!(shelf_commit.description(), "shelf");
+    assert_tree_eq!(shelf_commit.tree(), tree_selected);
+    // The shelf commit isn't visible
+    assert!(!repo.view().heads().contains(shelf_commit.id()));
+    let new_commit_b_id = repo
+        .view()
+        .heads()
+        .iter()
+        .filter(|id| *id != commit_c.id())
+        .exactly_one()
+        .unwrap();
+    let new_commit_b = repo.store().get_commit(new_commit_b_id)?;
+    assert_eq!(new_commit_b.change_id(), commit_b.change_id());
+    assert_tree_eq!(
+        new_commit_b.tree(),
+        create_tree(&repo, &[(path_1, "a\n"), (path_2, "b\n")])
+    );
+
+    // Apply the shelf to the sibling
+    let mut tx = repo.start_transaction();
+    let new_commit_c = apply_shelf(tx.repo_mut(), &shelf_commit, &commit_c).block_on()?;
+    assert_eq!(new_commit_c.change_id(), commit_c.change_id());
+    assert_tree_eq!(
+        new_commit_c.tree(),
+        create_tree(&repo, &[(path_1, "b\n"), (path_2, "c\n")])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_apply_shelf_conflict() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path = repo_path("file");
+    let tree_a = create_tree(repo, &[(path, "a\n")]);
+    let tree_b = create_tree(repo, &[(path, "b\n")]);
+    let tree_c = create_tree(repo, &[(path, "c\n")]);
+
+    let mut tx = repo.start_transaction();
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a.clone())
+        .write_unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b.clone())
+        .write_unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c)
+        .write_unwrap();
+    let selection = CommitWithSelection {
+        commit: commit_b,
+        selected_tree: tree_b,
+        parent_tree: tree_a,
+    };
+    let shelf_commit =
+        shelve_changes(tx.repo_mut(), &selection, "foo".into(), "".into()).block_on()?;
+
+    // Conflicts are recorded in the destination commit
+    let new_commit_c = apply_shelf(tx.repo_mut(), &shelf_commit, &commit_c).block_on()?;
+    assert!(new_commit_c.has_conflict());
+    Ok(())
+}
@@ -1,13 +1,18 @@
+use std::collections::HashMap;
+
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
+use jj_lib::operation::OpVerificationProblem;
+use jj_lib::operation::Operation;
 use jj_lib::repo::Repo as _;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignBehavior;
 use jj_lib::signing::Signer;
+use jj_lib::signing::TrustStore;
 use jj_lib::signing::Verification;
 use jj_lib::test_signing_backend::TestSigningBackend;
 use pollster::FutureExt as _;
@@ -210,3 +215,192 @@ fn drop_behavior(backend: TestRepoBackend) -> TestResult {
     assert_eq!(rewritten_commit.verification()?, None);
     Ok(())
 }
+
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn trusted_signature(backend: TestRepoBackend) -> TestResult {
+    let settings = user_settings(SignBehavior::Force);
+
+    let trust = TrustStore::new(
+        vec![],
+        HashMap::from([(
+            "Test.User@example.com".to_owned(),
+            vec!["impeccable".to_owned()],
+        )]),
+    );
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]).with_trust_store(trust);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+
+    let repo = &test_workspace.repo;
+
+    let repo = repo.clone();
+    let mut tx = repo.start_transaction();
+    let own_commit = write_random_commit(tx.repo_mut());
+    let other_commit = create_random_commit(tx.repo_mut())
+        .set_author(someone_else())
+        .write_unwrap();
+    let unsigned_commit = create_random_commit(tx.repo_mut())
+        .set_sign_behavior(SignBehavior::Drop)
+        .write_unwrap();
+    tx.commit("test").block_on()?;
+
+    // The key is trusted only for the commits of its owner
+    let own_commit = repo.store().get_commit(own_commit.id())?;
+    assert!(own_commit.has_trusted_signature()?);
+    let other_commit = repo.store().get_commit(other_commit.id())?;
+    assert_eq!(other_commit.verification()?, good_verification());
+    assert!(!other_commit.has_trusted_signature()?);
+    let unsigned_commit = repo.store().get_commit(unsigned_commit.id())?;
+    assert!(!unsigned_commit.has_trusted_signature()?);
+    Ok(())
+}
+
+#[test]
+fn signed_operation() -> TestResult {
+    let mut config = testutils::base_user_config();
+    config.add_layer(ConfigLayer::parse(
+        ConfigSource::User,
+        r#"
+        signing.key = "impeccable"
+        operation.sign = true
+        "#,
+    )?);
+    let settings = UserSettings::from_config(config)?;
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Simple, signer, &settings);
+    let repo = &test_workspace.repo;
+    let signer = repo.store().signer();
+
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").block_on()?;
+    let op = repo.operation();
+    assert!(op.signature().is_some());
+    assert_eq!(op.verify_signature(signer)?, good_verification());
+
+    // Tampering with the metadata invalidates the signature
+    let mut data = op.store_operation().clone();
+    data.metadata.description = "tampered".to_owned();
+    let tampered_op = Operation::new(repo.op_store().clone(), op.id().clone(), data);
+    let verification = tampered_op.verify_signature(signer)?.unwrap();
+    assert_eq!(verification.status, SigStatus::Bad);
+
+    // The root operation isn't signed
+    let root_op = repo.loader().root_operation().block_on();
+    assert_eq!(root_op.verify_signature(signer)?, None);
+    Ok(())
+}
+
+#[test]
+fn merge_unverified_operations() -> TestResult {
+    let mut config = testutils::base_user_config();
+    config.add_layer(ConfigLayer::parse(
+        ConfigSource::User,
+        r#"
+        signing.key = "impeccable"
+        operation.sign = true
+        "#,
+    )?);
+    let settings = UserSettings::from_config(config)?;
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Simple, signer, &settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo1 = tx.commit("signed").block_on()?;
+
+    // Write concurrent operations with an invalidated signature and without
+    // a signature
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let unpublished = tx.write("concurrent").block_on()?;
+    let write_op = |data| -> TestResult<Operation> {
+        let id = repo.op_store().write_operation(&data).block_on()?;
+        Ok(Operation::new(repo.op_store().clone(), id, data))
+    };
+    let mut data = unpublished.operation().store_operation().clone();
+    data.metadata.description = "tampered".to_owned();
+    let tampered_op = write_op(data.clone())?;
+    data.parents = vec![tampered_op.id().clone()];
+    data.metadata.description = "unsigned".to_owned();
+    data.signature = None;
+    let unsigned_op = write_op(data)?;
+
+    let mut tx = repo1.start_transaction();
+    tx.merge_operation(unsigned_op.clone()).block_on()?;
+    assert_eq!(
+        tx.unverified_operations(),
+        [
+            (unsigned_op, OpVerificationProblem::Unsigned),
+            (tampered_op, OpVerificationProblem::BadSignature),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn trust_store_key_matching() {
+    let verification = |status, key: &str, display: &str| {
+        Verification::new(status, Some(key.to_owned()), Some(display.to_owned()))
+    };
+    let trust = TrustStore::new(
+        vec!["F4A3 D0A1 B5C6 D7E8 F9A0  4ED5 56E9 729E 000F 4ED5".to_owned()],
+        HashMap::from([(
+            "alice@example.com".to_owned(),
+            vec!["SHA256:alice".to_owned()],
+        )]),
+    );
+
+    // GPG fingerprints match exactly, ignoring case
+    let gpg = verification(
+        SigStatus::Good,
+        "f4a3d0a1b5c6d7e8f9a04ed556e9729e000f4ed5",
+        "Bob",
+    );
+    assert!(trust.is_trusted(&gpg, "bob@example.com"));
+    let gpg = verification(SigStatus::Good, "56E9729E000F4ED5", "Bob");
+    assert!(!trust.is_trusted(&gpg, "bob@example.com"));
+    let gpg = verification(
+        SigStatus::Good,
+        "00F4A3D0A1B5C6D7E8F9A04ED556E9729E000F4ED5",
+        "Bob",
+    );
+    assert!(!trust.is_trusted(&gpg, "bob@example.com"));
+    let gpg = verification(
+        SigStatus::Bad,
+        "F4A3D0A1B5C6D7E8F9A04ED556E9729E000F4ED5",
+        "Bob",
+    );
+    assert!(!trust.is_trusted(&gpg, "bob@example.com"));
+
+    // Per-author keys aren't trusted for other authors
+    let ssh = verification(SigStatus::Good, "SHA256:alice", "alice@example.com");
+    assert!(trust.is_trusted(&ssh, "Alice@Example.com"));
+    assert!(!trust.is_trusted(&ssh, "bob@example.com"));
+    let ssh = verification(SigStatus::Good, "SHA256:ALICE", "alice@example.com");
+    assert!(!trust.is_trusted(&ssh, "alice@example.com"));
+    let ssh = verification(SigStatus::Unknown, "SHA256:alice", "alice@example.com");
+    assert!(!trust.is_trusted(&ssh, "alice@example.com"));
+}
+
+#[test]
+fn signed_by_key_id() -> TestResult {
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let data = b"data";
+    let fingerprint = "F4A3D0A1B5C6D7E8F9A04ED556E9729E000F4ED5";
+
+    // The signature may only tell the key ID, which ends the fingerprint
+    let signature = signer.sign(data, Some("56e9729e000f4ed5"))?;
+    assert!(signer.is_signed_by(data, &signature, Some(fingerprint))?);
+    assert!(!signer.is_signed_by(data, &signature, Some("56E9729E000F4ED6"))?);
+    assert!(!signer.is_signed_by(data, &signature, Some("9729E000F4ED5"))?);
+
+    let signature = signer.sign(data, Some(fingerprint))?;
+    assert!(signer.is_signed_by(data, &signature, Some(fingerprint))?);
+    assert!(!signer.is_signed_by(data, &signature, None)?);
+    Ok(())
+}
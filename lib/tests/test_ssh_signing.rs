@@ -221,3 +221,16 @@ fn ssh_signing_revocation_unrevoked() -> TestResult {
     assert_eq!(check.key.unwrap(), FINGERPRINT);
     Ok(())
 }
+
+#[test]
+fn ssh_key_fingerprints() -> TestResult {
+    let env = SshEnvironment::new()?;
+    let backend = backend(&env);
+
+    let fingerprints = backend.key_fingerprints(Some(env.private_key_path.to_str().unwrap()))?;
+    assert_eq!(fingerprints, [FINGERPRINT]);
+    let fingerprints = backend.key_fingerprints(Some(PUBLIC_KEY))?;
+    assert_eq!(fingerprints, [FINGERPRINT]);
+    assert!(backend.key_fingerprints(None)?.is_empty());
+    Ok(())
+}
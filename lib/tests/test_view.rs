@@ -540,6 +540,78 @@ fn test_merge_views_git_heads() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_merge_views_shelves() -> TestResult {
+    // Tests merging of shelves (by performing divergent operations).
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx0 = repo.start_transaction();
+    let commit_a = write_random_commit(tx0.repo_mut());
+    let commit_b = write_random_commit(tx0.repo_mut());
+    let commit_c = write_random_commit(tx0.repo_mut());
+    tx0.repo_mut()
+        .set_shelf("changed".into(), commit_a.id().clone());
+    tx0.repo_mut()
+        .set_shelf("dropped".into(), commit_a.id().clone());
+    let repo = tx0.commit("test").block_on()?;
+
+    let mut tx1 = repo.start_transaction();
+    tx1.repo_mut()
+        .set_shelf("changed".into(), commit_b.id().clone());
+    tx1.repo_mut()
+        .set_shelf("added1".into(), commit_b.id().clone());
+    tx1.repo_mut().remove_shelf("dropped");
+
+    let mut tx2 = repo.start_transaction();
+    tx2.repo_mut()
+        .set_shelf("changed".into(), commit_c.id().clone());
+    tx2.repo_mut()
+        .set_shelf("added2".into(), commit_c.id().clone());
+
+    let repo = commit_transactions(vec![tx1, tx2]);
+    // The side committed first wins if both sides changed the same shelf.
+    assert_eq!(
+        repo.view().shelves(),
+        &btreemap! {
+            "added1".to_owned() => commit_b.id().clone(),
+            "added2".to_owned() => commit_c.id().clone(),
+            "changed".to_owned() => commit_b.id().clone(),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_merge_views_push_bookmarks() -> TestResult {
+    // Tests merging of push bookmarks (by performing divergent operations).
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx0 = repo.start_transaction();
+    tx0.repo_mut().add_push_bookmark("kept".into());
+    tx0.repo_mut().add_push_bookmark("removed".into());
+    let repo = tx0.commit("test").block_on()?;
+
+    let mut tx1 = repo.start_transaction();
+    tx1.repo_mut().add_push_bookmark("added1".into());
+    tx1.repo_mut().remove_push_bookmark("removed".as_ref());
+
+    let mut tx2 = repo.start_transaction();
+    tx2.repo_mut().add_push_bookmark("added2".into());
+
+    let repo = commit_transactions(vec![tx1, tx2]);
+    assert_eq!(
+        repo.view()
+            .push_bookmarks()
+            .iter()
+            .map(|name| name.as_str())
+            .collect_vec(),
+        ["added1", "added2", "kept"]
+    );
+    Ok(())
+}
+
 #[test]
 fn test_merge_views_divergent() -> TestResult {
     // We start with just commit A. Operation 1 rewrites it as A2. Operation 2
@@ -0,0 +1,90 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal HTTP server to test code sending REST API requests.
+
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::net::TcpListener;
+use std::thread;
+
+/// HTTP request received by [`serve_json()`].
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    /// Request line such as `GET /path HTTP/1.1`.
+    pub request_line: String,
+    /// Header lines in the order they were received.
+    pub headers: Vec<String>,
+    /// Request body.
+    pub body: Vec<u8>,
+}
+
+/// Serves one HTTP request per `(status, body)` response, in order, with the
+/// JSON `body`. Returns the URL of the server and the handle to get the
+/// received requests.
+pub fn serve_json<'a, S: Into<String>>(
+    responses: impl IntoIterator<Item = (&'a str, S)>,
+) -> (String, thread::JoinHandle<Vec<HttpRequest>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let responses: Vec<(String, String)> = responses
+        .into_iter()
+        .map(|(status, body)| (status.to_owned(), body.into()))
+        .collect();
+    let handle = thread::spawn(move || {
+        let mut requests = vec![];
+        for (status, response) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = vec![];
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_owned();
+                if line.is_empty() {
+                    break;
+                }
+                headers.push(line);
+            }
+            let content_length: usize = headers
+                .iter()
+                .find_map(|header| {
+                    let (name, value) = header.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse().unwrap())
+                })
+                .unwrap_or(0);
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                &stream,
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: \
+                 {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+            requests.push(HttpRequest {
+                request_line: request_line.trim_end().to_owned(),
+                headers,
+                body,
+            });
+        }
+        requests
+    });
+    (url, handle)
+}
@@ -80,6 +80,7 @@ use tokio::io::AsyncReadExt as _;
 use crate::test_backend::TestBackendFactory;
 
 pub mod git;
+pub mod http;
 pub mod proptest;
 pub mod test_backend;
 